@@ -0,0 +1,203 @@
+use crate::check::CheckError;
+use crate::span::{CursorPosition, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident(String),
+    Int(u64),
+    Str,
+
+    ///
+    /// One character, or `...`, `<<`, `>>`, `->`, `::`
+    ///
+    Punct(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span
+}
+
+impl Token {
+    pub fn is(&self, punct: &str) -> bool {
+        matches!(&self.kind, TokenKind::Punct(text) if text == punct)
+    }
+
+    pub fn ident(&self) -> Option <&str> {
+        match &self.kind {
+            TokenKind::Ident(name) => Some(name),
+            _ => None
+        }
+    }
+}
+
+const PUNCTS: &[&str] = &["...", "<<", ">>", "->", "::"];
+
+///
+/// The positions of the bytes of the code, found moving forward only
+///
+struct Cursor <'a> {
+    code: &'a str,
+    idx: usize,
+    position: CursorPosition
+}
+
+impl Cursor <'_> {
+    fn at(&mut self, idx: usize) -> CursorPosition {
+        for char in self.code[self.idx..idx].chars() {
+            self.position = if char == '\n' {
+                CursorPosition {
+                    line: self.position.line + 1,
+                    column: 1,
+                    ..self.position
+                }
+            } else {
+                self.position.extend_column_by(1)
+            }
+        }
+
+        self.idx = idx;
+        self.position
+    }
+}
+
+fn unterminated(code: &str, start: usize, what: &str) -> CheckError {
+    CheckError {
+        span: Span::of_range(code, start..start + 1, 0),
+        message: format!("unterminated {what}"),
+        clarifying: String::from("starts here"),
+        help: vec![]
+    }
+}
+
+///
+/// Reads an integer literal, ignoring the `u` and `l` suffixes
+///
+fn int(literal: &str) -> Option <u64> {
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+
+    if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+        u64::from_str_radix(binary, 2).ok()
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u64::from_str_radix(&digits[1..], 8).ok()
+    } else {
+        digits.parse().ok()
+    }
+}
+
+///
+/// Splits a C header into tokens, skipping the comments and the preprocessor directives.
+///   A number other than an integer is kept as an unknown punctuation, so only what uses it fails to parse
+///
+pub fn tokenize(code: &str) -> Result <Vec <Token>, CheckError> {
+    let bytes = code.as_bytes();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    let mut is_line_start = true;
+    let mut cursor = Cursor {
+        code,
+        idx: 0,
+        position: CursorPosition::default()
+    };
+
+    while idx < bytes.len() {
+        let start = idx;
+        let char = bytes[idx];
+
+        if char == b'\n' {
+            is_line_start = true;
+            idx += 1;
+            continue
+        }
+
+        if char.is_ascii_whitespace() {
+            idx += 1;
+            continue
+        }
+
+        if code[idx..].starts_with("//") {
+            idx = code[idx..].find('\n').map_or(code.len(), |end| idx + end);
+            continue
+        }
+
+        if code[idx..].starts_with("/*") {
+            idx = match code[idx + 2..].find("*/") {
+                Some(end) => idx + 2 + end + 2,
+                None => return Err(unterminated(code, start, "comment"))
+            };
+            continue
+        }
+
+        if char == b'#' && is_line_start {
+            // A directive continues on the next line after a `\`
+            while idx < bytes.len() && (bytes[idx] != b'\n' || bytes[idx - 1] == b'\\') {
+                idx += 1
+            }
+            continue
+        }
+
+        is_line_start = false;
+
+        let kind = if char.is_ascii_alphabetic() || char == b'_' {
+            while idx < bytes.len() && (bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'_') {
+                idx += 1
+            }
+
+            TokenKind::Ident(code[start..idx].to_string())
+        } else if char.is_ascii_digit() {
+            while idx < bytes.len() && (bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'.') {
+                idx += 1
+            }
+
+            match int(&code[start..idx]) {
+                Some(value) => TokenKind::Int(value),
+                None => TokenKind::Punct(code[start..idx].to_string())
+            }
+        } else if char == b'"' || char == b'\'' {
+            idx += 1;
+
+            while idx < bytes.len() && bytes[idx] != char {
+                idx += if bytes[idx] == b'\\' { 2 } else { 1 }
+            }
+
+            if idx >= bytes.len() {
+                return Err(unterminated(code, start, if char == b'"' { "string" } else { "character" }))
+            }
+
+            idx += 1;
+
+            if char == b'"' {
+                TokenKind::Str
+            } else {
+                // Only plain characters are read as their codes
+                match &bytes[start + 1..idx - 1] {
+                    [char] => TokenKind::Int(*char as u64),
+                    _ => TokenKind::Punct(code[start..idx].to_string())
+                }
+            }
+        } else {
+            let len = PUNCTS
+                .iter()
+                .find(|punct| code[idx..].starts_with(*punct))
+                .map_or_else(|| code[idx..].chars().next().map_or(1, char::len_utf8), |punct| punct.len());
+
+            idx += len;
+            TokenKind::Punct(code[start..idx].to_string())
+        };
+
+        let span = Span {
+            start: cursor.at(start),
+            end: cursor.at(idx)
+        };
+
+        tokens.push(Token {
+            kind,
+            span
+        })
+    }
+
+    Ok(tokens)
+}
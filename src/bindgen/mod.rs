@@ -0,0 +1,311 @@
+//!
+//! This module turns C headers into `extern clang` declarations.
+//!
+//! The headers are read without a preprocessor, so only what is written in them is imported:
+//!   prototypes, structs, unions, enums and typedefs. Typedefs are resolved into the types they name,
+//!   a struct or a union becomes a `#[repr(C)]` type, the constants of an enum become `const`s and
+//!   its values are `int`s. A struct which is only declared is opaque and can only be pointed to, as `*mut u8`.
+//!
+//! Pointers keep the const-ness of what they point to: `const char *` is `*char`, `char *` is `*mut char`,
+//!   and `void *` is `*mut void`, so the declarations match the ones of the C compiler for the builtin functions.
+//!
+//! oko names have no `_`, so the names are joined in camel case: `SDL_Init` is `SDLInit`,
+//!   and a renamed function links to its C name with `#[link_name]`
+//!
+
+mod lexer;
+mod parse;
+
+use std::collections::{HashMap, HashSet};
+use crate::check::CheckError;
+use crate::span::Span;
+use parse::{CType, Decl, Field, FnType, Parser};
+
+const INDENT: &str = "    ";
+
+///
+/// Words which cannot be names in oko
+///
+const KEYWORDS: &[&str] = &[
+    "fn", "ty", "mut", "pub", "extern", "union", "const", "mod", "use", "impl",
+    "trait", "world", "channel", "macro", "for", "self"
+];
+
+///
+/// The oko declarations of a header
+///
+pub struct Bindings {
+    pub code: String,
+
+    ///
+    /// The declarations which were skipped and why
+    ///
+    pub warnings: Vec <CheckError>
+}
+
+///
+/// Joins the words of a C name in camel case: `SDL_GetError` is `SDLGetError`, `tv_sec` is `tvSec`
+///
+fn ident(name: &str) -> String {
+    let mut ident = String::new();
+
+    for (idx, word) in name.split('_').filter(|word| !word.is_empty()).enumerate() {
+        let mut chars = word.chars();
+
+        if idx > 0 {
+            ident.extend(chars.next().map(|char| char.to_ascii_uppercase()))
+        }
+
+        ident.extend(chars)
+    }
+
+    if !ident.starts_with(|char: char| char.is_ascii_alphabetic()) {
+        ident.insert(0, 'C')
+    }
+
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('0')
+    }
+
+    ident
+}
+
+///
+/// The name of a type, which starts with an uppercase letter: `struct timeval` is `Timeval`
+///
+fn type_name(name: &str) -> String {
+    let mut name = ident(name);
+    name[..1].make_ascii_uppercase();
+    name
+}
+
+fn skipped(span: Span, message: String) -> CheckError {
+    CheckError {
+        span,
+        message,
+        clarifying: String::from("skipped"),
+        help: vec![]
+    }
+}
+
+struct Generator <'a> {
+    typedefs: HashMap <&'a str, &'a CType>,
+
+    ///
+    /// The oko names of the structs and the unions by their tags, those which cannot be expressed are missing
+    ///
+    records: HashMap <&'a str, String>,
+
+    ///
+    /// The tags of the structs and the unions which are defined with fields oko cannot express
+    ///
+    failed: HashSet <&'a str>,
+
+    warnings: Vec <CheckError>
+}
+
+impl <'a> Generator <'a> {
+    ///
+    /// Looks through the typedefs
+    ///
+    fn resolve(&self, mut ty: &'a CType) -> &'a CType {
+        let mut depth = 0;
+
+        while let CType::Named(name) = ty {
+            match self.typedefs.get(name.as_str()) {
+                Some(named) if depth < self.typedefs.len() => ty = named,
+                _ => break
+            }
+
+            depth += 1
+        }
+
+        ty
+    }
+
+    ///
+    /// Whether only pointers to the type can be expressed
+    ///
+    fn is_opaque(&self, ty: &'a CType) -> bool {
+        match self.resolve(ty) {
+            CType::Void | CType::Named(_) => true,
+            CType::Record(tag) => !self.records.contains_key(tag.as_str()),
+            _ => false
+        }
+    }
+
+    ///
+    /// Spells a type in oko, or tells why it cannot be
+    ///
+    fn ty(&self, ty: &'a CType) -> Result <String, String> {
+        match self.resolve(ty) {
+            CType::Void => Err(String::from("`void` is not a type of values")),
+            CType::Builtin(name) => Ok(name.to_string()),
+            CType::Named(name) => Err(format!("unknown type `{name}`, which is defined in another header")),
+            CType::Record(tag) => match self.records.get(tag.as_str()) {
+                Some(name) => Ok(name.clone()),
+                None if self.failed.contains(tag.as_str()) => Err(format!("`{tag}` cannot be expressed")),
+                None => Err(format!("`{tag}` is only declared, so it can only be pointed to"))
+            },
+            CType::Enum => Ok(String::from("int")),
+            CType::Ptr(pointee, is_const) => {
+                let pointee = match self.resolve(pointee) {
+                    CType::Fn(fun) => return self.fn_type(fun),
+                    CType::Void => String::from("void"),
+                    pointee if self.is_opaque(pointee) => String::from("u8"),
+                    pointee => self.ty(pointee)?
+                };
+
+                Ok(if *is_const { format!("*{pointee}") } else { format!("*mut {pointee}") })
+            },
+            CType::Array(elem, Some(len @ 1..), _) => Ok(format!("[{}; {len}]", self.ty(elem)?)),
+            CType::Array(..) => Err(String::from("an array without a length")),
+            CType::Fn(_) => Err(String::from("a function is not a type of values, a pointer to it is")),
+            CType::Unsupported(what) => Err(format!("{what} is not supported")),
+            CType::Hole => unreachable!("holes are filled while parsing")
+        }
+    }
+
+    fn return_type(&self, ret: &'a CType) -> Result <String, String> {
+        match self.resolve(ret) {
+            CType::Void => Ok(String::new()),
+            CType::Array(..) => Err(String::from("a function cannot return an array")),
+            ret => self.ty(ret).map(|ret| format!(" -> {ret}"))
+        }
+    }
+
+    fn fn_type(&self, fun: &'a FnType) -> Result <String, String> {
+        if fun.is_variadic {
+            return Err(String::from("pointers to variadic functions are not supported"))
+        }
+
+        let params = fun.params.iter().map(|param| self.ty(&param.ty)).collect::<Result <Vec <_>, _>>()?;
+
+        Ok(format!("fn({}){}", params.join(", "), self.return_type(&fun.ret)?))
+    }
+
+    fn fields(&self, fields: &'a [Field]) -> Result <String, String> {
+        let mut body = String::new();
+
+        for field in fields {
+            let ty = self.ty(&field.ty).map_err(|reason| format!("field `{}`: {reason}", field.name))?;
+            body += &format!("{INDENT}{}: {ty}\n", ident(&field.name))
+        }
+
+        Ok(body)
+    }
+
+    fn extern_fn(&self, name: &str, fun: &'a FnType) -> Result <String, String> {
+        if fun.is_variadic && fun.params.is_empty() {
+            return Err(String::from("a variadic function needs a parameter before `...`"))
+        }
+
+        let mut params = vec![];
+
+        for (idx, param) in fun.params.iter().enumerate() {
+            let ty = self.ty(&param.ty).map_err(|reason| format!("parameter {}: {reason}", idx + 1))?;
+
+            params.push(match &param.name {
+                Some(name) => format!("{}: {ty}", ident(name)),
+                None => ty
+            })
+        }
+
+        if fun.is_variadic {
+            params.push(String::from("..."))
+        }
+
+        let ident = ident(name);
+        let link_name = if ident == name { String::new() } else { format!("#[link_name = \"{name}\"]\n") };
+
+        Ok(format!("{link_name}extern clang fn {ident}({}){}\n", params.join(", "), self.return_type(&fun.ret)?))
+    }
+}
+
+///
+/// Generates the declarations of a C header.
+///   A declaration which cannot be read or expressed is skipped with a warning, only an unterminated comment,
+///   string or character is an error
+///
+pub fn generate(header: &str) -> Result <Bindings, CheckError> {
+    let tokens = lexer::tokenize(header)?;
+    let decls = Parser::new(&tokens).parse();
+
+    let mut generator = Generator {
+        typedefs: HashMap::new(),
+        records: HashMap::new(),
+        failed: HashSet::new(),
+        warnings: vec![]
+    };
+
+    // The first typedef of a struct names it: `typedef struct _Point Point` makes `Point` of `_Point`
+    let mut names = HashMap::new();
+
+    for decl in &decls {
+        if let Decl::Typedef { name, ty } = decl {
+            generator.typedefs.insert(name, ty);
+
+            if let CType::Record(tag) = ty {
+                names.entry(tag.as_str()).or_insert_with(|| type_name(name));
+            }
+        }
+    }
+
+    let mut items = vec![];
+
+    // The C names of the functions by their oko names
+    let mut fns = HashMap::new();
+
+    for decl in &decls {
+        match decl {
+            Decl::Record { tag, is_union, fields, span } => {
+                if generator.records.contains_key(tag.as_str()) {
+                    continue
+                }
+
+                let Some(name) = names.get(tag.as_str()).cloned().or_else(|| (!tag.starts_with('@')).then(|| type_name(tag))) else {
+                    generator.warnings.push(skipped(*span, String::from("an anonymous struct or union without a typedef is not supported")));
+                    continue
+                };
+
+                if fields.is_empty() {
+                    continue
+                }
+
+                // The fields may point to the type itself
+                generator.records.insert(tag, name.clone());
+
+                match generator.fields(fields) {
+                    Ok(body) => items.push(format!("#[repr(C)]\nty {name} = {}{{\n{body}}}\n", if *is_union { "union " } else { "" })),
+                    Err(reason) => {
+                        generator.records.remove(tag.as_str());
+                        generator.failed.insert(tag);
+                        generator.warnings.push(skipped(*span, format!("`{name}` is skipped, {reason}")))
+                    }
+                }
+            },
+            Decl::Constant { name, value, span } => match value {
+                Some(value @ 0..) => items.push(format!("const {} = {value}\n", ident(name))),
+                Some(value) => generator.warnings.push(skipped(*span, format!("`{name}` is negative, {value}, but constants are unsigned"))),
+                None => generator.warnings.push(skipped(*span, format!("the value of `{name}` is not a known integer")))
+            },
+            Decl::Fn { name, ty, span } => match generator.extern_fn(name, ty) {
+                // A function may be declared several times
+                Ok(_) if fns.get(ident(name).as_str()) == Some(&name.as_str()) => (),
+                Ok(_) if fns.contains_key(ident(name).as_str()) => generator.warnings.push(skipped(*span, format!("`{name}` is skipped, its name `{}` is taken", ident(name)))),
+                Ok(item) => {
+                    fns.insert(ident(name), name);
+                    items.push(item)
+                },
+                Err(reason) => generator.warnings.push(skipped(*span, format!("`{name}` is skipped, {reason}")))
+            },
+            Decl::Typedef { .. } => (),
+            Decl::Skipped { reason, span } => generator.warnings.push(skipped(*span, reason.clone()))
+        }
+    }
+
+    Ok(Bindings {
+        code: items.join("\n"),
+        warnings: generator.warnings
+    })
+}
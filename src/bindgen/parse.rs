@@ -0,0 +1,916 @@
+use std::collections::HashMap;
+use crate::span::Span;
+use super::lexer::{Token, TokenKind};
+
+///
+/// A C type as it is written in a header
+///
+#[derive(Debug, Clone)]
+pub enum CType {
+    Void,
+
+    ///
+    /// A type with an oko equivalent, by its oko name: `unsigned char` is `u8`
+    ///
+    Builtin(&'static str),
+
+    ///
+    /// A name given by `typedef`, in this header or another one
+    ///
+    Named(String),
+
+    ///
+    /// `struct` and `union` by their tags
+    ///
+    Record(String),
+
+    ///
+    /// The values of an enumeration are `int`s
+    ///
+    Enum,
+
+    ///
+    /// A pointer and whether the pointee is `const`
+    ///
+    Ptr(Box <CType>, bool),
+
+    Array(Box <CType>, Option <u64>, bool),
+    Fn(FnType),
+
+    ///
+    /// A type oko has nothing for, with the reason
+    ///
+    Unsupported(String),
+
+    ///
+    /// The hole a nested declarator is filled into: `(*f)` in `int (*f)(int)`
+    ///
+    Hole
+}
+
+impl CType {
+    ///
+    /// Puts `ty` in place of the hole
+    ///
+    fn fill(self, ty: &CType) -> Self {
+        match self {
+            Self::Hole => ty.clone(),
+            Self::Ptr(pointee, is_const) => Self::Ptr(Box::new(pointee.fill(ty)), is_const),
+            Self::Array(elem, len, is_const) => Self::Array(Box::new(elem.fill(ty)), len, is_const),
+            Self::Fn(fun) => Self::Fn(FnType {
+                ret: Box::new(fun.ret.fill(ty)),
+                ..fun
+            }),
+            ty => ty
+        }
+    }
+
+    ///
+    /// An array parameter is a pointer to its first element
+    ///
+    fn decay(self) -> Self {
+        match self {
+            Self::Array(elem, _, is_const) => Self::Ptr(elem, is_const),
+            ty => ty
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Option <String>,
+    pub ty: CType
+}
+
+#[derive(Debug, Clone)]
+pub struct FnType {
+    pub ret: Box <CType>,
+    pub params: Vec <Param>,
+    pub is_variadic: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub ty: CType
+}
+
+#[derive(Debug)]
+pub enum Decl {
+    Fn {
+        name: String,
+        ty: FnType,
+        span: Span
+    },
+
+    ///
+    /// The definition of a `struct` or a `union`, the tags of anonymous ones start with `@`
+    ///
+    Record {
+        tag: String,
+        is_union: bool,
+        fields: Vec <Field>,
+        span: Span
+    },
+
+    ///
+    /// A constant of an enumeration, the value is `None` when it cannot be computed
+    ///
+    Constant {
+        name: String,
+        value: Option <i64>,
+        span: Span
+    },
+
+    Typedef {
+        name: String,
+        ty: CType
+    },
+
+    ///
+    /// A declaration oko cannot express, with the reason
+    ///
+    Skipped {
+        reason: String,
+        span: Span
+    }
+}
+
+type Result <T> = core::result::Result <T, String>;
+
+///
+/// What is written before the declarators: `static const unsigned int`
+///
+#[derive(Default)]
+struct Specifiers {
+    ty: Option <CType>,
+    is_const: bool,
+    is_static: bool,
+    is_typedef: bool,
+    signedness: Option <bool>,
+    has_int: bool,
+    shorts: usize,
+    longs: usize,
+    words: Vec <&'static str>
+}
+
+impl Specifiers {
+    fn ty(&self) -> Option <CType> {
+        if let Some(ty) = &self.ty {
+            return Some(ty.clone())
+        }
+
+        if !self.has_int && self.signedness.is_none() && self.shorts == 0 && self.longs == 0 && self.words.is_empty() {
+            return None
+        }
+
+        let is_unsigned = self.signedness == Some(false);
+        let pick = |signed, unsigned| CType::Builtin(if is_unsigned { unsigned } else { signed });
+
+        Some(match (self.words.first().copied(), self.shorts, self.longs) {
+            (Some("void"), ..) => CType::Void,
+            (Some("bool"), ..) => CType::Builtin("bool"),
+            (Some("char"), ..) => match self.signedness {
+                None => CType::Builtin("char"),
+                Some(_) => pick("i8", "u8")
+            },
+            (Some("float"), ..) => CType::Builtin("float"),
+            (Some("double"), _, 0) => CType::Builtin("f64"),
+            (Some("double"), ..) => CType::Unsupported(String::from("`long double`")),
+            (_, 1.., _) => pick("i16", "u16"),
+            (_, _, 2..) => pick("i64", "u64"),
+            (_, _, 1) => pick("isize", "usize"),
+            _ => pick("int", "u32")
+        })
+    }
+}
+
+///
+/// The type names of the standard headers oko has equivalents of
+///
+fn standard(name: &str) -> Option <&'static str> {
+    Some(match name {
+        "size_t" | "uintptr_t" => "usize",
+        "ssize_t" | "ptrdiff_t" | "intptr_t" => "isize",
+        "int8_t" => "i8",
+        "int16_t" => "i16",
+        "int32_t" => "i32",
+        "int64_t" => "i64",
+        "uint8_t" => "u8",
+        "uint16_t" => "u16",
+        "uint32_t" => "u32",
+        "uint64_t" => "u64",
+        _ => return None
+    })
+}
+
+///
+/// Words which are skipped wherever they are, they do not change how oko sees a declaration
+///
+const IGNORED: &[&str] = &[
+    "extern", "inline", "__inline", "__inline__", "register", "auto", "volatile", "__volatile__",
+    "restrict", "__restrict", "__restrict__", "_Noreturn", "__extension__", "_Nullable", "_Nonnull",
+    "_Null_unspecified", "__cdecl", "__stdcall", "__fastcall"
+];
+
+///
+/// Words followed by a parenthesized argument which is skipped: `__attribute__((noreturn))`
+///
+const IGNORED_CALLS: &[&str] = &["__attribute__", "__attribute", "__declspec", "__asm__", "__asm", "asm", "_Alignas", "__THROW"];
+
+///
+/// Whether a name is written as macros usually are: `SDLCALL`
+///
+fn is_macro(name: &str) -> bool {
+    name.chars().all(|char| char.is_ascii_uppercase() || char.is_ascii_digit() || char == '_')
+}
+
+fn is_specifier(name: &str) -> bool {
+    matches!(
+        name,
+        "void" | "char" | "short" | "int" | "long" | "float" | "double" | "signed" | "unsigned" | "_Bool" | "bool"
+            | "const" | "static" | "typedef" | "struct" | "union" | "enum"
+    ) || IGNORED.contains(&name) || IGNORED_CALLS.contains(&name)
+}
+
+pub struct Parser <'a> {
+    tokens: &'a [Token],
+    idx: usize,
+    typedefs: Vec <String>,
+    constants: HashMap <String, i64>,
+    anonymous: usize,
+    decls: Vec <Decl>
+}
+
+impl <'a> Parser <'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        Self {
+            tokens,
+            idx: 0,
+            typedefs: vec![],
+            constants: HashMap::new(),
+            anonymous: 0,
+            decls: vec![]
+        }
+    }
+
+    fn peek(&self) -> Option <&'a Token> {
+        self.tokens.get(self.idx)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option <&'a Token> {
+        self.tokens.get(self.idx + offset)
+    }
+
+    fn is(&self, punct: &str) -> bool {
+        self.peek().is_some_and(|token| token.is(punct))
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let is = self.is(punct);
+        self.idx += is as usize;
+        is
+    }
+
+    fn expect(&mut self, punct: &str) -> Result <()> {
+        if self.eat(punct) {
+            return Ok(())
+        }
+
+        Err(match self.peek() {
+            Some(token) => format!("expected `{punct}`, found {}", describe(token)),
+            None => format!("expected `{punct}`, found the end of the header")
+        })
+    }
+
+    fn span(&self) -> Span {
+        self.peek().or(self.tokens.last()).map_or(Span::EOF, |token| token.span)
+    }
+
+    ///
+    /// Skips a balanced group starting with the current `(`, `[` or `{`
+    ///
+    fn skip_group(&mut self) {
+        let mut depth = 0usize;
+
+        while let Some(token) = self.peek() {
+            self.idx += 1;
+
+            if token.is("(") || token.is("[") || token.is("{") {
+                depth += 1
+            } else if token.is(")") || token.is("]") || token.is("}") {
+                depth = depth.saturating_sub(1);
+
+                if depth == 0 {
+                    return
+                }
+            }
+        }
+    }
+
+    ///
+    /// Skips the rest of a declaration after an error: up to the `;` or the body ending it
+    ///
+    fn recover(&mut self) {
+        while let Some(token) = self.peek() {
+            if token.is(";") {
+                self.idx += 1;
+                return
+            }
+
+            if token.is("{") {
+                self.skip_group();
+
+                if !self.is(";") && self.peek().is_none_or(|token| token.ident().is_none()) {
+                    return
+                }
+            } else if token.is("(") || token.is("[") {
+                self.skip_group()
+            } else {
+                self.idx += 1
+            }
+        }
+    }
+
+    ///
+    /// Skips the words which do not matter to oko, returning whether any was skipped
+    ///
+    fn skip_ignored(&mut self) -> bool {
+        let start = self.idx;
+
+        while let Some(name) = self.peek().and_then(Token::ident) {
+            if IGNORED.contains(&name) {
+                self.idx += 1
+            } else if IGNORED_CALLS.contains(&name) {
+                self.idx += 1;
+
+                if self.is("(") {
+                    self.skip_group()
+                }
+            } else {
+                break
+            }
+        }
+
+        self.idx != start
+    }
+
+    ///
+    /// Whether the identifier at `offset` starts or continues a type
+    ///
+    fn is_type_at(&self, offset: usize) -> bool {
+        self.peek_at(offset).and_then(Token::ident).is_some_and(|name| {
+            is_specifier(name) || standard(name).is_some() || self.typedefs.iter().any(|typedef| typedef == name)
+        })
+    }
+
+    ///
+    /// Whether the name before `offset` is followed by a type rather than the name of a declarator:
+    ///   `DECLSPEC Window *f` and not `FILE f;`
+    ///
+    fn is_macro_at(&self, offset: usize) -> bool {
+        self.peek_at(offset).and_then(Token::ident).is_some()
+            && !self.peek_at(offset + 1).is_some_and(|token| [")", ",", ";", "[", "=", ":"].iter().any(|punct| token.is(punct)))
+    }
+
+    fn specifiers(&mut self) -> Result <Specifiers> {
+        let mut specifiers = Specifiers::default();
+
+        loop {
+            if self.skip_ignored() {
+                continue
+            }
+
+            let Some(token) = self.peek() else {
+                break
+            };
+
+            let Some(name) = token.ident() else {
+                break
+            };
+
+            let has_type = specifiers.ty().is_some();
+
+            match name {
+                "const" => specifiers.is_const = true,
+                "static" => specifiers.is_static = true,
+                "typedef" => specifiers.is_typedef = true,
+                "signed" => specifiers.signedness = Some(true),
+                "unsigned" => specifiers.signedness = Some(false),
+                "short" => specifiers.shorts += 1,
+                "long" => specifiers.longs += 1,
+                "int" => specifiers.has_int = true,
+                "void" => specifiers.words.push("void"),
+                "char" => specifiers.words.push("char"),
+                "float" => specifiers.words.push("float"),
+                "double" => specifiers.words.push("double"),
+                "_Bool" | "bool" => specifiers.words.push("bool"),
+                "struct" | "union" | "enum" => {
+                    self.idx += 1;
+                    specifiers.ty = Some(self.tagged(name)?);
+                    continue
+                },
+                _ if has_type => break,
+                name => {
+                    if let Some(builtin) = standard(name) {
+                        specifiers.ty = Some(CType::Builtin(builtin))
+                    } else if !self.typedefs.iter().any(|typedef| typedef == name) && (self.is_type_at(1) || is_macro(name) && self.is_macro_at(1)) {
+                        // A macro expanding to nothing, like `DECLSPEC` in `DECLSPEC int f(void)`
+                    } else {
+                        specifiers.ty = Some(CType::Named(name.to_string()))
+                    }
+                }
+            }
+
+            self.idx += 1
+        }
+
+        Ok(specifiers)
+    }
+
+    ///
+    /// Parses what follows `struct`, `union` or `enum`, recording the definition if there is one
+    ///
+    fn tagged(&mut self, keyword: &str) -> Result <CType> {
+        let span = self.span();
+
+        self.skip_ignored();
+
+        let tag = match self.peek().and_then(Token::ident) {
+            Some(tag) => {
+                self.idx += 1;
+                tag.to_string()
+            },
+            None => {
+                self.anonymous += 1;
+                format!("@{}", self.anonymous)
+            }
+        };
+
+        if keyword == "enum" {
+            if self.eat("{") {
+                self.enumerators()?
+            }
+
+            return Ok(CType::Enum)
+        }
+
+        if self.eat("{") {
+            let mut fields = vec![];
+
+            while !self.eat("}") {
+                if self.peek().is_none() {
+                    return Err(String::from("unterminated body"))
+                }
+
+                fields.append(&mut self.fields()?)
+            }
+
+            self.decls.push(Decl::Record {
+                tag: tag.clone(),
+                is_union: keyword == "union",
+                fields,
+                span
+            })
+        }
+
+        Ok(CType::Record(tag))
+    }
+
+    fn fields(&mut self) -> Result <Vec <Field>> {
+        let specifiers = self.specifiers()?;
+
+        let Some(ty) = specifiers.ty() else {
+            return Err(format!("expected the type of a field, found {}", self.peek().map_or_else(|| String::from("the end of the header"), describe)))
+        };
+
+        if self.eat(";") {
+            return Err(String::from("anonymous members are not supported"))
+        }
+
+        let mut fields = vec![];
+
+        loop {
+            let (name, ty, _) = self.declarator(ty.clone(), specifiers.is_const)?;
+
+            if self.is(":") {
+                return Err(String::from("bit-fields are not supported"))
+            }
+
+            let Some(name) = name else {
+                return Err(String::from("a field needs a name"))
+            };
+
+            fields.push(Field {
+                name,
+                ty
+            });
+
+            self.skip_ignored();
+
+            if !self.eat(",") {
+                break
+            }
+        }
+
+        self.expect(";")?;
+
+        Ok(fields)
+    }
+
+    fn enumerators(&mut self) -> Result <()> {
+        let mut next = Some(0);
+
+        while !self.eat("}") {
+            let span = self.span();
+
+            let Some(name) = self.peek().and_then(Token::ident).map(String::from) else {
+                return Err(String::from("expected the name of an enumerator"))
+            };
+
+            self.idx += 1;
+
+            let value = if self.eat("=") {
+                let start = self.idx;
+                let value = self.const_expr(0);
+
+                // Skips what could not be computed
+                if value.is_none() {
+                    self.idx = start;
+
+                    while !self.is(",") && !self.is("}") && self.peek().is_some() {
+                        if self.is("(") { self.skip_group() } else { self.idx += 1 }
+                    }
+                }
+
+                value
+            } else {
+                next
+            };
+
+            if let Some(value) = value {
+                self.constants.insert(name.clone(), value);
+            }
+
+            self.decls.push(Decl::Constant {
+                name,
+                value,
+                span
+            });
+
+            next = value.and_then(|value| value.checked_add(1));
+
+            if !self.eat(",") {
+                self.expect("}")?;
+                break
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Computes an integer constant expression by precedence climbing,
+    ///   returning `None` for what is not an integer, a known enumerator or an arithmetic of them
+    ///
+    fn const_expr(&mut self, min_precedence: u8) -> Option <i64> {
+        let mut lhs = self.const_operand()?;
+
+        loop {
+            let Some(TokenKind::Punct(op)) = self.peek().map(|token| &token.kind) else {
+                return Some(lhs)
+            };
+
+            let precedence = match op.as_str() {
+                "|" => 1,
+                "^" => 2,
+                "&" => 3,
+                "<<" | ">>" => 4,
+                "+" | "-" => 5,
+                "*" | "/" | "%" => 6,
+                _ => return Some(lhs)
+            };
+
+            if precedence < min_precedence {
+                return Some(lhs)
+            }
+
+            self.idx += 1;
+            let rhs = self.const_expr(precedence + 1)?;
+
+            lhs = match op.as_str() {
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "<<" => lhs.checked_shl(u32::try_from(rhs).ok()?)?,
+                ">>" => lhs.checked_shr(u32::try_from(rhs).ok()?)?,
+                "+" => lhs.checked_add(rhs)?,
+                "-" => lhs.checked_sub(rhs)?,
+                "*" => lhs.checked_mul(rhs)?,
+                "/" => lhs.checked_div(rhs)?,
+                _ => lhs.checked_rem(rhs)?
+            }
+        }
+    }
+
+    fn const_operand(&mut self) -> Option <i64> {
+        let token = self.peek()?;
+        self.idx += 1;
+
+        match &token.kind {
+            TokenKind::Int(value) => i64::try_from(*value).ok(),
+            TokenKind::Ident(name) => self.constants.get(name).copied(),
+            TokenKind::Punct(op) if op == "-" => self.const_operand()?.checked_neg(),
+            TokenKind::Punct(op) if op == "~" => Some(!self.const_operand()?),
+            TokenKind::Punct(op) if op == "+" => self.const_operand(),
+            TokenKind::Punct(op) if op == "(" => {
+                let value = self.const_expr(0)?;
+                self.eat(")").then_some(value)
+            },
+            _ => None
+        }
+    }
+
+    ///
+    /// Parses a declarator around the type `ty`, possibly without a name,
+    ///   returning whether it declares a function rather than a pointer to one
+    ///
+    fn declarator(&mut self, mut ty: CType, mut is_const: bool) -> Result <(Option <String>, CType, bool)> {
+        loop {
+            self.skip_ignored();
+
+            // A macro before a pointer, like `FAR` in `z_stream FAR *strm`
+            if self.peek().and_then(Token::ident).is_some() && self.peek_at(1).is_some_and(|token| token.is("*")) {
+                self.idx += 1
+            }
+
+            if !self.eat("*") {
+                break
+            }
+
+            ty = CType::Ptr(Box::new(ty), is_const);
+            is_const = false;
+
+            loop {
+                if self.peek().and_then(Token::ident) == Some("const") {
+                    self.idx += 1;
+                    is_const = true
+                } else if !self.skip_ignored() {
+                    break
+                }
+            }
+        }
+
+        let mut nested = None;
+        let mut name = None;
+
+        // A macro like `SDLCALL` in `int (SDLCALL *f)(void)` and `int SDLCALL f(void)` is skipped
+        let is_grouped = self.is("(") && (self.peek_at(1).is_some_and(|token| token.is("*"))
+            || self.peek_at(1).and_then(Token::ident).is_some() && self.peek_at(2).is_some_and(|token| token.is("*")));
+
+        if is_grouped {
+            self.idx += 1;
+
+            if self.peek().and_then(Token::ident).is_some() {
+                self.idx += 1
+            }
+
+            nested = Some(self.declarator(CType::Hole, false)?);
+            self.expect(")")?
+        } else if let Some(ident) = self.peek().and_then(Token::ident) {
+            self.idx += 1;
+
+            if let Some(next) = self.peek().and_then(Token::ident) {
+                if !IGNORED.contains(&next) && !IGNORED_CALLS.contains(&next) {
+                    self.idx += 1;
+                    name = Some(next.to_string())
+                } else {
+                    name = Some(ident.to_string())
+                }
+            } else {
+                name = Some(ident.to_string())
+            }
+        }
+
+        self.skip_ignored();
+
+        let mut suffixes = vec![];
+
+        loop {
+            if self.eat("[") {
+                let len = if self.is("]") {
+                    None
+                } else {
+                    let len = self.const_expr(0).and_then(|len| u64::try_from(len).ok());
+
+                    if len.is_none() {
+                        return Err(String::from("the length of an array is not a known integer"))
+                    }
+
+                    len
+                };
+
+                self.expect("]")?;
+                suffixes.push(Err(len))
+            } else if self.is("(") {
+                suffixes.push(Ok(self.params()?))
+            } else {
+                break
+            }
+
+            self.skip_ignored();
+        }
+
+        let is_fn = nested.is_none() && matches!(suffixes.first(), Some(Ok(_)));
+
+        for suffix in suffixes.into_iter().rev() {
+            ty = match suffix {
+                Ok((params, is_variadic)) => CType::Fn(FnType {
+                    ret: Box::new(ty),
+                    params,
+                    is_variadic
+                }),
+                Err(len) => CType::Array(Box::new(ty), len, is_const)
+            };
+            is_const = false
+        }
+
+        Ok(match nested {
+            Some((name, inner, _)) => (name, inner.fill(&ty), false),
+            None => (name, ty, is_fn)
+        })
+    }
+
+    ///
+    /// Parses a parenthesized list of parameters, returning them and whether the function is variadic
+    ///
+    fn params(&mut self) -> Result <(Vec <Param>, bool)> {
+        self.expect("(")?;
+
+        let mut params = vec![];
+
+        if self.peek().and_then(Token::ident) == Some("void") && self.peek_at(1).is_some_and(|token| token.is(")")) {
+            self.idx += 2;
+            return Ok((params, false))
+        }
+
+        if self.eat(")") {
+            return Ok((params, false))
+        }
+
+        loop {
+            if self.eat("...") {
+                self.expect(")")?;
+                return Ok((params, true))
+            }
+
+            let specifiers = self.specifiers()?;
+
+            let Some(ty) = specifiers.ty() else {
+                return Err(format!("expected the type of a parameter, found {}", self.peek().map_or_else(|| String::from("the end of the header"), describe)))
+            };
+
+            let (name, ty, is_fn) = self.declarator(ty, specifiers.is_const)?;
+
+            params.push(Param {
+                name,
+                // A function parameter is a pointer to a function
+                ty: if is_fn { CType::Ptr(Box::new(ty), false) } else { ty.decay() }
+            });
+
+            if !self.eat(",") {
+                self.expect(")")?;
+                return Ok((params, false))
+            }
+        }
+    }
+
+    ///
+    /// Parses one top level declaration
+    ///
+    fn declaration(&mut self) -> Result <()> {
+        let specifiers = self.specifiers()?;
+
+        if self.eat(";") {
+            return Ok(())
+        }
+
+        let Some(ty) = specifiers.ty() else {
+            return Err(format!("expected a declaration, found {}", self.peek().map_or_else(|| String::from("the end of the header"), describe)))
+        };
+
+        loop {
+            let span = self.span();
+            let (name, ty, is_fn) = self.declarator(ty.clone(), specifiers.is_const)?;
+
+            let Some(name) = name else {
+                return Err(String::from("a declaration needs a name"))
+            };
+
+            if specifiers.is_typedef {
+                self.typedefs.push(name.clone());
+                self.decls.push(Decl::Typedef {
+                    name,
+                    ty
+                })
+            } else if is_fn {
+                let CType::Fn(ty) = ty else {
+                    unreachable!("a function declarator makes a function type")
+                };
+
+                if self.is("{") {
+                    self.skip_group();
+                    self.decls.push(Decl::Skipped {
+                        reason: format!("`{name}` is defined in the header, so it may be not exported"),
+                        span
+                    });
+                    return Ok(())
+                }
+
+                if specifiers.is_static {
+                    self.decls.push(Decl::Skipped {
+                        reason: format!("`{name}` is `static`"),
+                        span
+                    })
+                } else {
+                    self.decls.push(Decl::Fn {
+                        name,
+                        ty,
+                        span
+                    })
+                }
+            } else {
+                self.decls.push(Decl::Skipped {
+                    reason: format!("`{name}` is a variable, only functions and types are imported"),
+                    span
+                })
+            }
+
+            // Macros after a declarator, like `__THROW` and `__nonnull ((1))`
+            while self.peek().and_then(Token::ident).is_some() {
+                self.idx += 1;
+
+                if self.is("(") {
+                    self.skip_group()
+                }
+            }
+
+            if self.eat("=") {
+                while !self.is(",") && !self.is(";") && self.peek().is_some() {
+                    if self.is("(") || self.is("{") { self.skip_group() } else { self.idx += 1 }
+                }
+            }
+
+            if !self.eat(",") {
+                break
+            }
+        }
+
+        self.expect(";")
+    }
+
+    ///
+    /// Parses the whole header, skipping the declarations which fail to parse
+    ///
+    pub fn parse(mut self) -> Vec <Decl> {
+        while let Some(token) = self.peek() {
+            // `extern "C" {` of C++ and its `}`
+            if token.ident() == Some("extern") && self.peek_at(1).is_some_and(|token| token.kind == TokenKind::Str) {
+                self.idx += 2;
+                self.eat("{");
+                continue
+            }
+
+            if token.is("}") || token.is(";") {
+                self.idx += 1;
+                continue
+            }
+
+            let start = self.idx;
+            let decls = self.decls.len();
+
+            if let Err(reason) = self.declaration() {
+                let span = self.tokens.get(start).map_or(Span::EOF, |token| token.span);
+
+                self.decls.truncate(decls);
+                self.idx = start;
+                self.recover();
+                self.decls.push(Decl::Skipped {
+                    reason,
+                    span
+                })
+            }
+        }
+
+        self.decls
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match &token.kind {
+        TokenKind::Ident(name) => format!("`{name}`"),
+        TokenKind::Int(value) => format!("`{value}`"),
+        TokenKind::Str => String::from("a string"),
+        TokenKind::Punct(punct) => format!("`{punct}`")
+    }
+}
@@ -0,0 +1,316 @@
+use crate::parse::attribute::{Attribute, Lit, Meta};
+use crate::parse::signature::Arg;
+use crate::parse::stmt::{Stmt, TyStmtBody};
+use super::visit::signatures_in_stmt;
+use super::{CheckError, Diagnostics};
+
+///
+/// What an attribute is written before
+///
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Target {
+    Fn,
+    ExternFn,
+
+    ///
+    /// A function with a body exported by `extern clang fn` or `extern rust fn`
+    ///
+    ExportedFn,
+
+    Ty,
+    Const,
+    Mod,
+    Use,
+    World,
+    Channel,
+    Macro,
+    Trait,
+    Impl,
+
+    ///
+    /// A method declared in a trait
+    ///
+    Method,
+
+    ///
+    /// A field of a struct or a union
+    ///
+    Field,
+
+    ///
+    /// A variant of an enum
+    ///
+    Variant,
+
+    ///
+    /// A named parameter of a function, a lambda or a function type
+    ///
+    Param
+}
+
+impl Target {
+    pub fn of(stmt: &Stmt) -> Self {
+        match stmt {
+            Stmt::Fn(fun) if fun.export.is_some() => Self::ExportedFn,
+            Stmt::Fn(_) => Self::Fn,
+            Stmt::Ty(_) => Self::Ty,
+            Stmt::ExternFn(_) => Self::ExternFn,
+            Stmt::Macro(_) => Self::Macro,
+            Stmt::World(_) => Self::World,
+            Stmt::Channel(_) => Self::Channel,
+            Stmt::Const(_) => Self::Const,
+            Stmt::Mod(_) => Self::Mod,
+            Stmt::Use(_) => Self::Use,
+            Stmt::Trait(_) => Self::Trait,
+            Stmt::Impl(_) => Self::Impl
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Fn => "function",
+            Self::ExternFn => "foreign function",
+            Self::ExportedFn => "exported function",
+            Self::Ty => "type",
+            Self::Const => "constant",
+            Self::Mod => "module",
+            Self::Use => "`use` statement",
+            Self::World => "world",
+            Self::Channel => "channel",
+            Self::Macro => "macro",
+            Self::Trait => "trait",
+            Self::Impl => "`impl` block",
+            Self::Method => "method of a trait",
+            Self::Field => "field",
+            Self::Variant => "variant",
+            Self::Param => "parameter"
+        }
+    }
+}
+
+///
+/// A way to write an attribute
+///
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Form {
+    ///
+    /// `#[inline]`
+    ///
+    Word,
+
+    ///
+    /// `#[derive(Debug)]`
+    ///
+    List,
+
+    ///
+    /// `#[link_name = "puts"]`
+    ///
+    Str
+}
+
+impl Form {
+    fn of(meta: &Meta) -> Option <Self> {
+        match meta {
+            Meta::Word(_) => Some(Self::Word),
+            Meta::List(..) => Some(Self::List),
+            Meta::NameValue(_, Lit::Str(_)) => Some(Self::Str),
+            Meta::NameValue(_, Lit::Int(..)) | Meta::Lit(_) => None
+        }
+    }
+}
+
+///
+/// An attribute known to the compiler. The passes it affects read it from the AST
+///
+#[derive(Debug)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub forms: &'static [Form],
+    pub targets: &'static [Target],
+
+    ///
+    /// How the attribute is written, shown when it is malformed
+    ///
+    pub template: &'static str
+}
+
+///
+/// The items the levels of the lints can be set on
+///
+const LINTED: &[Target] = &[
+    Target::Fn,
+    Target::ExportedFn,
+    Target::Ty,
+    Target::Macro,
+    Target::Impl,
+    Target::World
+];
+
+const DEPRECATABLE: &[Target] = &[
+    Target::Fn,
+    Target::ExternFn,
+    Target::ExportedFn,
+    Target::Ty,
+    Target::Const,
+    Target::Trait,
+    Target::Method,
+    Target::Field,
+    Target::Variant
+];
+
+pub const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "repr",
+        forms: &[Form::List],
+        targets: &[Target::Ty],
+        template: "#[repr(C, packed, align(N))]"
+    },
+    Builtin {
+        name: "derive",
+        forms: &[Form::List],
+        targets: &[Target::Ty],
+        template: "#[derive(Debug)]"
+    },
+    Builtin {
+        name: "inline",
+        forms: &[Form::Word],
+        targets: &[Target::Fn, Target::ExportedFn],
+        template: "#[inline]"
+    },
+    Builtin {
+        name: "link_name",
+        forms: &[Form::Str],
+        targets: &[Target::ExternFn, Target::ExportedFn],
+        template: "#[link_name = \"name\"]"
+    },
+    Builtin {
+        name: "callconv",
+        forms: &[Form::List],
+        targets: &[Target::ExternFn, Target::ExportedFn],
+        template: "#[callconv(stdcall)]"
+    },
+    Builtin {
+        name: "deprecated",
+        forms: &[Form::Word, Form::Str],
+        targets: DEPRECATABLE,
+        template: "#[deprecated = \"reason\"]"
+    },
+    Builtin {
+        name: "allow",
+        forms: &[Form::List],
+        targets: LINTED,
+        template: "#[allow(lint, ...)]"
+    },
+    Builtin {
+        name: "warn",
+        forms: &[Form::List],
+        targets: LINTED,
+        template: "#[warn(lint, ...)]"
+    },
+    Builtin {
+        name: "deny",
+        forms: &[Form::List],
+        targets: LINTED,
+        template: "#[deny(lint, ...)]"
+    }
+];
+
+pub fn builtin(name: &str) -> Option <&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
+
+fn check_attr(attr: &Attribute, target: Target, diagnostics: &mut Diagnostics) -> Result <(), CheckError> {
+    let Some(name) = attr.meta.name() else {
+        return Err(CheckError {
+            span: attr.span,
+            message: String::from("an attribute must start with its name"),
+            clarifying: String::from("no name"),
+            help: vec![String::from("write it as `#[name]`, `#[name(...)]` or `#[name = \"...\"]`")]
+        })
+    };
+
+    let Some(builtin) = builtin(&name.name) else {
+        diagnostics.warn(CheckError {
+            span: name.span(),
+            message: format!("unknown attribute `{}`", name.name),
+            clarifying: String::from("ignored"),
+            help: vec![format!("the known attributes are {}", BUILTINS
+                .iter()
+                .map(|builtin| format!("`{}`", builtin.name))
+                .collect::<Vec <_>>()
+                .join(", "))]
+        });
+
+        return Ok(())
+    };
+
+    let is_malformed = match (&attr.meta, Form::of(&attr.meta)) {
+        // `#[derive(Debug)]` and `#[allow(unused_fns)]` list names only
+        (Meta::List(_, list), Some(Form::List)) if builtin.forms.contains(&Form::List) && builtin.name != "repr" => {
+            !list.0.iter().all(|meta| matches!(meta, Meta::Word(_)))
+        },
+        (_, Some(form)) => !builtin.forms.contains(&form),
+        (_, None) => true
+    };
+
+    if is_malformed {
+        return Err(CheckError {
+            span: attr.span,
+            message: format!("malformed attribute `{}`", name.name),
+            clarifying: String::from("not written as expected"),
+            help: vec![format!("write it as `{}`", builtin.template)]
+        })
+    }
+
+    if !builtin.targets.contains(&target) {
+        return Err(CheckError {
+            span: name.span(),
+            message: format!("attribute `{}` cannot be used on a {}", name.name, target.describe()),
+            clarifying: String::from("not allowed here"),
+            help: vec![format!("it can be used on: {}", builtin.targets
+                .iter()
+                .map(|target| target.describe())
+                .collect::<Vec <_>>()
+                .join(", "))]
+        })
+    }
+
+    Ok(())
+}
+
+fn check_attrs(attrs: &[Attribute], target: Target, diagnostics: &mut Diagnostics) -> Result <(), CheckError> {
+    attrs.iter().try_for_each(|attr| check_attr(attr, target, diagnostics))
+}
+
+///
+/// Checks the attributes of the statements, their fields and their parameters against [`BUILTINS`].
+///   Unknown attributes are ignored with a warning, the known ones must be written in their places and forms
+///
+pub fn check(stmts: &[Stmt], diagnostics: &mut Diagnostics) -> Result <(), CheckError> {
+    for stmt in stmts {
+        check_attrs(stmt.attrs(), Target::of(stmt), diagnostics)?;
+
+        match stmt {
+            Stmt::Ty(ty) => match &ty.body {
+                TyStmtBody::Enum(body) => body.fields.0.iter().try_for_each(|variant| check_attrs(&variant.attrs, Target::Variant, diagnostics))?,
+                body => body.fields().unwrap_or_default().iter().try_for_each(|field| check_attrs(&field.attrs, Target::Field, diagnostics))?
+            },
+            Stmt::Trait(tr) => tr.methods.iter().try_for_each(|method| check_attrs(&method.attrs, Target::Method, diagnostics))?,
+            Stmt::Impl(imp) => imp.fns.iter().try_for_each(|fun| check_attrs(&fun.attrs, Target::Fn, diagnostics))?,
+            Stmt::World(world) => check(&world.body, diagnostics)?,
+            _ => ()
+        }
+
+        let mut sigs = vec![];
+        signatures_in_stmt(stmt, &mut sigs);
+
+        for arg in sigs.into_iter().flat_map(|sig| &sig.args.0) {
+            if let Arg::Named(named) = arg {
+                check_attrs(&named.attrs, Target::Param, diagnostics)?
+            }
+        }
+    }
+
+    Ok(())
+}
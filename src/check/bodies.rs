@@ -0,0 +1,376 @@
+use std::collections::{HashMap, HashSet};
+use crate::parse::expr::{Expr, FieldExpr, IndexExpr, LambdaExpr, MethodCallExpr, StructExpr};
+use crate::parse::signature::Signature;
+use crate::parse::stmt::{FnStmt, Stmt, TyStmt, TyStmtBody};
+use crate::parse::ty::{IndirectionKind, Type, TypeKind};
+use crate::span::Span;
+use super::types::{coerces, fn_type, is_numeric, is_unit, named, return_type, unit};
+use super::visit::{free_vars, params};
+use super::CheckError;
+
+///
+/// The types of the parameters of the checked function, and of its lambdas inside them
+///
+type Scope = HashMap <String, Type>;
+
+fn mismatch(span: Span, expected: &Type, found: &Type) -> CheckError {
+    CheckError {
+        span,
+        message: format!("expected `{expected:?}`, found `{found:?}`"),
+        clarifying: String::from("mismatched types"),
+        help: vec![]
+    }
+}
+
+#[derive(Default)]
+struct Checker <'a> {
+    tys: HashMap <&'a str, &'a TyStmt>,
+
+    ///
+    /// The signatures of every named function, the foreign ones too
+    ///
+    fns: HashMap <&'a str, &'a Signature>,
+
+    externs: HashSet <&'a str>
+}
+
+impl <'a> Checker <'a> {
+    fn collect(&mut self, stmts: &'a [Stmt], fns: &mut Vec <&'a FnStmt>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Ty(ty) if ty.generics.is_none() => {
+                    self.tys.insert(&ty.name.name, ty);
+                },
+                Stmt::Fn(fun) if fun.generics.is_none() => {
+                    self.fns.insert(&fun.name.name, &fun.sig);
+                    fns.push(fun)
+                },
+                Stmt::ExternFn(fun) => {
+                    self.fns.insert(&fun.name.name, &fun.sig);
+                    self.externs.insert(&fun.name.name);
+                },
+                Stmt::World(world) => self.collect(&world.body, fns),
+                _ => ()
+            }
+        }
+    }
+
+    ///
+    /// The type of a field of a struct or a union
+    ///
+    fn field_type(&self, ty: &Type, field: &str) -> Option <&'a Type> {
+        let decl = self.tys.get(ty.name()?.name.as_str())?;
+
+        decl.body
+            .fields()?
+            .iter()
+            .find(|fields| fields.names.iter().any(|name| name.name == field))
+            .map(|fields| &fields.ty)
+    }
+
+    ///
+    /// Checks an expression whose value is stored where a value of type `expected` is
+    ///
+    fn expect(&self, expr: &Expr, expected: &Type, scope: &Scope) -> Result <(), CheckError> {
+        let found = self.expr(expr, Some(expected), scope)?;
+
+        if coerces(expected, &found) {
+            Ok(())
+        } else {
+            Err(mismatch(expr.span(), expected, &found))
+        }
+    }
+
+    ///
+    /// Checks a function passed to a foreign one as a plain C function pointer, which cannot carry captured variables
+    ///
+    fn callback(&self, arg: &Expr, scope: &Scope) -> Result <(), CheckError> {
+        match arg {
+            Expr::Lambda(lambda) => {
+                let bound = params(&lambda.sig).into_iter().map(|(name, _)| name).collect();
+                let mut vars = vec![];
+                free_vars(&lambda.body, &bound, &mut vars);
+
+                if vars.iter().any(|var| scope.contains_key(var)) {
+                    return Err(CheckError {
+                        span: lambda.span,
+                        message: String::from("a lambda passed to C cannot capture variables"),
+                        clarifying: String::from("C callbacks are plain function pointers"),
+                        help: vec![String::from("pass the captured values through other parameters of the C function")]
+                    })
+                }
+
+                Ok(())
+            },
+            Expr::Var(var) if !scope.contains_key(&var.name.last().name) => Ok(()),
+            _ => Err(CheckError {
+                span: arg.span(),
+                message: String::from("only a named function or a lambda can be passed to C as a callback"),
+                clarifying: String::from("a C function pointer is expected here"),
+                help: vec![String::from("closures stored in variables carry their captured variables, which C cannot receive")]
+            })
+        }
+    }
+
+    ///
+    /// Checks the arguments of a call against the parameters of `sig`, `span` pointing at the called function.
+    ///   The functions passed to a foreign function are C callbacks unless its parameter is behind a pointer
+    ///
+    fn args(&self, name: &str, sig: &Signature, args: &[Expr], span: Span, scope: &Scope) -> Result <(), CheckError> {
+        let params = sig.param_types();
+
+        if args.len() < params.len() || (args.len() > params.len() && sig.variadic.is_none()) {
+            let at_least = if sig.variadic.is_some() { "at least " } else { "" };
+
+            return Err(CheckError {
+                span,
+                message: format!("function `{name}` takes {at_least}{} argument(s), but {} were given", params.len(), args.len()),
+                clarifying: String::from("wrong number of arguments"),
+                help: vec![format!("`{name}` is declared as `fn {name}{sig:?}`")]
+            })
+        }
+
+        for (idx, arg) in args.iter().enumerate() {
+            match params.get(idx) {
+                Some(param) if self.externs.contains(name) && !scope.contains_key(name) && param.is_pure() && matches!(param.kind, TypeKind::Fn(_)) => {
+                    self.expect(arg, param, scope)?;
+                    self.callback(arg, scope)?
+                },
+                Some(param) => self.expect(arg, param, scope)?,
+                None => {
+                    self.expr(arg, None, scope)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// The type of the place an expression denotes with every reference and pointer it is behind read through
+    ///
+    fn pointee(&self, expr: &Expr, scope: &Scope) -> Result <Type, CheckError> {
+        let mut ty = self.expr(expr, None, scope)?;
+
+        while let TypeKind::Indirect(_, inner) = ty.kind {
+            ty = *inner
+        }
+
+        Ok(ty)
+    }
+
+    fn field(&self, field: &FieldExpr, scope: &Scope) -> Result <Type, CheckError> {
+        let ty = self.pointee(&field.base, scope)?;
+
+        match self.field_type(&ty, &field.field.name) {
+            Some(field) => Ok(field.clone()),
+            None => Err(CheckError {
+                span: field.field.span(),
+                message: format!("no field `{}` in type `{ty:?}`", field.field.name),
+                clarifying: String::from("unknown field"),
+                help: vec![]
+            })
+        }
+    }
+
+    fn index(&self, index: &IndexExpr, scope: &Scope) -> Result <Type, CheckError> {
+        let ty = self.pointee(&index.base, scope)?;
+
+        let error = |message: String, clarifying: String, help: Vec <String>| CheckError {
+            span: index.span,
+            message,
+            clarifying,
+            help
+        };
+
+        match (&index.index, &ty.kind) {
+            (Expr::Int(int), TypeKind::Array(elem, len)) if int.value < *len => Ok((**elem).clone()),
+            (Expr::Int(int), TypeKind::Tuple(elems)) if int.value < elems.0.len() => Ok(elems.0[int.value].clone()),
+            (Expr::Int(int), TypeKind::Array(_, len)) => Err(error(format!("index {} is out of bounds for `{ty:?}`", int.value), format!("the length is {len}"), vec![])),
+            (Expr::Int(int), TypeKind::Tuple(elems)) => Err(error(format!("index {} is out of bounds for `{ty:?}`", int.value), format!("the length is {}", elems.0.len()), vec![])),
+            (_, TypeKind::Tuple(_)) => Err(error(String::from("a tuple can only be indexed by an integer literal"), String::from("indexed here"), vec![])),
+            (value, TypeKind::Array(elem, _) | TypeKind::Slice(elem)) => {
+                let found = self.expr(value, None, scope)?;
+
+                if !is_numeric(&found) {
+                    return Err(CheckError {
+                        span: value.span(),
+                        message: format!("an index must be an integer, found `{found:?}`"),
+                        clarifying: String::from("used as an index"),
+                        help: vec![]
+                    })
+                }
+
+                Ok((**elem).clone())
+            },
+            _ => Err(error(
+                format!("cannot index into a value of type `{ty:?}`"),
+                String::from("indexed here"),
+                vec![String::from("only arrays, slices and tuples can be indexed")]
+            ))
+        }
+    }
+
+    fn method_call(&self, call: &MethodCallExpr, scope: &Scope) -> Result <Type, CheckError> {
+        let dispatch = call.dispatch.as_ref().expect("method calls are dispatched before the check");
+        let sig = self.fns[dispatch.fun.name.as_str()];
+
+        // The receiver is passed as the dispatch adjusts it
+        self.expr(&call.receiver, None, scope)?;
+
+        for (arg, param) in call.args.0.iter().zip(sig.param_types().into_iter().skip(1)) {
+            self.expect(arg, param, scope)?
+        }
+
+        Ok(return_type(sig, call.span))
+    }
+
+    fn literal(&self, lit: &StructExpr, scope: &Scope) -> Result <Type, CheckError> {
+        let name = &lit.name.last().name;
+        let ty = named(name, lit.name.span());
+
+        if let Some(TyStmtBody::Enum(body)) = self.tys.get(name.as_str()).map(|ty| &ty.body) {
+            let value = &lit.fields.0[0];
+            let attached = body.fields.0
+                .iter()
+                .find(|variant| variant.name.name == value.name.name)
+                .and_then(|variant| variant.attached_type.as_ref());
+
+            match (&value.value, attached) {
+                (Expr::Block(block), _) if block.expressions.is_empty() => (),
+                (value, Some(attached)) => self.expect(value, attached, scope)?,
+                (value, None) => {
+                    self.expr(value, None, scope)?;
+                }
+            }
+
+            return Ok(ty)
+        }
+
+        for field in &lit.fields.0 {
+            match self.field_type(&ty, &field.name.name) {
+                Some(expected) => self.expect(&field.value, expected, scope)?,
+                None => {
+                    self.expr(&field.value, None, scope)?;
+                }
+            }
+        }
+
+        Ok(ty)
+    }
+
+    fn lambda(&self, lambda: &LambdaExpr, scope: &Scope) -> Result <Type, CheckError> {
+        let mut scope = scope.clone();
+        scope.extend(params(&lambda.sig).into_iter().map(|(name, ty)| (name, ty.clone())));
+
+        self.body(&lambda.body, &lambda.sig, &scope)?;
+
+        Ok(fn_type(&lambda.sig, lambda.span))
+    }
+
+    ///
+    /// Infers the type of an expression, `expected` giving the type of an integer literal
+    ///
+    fn expr(&self, expr: &Expr, expected: Option <&Type>, scope: &Scope) -> Result <Type, CheckError> {
+        match expr {
+            Expr::Var(var) => {
+                let name = &var.name.last().name;
+
+                if let Some(ty) = scope.get(name) {
+                    return Ok(ty.clone())
+                }
+
+                match self.fns.get(name.as_str()) {
+                    Some(sig) if sig.variadic.is_some() => Err(CheckError {
+                        span: var.name.span(),
+                        message: String::from("using a variadic function as a value is not supported yet"),
+                        clarifying: String::from("used here"),
+                        help: vec![]
+                    }),
+                    Some(sig) => Ok(fn_type(sig, var.name.span())),
+                    None => Err(CheckError {
+                        span: var.name.span(),
+                        message: format!("cannot find value `{name}` in this scope"),
+                        clarifying: String::from("not found in this scope"),
+                        help: vec![]
+                    })
+                }
+            },
+            Expr::Int(int) => Ok(match expected {
+                Some(ty) if is_numeric(ty) => ty.clone(),
+                Some(ty) if int.value == 0 && matches!(&ty.kind, TypeKind::Indirect(indirection, _) if indirection.kind == IndirectionKind::Ptr) => ty.clone(),
+                _ => named("int", int.span)
+            }),
+            Expr::Field(field) => self.field(field, scope),
+            Expr::Index(index) => self.index(index, scope),
+            Expr::Call(call) => {
+                let name = &call.fun.last().name;
+
+                let sig = match scope.get(name) {
+                    Some(Type { kind: TypeKind::Fn(sig), .. }) => &**sig,
+                    Some(ty) => return Err(CheckError {
+                        span: call.fun.span(),
+                        message: format!("`{name}` of type `{ty:?}` is not a function"),
+                        clarifying: String::from("called here"),
+                        help: vec![]
+                    }),
+                    None => match self.fns.get(name.as_str()) {
+                        Some(sig) => *sig,
+                        None => return Err(CheckError {
+                            span: call.fun.span(),
+                            message: format!("cannot find function `{name}` in this scope"),
+                            clarifying: String::from("not found in this scope"),
+                            help: vec![]
+                        })
+                    }
+                };
+
+                self.args(name, sig, &call.args.0, call.fun.span(), scope)?;
+
+                Ok(return_type(sig, call.fun.span()))
+            },
+            Expr::MethodCall(call) => self.method_call(call, scope),
+            Expr::Struct(lit) => self.literal(lit, scope),
+            Expr::Lambda(lambda) => self.lambda(lambda, scope),
+            Expr::Block(block) => {
+                let Some((last, exprs)) = block.expressions.split_last() else {
+                    return Ok(unit(Span::EOF))
+                };
+
+                for expr in exprs {
+                    self.expr(expr, None, scope)?;
+                }
+
+                self.expr(last, expected, scope)
+            }
+        }
+    }
+
+    ///
+    /// Checks the body of a function or a lambda, the value of which is discarded if it returns `()`
+    ///
+    fn body(&self, body: &Expr, sig: &Signature, scope: &Scope) -> Result <(), CheckError> {
+        match &sig.return_ty {
+            Some(ty) if !is_unit(ty) => self.expect(body, ty, scope),
+            _ => self.expr(body, None, scope).map(|_| ())
+        }
+    }
+}
+
+///
+/// Checks that every variable and function used in a body exists, that the calls have as many arguments as the functions
+///   have parameters and that every value has the type of the place it is stored in.
+///   The statements must be monomorphized and their traits resolved
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    let mut checker = Checker::default();
+    let mut fns = vec![];
+    checker.collect(stmts, &mut fns);
+
+    for fun in fns {
+        let scope = params(&fun.sig).into_iter().map(|(name, ty)| (name, ty.clone())).collect();
+        checker.body(&fun.body, &fun.sig, &scope)?
+    }
+
+    Ok(())
+}
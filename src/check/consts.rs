@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+use crate::parse::signature::Arg;
+use crate::parse::span::Ident;
+use crate::parse::stmt::{ConstStmt, Stmt};
+use crate::parse::x_times::{Times, MAX_COUNT};
+use super::visit::{for_each_signature_mut, signatures_in_stmt};
+use super::CheckError;
+
+type Scope <'a> = HashMap <&'a str, &'a ConstStmt>;
+
+fn extended <'a> (outer: &Scope <'a>, stmts: &'a [Stmt]) -> Result <Scope <'a>, CheckError> {
+    let mut scope = outer.clone();
+    let mut declared = HashSet::new();
+
+    for stmt in stmts {
+        let Stmt::Const(constant) = stmt else { continue };
+
+        if !declared.insert(constant.name.name.as_str()) {
+            return Err(CheckError {
+                span: constant.name.span(),
+                message: format!("constant `{}` is declared twice", constant.name.name),
+                clarifying: String::from("redeclared here"),
+                help: vec![]
+            })
+        }
+
+        scope.insert(&constant.name.name, constant);
+    }
+
+    Ok(scope)
+}
+
+fn check_count(name: &Ident, scope: &Scope) -> Result <(), CheckError> {
+    match scope.get(name.name.as_str()) {
+        None => Err(CheckError {
+            span: name.span(),
+            message: format!("cannot find constant `{}`", name.name),
+            clarifying: String::from("not found"),
+            help: vec![format!("declare it: `const {} = 2`", name.name)]
+        }),
+        Some(constant) if constant.value == 0 => Err(CheckError {
+            span: name.span(),
+            message: format!("`{}` is zero, but a count must be at least 1", name.name),
+            clarifying: String::from("used as a count here"),
+            help: vec![]
+        }),
+        Some(constant) if constant.value > MAX_COUNT => Err(CheckError {
+            span: name.span(),
+            message: format!("`{}` is {}, but a count must be at most {MAX_COUNT}", name.name, constant.value),
+            clarifying: String::from("used as a count here"),
+            help: vec![String::from("the count is too large, pass an array or a pointer instead")]
+        }),
+        Some(_) => Ok(())
+    }
+}
+
+fn check_stmts(stmts: &[Stmt], outer: &Scope) -> Result <(), CheckError> {
+    let scope = extended(outer, stmts)?;
+
+    for stmt in stmts {
+        if let Stmt::World(world) = stmt {
+            check_stmts(&world.body, &scope)?;
+            continue
+        }
+
+        let mut sigs = vec![];
+        signatures_in_stmt(stmt, &mut sigs);
+
+        for arg in sigs.into_iter().flat_map(|sig| sig.args.0.iter()) {
+            if let Arg::Unnamed(unnamed) = arg {
+                if let Times::Const(name) = &unnamed.times {
+                    check_count(name, &scope)?
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Checks that every named count (`int x N`) refers to a `const` visible from its place and that the constant is a valid count
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    check_stmts(stmts, &Scope::default())
+}
+
+fn resolve_stmts(stmts: &mut [Stmt], outer: &HashMap <String, usize>) {
+    let mut scope = outer.clone();
+
+    for stmt in stmts.iter() {
+        if let Stmt::Const(constant) = stmt {
+            scope.insert(constant.name.name.clone(), constant.value);
+        }
+    }
+
+    for stmt in stmts {
+        if let Stmt::World(world) = stmt {
+            resolve_stmts(&mut world.body, &scope);
+            continue
+        }
+
+        for_each_signature_mut(stmt, &mut |sig| for arg in &mut sig.args.0 {
+            if let Arg::Unnamed(unnamed) = arg {
+                if let Times::Const(name) = &unnamed.times {
+                    unnamed.times = Times::Count(scope[&name.name])
+                }
+            }
+        })
+    }
+}
+
+///
+/// Replaces every named count with the value of its constant. The statements must have passed [`check`]
+///
+pub fn resolve(stmts: &mut [Stmt]) {
+    resolve_stmts(stmts, &HashMap::new())
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use crate::driver::written;
+use crate::parse::attribute::{Attribute, Lit, Meta};
+use crate::parse::span::Ident;
+use crate::parse::stmt::{ExternFnStmt, FFILanguage, FnStmt, Stmt, TyStmt};
+use crate::parse::ty::{Type, TypeKind};
+use crate::span::Span;
+use super::layout::layout;
+use super::visit::signatures_in_stmt;
+use super::CheckError;
+
+///
+/// The calling conventions `#[callconv(...)]` picks from, named as C compilers name them
+///
+pub const CONVENTIONS: &[&str] = &["cdecl", "stdcall", "fastcall", "thiscall", "vectorcall", "ms_abi", "sysv_abi"];
+
+fn is_c_ident(name: &str) -> bool {
+    name.starts_with(|char: char| char.is_ascii_alphabetic() || char == '_')
+        && name.chars().all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+///
+/// The name of an exported function in C, its `#[link_name]` or its name as written in its module
+///
+pub fn exported_name(fun: &FnStmt) -> &str {
+    fun.link_name().unwrap_or(written(&fun.name.name))
+}
+
+///
+/// Checks `#[link_name]` and `#[callconv]` of a foreign or an exported function, returning the convention
+///
+fn check_attrs <'a> (name: &Ident, attrs: &'a [Attribute]) -> Result <Option <&'a Ident>, CheckError> {
+    let mut link_name = None;
+    let mut convention = None;
+
+    for attr in attrs {
+        match &attr.meta {
+            Meta::NameValue(ident, Lit::Str(string)) if ident.name == "link_name" => {
+                if link_name.replace(string).is_some() {
+                    return Err(CheckError {
+                        span: attr.span,
+                        message: format!("`{}` has two link names", name.name),
+                        clarifying: String::from("given again"),
+                        help: vec![]
+                    })
+                }
+
+                if !is_c_ident(&string.value) {
+                    return Err(CheckError {
+                        span: string.span(),
+                        message: format!("`{}` is not a name of a C function", string.value),
+                        clarifying: String::from("not a C identifier"),
+                        help: vec![String::from("a C name consists of letters, digits and `_`, and does not start with a digit")]
+                    })
+                }
+            },
+            Meta::List(ident, list) if ident.name == "callconv" => {
+                let [Meta::Word(word)] = list.0.as_slice() else {
+                    return Err(CheckError {
+                        span: attr.span,
+                        message: String::from("`callconv` takes one calling convention"),
+                        clarifying: String::from("not one convention"),
+                        help: vec![String::from("write it as `#[callconv(stdcall)]`")]
+                    })
+                };
+
+                if !CONVENTIONS.contains(&word.name.as_str()) {
+                    return Err(CheckError {
+                        span: word.span(),
+                        message: format!("unknown calling convention `{}`", word.name),
+                        clarifying: String::from("not a convention"),
+                        help: vec![format!("the conventions are {}", CONVENTIONS
+                            .iter()
+                            .map(|convention| format!("`{convention}`"))
+                            .collect::<Vec <_>>()
+                            .join(", "))]
+                    })
+                }
+
+                if convention.replace(word).is_some() {
+                    return Err(CheckError {
+                        span: attr.span,
+                        message: format!("`{}` has two calling conventions", name.name),
+                        clarifying: String::from("given again"),
+                        help: vec![]
+                    })
+                }
+            },
+            _ => ()
+        }
+    }
+
+    Ok(convention)
+}
+
+fn check_extern_fn(fun: &ExternFnStmt) -> Result <(), CheckError> {
+    let convention = check_attrs(&fun.name, &fun.attrs)?;
+
+    let Some(variadic) = fun.sig.variadic else {
+        return Ok(())
+    };
+
+    if fun.lang == FFILanguage::Rust {
+        return Err(CheckError {
+            span: variadic,
+            message: format!("Rust function `{}` cannot be variadic", fun.name.name),
+            clarifying: String::from("Rust defines no variadic functions"),
+            help: vec![String::from("take a pointer to the values and their count instead")]
+        })
+    }
+
+    if fun.sig.args.0.is_empty() {
+        return Err(CheckError {
+            span: variadic,
+            message: format!("variadic function `{}` has no parameters before `...`", fun.name.name),
+            clarifying: String::from("C needs one before it"),
+            help: vec![format!("declare it as `extern clang fn {}(first: int, ...)`", fun.name.name)]
+        })
+    }
+
+    // Only the caller knows how many arguments there are, so only it can clean them up
+    match convention {
+        Some(convention) if !matches!(convention.name.as_str(), "cdecl" | "ms_abi" | "sysv_abi") => Err(CheckError {
+            span: convention.span(),
+            message: format!("variadic function `{}` cannot use the `{}` calling convention", fun.name.name, convention.name),
+            clarifying: String::from("the callee cleans up the arguments"),
+            help: vec![String::from("use `cdecl` or remove `#[callconv(...)]`")]
+        }),
+        _ => Ok(())
+    }
+}
+
+///
+/// Checks that a type of an exported signature means the same in the language it is exported to
+///
+fn check_foreign_type(ty: &Type, lang: FFILanguage, tys: &HashMap <&str, &TyStmt>) -> Result <(), CheckError> {
+    let no_equivalent = |help: &str| CheckError {
+        span: ty.span,
+        message: format!("`{ty:?}` has no {} equivalent", lang.name()),
+        clarifying: String::from("in an exported signature"),
+        help: vec![String::from(help)]
+    };
+
+    match &ty.kind {
+        TypeKind::Named(named) if named.generics.is_some() => Err(no_equivalent("use a type without generic arguments")),
+        TypeKind::Named(named) => match tys.get(named.name.last().name.as_str()) {
+            Some(decl) if !layout(decl)?.is_c => Err(CheckError {
+                span: ty.span,
+                message: format!("`{}` is used by {}, but its layout is not fixed", written(&decl.name.name), lang.name()),
+                clarifying: String::from("in an exported signature"),
+                help: vec![format!("add `#[repr(C)]` to `ty {}`", written(&decl.name.name))]
+            }),
+            _ => Ok(())
+        },
+        TypeKind::Indirect(_, inner) if matches!(inner.kind, TypeKind::Slice(_)) => Err(no_equivalent("pass a pointer to the first element and the length")),
+        TypeKind::Indirect(_, inner) => check_foreign_type(inner, lang, tys),
+        TypeKind::Array(..) => Err(no_equivalent("pass a pointer to the first element")),
+        TypeKind::Slice(_) => Err(no_equivalent("pass a pointer to the first element and the length")),
+        TypeKind::Tuple(elems) if elems.0.is_empty() => Ok(()),
+        TypeKind::Tuple(_) => Err(no_equivalent("use a `#[repr(C)]` struct")),
+        TypeKind::Fn(_) => Err(no_equivalent("oko functions carry their captured variables, which C cannot receive"))
+    }
+}
+
+fn check_exported_fn(fun: &FnStmt, tys: &HashMap <&str, &TyStmt>) -> Result <(), CheckError> {
+    let Some(lang) = fun.export else {
+        return Ok(())
+    };
+
+    check_attrs(&fun.name, &fun.attrs)?;
+
+    if fun.generics.is_some() {
+        return Err(CheckError {
+            span: fun.name.span(),
+            message: format!("exported function `{}` is generic", written(&fun.name.name)),
+            clarifying: format!("{} cannot instantiate it", lang.name()),
+            help: vec![String::from("export a function for each type it is used with")]
+        })
+    }
+
+    fun.sig.types().try_for_each(|ty| check_foreign_type(ty, lang, tys))
+}
+
+fn collect <'a> (stmts: &'a [Stmt], tys: &mut HashMap <&'a str, &'a TyStmt>, names: &mut Vec <(&'a str, Option <Span>)>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Ty(ty) => {
+                tys.insert(&ty.name.name, ty);
+            },
+            Stmt::Fn(fun) if fun.export.is_some() => names.push((exported_name(fun), Some(fun.name.span()))),
+            Stmt::Fn(fun) => names.push((&fun.name.name, None)),
+            Stmt::ExternFn(fun) => names.push((fun.link_name(), None)),
+            Stmt::World(world) => collect(&world.body, tys, names),
+            _ => ()
+        }
+    }
+}
+
+fn check_stmts(stmts: &[Stmt], tys: &HashMap <&str, &TyStmt>) -> Result <(), CheckError> {
+    for stmt in stmts {
+        match stmt {
+            Stmt::ExternFn(fun) => check_extern_fn(fun)?,
+            Stmt::Fn(fun) if fun.export.is_some() => check_exported_fn(fun, tys)?,
+            Stmt::Impl(imp) => if let Some(fun) = imp.fns.iter().find(|fun| fun.export.is_some()) {
+                return Err(CheckError {
+                    span: fun.name.span(),
+                    message: format!("function `{}` of an `impl` block is exported", fun.name.name),
+                    clarifying: String::from("only free functions can be exported"),
+                    help: vec![String::from("export a free function which calls it")]
+                })
+            },
+            Stmt::World(world) => check_stmts(&world.body, tys)?,
+            _ => ()
+        }
+
+        let mut sigs = vec![];
+        signatures_in_stmt(stmt, &mut sigs);
+
+        let is_extern = |sig| matches!(stmt, Stmt::ExternFn(fun) if core::ptr::eq(&fun.sig, sig));
+
+        if let Some(variadic) = sigs.into_iter().filter(|sig| !is_extern(*sig)).find_map(|sig| sig.variadic) {
+            return Err(CheckError {
+                span: variadic,
+                message: String::from("only foreign functions can be variadic"),
+                clarifying: String::from("not an `extern` function without a body"),
+                help: vec![String::from("take a slice instead: `values: &[int]`")]
+            })
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Checks the foreign and the exported functions: their link names, calling conventions, variadic parameters,
+///   which only foreign functions can have, and the types of the exported ones, which must mean the same in C
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    let mut tys = HashMap::new();
+    let mut names = vec![];
+
+    collect(stmts, &mut tys, &mut names);
+    check_stmts(stmts, &tys)?;
+
+    for (idx, (name, span)) in names.iter().enumerate() {
+        let Some(span) = span else { continue };
+
+        if names.iter().enumerate().any(|(other, (other_name, _))| other != idx && other_name == name) {
+            return Err(CheckError {
+                span: *span,
+                message: format!("the C function `{name}` is defined twice"),
+                clarifying: String::from("exported under a taken name"),
+                help: vec![String::from("give it another C name with `#[link_name = \"...\"]`")]
+            })
+        }
+    }
+
+    Ok(())
+}
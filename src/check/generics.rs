@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet};
+use crate::parse::expr::Expr;
+use crate::parse::generics::GenericParams;
+use crate::parse::span::Ident;
+use crate::parse::stmt::{MacroStmtBody, Stmt};
+use crate::parse::ty::{Type, TypeKind};
+use super::visit::{calls_in_expr, types_in_stmt};
+use super::CheckError;
+
+///
+/// A generic `ty` or `fn` declaration visible from some scope
+///
+#[derive(Clone, Copy)]
+struct Decl <'a> {
+    name: &'a Ident,
+    generics: Option <&'a GenericParams>
+}
+
+impl Decl <'_> {
+    fn arity(&self) -> usize {
+        self.generics.map_or(0, GenericParams::len)
+    }
+
+    fn signature(&self, keyword: &str) -> String {
+        match self.generics {
+            Some(generics) => format!("{keyword} {} {generics:?}", self.name.name),
+            None => format!("{keyword} {}", self.name.name)
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+struct Scope <'a> {
+    tys: HashMap <&'a str, Decl <'a>>,
+    fns: HashMap <&'a str, Decl <'a>>
+}
+
+impl <'a> Scope <'a> {
+    fn extended(&self, stmts: &'a [Stmt]) -> Self {
+        let mut scope = self.clone();
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::Ty(ty) => {
+                    scope.tys.insert(&ty.name.name, Decl {
+                        name: &ty.name,
+                        generics: ty.generics.as_ref()
+                    });
+                },
+                Stmt::Fn(fun) => {
+                    scope.fns.insert(&fun.name.name, Decl {
+                        name: &fun.name,
+                        generics: fun.generics.as_ref()
+                    });
+                },
+                _ => ()
+            }
+        }
+
+        scope
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+fn check_params(generics: Option <&GenericParams>) -> Result <HashSet <&str>, CheckError> {
+    let mut names = HashSet::new();
+
+    for param in generics.iter().flat_map(|generics| generics.params.0.iter()) {
+        if !names.insert(param.name.as_str()) {
+            return Err(CheckError {
+                span: param.span(),
+                message: format!("generic parameter `{}` is declared twice", param.name),
+                clarifying: String::from("redeclared here"),
+                help: vec![]
+            })
+        }
+    }
+
+    Ok(names)
+}
+
+fn check_type(ty: &Type, params: &HashSet <&str>, scope: &Scope) -> Result <(), CheckError> {
+    if let TypeKind::Named(named) = &ty.kind {
+        let given = named.generics.as_ref().map_or(0, |generics| generics.0.len());
+
+        if params.contains(named.name.last().name.as_str()) {
+            if given != 0 {
+                return Err(CheckError {
+                    span: ty.span,
+                    message: format!("generic parameter `{}` cannot take generic arguments", named.name.last().name),
+                    clarifying: String::from("generic arguments given here"),
+                    help: vec![]
+                })
+            }
+        } else if let Some(decl) = scope.tys.get(named.name.last().name.as_str()) {
+            let expected = decl.arity();
+
+            if given != expected {
+                return Err(CheckError {
+                    span: ty.span,
+                    message: format!("wrong number of generic arguments for `{}`", named.name.last().name),
+                    clarifying: format!("expected {expected} generic argument{}, found {given}", plural(expected)),
+                    help: vec![format!("`{}` is declared as `{}`", named.name.last().name, decl.signature("ty"))]
+                })
+            }
+        }
+    }
+
+    ty.inner().into_iter().try_for_each(|ty| check_type(ty, params, scope))
+}
+
+fn check_calls(expr: &Expr, scope: &Scope) -> Result <(), CheckError> {
+    let mut calls = vec![];
+    calls_in_expr(expr, &mut calls);
+
+    for call in calls {
+        let Some(decl) = scope.fns.get(call.fun.last().name.as_str()) else { continue };
+
+        let expected = decl.arity();
+
+        match &call.generics {
+            None if expected != 0 => return Err(CheckError {
+                span: call.fun.span(),
+                message: format!("cannot infer the generic arguments of `{}`", call.fun.last().name),
+                clarifying: format!("{expected} generic argument{} required", plural(expected)),
+                help: vec![format!(
+                    "specify them explicitly: `{}<{}>(...)`",
+                    call.fun.last().name,
+                    decl.generics.into_iter().flat_map(GenericParams::names).collect::<Vec <_>>().join(", ")
+                )]
+            }),
+            Some(generics) if generics.0.len() != expected => return Err(CheckError {
+                span: call.fun.span(),
+                message: format!("wrong number of generic arguments for `{}`", call.fun.last().name),
+                clarifying: format!("expected {expected} generic argument{}, found {}", plural(expected), generics.0.len()),
+                help: vec![format!("`{}` is declared as `{}`", call.fun.last().name, decl.signature("fn"))]
+            }),
+            _ => ()
+        }
+    }
+
+    Ok(())
+}
+
+fn check_stmts(stmts: &[Stmt], outer: &Scope) -> Result <(), CheckError> {
+    let scope = outer.extended(stmts);
+
+    for stmt in stmts {
+        let params = match stmt {
+            Stmt::Ty(ty) => check_params(ty.generics.as_ref())?,
+            Stmt::Fn(fun) => check_params(fun.generics.as_ref())?,
+            Stmt::World(world) => {
+                check_stmts(&world.body, &scope)?;
+                continue
+            },
+            _ => HashSet::new()
+        };
+
+        let mut types = vec![];
+        types_in_stmt(stmt, &mut types);
+        types.into_iter().try_for_each(|ty| check_type(ty, &params, &scope))?;
+
+        match stmt {
+            Stmt::Fn(fun) => check_calls(&fun.body, &scope)?,
+            Stmt::Macro(mac) => match &mac.body {
+                MacroStmtBody::Literal(lit) => check_calls(&lit.body, &scope)?
+            },
+            _ => ()
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Checks that generic parameters are unique and that every use of a generic `ty` or `fn`
+///   provides exactly as many generic arguments as it declares
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    check_stmts(stmts, &Scope::default())
+}
@@ -0,0 +1,183 @@
+use crate::driver::written;
+use crate::parse::attribute::{Lit, Meta};
+use crate::parse::span::Ident;
+use crate::parse::stmt::{EnumTyStmtBody, Stmt, TyStmt, TyStmtBody};
+use super::CheckError;
+
+///
+/// The largest discriminant, since the tags of enums are C enums, which are `int`s
+///
+pub const MAX_DISCRIMINANT: usize = i32::MAX as usize;
+
+///
+/// How a type is laid out in memory, described by `#[repr(...)]`
+///
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Layout {
+    ///
+    /// `#[repr(C)]`: the fields are laid out in the order they are written, as C does.
+    ///   The C backend lays out every type so, this promises it to other backends and to foreign code
+    ///
+    pub is_c: bool,
+
+    ///
+    /// `#[repr(packed)]`: no padding between the fields, the alignment of the type is 1
+    ///
+    pub is_packed: bool,
+
+    ///
+    /// `#[repr(align(N))]`: the alignment of the type is at least `N` bytes
+    ///
+    pub align: Option <usize>
+}
+
+fn no_alignment(align: &Ident) -> CheckError {
+    CheckError {
+        span: align.span(),
+        message: String::from("`align` expects the alignment in bytes"),
+        clarifying: String::from("no alignment"),
+        help: vec![String::from("write it as `align(8)`")]
+    }
+}
+
+fn unknown_repr(meta: &Meta) -> CheckError {
+    CheckError {
+        span: meta.span(),
+        message: match meta.name() {
+            Some(name) => format!("unknown representation `{}`", name.name),
+            None => String::from("unknown representation")
+        },
+        clarifying: String::from("not a representation"),
+        help: vec![String::from("use `C`, `packed` or `align(N)`")]
+    }
+}
+
+///
+/// Reads the layout of a type from its attributes
+///
+pub fn layout(ty: &TyStmt) -> Result <Layout, CheckError> {
+    let mut layout = Layout::default();
+
+    // The other attributes and the form of `repr` are checked by `attributes`
+    for attr in &ty.attrs {
+        let Meta::List(name, reprs) = &attr.meta else { continue };
+
+        if name.name != "repr" {
+            continue
+        }
+
+        for repr in &reprs.0 {
+            match repr {
+                Meta::Word(word) if word.name == "C" => layout.is_c = true,
+                Meta::Word(word) if word.name == "packed" => layout.is_packed = true,
+                Meta::List(word, args) if word.name == "align" => match args.0.as_slice() {
+                    [Meta::Lit(Lit::Int(align, span))] if !align.is_power_of_two() => return Err(CheckError {
+                        span: *span,
+                        message: format!("alignment {align} is not a power of two"),
+                        clarifying: String::from("invalid alignment"),
+                        help: vec![]
+                    }),
+                    [Meta::Lit(Lit::Int(align, _))] => layout.align = Some(*align),
+                    _ => return Err(no_alignment(word))
+                },
+                Meta::Word(word) | Meta::NameValue(word, _) if word.name == "align" => return Err(no_alignment(word)),
+                _ => return Err(unknown_repr(repr))
+            }
+        }
+    }
+
+    if layout.is_packed && layout.align.is_some() {
+        return Err(CheckError {
+            span: ty.name.span(),
+            message: format!("type `{}` cannot be both packed and aligned", ty.name.name),
+            clarifying: String::from("conflicting representations"),
+            help: vec![String::from("remove either `packed` or `align`")]
+        })
+    }
+
+    Ok(layout)
+}
+
+///
+/// The values of the tags of the variants of an enum.
+///   A variant without an explicit discriminant follows the previous one, the first one is 0
+///
+pub fn discriminants(body: &EnumTyStmtBody) -> Result <Vec <usize>, CheckError> {
+    let mut values: Vec <usize> = vec![];
+
+    for variant in &body.fields.0 {
+        let value = match (&variant.discriminant, values.last()) {
+            (Some(discriminant), _) => discriminant.value,
+            (None, Some(previous)) => previous + 1,
+            (None, None) => 0
+        };
+
+        if value > MAX_DISCRIMINANT {
+            return Err(CheckError {
+                span: variant.discriminant.as_ref().map_or_else(|| variant.name.span(), |discriminant| discriminant.span),
+                message: format!("discriminant of `{}` is too large", variant.name.name),
+                clarifying: format!("at most {MAX_DISCRIMINANT}"),
+                help: vec![]
+            })
+        }
+
+        if let Some(other) = values.iter().position(|&other| other == value) {
+            return Err(CheckError {
+                span: variant.name.span(),
+                message: format!("discriminant {value} of `{}` is already used by `{}`", variant.name.name, body.fields.0[other].name.name),
+                clarifying: String::from("duplicate discriminant"),
+                help: vec![String::from("give the variants distinct discriminants")]
+            })
+        }
+
+        values.push(value)
+    }
+
+    Ok(values)
+}
+
+///
+/// Checks that every field of a struct or a union and every variant of an enum has its own name
+///
+fn check_names(ty: &TyStmt) -> Result <(), CheckError> {
+    let (names, what): (Vec <&Ident>, _) = match &ty.body {
+        TyStmtBody::Struct(body) => (body.fields.0.iter().flat_map(|field| &field.names).collect(), "field"),
+        TyStmtBody::Union(body) => (body.fields.0.iter().flat_map(|field| &field.names).collect(), "field"),
+        TyStmtBody::Enum(body) => (body.fields.0.iter().map(|field| &field.name).collect(), "variant")
+    };
+
+    for (idx, name) in names.iter().enumerate() {
+        if names[..idx].iter().any(|previous| previous.name == name.name) {
+            return Err(CheckError {
+                span: name.span(),
+                message: format!("{what} `{}` is declared twice in `{}`", name.name, written(&ty.name.name)),
+                clarifying: String::from("redeclared here"),
+                help: vec![]
+            })
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Checks the layout attributes and the field names of the types and the discriminants of the enums
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Ty(ty) => {
+                check_names(ty)?;
+                layout(ty)?;
+
+                if let TyStmtBody::Enum(body) = &ty.body {
+                    discriminants(body)?;
+                }
+            },
+            Stmt::World(world) => check(&world.body)?,
+            _ => ()
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,589 @@
+use std::collections::{HashMap, HashSet};
+use crate::driver::written;
+use crate::error::Severity;
+use crate::parse::attribute::{Attribute, Lit, Meta};
+use crate::parse::expr::Expr;
+use crate::parse::signature::{Arg, Signature};
+use crate::parse::span::Ident;
+use crate::parse::stmt::{Affix, FnStmt, MacroStmtBody, Stmt, TyStmtBody};
+use super::visit::{bodies_in_stmt, paths_in_expr, struct_lits_in_expr, type_refs_in_stmt};
+use super::{CheckError, Diagnostics};
+
+///
+/// What happens when a lint finds something
+///
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Level {
+    ///
+    /// Nothing
+    ///
+    Allow,
+
+    ///
+    /// A warning is reported
+    ///
+    Warn,
+
+    ///
+    /// An error is reported, which stops the compilation
+    ///
+    Deny
+}
+
+impl Level {
+    ///
+    /// The level set by `#[allow(...)]`, `#[warn(...)]` or `#[deny(...)]`
+    ///
+    pub fn from_attribute(name: &str) -> Option <Self> {
+        match name {
+            "allow" => Some(Self::Allow),
+            "warn" => Some(Self::Warn),
+            "deny" => Some(Self::Deny),
+            _ => None
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Lint {
+    ///
+    /// A private function that no code reachable from `main`, public functions, methods or worlds calls
+    ///
+    UnusedFns,
+
+    ///
+    /// A private type no other item refers to
+    ///
+    UnusedTys,
+
+    ///
+    /// An affix macro no literal is written with
+    ///
+    UnusedMacros,
+
+    ///
+    /// A parameter named as a parameter of an enclosing function or as a function of the same module
+    ///
+    Shadowing,
+
+    ///
+    /// A type whose name does not start with an uppercase letter
+    ///
+    NonCamelCaseTys,
+
+    ///
+    /// A use of an item, a field or a variant marked with `#[deprecated]`
+    ///
+    Deprecated
+}
+
+impl Lint {
+    pub const ALL: [Self; 6] = [
+        Self::UnusedFns,
+        Self::UnusedTys,
+        Self::UnusedMacros,
+        Self::Shadowing,
+        Self::NonCamelCaseTys,
+        Self::Deprecated
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UnusedFns => "unused_fns",
+            Self::UnusedTys => "unused_tys",
+            Self::UnusedMacros => "unused_macros",
+            Self::Shadowing => "shadowing",
+            Self::NonCamelCaseTys => "non_camel_case_tys",
+            Self::Deprecated => "deprecated"
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option <Self> {
+        Self::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+fn lint_names() -> String {
+    Lint::ALL.iter().map(|lint| format!("`{}`", lint.name())).collect::<Vec <_>>().join(", ")
+}
+
+///
+/// The levels of the lints. Every lint warns unless told otherwise by the `-A`, `-W` and `-D` flags,
+///   which are overridden by the attributes of a world and then by the attributes of an item
+///
+#[derive(Clone, Debug, Default)]
+pub struct Levels(HashMap <Lint, Level>);
+
+impl Levels {
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.0.insert(lint, level);
+    }
+
+    pub fn get(&self, lint: Lint) -> Level {
+        self.0.get(&lint).copied().unwrap_or(Level::Warn)
+    }
+
+    ///
+    /// The levels inside of an item with the attributes
+    ///
+    fn with(&self, attrs: &[Attribute], diagnostics: &mut Diagnostics) -> Self {
+        let mut levels = self.clone();
+
+        for attr in attrs {
+            let Meta::List(name, lints) = &attr.meta else { continue };
+            let Some(level) = Level::from_attribute(&name.name) else { continue };
+
+            // The form is checked by `attributes`
+            for lint in &lints.0 {
+                let Meta::Word(lint) = lint else { continue };
+
+                match Lint::from_name(&lint.name) {
+                    Some(known) => levels.set(known, level),
+                    None => diagnostics.warn(CheckError {
+                        span: lint.span(),
+                        message: format!("unknown lint `{}`", lint.name),
+                        clarifying: String::from("ignored"),
+                        help: vec![format!("the lints are {}", lint_names())]
+                    })
+                }
+            }
+        }
+
+        levels
+    }
+}
+
+///
+/// A statement with the levels of the lints inside of it
+///
+struct Item <'a> {
+    stmt: &'a Stmt,
+    levels: Levels,
+    in_world: bool
+}
+
+///
+/// Reports what a lint found according to its level, followed by the notes explaining it
+///
+fn report(levels: &Levels, lint: Lint, mut err: CheckError, notes: Vec <CheckError>, diagnostics: &mut Diagnostics) {
+    let severity = match levels.get(lint) {
+        Level::Allow => return,
+        Level::Warn => {
+            err.help.push(format!("allow it with `#[allow({})]`", lint.name()));
+            Severity::Warning
+        },
+        Level::Deny => {
+            err.help.push(format!("denied by `#[deny({0})]` or `-D {0}`", lint.name()));
+            Severity::Error
+        }
+    };
+
+    diagnostics.push(severity, Some(lint.name()), err);
+    notes.into_iter().for_each(|note| diagnostics.note(note))
+}
+
+fn unused_fns(items: &[Item], diagnostics: &mut Diagnostics) {
+    // Worlds call the functions declared in them themselves
+    let is_linted = |item: &Item, fun: &FnStmt| !fun.is_pub && fun.export.is_none() && !item.in_world && fun.name.name != "main";
+
+    let mut fns: HashMap <&str, &FnStmt> = HashMap::new();
+    let mut stack = vec![];
+
+    for item in items {
+        match item.stmt {
+            Stmt::Fn(fun) if is_linted(item, fun) => {
+                fns.insert(&fun.name.name, fun);
+            },
+            stmt => bodies_in_stmt(stmt, &mut stack)
+        }
+    }
+
+    let mut used = HashSet::new();
+
+    while let Some(body) = stack.pop() {
+        let mut paths = vec![];
+        paths_in_expr(body, &mut paths);
+
+        for path in paths {
+            let name = path.last().name.as_str();
+
+            if let Some(fun) = fns.get(name) {
+                if used.insert(name) {
+                    stack.push(&fun.body)
+                }
+            }
+        }
+    }
+
+    for item in items {
+        let Stmt::Fn(fun) = item.stmt else { continue };
+
+        if !fns.contains_key(fun.name.name.as_str()) || used.contains(fun.name.name.as_str()) {
+            continue
+        }
+
+        report(&item.levels, Lint::UnusedFns, CheckError {
+            span: fun.name.span(),
+            message: format!("function `{}` is never used", written(&fun.name.name)),
+            clarifying: String::from("never called"),
+            help: vec![]
+        }, vec![], diagnostics)
+    }
+}
+
+fn unused_tys(items: &[Item], diagnostics: &mut Diagnostics) {
+    let mut used = HashSet::new();
+
+    for item in items {
+        let mut refs = vec![];
+        type_refs_in_stmt(item.stmt, &mut refs);
+
+        let own = match item.stmt {
+            Stmt::Ty(ty) => Some(ty.name.name.as_str()),
+            _ => None
+        };
+
+        used.extend(refs.into_iter().map(|ident| ident.name.as_str()).filter(|&name| Some(name) != own))
+    }
+
+    for item in items {
+        let Stmt::Ty(ty) = item.stmt else { continue };
+
+        if ty.is_pub || used.contains(ty.name.name.as_str()) {
+            continue
+        }
+
+        report(&item.levels, Lint::UnusedTys, CheckError {
+            span: ty.name.span(),
+            message: format!("type `{}` is never used", written(&ty.name.name)),
+            clarifying: String::from("never referred to"),
+            help: vec![]
+        }, vec![], diagnostics)
+    }
+}
+
+fn unused_macros(items: &[Item], diagnostics: &mut Diagnostics) {
+    for item in items {
+        let Stmt::Macro(mac) = item.stmt else { continue };
+
+        // Literals are not written with affixes yet, so no macro is used
+        let MacroStmtBody::Literal(lit) = &mac.body;
+
+        let affix = match lit.affix {
+            Affix::Prefix => "prefix",
+            Affix::Suffix => "suffix"
+        };
+
+        report(&item.levels, Lint::UnusedMacros, CheckError {
+            span: lit.lit.span(),
+            message: format!("{affix} macro `{}` is never used", lit.lit.value),
+            clarifying: format!("no literal is written with this {affix}"),
+            help: vec![]
+        }, vec![], diagnostics)
+    }
+}
+
+fn non_camel_case_tys(items: &[Item], diagnostics: &mut Diagnostics) {
+    for item in items {
+        let Stmt::Ty(ty) = item.stmt else { continue };
+        let name = written(&ty.name.name);
+
+        if name.starts_with(|first: char| first.is_ascii_uppercase()) {
+            continue
+        }
+
+        let mut chars = name.chars();
+        let renamed = chars.next().map(|first| first.to_ascii_uppercase()).into_iter().chain(chars).collect::<String>();
+
+        report(&item.levels, Lint::NonCamelCaseTys, CheckError {
+            span: ty.name.span(),
+            message: format!("type `{name}` should have an upper camel case name"),
+            clarifying: String::from("starts with a lowercase letter"),
+            help: vec![format!("rename it to `{renamed}`")]
+        }, vec![], diagnostics)
+    }
+}
+
+///
+/// What a use of a deprecated thing reports after its name: the reason of `#[deprecated = "reason"]`, if any.
+///   `None` if the thing is not deprecated
+///
+fn deprecation(attrs: &[Attribute]) -> Option <String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::Word(name) if name.name == "deprecated" => Some(String::new()),
+        Meta::NameValue(name, Lit::Str(reason)) if name.name == "deprecated" => Some(format!(": {}", reason.value)),
+        _ => None
+    })
+}
+
+///
+/// A deprecated thing: what it is and what its uses report after its name
+///
+struct Deprecation {
+    kind: &'static str,
+    note: String
+}
+
+fn report_deprecated(levels: &Levels, name: &Ident, written_name: &str, deprecation: &Deprecation, diagnostics: &mut Diagnostics) {
+    report(levels, Lint::Deprecated, CheckError {
+        span: name.span(),
+        message: format!("use of deprecated {} `{written_name}`{}", deprecation.kind, deprecation.note),
+        clarifying: String::from("deprecated"),
+        help: vec![]
+    }, vec![], diagnostics)
+}
+
+///
+/// Reports the uses of deprecated functions, constants, types, traits, fields and variants by their names.
+///   Methods and accessed fields are not reported, as the types of the receivers are not known here.
+///   The items which are deprecated themselves may use the others freely
+///
+fn deprecated(items: &[Item], diagnostics: &mut Diagnostics) {
+    let mut named = HashMap::new();
+    let mut members = HashMap::new();
+
+    for item in items {
+        let (kind, name) = match item.stmt {
+            Stmt::Fn(fun) => ("function", &fun.name),
+            Stmt::ExternFn(fun) => ("function", &fun.name),
+            Stmt::Const(constant) => ("constant", &constant.name),
+            Stmt::Trait(tr) => ("trait", &tr.name),
+            Stmt::Ty(ty) => {
+                let fields = ty.body.fields().into_iter().flatten().flat_map(|vars| vars.names.iter().map(|name| ("field", name, &vars.attrs)));
+
+                let variants = match &ty.body {
+                    TyStmtBody::Enum(body) => body.fields.0.iter().map(|variant| ("variant", &variant.name, &variant.attrs)).collect(),
+                    _ => vec![]
+                };
+
+                for (kind, member, attrs) in fields.chain(variants) {
+                    if let Some(note) = deprecation(attrs) {
+                        members.insert((ty.name.name.as_str(), member.name.as_str()), Deprecation { kind, note });
+                    }
+                }
+
+                ("type", &ty.name)
+            },
+            _ => continue
+        };
+
+        if let Some(note) = deprecation(item.stmt.attrs()) {
+            named.insert(name.name.as_str(), Deprecation { kind, note });
+        }
+    }
+
+    for item in items {
+        if deprecation(item.stmt.attrs()).is_some() {
+            continue
+        }
+
+        let mut refs = vec![];
+        type_refs_in_stmt(item.stmt, &mut refs);
+
+        let mut bodies = vec![];
+        bodies_in_stmt(item.stmt, &mut bodies);
+
+        let mut paths = vec![];
+        let mut lits = vec![];
+
+        for body in bodies {
+            paths_in_expr(body, &mut paths);
+            struct_lits_in_expr(body, &mut lits)
+        }
+
+        let traits = match item.stmt {
+            Stmt::Impl(imp) => imp.trait_name.iter().map(|path| path.last()).collect(),
+            _ => vec![]
+        };
+
+        let names = refs.into_iter().chain(paths.into_iter().map(|path| path.last())).chain(traits);
+
+        for name in names {
+            if let Some(deprecation) = named.get(name.name.as_str()) {
+                report_deprecated(&item.levels, name, written(&name.name), deprecation, diagnostics)
+            }
+        }
+
+        for lit in lits {
+            let ty = lit.name.last().name.as_str();
+
+            for field in &lit.fields.0 {
+                if let Some(deprecation) = members.get(&(ty, field.name.name.as_str())) {
+                    report_deprecated(&item.levels, &field.name, &format!("{}::{}", written(ty), field.name.name), deprecation, diagnostics)
+                }
+            }
+        }
+    }
+}
+
+///
+/// What the parameters of functions are checked against
+///
+struct Scope <'a> {
+    ///
+    /// The functions by their full names
+    ///
+    fns: &'a HashMap <&'a str, &'a Ident>,
+
+    ///
+    /// The prefix of the module the function is declared in
+    ///
+    prefix: &'a str,
+
+    ///
+    /// The parameters of the enclosing functions
+    ///
+    params: Vec <&'a Ident>
+}
+
+fn params(sig: &Signature) -> impl Iterator <Item = &Ident> {
+    sig.args.0
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Named(named) => Some(&named.names),
+            Arg::Unnamed(_) => None
+        })
+        .flatten()
+        .filter(|name| name.name != "self")
+}
+
+fn shadowing_in_fn <'a> (sig: &'a Signature, body: &'a Expr, scope: &mut Scope <'a>, levels: &Levels, diagnostics: &mut Diagnostics) {
+    let depth = scope.params.len();
+
+    for param in params(sig) {
+        if let Some(outer) = scope.params.iter().find(|outer| outer.name == param.name) {
+            report(levels, Lint::Shadowing, CheckError {
+                span: param.span(),
+                message: format!("parameter `{}` shadows a parameter of an enclosing function", param.name),
+                clarifying: String::from("shadows the outer parameter"),
+                help: vec![String::from("rename one of them")]
+            }, vec![CheckError {
+                span: outer.span(),
+                message: format!("the shadowed `{}` is declared here", outer.name),
+                clarifying: String::from("shadowed parameter"),
+                help: vec![]
+            }], diagnostics)
+        } else if let Some(fun) = scope.fns.get(format!("{}{}", scope.prefix, param.name).as_str()) {
+            report(levels, Lint::Shadowing, CheckError {
+                span: param.span(),
+                message: format!("parameter `{}` shadows the function `{}`", param.name, param.name),
+                clarifying: String::from("the function cannot be called here"),
+                help: vec![String::from("rename the parameter")]
+            }, vec![CheckError {
+                span: fun.span(),
+                message: format!("the shadowed function `{}` is declared here", param.name),
+                clarifying: String::from("shadowed function"),
+                help: vec![]
+            }], diagnostics)
+        }
+    }
+
+    scope.params.extend(params(sig));
+
+    let mut lambdas = vec![];
+    outer_lambdas_in_expr(body, &mut lambdas);
+
+    for (sig, body) in lambdas {
+        shadowing_in_fn(sig, body, scope, levels, diagnostics)
+    }
+
+    scope.params.truncate(depth)
+}
+
+///
+/// Collects the signatures and the bodies of the lambdas which are not inside of other lambdas
+///
+fn outer_lambdas_in_expr <'a> (expr: &'a Expr, lambdas: &mut Vec <(&'a Signature, &'a Expr)>) {
+    match expr {
+        Expr::Call(call) => call.args.0.iter().for_each(|arg| outer_lambdas_in_expr(arg, lambdas)),
+        Expr::Struct(lit) => lit.fields.0.iter().for_each(|field| outer_lambdas_in_expr(&field.value, lambdas)),
+        Expr::Lambda(lambda) => lambdas.push((&lambda.sig, &lambda.body)),
+        Expr::MethodCall(call) => {
+            outer_lambdas_in_expr(&call.receiver, lambdas);
+            call.args.0.iter().for_each(|arg| outer_lambdas_in_expr(arg, lambdas))
+        },
+        Expr::Index(index) => {
+            outer_lambdas_in_expr(&index.base, lambdas);
+            outer_lambdas_in_expr(&index.index, lambdas)
+        },
+        Expr::Field(field) => outer_lambdas_in_expr(&field.base, lambdas),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| outer_lambdas_in_expr(expr, lambdas))
+    }
+}
+
+fn prefix(name: &str) -> &str {
+    &name[..name.len() - written(name).len()]
+}
+
+fn shadowing(items: &[Item], diagnostics: &mut Diagnostics) {
+    let mut fns = HashMap::new();
+
+    for item in items {
+        match item.stmt {
+            Stmt::Fn(fun) => fns.insert(fun.name.name.as_str(), &fun.name),
+            Stmt::ExternFn(fun) => fns.insert(fun.name.name.as_str(), &fun.name),
+            _ => continue
+        };
+    }
+
+    for item in items {
+        let scope = |prefix| Scope {
+            fns: &fns,
+            prefix,
+            params: vec![]
+        };
+
+        match item.stmt {
+            Stmt::Fn(fun) => shadowing_in_fn(&fun.sig, &fun.body, &mut scope(prefix(&fun.name.name)), &item.levels, diagnostics),
+            Stmt::Impl(imp) => for fun in &imp.fns {
+                let levels = item.levels.with(&fun.attrs, diagnostics);
+                let prefix = imp.ty.name().map_or("", |name| prefix(&name.name));
+
+                shadowing_in_fn(&fun.sig, &fun.body, &mut scope(prefix), &levels, diagnostics)
+            },
+            Stmt::Macro(mac) => match &mac.body {
+                MacroStmtBody::Literal(lit) => {
+                    let mut lambdas = vec![];
+                    outer_lambdas_in_expr(&lit.body, &mut lambdas);
+
+                    for (sig, body) in lambdas {
+                        shadowing_in_fn(sig, body, &mut scope(""), &item.levels, diagnostics)
+                    }
+                }
+            },
+            _ => ()
+        }
+    }
+}
+
+fn collect_items <'a> (stmts: &'a [Stmt], levels: &Levels, in_world: bool, items: &mut Vec <Item <'a>>, diagnostics: &mut Diagnostics) {
+    for stmt in stmts {
+        let levels = levels.with(stmt.attrs(), diagnostics);
+
+        match stmt {
+            Stmt::World(world) => collect_items(&world.body, &levels, true, items, diagnostics),
+            stmt => items.push(Item {
+                stmt,
+                levels,
+                in_world
+            })
+        }
+    }
+}
+
+///
+/// Runs the lints over the statements. What they find is a warning, an error if the lint is denied,
+///   or nothing if it is allowed
+///
+pub fn check(stmts: &[Stmt], levels: &Levels, diagnostics: &mut Diagnostics) {
+    let mut items = vec![];
+    collect_items(stmts, levels, false, &mut items, diagnostics);
+
+    unused_fns(&items, diagnostics);
+    unused_tys(&items, diagnostics);
+    unused_macros(&items, diagnostics);
+    shadowing(&items, diagnostics);
+    non_camel_case_tys(&items, diagnostics);
+    deprecated(&items, diagnostics)
+}
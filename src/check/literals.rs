@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use crate::driver::written;
+use crate::parse::expr::{Expr, FieldValue, StructExpr};
+use crate::parse::stmt::{Stmt, TyStmt, TyStmtBody};
+use super::visit::{bodies_in_stmt, struct_lits_in_expr};
+use super::CheckError;
+
+fn collect_tys <'a> (stmts: &'a [Stmt], tys: &mut HashMap <&'a str, &'a TyStmt>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Ty(ty) => {
+                tys.insert(&ty.name.name, ty);
+            },
+            Stmt::World(world) => collect_tys(&world.body, tys),
+            _ => ()
+        }
+    }
+}
+
+fn list(names: &[&str]) -> String {
+    names.iter().map(|name| format!("`{name}`")).collect::<Vec <_>>().join(", ")
+}
+
+fn unknown_field(field: &FieldValue, name: &str, fields: &[&str]) -> CheckError {
+    CheckError {
+        span: field.name.span(),
+        message: format!("no field `{}` in type `{name}`", field.name.name),
+        clarifying: String::from("unknown field"),
+        help: vec![format!("the fields of `{name}` are {}", list(fields))]
+    }
+}
+
+fn check_struct(lit: &StructExpr, ty: &TyStmt, fields: &[&str]) -> Result <(), CheckError> {
+    let name = written(&ty.name.name);
+    let mut initialized = vec![];
+
+    for field in &lit.fields.0 {
+        if !fields.contains(&field.name.name.as_str()) {
+            return Err(unknown_field(field, name, fields))
+        }
+
+        if initialized.contains(&field.name.name.as_str()) {
+            return Err(CheckError {
+                span: field.name.span(),
+                message: format!("field `{}` is initialized twice", field.name.name),
+                clarifying: String::from("initialized again here"),
+                help: vec![]
+            })
+        }
+
+        initialized.push(&field.name.name)
+    }
+
+    let missing = fields.iter().copied().filter(|field| !initialized.contains(field)).collect::<Vec <_>>();
+
+    if !missing.is_empty() {
+        return Err(CheckError {
+            span: lit.name.span(),
+            message: format!("missing {} {} in the literal of `{name}`", if missing.len() == 1 { "field" } else { "fields" }, list(&missing)),
+            clarifying: String::from("not every field is initialized"),
+            help: vec![format!("initialize them: `{name} {{ {} }}`", missing.iter().map(|field| format!("{field}: ...")).collect::<Vec <_>>().join(", "))]
+        })
+    }
+
+    Ok(())
+}
+
+fn check_union(lit: &StructExpr, ty: &TyStmt, fields: &[&str]) -> Result <(), CheckError> {
+    let name = written(&ty.name.name);
+
+    let [field] = lit.fields.0.as_slice() else {
+        return Err(CheckError {
+            span: lit.name.span(),
+            message: format!("a literal of the union `{name}` must initialize exactly one field"),
+            clarifying: format!("{} fields initialized", lit.fields.0.len()),
+            help: vec![format!("the fields of `{name}` are {}", list(fields))]
+        })
+    };
+
+    if !fields.contains(&field.name.name.as_str()) {
+        return Err(unknown_field(field, name, fields))
+    }
+
+    Ok(())
+}
+
+fn check_enum(lit: &StructExpr, ty: &TyStmt, variants: &[(&str, bool)]) -> Result <(), CheckError> {
+    let name = written(&ty.name.name);
+
+    let [field] = lit.fields.0.as_slice() else {
+        return Err(CheckError {
+            span: lit.name.span(),
+            message: format!("a literal of the enum `{name}` must name exactly one variant"),
+            clarifying: format!("{} variants named", lit.fields.0.len()),
+            help: vec![format!("construct a variant as `{name}::{}`", variants[0].0)]
+        })
+    };
+
+    let Some(&(variant, has_value)) = variants.iter().find(|(variant, _)| *variant == field.name.name) else {
+        let names = variants.iter().map(|&(variant, _)| variant).collect::<Vec <_>>();
+
+        return Err(CheckError {
+            span: field.name.span(),
+            message: format!("no variant `{}` in enum `{name}`", field.name.name),
+            clarifying: String::from("unknown variant"),
+            help: vec![format!("the variants of `{name}` are {}", list(&names))]
+        })
+    };
+
+    match &field.value {
+        Expr::Block(block) if !has_value && block.expressions.is_empty() => Ok(()),
+        _ if !has_value => Err(CheckError {
+            span: field.name.span(),
+            message: format!("variant `{variant}` of `{name}` takes no value"),
+            clarifying: String::from("given a value"),
+            help: vec![format!("construct it as `{name}::{variant}`")]
+        }),
+        _ => Ok(())
+    }
+}
+
+fn check_lit(lit: &StructExpr, tys: &HashMap <&str, &TyStmt>) -> Result <(), CheckError> {
+    // Builtin and unknown types are reported elsewhere
+    let Some(ty) = tys.get(lit.name.last().name.as_str()) else {
+        return Ok(())
+    };
+
+    if ty.generics.is_some() {
+        return Err(CheckError {
+            span: lit.name.span(),
+            message: format!("a value of the generic type `{}` cannot be constructed with a literal", written(&ty.name.name)),
+            clarifying: String::from("the generic arguments cannot be inferred"),
+            help: vec![String::from("construct it inside of a generic function returning it")]
+        })
+    }
+
+    let fields = ty.body.fields().unwrap_or_default().iter().flat_map(|field| &field.names).map(|name| name.name.as_str()).collect::<Vec <_>>();
+
+    match &ty.body {
+        TyStmtBody::Struct(_) => check_struct(lit, ty, &fields),
+        TyStmtBody::Union(_) => check_union(lit, ty, &fields),
+        TyStmtBody::Enum(body) => {
+            let variants = body.fields.0.iter().map(|field| (field.name.name.as_str(), field.attached_type.is_some())).collect::<Vec <_>>();
+            check_enum(lit, ty, &variants)
+        }
+    }
+}
+
+fn check_stmts(stmts: &[Stmt], tys: &HashMap <&str, &TyStmt>) -> Result <(), CheckError> {
+    for stmt in stmts {
+        if let Stmt::World(world) = stmt {
+            check_stmts(&world.body, tys)?;
+            continue
+        }
+
+        let mut bodies = vec![];
+        bodies_in_stmt(stmt, &mut bodies);
+
+        let mut lits = vec![];
+        bodies.into_iter().for_each(|body| struct_lits_in_expr(body, &mut lits));
+        lits.into_iter().try_for_each(|lit| check_lit(lit, tys))?
+    }
+
+    Ok(())
+}
+
+///
+/// Checks that struct literals initialize every field of their types exactly once, union literals one of the fields,
+///   and that enum literals, `Option::Some(5)` included, construct one of the variants of their types
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    let mut tys = HashMap::new();
+    collect_tys(stmts, &mut tys);
+
+    check_stmts(stmts, &tys)
+}
@@ -0,0 +1,132 @@
+//!
+//! This module checks the AST for semantic errors
+//!
+
+pub mod visit;
+pub mod attributes;
+pub mod ffi;
+pub mod worlds;
+pub mod generics;
+pub mod types;
+pub mod consts;
+pub mod traits;
+pub mod bodies;
+pub mod literals;
+pub mod layout;
+pub mod lints;
+
+use crate::driver::Sources;
+use crate::error::{Error, Severity};
+use crate::parse::stmt::Stmt;
+use crate::span::Span;
+
+#[derive(Clone, Debug)]
+pub struct CheckError {
+    ///
+    /// The span of the problematic place in the code
+    ///
+    pub span: Span,
+
+    ///
+    /// What is wrong
+    ///
+    pub message: String,
+
+    ///
+    /// Shown under the problematic place
+    ///
+    pub clarifying: String,
+
+    ///
+    /// Optional help(s) that might be useful to user
+    ///
+    pub help: Vec <String>
+}
+
+impl CheckError {
+    pub fn to_error(self, code: &str, filename: String) -> Error {
+        Error {
+            severity: Severity::Error,
+            code: None,
+            span: self.span.eof_or(code),
+            message: self.message,
+            spanned: self.span.apply(code),
+            clarifying: self.clarifying,
+            help: self.help,
+            filename,
+            source: code.to_string()
+        }
+    }
+}
+
+///
+/// Something found by the checks
+///
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    ///
+    /// The name of the lint which found it
+    ///
+    pub lint: Option <&'static str>,
+
+    pub error: CheckError
+}
+
+///
+/// The diagnostics found by the checks. Only the errors stop the compilation
+///
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics(pub Vec <Diagnostic>);
+
+impl Diagnostics {
+    pub fn push(&mut self, severity: Severity, lint: Option <&'static str>, error: CheckError) {
+        self.0.push(Diagnostic {
+            severity,
+            lint,
+            error
+        })
+    }
+
+    pub fn error(&mut self, error: CheckError) {
+        self.push(Severity::Error, None, error)
+    }
+
+    pub fn warn(&mut self, warning: CheckError) {
+        self.push(Severity::Warning, None, warning)
+    }
+
+    pub fn note(&mut self, note: CheckError) {
+        self.push(Severity::Note, None, note)
+    }
+}
+
+///
+/// Runs every check over the parsed statements and then the lints if no check fails, returning everything they found
+///
+pub fn check(stmts: &[Stmt], sources: &Sources, levels: &lints::Levels) -> Vec <Error> {
+    let mut diagnostics = Diagnostics::default();
+
+    let result = attributes::check(stmts, &mut diagnostics)
+        .and_then(|_| ffi::check(stmts))
+        .and_then(|_| worlds::check(stmts))
+        .and_then(|_| consts::check(stmts))
+        .and_then(|_| generics::check(stmts))
+        .and_then(|_| traits::check(stmts))
+        .and_then(|_| literals::check(stmts))
+        .and_then(|_| layout::check(stmts))
+        .and_then(|_| types::check(stmts));
+
+    match result {
+        Ok(()) => lints::check(stmts, levels, &mut diagnostics),
+        Err(err) => diagnostics.error(err)
+    }
+
+    diagnostics.0
+        .into_iter()
+        .map(|diagnostic| sources.error(diagnostic.error)
+            .with_severity(diagnostic.severity)
+            .with_code(diagnostic.lint.map(String::from)))
+        .collect()
+}
@@ -0,0 +1,607 @@
+use std::collections::{HashMap, HashSet};
+use crate::mono::{mangle_type, substitute_params};
+use crate::parse::expr::{Adjustment, Dispatch, Expr, FieldExpr, MethodCallExpr};
+use crate::parse::signature::{Arg, Signature};
+use crate::parse::span::Ident;
+use crate::parse::stmt::{FnStmt, ImplStmt, Stmt, TraitStmt, WorldStmt};
+use crate::parse::ty::{IndirectionKind, NamedType, Type, TypeKind, SELF};
+use crate::parse::x_times::Times;
+use super::types::named;
+use super::visit::for_each_type_in_expr_mut;
+use super::CheckError;
+
+///
+/// The traits and the types visible from some scope
+///
+#[derive(Default, Clone)]
+struct Scope <'a> {
+    traits: HashMap <&'a str, &'a TraitStmt>,
+    tys: HashSet <&'a str>
+}
+
+fn with_self(sig: &Signature, ty: &Type) -> Signature {
+    let params = HashMap::from([(SELF, ty)]);
+    let mut sig = sig.clone();
+
+    sig.types_mut().for_each(|param| substitute_params(param, &params));
+    sig
+}
+
+fn receiver(sig: &Signature) -> Option <&Type> {
+    sig.args.0.first().map(|arg| match arg {
+        Arg::Named(named) => &named.ty,
+        Arg::Unnamed(unnamed) => &unnamed.it
+    })
+}
+
+///
+/// Whether a receiver of type `ty` can be passed as the receiver `param` as is: `&mut T` is also passed as `&T`
+///
+fn passes_as(ty: &Type, param: &Type) -> bool {
+    match (&ty.kind, &param.kind) {
+        (TypeKind::Indirect(outer, inner), TypeKind::Indirect(param_outer, param_inner)) if outer.kind == IndirectionKind::Ref
+            && param_outer.kind == IndirectionKind::Ref
+            && outer.is_mutable && !param_outer.is_mutable => mangle_type(inner) == mangle_type(param_inner),
+        _ => mangle_type(ty) == mangle_type(param)
+    }
+}
+
+///
+/// The parameter and the return types of a signature in a comparable form, whatever the names of the parameters are
+///
+fn shape(sig: &Signature) -> Vec <String> {
+    let mut shape = vec![];
+
+    for arg in &sig.args.0 {
+        match arg {
+            Arg::Named(named) => shape.extend(vec![mangle_type(&named.ty); named.names.len()]),
+            Arg::Unnamed(unnamed) => match &unnamed.times {
+                Times::Count(count) => shape.extend(vec![mangle_type(&unnamed.it); *count]),
+                Times::Const(name) => shape.push(format!("{} x {}", mangle_type(&unnamed.it), name.name))
+            }
+        }
+    }
+
+    shape.push(sig.return_ty.as_ref().map(mangle_type).unwrap_or_default());
+    shape
+}
+
+fn extended <'a> (outer: &Scope <'a>, stmts: &'a [Stmt]) -> Result <Scope <'a>, CheckError> {
+    let mut scope = outer.clone();
+    let mut declared = HashSet::new();
+
+    for stmt in stmts {
+        if let Stmt::Ty(ty) = stmt {
+            scope.tys.insert(&ty.name.name);
+        }
+
+        let Stmt::Trait(tr) = stmt else { continue };
+
+        if !declared.insert(tr.name.name.as_str()) {
+            return Err(CheckError {
+                span: tr.name.span(),
+                message: format!("trait `{}` is declared twice", tr.name.name),
+                clarifying: String::from("redeclared here"),
+                help: vec![]
+            })
+        }
+
+        scope.traits.insert(&tr.name.name, tr);
+    }
+
+    Ok(scope)
+}
+
+fn check_trait(tr: &TraitStmt) -> Result <(), CheckError> {
+    let mut names = HashSet::new();
+
+    for method in &tr.methods {
+        if !names.insert(method.name.name.as_str()) {
+            return Err(CheckError {
+                span: method.name.span(),
+                message: format!("method `{}` is declared twice in trait `{}`", method.name.name, tr.name.name),
+                clarifying: String::from("redeclared here"),
+                help: vec![]
+            })
+        }
+
+        let has_receiver = receiver(&method.sig).is_some_and(|ty| ty.base().name().is_some_and(|name| name.name == SELF));
+
+        if !has_receiver {
+            return Err(CheckError {
+                span: method.name.span(),
+                message: format!("method `{}` has no receiver", method.name.name),
+                clarifying: format!("the first parameter must be `{SELF}` or a reference or a pointer to it"),
+                help: vec![format!("add the receiver: `fn {}(&self, ...)`", method.name.name)]
+            })
+        }
+    }
+
+    Ok(())
+}
+
+fn check_generics(fun: &FnStmt) -> Result <(), CheckError> {
+    match fun.generics {
+        Some(_) => Err(CheckError {
+            span: fun.name.span(),
+            message: format!("function `{}` of an `impl` cannot be generic", fun.name.name),
+            clarifying: String::from("generic parameters declared here"),
+            help: vec![]
+        }),
+        None => Ok(())
+    }
+}
+
+///
+/// Checks an `impl` without a trait: it is for a type declared with `ty`, so the functions are called as `Vec2::new`
+///
+fn check_inherent(imp: &ImplStmt, scope: &Scope) -> Result <(), CheckError> {
+    match &imp.ty.kind {
+        TypeKind::Named(NamedType { name, generics: None }) if scope.tys.contains(name.last().name.as_str()) => (),
+        TypeKind::Named(NamedType { generics: Some(_), .. }) => return Err(CheckError {
+            span: imp.ty.span,
+            message: String::from("functions cannot be added to an instance of a generic type"),
+            clarifying: String::from("has generic arguments"),
+            help: vec![String::from("implement a trait for it instead")]
+        }),
+        _ => return Err(CheckError {
+            span: imp.ty.span,
+            message: format!("functions can only be added to types declared with `ty`, not to `{:?}`", imp.ty),
+            clarifying: String::from("not a `ty` type"),
+            help: vec![String::from("implement a trait for it instead")]
+        })
+    }
+
+    imp.fns.iter().try_for_each(check_generics)
+}
+
+fn check_impl(imp: &ImplStmt, scope: &Scope, implemented: &mut HashSet <(String, String)>) -> Result <(), CheckError> {
+    let Some(trait_path) = &imp.trait_name else {
+        return check_inherent(imp, scope)
+    };
+
+    let trait_name = &trait_path.last().name;
+
+    let Some(tr) = scope.traits.get(trait_name.as_str()) else {
+        return Err(CheckError {
+            span: trait_path.span(),
+            message: format!("cannot find trait `{trait_name}`"),
+            clarifying: String::from("not found"),
+            help: vec![]
+        })
+    };
+
+    if !implemented.insert((trait_name.clone(), mangle_type(&imp.ty))) {
+        return Err(CheckError {
+            span: imp.ty.span,
+            message: format!("trait `{trait_name}` is already implemented for `{:?}`", imp.ty),
+            clarifying: String::from("implemented again here"),
+            help: vec![]
+        })
+    }
+
+    let mut names = HashSet::new();
+
+    for fun in &imp.fns {
+        check_generics(fun)?;
+
+        if !names.insert(fun.name.name.as_str()) {
+            return Err(CheckError {
+                span: fun.name.span(),
+                message: format!("method `{}` is implemented twice", fun.name.name),
+                clarifying: String::from("implemented again here"),
+                help: vec![]
+            })
+        }
+
+        let Some(method) = tr.methods.iter().find(|method| method.name.name == fun.name.name) else {
+            return Err(CheckError {
+                span: fun.name.span(),
+                message: format!("`{}` is not a method of trait `{trait_name}`", fun.name.name),
+                clarifying: String::from("not declared in the trait"),
+                help: vec![format!("the methods are {}", tr.methods.iter().map(|method| format!("`{}`", method.name.name)).collect::<Vec <_>>().join(", "))]
+            })
+        };
+
+        let expected = with_self(&method.sig, &imp.ty);
+
+        if shape(&expected) != shape(&with_self(&fun.sig, &imp.ty)) {
+            return Err(CheckError {
+                span: fun.name.span(),
+                message: format!("method `{}` does not match its declaration in trait `{trait_name}`", fun.name.name),
+                clarifying: format!("expected `fn {}{expected:?}`", fun.name.name),
+                help: vec![]
+            })
+        }
+    }
+
+    if let Some(missing) = tr.methods.iter().find(|method| !names.contains(method.name.name.as_str())) {
+        return Err(CheckError {
+            span: imp.ty.span,
+            message: format!("not all methods of trait `{trait_name}` are implemented for `{:?}`", imp.ty),
+            clarifying: format!("`{}` is missing", missing.name.name),
+            help: vec![format!("implement it: `{missing:?}`")]
+        })
+    }
+
+    Ok(())
+}
+
+fn check_stmts(stmts: &[Stmt], outer: &Scope, implemented: &mut HashSet <(String, String)>) -> Result <(), CheckError> {
+    let scope = extended(outer, stmts)?;
+
+    for stmt in stmts {
+        match stmt {
+            Stmt::Trait(tr) => check_trait(tr)?,
+            Stmt::Impl(imp) => check_impl(imp, &scope, implemented)?,
+            Stmt::World(world) => check_stmts(&world.body, &scope, implemented)?,
+            _ => ()
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Checks that traits are declared once with a receiver in every method,
+///   that every `impl` of a trait implements each method of an existing trait with its declared signature
+///   and that every other `impl` is for a type declared with `ty`
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    check_stmts(stmts, &Scope::default(), &mut HashSet::new())
+}
+
+///
+/// A method of an `impl`, lowered to a function
+///
+struct Method <'a> {
+    ty: &'a Type,
+    name: &'a str,
+    fun: String,
+
+    ///
+    /// Whether the method is of an `impl` without a trait, which is preferred over the methods of traits
+    ///
+    is_inherent: bool
+}
+
+///
+/// The name of the function a function of an `impl` without a trait is lowered to: `Vec2_new`.
+///   Identifiers cannot contain `_`, so it never clashes with user names
+///
+pub fn associated_name(ty: &str, fun: &str) -> String {
+    format!("{ty}_{fun}")
+}
+
+///
+/// The name of the function a function of an `impl` is lowered to: `Display_4Vec2_show` or `Vec2_new`
+///
+fn lowered_name(imp: &ImplStmt, fun: &FnStmt) -> String {
+    match (&imp.trait_name, imp.ty.name()) {
+        (Some(trait_name), _) => format!("{}_{}_{}", trait_name.last().name, mangle_type(&imp.ty), fun.name.name),
+        (None, Some(ty)) => associated_name(&ty.name, &fun.name.name),
+        (None, None) => unreachable!("an `impl` without a trait is for a named type")
+    }
+}
+
+fn locals(sig: &Signature) -> impl Iterator <Item = (String, Type)> + '_ {
+    sig.args.0.iter().flat_map(|arg| match arg {
+        Arg::Named(named) => named.names.iter().map(|name| (name.name.clone(), named.ty.clone())).collect(),
+        Arg::Unnamed(_) => vec![]
+    })
+}
+
+#[derive(Default)]
+struct Resolver <'a> {
+    methods: Vec <Method <'a>>,
+
+    ///
+    /// The fields of every struct and union by its name
+    ///
+    structs: HashMap <&'a str, Vec <(&'a Ident, &'a Type)>>,
+
+    ///
+    /// The signatures of all the functions, the methods included, by their names
+    ///
+    fns: HashMap <String, Signature>
+}
+
+impl <'a> Resolver <'a> {
+    fn collect(&mut self, stmts: &'a [Stmt]) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Fn(fun) => {
+                    self.fns.insert(fun.name.name.clone(), fun.sig.clone());
+                },
+                Stmt::ExternFn(fun) => {
+                    self.fns.insert(fun.name.name.clone(), fun.sig.clone());
+                },
+                Stmt::Ty(ty) => if let Some(fields) = ty.body.fields() {
+                    let fields = fields
+                        .iter()
+                        .flat_map(|fields| fields.names.iter().map(|name| (name, &fields.ty)))
+                        .collect();
+
+                    self.structs.insert(&ty.name.name, fields);
+                },
+                Stmt::Impl(imp) => for fun in &imp.fns {
+                    let name = lowered_name(imp, fun);
+                    let sig = with_self(&fun.sig, &imp.ty);
+
+                    // A function of an `impl` without a trait is a method only if it takes the type as the receiver
+                    let is_method = imp.trait_name.is_some() || receiver(&sig).is_some_and(|ty| mangle_type(ty.base()) == mangle_type(&imp.ty));
+
+                    if is_method {
+                        self.methods.push(Method {
+                            ty: &imp.ty,
+                            name: &fun.name.name,
+                            fun: name.clone(),
+                            is_inherent: imp.trait_name.is_none()
+                        })
+                    }
+
+                    self.fns.insert(name, sig);
+                },
+                Stmt::World(world) => self.collect(&world.body),
+                _ => ()
+            }
+        }
+    }
+
+    ///
+    /// Infers the type of an expression whose method calls are already dispatched
+    ///
+    fn type_of(&self, expr: &Expr, locals: &HashMap <String, Type>) -> Option <Type> {
+        match expr {
+            Expr::Var(var) => match locals.get(&var.name.last().name) {
+                Some(ty) => Some(ty.clone()),
+                None => self.fns.get(&var.name.last().name).map(|sig| Type {
+                    kind: TypeKind::Fn(Box::new(sig.clone())),
+                    span: var.name.span()
+                })
+            },
+            Expr::Call(call) => match locals.get(&call.fun.last().name) {
+                Some(Type { kind: TypeKind::Fn(sig), .. }) => sig.return_ty.clone(),
+                Some(_) => None,
+                None => self.fns.get(&call.fun.last().name)?.return_ty.clone()
+            },
+            Expr::MethodCall(call) => self.fns.get(&call.dispatch.as_ref()?.fun.name)?.return_ty.clone(),
+            Expr::Struct(lit) => Some(named(&lit.name.last().name, lit.name.span())),
+            Expr::Int(int) => Some(named("int", int.span)),
+            Expr::Lambda(lambda) => Some(Type {
+                kind: TypeKind::Fn(Box::new(lambda.sig.clone())),
+                span: lambda.span
+            }),
+            Expr::Index(index) => {
+                let base = self.type_of(&index.base, locals)?;
+
+                match (&base.base().kind, &index.index) {
+                    (TypeKind::Array(elem, _) | TypeKind::Slice(elem), _) => Some((**elem).clone()),
+                    (TypeKind::Tuple(elems), Expr::Int(int)) => elems.0.get(int.value).cloned(),
+                    _ => None
+                }
+            },
+            Expr::Field(field) => self.fields(&self.type_of(&field.base, locals)?)?
+                .iter()
+                .find(|(name, _)| name.name == field.field.name)
+                .map(|(_, ty)| (*ty).clone()),
+            Expr::Block(block) => self.type_of(block.expressions.last()?, locals)
+        }
+    }
+
+    ///
+    /// The fields of a struct, also behind references and pointers to it
+    ///
+    fn fields(&self, ty: &Type) -> Option <&Vec <(&'a Ident, &'a Type)>> {
+        self.structs.get(ty.base().name()?.name.as_str())
+    }
+
+    fn check_field(&self, field: &FieldExpr, locals: &HashMap <String, Type>) -> Result <(), CheckError> {
+        let Some(ty) = self.type_of(&field.base, locals) else {
+            return Err(CheckError {
+                span: field.field.span(),
+                message: format!("cannot infer the type of the value the field `{}` is read from", field.field.name),
+                clarifying: String::from("the field is unknown until the type is"),
+                help: vec![]
+            })
+        };
+
+        let Some(fields) = self.fields(&ty) else {
+            return Err(CheckError {
+                span: field.field.span(),
+                message: format!("type `{ty:?}` has no fields"),
+                clarifying: String::from("not a struct"),
+                help: vec![]
+            })
+        };
+
+        if fields.iter().any(|(name, _)| name.name == field.field.name) {
+            return Ok(())
+        }
+
+        Err(CheckError {
+            span: field.field.span(),
+            message: format!("no field `{}` in type `{ty:?}`", field.field.name),
+            clarifying: String::from("unknown field"),
+            help: vec![format!("the fields are {}", fields.iter().map(|(name, _)| format!("`{}`", name.name)).collect::<Vec <_>>().join(", "))]
+        })
+    }
+
+    fn dispatch(&self, call: &MethodCallExpr, locals: &HashMap <String, Type>) -> Result <Dispatch, CheckError> {
+        let Some(ty) = self.type_of(&call.receiver, locals) else {
+            return Err(CheckError {
+                span: call.method.span(),
+                message: format!("cannot infer the type of the receiver of `{}`", call.method.name),
+                clarifying: String::from("the method is unknown until the type is"),
+                help: vec![]
+            })
+        };
+
+        // The receiver is dereferenced until a type with the method is found
+        let mut derefs = 0;
+        let mut current = &ty;
+
+        let method = loop {
+            let mangled = mangle_type(current);
+            let mut candidates = self.methods
+                .iter()
+                .filter(|method| method.name == call.method.name && mangle_type(method.ty) == mangled)
+                .collect::<Vec <_>>();
+
+            if candidates.iter().any(|method| method.is_inherent) {
+                candidates.retain(|method| method.is_inherent)
+            }
+
+            match (candidates.as_slice(), &current.kind) {
+                ([method], _) => break *method,
+                ([], TypeKind::Indirect(_, inner)) => {
+                    current = inner;
+                    derefs += 1
+                },
+                ([], _) => return Err(CheckError {
+                    span: call.method.span(),
+                    message: format!("no method `{}` for type `{ty:?}`", call.method.name),
+                    clarifying: String::from("not found in any `impl`"),
+                    help: ty.base().name()
+                        .filter(|name| self.fns.contains_key(&associated_name(&name.name, &call.method.name)))
+                        .map(|name| format!("`{}` takes no receiver, call it as `{}::{}(...)`", call.method.name, name.name, call.method.name))
+                        .into_iter()
+                        .collect()
+                }),
+                (_, _) => return Err(CheckError {
+                    span: call.method.span(),
+                    message: format!("several traits implement `{}` for `{current:?}`", call.method.name),
+                    clarifying: String::from("ambiguous method"),
+                    help: vec![String::from("rename the method in one of the traits")]
+                })
+            }
+        };
+
+        let sig = &self.fns[&method.fun];
+        let param = receiver(sig).expect("methods have a receiver");
+        let expected_args = sig.param_types().len() - 1;
+
+        if call.args.0.len() != expected_args {
+            return Err(CheckError {
+                span: call.span,
+                message: format!("method `{}` takes {expected_args} argument(s), but {} were given", call.method.name, call.args.0.len()),
+                clarifying: String::from("wrong number of arguments"),
+                help: vec![]
+            })
+        }
+
+        let adjustment = if passes_as(&ty, param) {
+            Adjustment::None
+        } else if matches!(&param.kind, TypeKind::Indirect(_, inner) if derefs == 0 && mangle_type(inner) == mangle_type(&ty)) {
+            Adjustment::Ref
+        } else {
+            let mut stripped = &ty;
+            let mut times = 0;
+
+            loop {
+                let TypeKind::Indirect(_, inner) = &stripped.kind else {
+                    return Err(CheckError {
+                        span: call.span,
+                        message: format!("the receiver of type `{ty:?}` cannot be passed as `{param:?}`"),
+                        clarifying: format!("`{}` takes `{param:?}`", call.method.name),
+                        help: vec![]
+                    })
+                };
+
+                stripped = inner;
+                times += 1;
+
+                if passes_as(stripped, param) {
+                    break Adjustment::Deref(times)
+                }
+            }
+        };
+
+        Ok(Dispatch {
+            fun: Ident::new(method.fun.clone(), call.method.span().start),
+            adjustment
+        })
+    }
+
+    fn expr(&self, expr: &mut Expr, locals: &HashMap <String, Type>) -> Result <(), CheckError> {
+        match expr {
+                Expr::Lambda(lambda) => {
+                let mut locals = locals.clone();
+                locals.extend(self::locals(&lambda.sig));
+                self.expr(&mut lambda.body, &locals)
+            },
+            Expr::MethodCall(call) => {
+                self.expr(&mut call.receiver, locals)?;
+                call.args.0.iter_mut().try_for_each(|arg| self.expr(arg, locals))?;
+                call.dispatch = Some(self.dispatch(call, locals)?);
+                Ok(())
+            },
+            Expr::Index(index) => {
+                self.expr(&mut index.base, locals)?;
+                self.expr(&mut index.index, locals)
+            },
+            Expr::Field(field) => {
+                self.expr(&mut field.base, locals)?;
+                self.check_field(field, locals)
+            },
+            Expr::Call(call) => call.args.0.iter_mut().try_for_each(|arg| self.expr(arg, locals)),
+            Expr::Struct(lit) => lit.fields.0.iter_mut().try_for_each(|field| self.expr(&mut field.value, locals)),
+            Expr::Int(_) | Expr::Var(_) => Ok(()),
+            Expr::Block(block) => block.expressions.iter_mut().try_for_each(|expr| self.expr(expr, locals))
+        }
+    }
+
+    fn function(&self, mut fun: FnStmt) -> Result <Stmt, CheckError> {
+        let locals = locals(&fun.sig).collect();
+        self.expr(&mut fun.body, &locals)?;
+
+        Ok(Stmt::Fn(Box::new(fun)))
+    }
+
+    fn stmts(&self, stmts: &[Stmt]) -> Result <Vec <Stmt>, CheckError> {
+        let mut output = vec![];
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::Trait(_) => (),
+                Stmt::Impl(imp) => for fun in &imp.fns {
+                    let name = lowered_name(imp, fun);
+                    let params = HashMap::from([(SELF, &imp.ty)]);
+                    let mut body = fun.body.clone();
+
+                    for_each_type_in_expr_mut(&mut body, &mut |ty| substitute_params(ty, &params));
+
+                    output.push(self.function(FnStmt {
+                        attrs: fun.attrs.clone(),
+                        is_pub: false,
+                        export: None,
+                        name: Ident::new(name, fun.name.span().start),
+                        generics: None,
+                        sig: with_self(&fun.sig, &imp.ty),
+                        body
+                    })?)
+                },
+                Stmt::Fn(fun) => output.push(self.function((**fun).clone())?),
+                Stmt::World(world) => output.push(Stmt::World(Box::new(WorldStmt {
+                    attrs: world.attrs.clone(),
+                    name: world.name.clone(),
+                    body: self.stmts(&world.body)?
+                }))),
+                _ => output.push(stmt.clone())
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+///
+/// Dispatches every method call statically by the type of its receiver, checks that every field read exists
+///   and lowers the functions of `impl`s to functions named after the trait, the type and the function.
+///   The statements must be monomorphized
+///
+pub fn resolve(stmts: &[Stmt]) -> Result <Vec <Stmt>, CheckError> {
+    let mut resolver = Resolver::default();
+    resolver.collect(stmts);
+    resolver.stmts(stmts)
+}
@@ -0,0 +1,142 @@
+use crate::parse::path::Path;
+use crate::parse::punctuated::Punctuated;
+use crate::parse::signature::Signature;
+use crate::parse::span::Ident;
+use crate::parse::stmt::Stmt;
+use crate::parse::ty::{NamedType, Type, TypeKind};
+use crate::span::Span;
+use super::visit::types_in_stmt;
+use super::CheckError;
+
+///
+/// Whether the type is `void`, which C functions point to when the type of the pointee is unknown
+///
+pub fn is_void(ty: &Type) -> bool {
+    matches!(&ty.kind, TypeKind::Named(named) if named.generics.is_none() && named.name.last().name == "void")
+}
+
+///
+/// Whether the type is the empty tuple `()`, which functions without a written return type return
+///
+pub fn is_unit(ty: &Type) -> bool {
+    ty.is_pure() && matches!(&ty.kind, TypeKind::Tuple(elems) if elems.0.is_empty())
+}
+
+///
+/// The types an integer constant can have, besides the raw pointers a `0` is the null value of
+///
+pub const NUMERIC: &[&str] = &["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "isize", "usize", "int", "float", "f32", "f64", "char"];
+
+pub fn named(name: &str, span: Span) -> Type {
+    Type {
+        kind: TypeKind::Named(NamedType {
+            name: Path::from(Ident::new(name.to_string(), span.start)),
+            generics: None
+        }),
+        span
+    }
+}
+
+pub fn unit(span: Span) -> Type {
+    Type {
+        kind: TypeKind::Tuple(Punctuated(vec![])),
+        span
+    }
+}
+
+pub fn fn_type(sig: &Signature, span: Span) -> Type {
+    Type {
+        kind: TypeKind::Fn(Box::new(sig.clone())),
+        span
+    }
+}
+
+///
+/// The type a function returns, `()` if it is not written
+///
+pub fn return_type(sig: &Signature, span: Span) -> Type {
+    sig.return_ty.clone().unwrap_or_else(|| unit(span))
+}
+
+///
+/// Whether an integer type, which integer literals and indices can have
+///
+pub fn is_numeric(ty: &Type) -> bool {
+    ty.is_pure() && ty.name().is_some_and(|name| NUMERIC.contains(&name.name.as_str()))
+}
+
+///
+/// Whether two types are the same, ignoring the names of the parameters of function types and spans
+///
+pub fn same(a_ty: &Type, b_ty: &Type) -> bool {
+    let all_same = |a: &[&Type], b: &[&Type]| a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b));
+
+    match (&a_ty.kind, &b_ty.kind) {
+        (TypeKind::Named(a), TypeKind::Named(b)) => a.name.last().name == b.name.last().name && all_same(
+            &a.generics.iter().flat_map(|generics| &generics.0).collect::<Vec <_>>(),
+            &b.generics.iter().flat_map(|generics| &generics.0).collect::<Vec <_>>()
+        ),
+        (TypeKind::Array(a, a_len), TypeKind::Array(b, b_len)) => a_len == b_len && same(a, b),
+        (TypeKind::Slice(a), TypeKind::Slice(b)) => same(a, b),
+        (TypeKind::Tuple(a), TypeKind::Tuple(b)) => all_same(&a.0.iter().collect::<Vec <_>>(), &b.0.iter().collect::<Vec <_>>()),
+        (TypeKind::Fn(a), TypeKind::Fn(b)) => all_same(&a.param_types(), &b.param_types())
+            && a.variadic.is_some() == b.variadic.is_some()
+            && same(&return_type(a, a_ty.span), &return_type(b, b_ty.span)),
+        (TypeKind::Indirect(a_indirection, a), TypeKind::Indirect(b_indirection, b)) => a_indirection == b_indirection && same(a, b),
+        _ => false
+    }
+}
+
+///
+/// Whether a value of type `found` can be stored where `expected` is: `&mut T` is also stored as `&T`, and `*mut T` as `*T`
+///
+pub fn coerces(expected: &Type, found: &Type) -> bool {
+    match (&expected.kind, &found.kind) {
+        (TypeKind::Indirect(expected_indirection, expected_inner), TypeKind::Indirect(found_indirection, found_inner)) if expected_indirection.kind == found_indirection.kind
+            && found_indirection.is_mutable && !expected_indirection.is_mutable => same(expected_inner, found_inner),
+        _ => same(expected, found)
+    }
+}
+
+///
+/// `is_indirect` tells whether `ty` is directly behind a reference or a pointer
+///
+fn check_type(ty: &Type, is_indirect: bool) -> Result <(), CheckError> {
+    match &ty.kind {
+        TypeKind::Slice(elem) if !is_indirect => return Err(CheckError {
+            span: ty.span,
+            message: String::from("a slice cannot be used by value"),
+            clarifying: String::from("the size of a slice is unknown at compile time"),
+            help: vec![format!("use it through a reference: `&{ty:?}`"), format!("or use an array: `[{elem:?}; N]`")]
+        }),
+        TypeKind::Array(_, 0) => return Err(CheckError {
+            span: ty.span,
+            message: String::from("an array cannot be empty"),
+            clarifying: String::from("zero elements"),
+            help: vec![String::from("use the empty tuple `()` instead")]
+        }),
+        _ => ()
+    }
+
+    let is_indirect = !ty.is_pure();
+
+    ty.inner().into_iter().try_for_each(|inner| check_type(inner, is_indirect))
+}
+
+///
+/// Checks that slices are only used behind a reference or a pointer and that arrays are not empty
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    for stmt in stmts {
+        if let Stmt::World(world) = stmt {
+            check(&world.body)?;
+            continue
+        }
+
+        let mut types = vec![];
+        types_in_stmt(stmt, &mut types);
+        types.into_iter().try_for_each(|ty| check_type(ty, false))?
+    }
+
+    Ok(())
+}
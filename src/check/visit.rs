@@ -0,0 +1,485 @@
+//!
+//! Walks over the AST collecting or rewriting the pieces of items: types, signatures, calls, literals, variables and bodies.
+//!
+//! The walkers over a statement do not descend into nested `world` statements,
+//!   a world is visited statement by statement by its caller
+//!
+
+use std::collections::HashSet;
+use crate::parse::expr::{CallExpr, Expr, StructExpr};
+use crate::parse::path::Path;
+use crate::parse::signature::{Arg, Signature};
+use crate::parse::span::Ident;
+use crate::parse::stmt::{MacroStmtBody, Stmt, TyStmtBody};
+use crate::parse::ty::{Type, TypeKind};
+
+///
+/// Collects the names of all the types an item refers to,
+///   including the names of struct literals and generic arguments
+///
+pub fn type_refs_in_stmt <'a> (stmt: &'a Stmt, refs: &mut Vec <&'a Ident>) {
+    let mut types = vec![];
+    types_in_stmt(stmt, &mut types);
+    types.into_iter().for_each(|ty| type_refs_in_type(ty, refs));
+
+    let mut literals = |expr: &'a Expr| struct_names_in_expr(expr, refs);
+
+    match stmt {
+        Stmt::Fn(fun) => literals(&fun.body),
+        Stmt::Impl(imp) => imp.fns.iter().for_each(|fun| literals(&fun.body)),
+        Stmt::Macro(mac) => match &mac.body {
+            MacroStmtBody::Literal(lit) => literals(&lit.body)
+        },
+        _ => ()
+    }
+}
+
+///
+/// Collects the names of the type and all the types it is made of
+///
+pub fn type_refs_in_type <'a> (ty: &'a Type, refs: &mut Vec <&'a Ident>) {
+    refs.extend(ty.name());
+    ty.inner().into_iter().for_each(|ty| type_refs_in_type(ty, refs))
+}
+
+fn struct_names_in_expr <'a> (expr: &'a Expr, refs: &mut Vec <&'a Ident>) {
+    match expr {
+        Expr::Call(call) => call.args.0.iter().for_each(|arg| struct_names_in_expr(arg, refs)),
+        Expr::Struct(lit) => {
+            refs.push(lit.name.last());
+            lit.fields.0.iter().for_each(|field| struct_names_in_expr(&field.value, refs))
+        },
+        Expr::Lambda(lambda) => struct_names_in_expr(&lambda.body, refs),
+        Expr::MethodCall(call) => {
+            struct_names_in_expr(&call.receiver, refs);
+            call.args.0.iter().for_each(|arg| struct_names_in_expr(arg, refs))
+        },
+        Expr::Index(index) => {
+            struct_names_in_expr(&index.base, refs);
+            struct_names_in_expr(&index.index, refs)
+        },
+        Expr::Field(field) => struct_names_in_expr(&field.base, refs),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| struct_names_in_expr(expr, refs))
+    }
+}
+
+///
+/// Collects all the types written in an item, without descending into the types they are made of
+///
+pub fn types_in_stmt <'a> (stmt: &'a Stmt, types: &mut Vec <&'a Type>) {
+    match stmt {
+        Stmt::Fn(fun) => {
+            types_in_signature(&fun.sig, types);
+            types_in_expr(&fun.body, types)
+        },
+        Stmt::Ty(ty) => match &ty.body {
+            TyStmtBody::Union(body) => types.extend(body.fields.0.iter().map(|field| &field.ty)),
+            TyStmtBody::Struct(body) => types.extend(body.fields.0.iter().map(|field| &field.ty)),
+            TyStmtBody::Enum(body) => types.extend(body.fields.0.iter().filter_map(|field| field.attached_type.as_ref()))
+        },
+        Stmt::ExternFn(fun) => types_in_signature(&fun.sig, types),
+        Stmt::Trait(tr) => tr.methods.iter().for_each(|method| types_in_signature(&method.sig, types)),
+        Stmt::Impl(imp) => {
+            types.push(&imp.ty);
+
+            for fun in &imp.fns {
+                types_in_signature(&fun.sig, types);
+                types_in_expr(&fun.body, types)
+            }
+        },
+        Stmt::Macro(mac) => match &mac.body {
+            MacroStmtBody::Literal(lit) => {
+                types.extend(lit.return_ty.as_ref());
+                types_in_expr(&lit.body, types)
+            }
+        },
+        Stmt::World(_) | Stmt::Const(_) | Stmt::Mod(_) | Stmt::Use(_) => (),
+        Stmt::Channel(channel) => types.push(&channel.message)
+    }
+}
+
+pub fn types_in_signature <'a> (sig: &'a Signature, types: &mut Vec <&'a Type>) {
+    types.extend(sig.types())
+}
+
+pub fn types_in_expr <'a> (expr: &'a Expr, types: &mut Vec <&'a Type>) {
+    match expr {
+        Expr::Call(call) => {
+            types.extend(call.generics.iter().flat_map(|generics| generics.0.iter()));
+            call.args.0.iter().for_each(|arg| types_in_expr(arg, types))
+        },
+        Expr::Struct(lit) => lit.fields.0.iter().for_each(|field| types_in_expr(&field.value, types)),
+        Expr::Lambda(lambda) => {
+            types_in_signature(&lambda.sig, types);
+            types_in_expr(&lambda.body, types)
+        },
+        Expr::MethodCall(call) => {
+            types_in_expr(&call.receiver, types);
+            call.args.0.iter().for_each(|arg| types_in_expr(arg, types))
+        },
+        Expr::Index(index) => {
+            types_in_expr(&index.base, types);
+            types_in_expr(&index.index, types)
+        },
+        Expr::Field(field) => types_in_expr(&field.base, types),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| types_in_expr(expr, types))
+    }
+}
+
+///
+/// Collects all the calls inside of an expression
+///
+pub fn calls_in_expr <'a> (expr: &'a Expr, calls: &mut Vec <&'a CallExpr>) {
+    match expr {
+        Expr::Call(call) => {
+            calls.push(call);
+            call.args.0.iter().for_each(|arg| calls_in_expr(arg, calls))
+        },
+        Expr::Struct(lit) => lit.fields.0.iter().for_each(|field| calls_in_expr(&field.value, calls)),
+        Expr::Lambda(lambda) => calls_in_expr(&lambda.body, calls),
+        Expr::MethodCall(call) => {
+            calls_in_expr(&call.receiver, calls);
+            call.args.0.iter().for_each(|arg| calls_in_expr(arg, calls))
+        },
+        Expr::Index(index) => {
+            calls_in_expr(&index.base, calls);
+            calls_in_expr(&index.index, calls)
+        },
+        Expr::Field(field) => calls_in_expr(&field.base, calls),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| calls_in_expr(expr, calls))
+    }
+}
+
+///
+/// Collects the paths of all the called functions and used variables inside of an expression
+///
+pub fn paths_in_expr <'a> (expr: &'a Expr, paths: &mut Vec <&'a Path>) {
+    match expr {
+        Expr::Call(call) => {
+            paths.push(&call.fun);
+            call.args.0.iter().for_each(|arg| paths_in_expr(arg, paths))
+        },
+        Expr::Struct(lit) => lit.fields.0.iter().for_each(|field| paths_in_expr(&field.value, paths)),
+        Expr::Lambda(lambda) => paths_in_expr(&lambda.body, paths),
+        Expr::MethodCall(call) => {
+            paths_in_expr(&call.receiver, paths);
+            call.args.0.iter().for_each(|arg| paths_in_expr(arg, paths))
+        },
+        Expr::Index(index) => {
+            paths_in_expr(&index.base, paths);
+            paths_in_expr(&index.index, paths)
+        },
+        Expr::Field(field) => paths_in_expr(&field.base, paths),
+        Expr::Var(var) => paths.push(&var.name),
+        Expr::Int(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| paths_in_expr(expr, paths))
+    }
+}
+
+///
+/// Returns the parameters of a signature with their names; unnamed parameters are named `argN`
+///
+pub fn params(sig: &Signature) -> Vec <(String, &Type)> {
+    let mut params = vec![];
+
+    for arg in &sig.args.0 {
+        match arg {
+            Arg::Named(named) => params.extend(named.names.iter().map(|name| (name.name.clone(), &named.ty))),
+            Arg::Unnamed(unnamed) => for _ in 0..unnamed.times.count() {
+                params.push((format!("arg{}", params.len()), &unnamed.it))
+            }
+        }
+    }
+
+    params
+}
+
+///
+/// Collects the variables an expression uses but does not declare, in order of appearance
+///
+pub fn free_vars(expr: &Expr, bound: &HashSet <String>, vars: &mut Vec <String>) {
+    match expr {
+        Expr::Lambda(lambda) => {
+            let mut bound = bound.clone();
+            bound.extend(params(&lambda.sig).into_iter().map(|(name, _)| name));
+            free_vars(&lambda.body, &bound, vars)
+        },
+        Expr::Index(index) => {
+            free_vars(&index.base, bound, vars);
+            free_vars(&index.index, bound, vars)
+        },
+        Expr::MethodCall(call) => {
+            free_vars(&call.receiver, bound, vars);
+            call.args.0.iter().for_each(|arg| free_vars(arg, bound, vars))
+        },
+        Expr::Field(field) => free_vars(&field.base, bound, vars),
+        Expr::Call(call) => {
+            if !bound.contains(&call.fun.last().name) && !vars.contains(&call.fun.last().name) {
+                vars.push(call.fun.last().name.clone())
+            }
+
+            call.args.0.iter().for_each(|arg| free_vars(arg, bound, vars))
+        },
+        Expr::Struct(lit) => lit.fields.0.iter().for_each(|field| free_vars(&field.value, bound, vars)),
+        Expr::Int(_) => (),
+        Expr::Var(var) => if !bound.contains(&var.name.last().name) && !vars.contains(&var.name.last().name) {
+            vars.push(var.name.last().name.clone())
+        },
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| free_vars(expr, bound, vars))
+    }
+}
+
+///
+/// Collects the bodies of the functions of an item
+///
+pub fn bodies_in_stmt <'a> (stmt: &'a Stmt, bodies: &mut Vec <&'a Expr>) {
+    match stmt {
+        Stmt::Fn(fun) => bodies.push(&fun.body),
+        Stmt::Impl(imp) => bodies.extend(imp.fns.iter().map(|fun| &fun.body)),
+        Stmt::Macro(mac) => match &mac.body {
+            MacroStmtBody::Literal(lit) => bodies.push(&lit.body)
+        },
+        _ => ()
+    }
+}
+
+///
+/// Collects all the struct and enum literals inside of an expression
+///
+pub fn struct_lits_in_expr <'a> (expr: &'a Expr, lits: &mut Vec <&'a StructExpr>) {
+    match expr {
+        Expr::Call(call) => call.args.0.iter().for_each(|arg| struct_lits_in_expr(arg, lits)),
+        Expr::Struct(lit) => {
+            lits.push(lit);
+            lit.fields.0.iter().for_each(|field| struct_lits_in_expr(&field.value, lits))
+        },
+        Expr::Lambda(lambda) => struct_lits_in_expr(&lambda.body, lits),
+        Expr::MethodCall(call) => {
+            struct_lits_in_expr(&call.receiver, lits);
+            call.args.0.iter().for_each(|arg| struct_lits_in_expr(arg, lits))
+        },
+        Expr::Index(index) => {
+            struct_lits_in_expr(&index.base, lits);
+            struct_lits_in_expr(&index.index, lits)
+        },
+        Expr::Field(field) => struct_lits_in_expr(&field.base, lits),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| struct_lits_in_expr(expr, lits))
+    }
+}
+
+///
+/// The same as [`types_in_stmt`], but calls `f` with a mutable reference to every type
+///
+pub fn for_each_type_mut(stmt: &mut Stmt, f: &mut impl FnMut(&mut Type)) {
+    match stmt {
+        Stmt::Fn(fun) => {
+            for_each_type_in_signature_mut(&mut fun.sig, f);
+            for_each_type_in_expr_mut(&mut fun.body, f)
+        },
+        Stmt::Ty(ty) => match &mut ty.body {
+            TyStmtBody::Union(body) => body.fields.0.iter_mut().for_each(|field| f(&mut field.ty)),
+            TyStmtBody::Struct(body) => body.fields.0.iter_mut().for_each(|field| f(&mut field.ty)),
+            TyStmtBody::Enum(body) => body.fields.0.iter_mut().filter_map(|field| field.attached_type.as_mut()).for_each(f)
+        },
+        Stmt::ExternFn(fun) => for_each_type_in_signature_mut(&mut fun.sig, f),
+        Stmt::Trait(tr) => tr.methods.iter_mut().for_each(|method| for_each_type_in_signature_mut(&mut method.sig, f)),
+        Stmt::Impl(imp) => {
+            f(&mut imp.ty);
+
+            for fun in &mut imp.fns {
+                for_each_type_in_signature_mut(&mut fun.sig, f);
+                for_each_type_in_expr_mut(&mut fun.body, f)
+            }
+        },
+        Stmt::Macro(mac) => match &mut mac.body {
+            MacroStmtBody::Literal(lit) => {
+                lit.return_ty.iter_mut().for_each(&mut *f);
+                for_each_type_in_expr_mut(&mut lit.body, f)
+            }
+        },
+        Stmt::World(_) | Stmt::Const(_) | Stmt::Mod(_) | Stmt::Use(_) => (),
+        Stmt::Channel(channel) => f(&mut channel.message)
+    }
+}
+
+pub fn for_each_type_in_signature_mut(sig: &mut Signature, f: &mut impl FnMut(&mut Type)) {
+    sig.types_mut().for_each(f)
+}
+
+pub fn for_each_type_in_expr_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Type)) {
+    match expr {
+        Expr::Call(call) => {
+            call.generics.iter_mut().flat_map(|generics| generics.0.iter_mut()).for_each(&mut *f);
+            call.args.0.iter_mut().for_each(|arg| for_each_type_in_expr_mut(arg, f))
+        },
+        Expr::Struct(lit) => lit.fields.0.iter_mut().for_each(|field| for_each_type_in_expr_mut(&mut field.value, f)),
+        Expr::Lambda(lambda) => {
+            for_each_type_in_signature_mut(&mut lambda.sig, f);
+            for_each_type_in_expr_mut(&mut lambda.body, f)
+        },
+        Expr::MethodCall(call) => {
+            for_each_type_in_expr_mut(&mut call.receiver, f);
+            call.args.0.iter_mut().for_each(|arg| for_each_type_in_expr_mut(arg, f))
+        },
+        Expr::Index(index) => {
+            for_each_type_in_expr_mut(&mut index.base, f);
+            for_each_type_in_expr_mut(&mut index.index, f)
+        },
+        Expr::Field(field) => for_each_type_in_expr_mut(&mut field.base, f),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter_mut().for_each(|expr| for_each_type_in_expr_mut(expr, f))
+    }
+}
+
+///
+/// Calls `f` with a mutable reference to the body of every function of an item
+///
+pub fn for_each_body_mut(stmt: &mut Stmt, f: &mut impl FnMut(&mut Expr)) {
+    match stmt {
+        Stmt::Fn(fun) => f(&mut fun.body),
+        Stmt::Impl(imp) => imp.fns.iter_mut().for_each(|fun| f(&mut fun.body)),
+        Stmt::Macro(mac) => match &mut mac.body {
+            MacroStmtBody::Literal(lit) => f(&mut lit.body)
+        },
+        _ => ()
+    }
+}
+
+///
+/// Calls `f` with a mutable reference to every call inside of an expression
+///
+pub fn for_each_call_mut(expr: &mut Expr, f: &mut impl FnMut(&mut CallExpr)) {
+    match expr {
+        Expr::Call(call) => {
+            f(call);
+            call.args.0.iter_mut().for_each(|arg| for_each_call_mut(arg, f))
+        },
+        Expr::Struct(lit) => lit.fields.0.iter_mut().for_each(|field| for_each_call_mut(&mut field.value, f)),
+        Expr::Lambda(lambda) => for_each_call_mut(&mut lambda.body, f),
+        Expr::MethodCall(call) => {
+            for_each_call_mut(&mut call.receiver, f);
+            call.args.0.iter_mut().for_each(|arg| for_each_call_mut(arg, f))
+        },
+        Expr::Index(index) => {
+            for_each_call_mut(&mut index.base, f);
+            for_each_call_mut(&mut index.index, f)
+        },
+        Expr::Field(field) => for_each_call_mut(&mut field.base, f),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter_mut().for_each(|expr| for_each_call_mut(expr, f))
+    }
+}
+
+///
+/// Collects every signature written in an item: of the item itself, of lambdas and of function types
+///
+pub fn signatures_in_stmt <'a> (stmt: &'a Stmt, sigs: &mut Vec <&'a Signature>) {
+    match stmt {
+        Stmt::Fn(fun) => {
+            sigs.push(&fun.sig);
+            signatures_in_expr(&fun.body, sigs)
+        },
+        Stmt::ExternFn(fun) => sigs.push(&fun.sig),
+        Stmt::Trait(tr) => sigs.extend(tr.methods.iter().map(|method| &method.sig)),
+        Stmt::Impl(imp) => for fun in &imp.fns {
+            sigs.push(&fun.sig);
+            signatures_in_expr(&fun.body, sigs)
+        },
+        Stmt::Macro(mac) => match &mac.body {
+            MacroStmtBody::Literal(lit) => signatures_in_expr(&lit.body, sigs)
+        },
+        _ => ()
+    }
+
+    let mut types = vec![];
+    types_in_stmt(stmt, &mut types);
+    types.into_iter().for_each(|ty| signatures_in_type(ty, sigs))
+}
+
+fn signatures_in_type <'a> (ty: &'a Type, sigs: &mut Vec <&'a Signature>) {
+    if let TypeKind::Fn(sig) = &ty.kind {
+        sigs.push(sig)
+    }
+
+    ty.inner().into_iter().for_each(|ty| signatures_in_type(ty, sigs))
+}
+
+fn signatures_in_expr <'a> (expr: &'a Expr, sigs: &mut Vec <&'a Signature>) {
+    match expr {
+        Expr::Call(call) => call.args.0.iter().for_each(|arg| signatures_in_expr(arg, sigs)),
+        Expr::Struct(lit) => lit.fields.0.iter().for_each(|field| signatures_in_expr(&field.value, sigs)),
+        Expr::Lambda(lambda) => {
+            sigs.push(&lambda.sig);
+            signatures_in_expr(&lambda.body, sigs)
+        },
+        Expr::MethodCall(call) => {
+            signatures_in_expr(&call.receiver, sigs);
+            call.args.0.iter().for_each(|arg| signatures_in_expr(arg, sigs))
+        },
+        Expr::Index(index) => {
+            signatures_in_expr(&index.base, sigs);
+            signatures_in_expr(&index.index, sigs)
+        },
+        Expr::Field(field) => signatures_in_expr(&field.base, sigs),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter().for_each(|expr| signatures_in_expr(expr, sigs))
+    }
+}
+
+///
+/// The same as [`signatures_in_stmt`], but calls `f` with a mutable reference to every signature
+///
+pub fn for_each_signature_mut(stmt: &mut Stmt, f: &mut impl FnMut(&mut Signature)) {
+    match stmt {
+        Stmt::Fn(fun) => {
+            f(&mut fun.sig);
+            for_each_signature_in_expr_mut(&mut fun.body, f)
+        },
+        Stmt::ExternFn(fun) => f(&mut fun.sig),
+        Stmt::Trait(tr) => tr.methods.iter_mut().for_each(|method| f(&mut method.sig)),
+        Stmt::Impl(imp) => for fun in &mut imp.fns {
+            f(&mut fun.sig);
+            for_each_signature_in_expr_mut(&mut fun.body, f)
+        },
+        Stmt::Macro(mac) => match &mut mac.body {
+            MacroStmtBody::Literal(lit) => for_each_signature_in_expr_mut(&mut lit.body, f)
+        },
+        _ => ()
+    }
+
+    for_each_type_mut(stmt, &mut |ty| for_each_signature_in_type_mut(ty, f))
+}
+
+fn for_each_signature_in_type_mut(ty: &mut Type, f: &mut impl FnMut(&mut Signature)) {
+    if let TypeKind::Fn(sig) = &mut ty.kind {
+        f(sig)
+    }
+
+    ty.inner_mut().into_iter().for_each(|ty| for_each_signature_in_type_mut(ty, f))
+}
+
+fn for_each_signature_in_expr_mut(expr: &mut Expr, f: &mut impl FnMut(&mut Signature)) {
+    match expr {
+        Expr::Call(call) => call.args.0.iter_mut().for_each(|arg| for_each_signature_in_expr_mut(arg, f)),
+        Expr::Struct(lit) => lit.fields.0.iter_mut().for_each(|field| for_each_signature_in_expr_mut(&mut field.value, f)),
+        Expr::Lambda(lambda) => {
+            f(&mut lambda.sig);
+            for_each_signature_in_expr_mut(&mut lambda.body, f)
+        },
+        Expr::MethodCall(call) => {
+            for_each_signature_in_expr_mut(&mut call.receiver, f);
+            call.args.0.iter_mut().for_each(|arg| for_each_signature_in_expr_mut(arg, f))
+        },
+        Expr::Index(index) => {
+            for_each_signature_in_expr_mut(&mut index.base, f);
+            for_each_signature_in_expr_mut(&mut index.index, f)
+        },
+        Expr::Field(field) => for_each_signature_in_expr_mut(&mut field.base, f),
+        Expr::Int(_) | Expr::Var(_) => (),
+        Expr::Block(block) => block.expressions.iter_mut().for_each(|expr| for_each_signature_in_expr_mut(expr, f))
+    }
+}
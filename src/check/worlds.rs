@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet};
+use crate::driver::written;
+use crate::parse::span::Ident;
+use crate::parse::stmt::{Stmt, WorldStmt};
+use super::visit::type_refs_in_stmt;
+use super::CheckError;
+
+///
+/// Checks that worlds are independent:
+///
+/// - world names are unique and worlds are not nested
+///
+/// - a `ty` declared inside a world is referred to only from that world. Several worlds may declare types of the same name,
+///   a name refers to the type of the world it is used in, or else to a top-level one
+///
+/// - channels connect two different existing worlds and carry shared (top-level) types,
+///   and are rejected then, since nothing compiles them yet
+///
+pub fn check(stmts: &[Stmt]) -> Result <(), CheckError> {
+    let mut worlds: HashMap <&str, &WorldStmt> = HashMap::new();
+    let mut order = vec![];
+
+    // The types of the worlds by the names of their worlds and their names as written
+    let mut owners: HashSet <(&str, &str)> = HashSet::new();
+    let mut shared: HashSet <&str> = HashSet::new();
+
+    for stmt in stmts {
+        let world = match stmt {
+            Stmt::World(world) => world,
+            Stmt::Ty(ty) => {
+                shared.insert(&ty.name.name);
+                continue
+            },
+            _ => continue
+        };
+
+        order.push(&world.name);
+
+        if let Some(previous) = worlds.insert(&world.name.name, world) {
+            return Err(CheckError {
+                span: world.name.span(),
+                message: format!("world `{}` is declared twice", world.name.name),
+                clarifying: String::from("redeclared here"),
+                help: vec![format!("the previous declaration is at {:?}", previous.name.span().start)]
+            })
+        }
+
+        for inner in &world.body {
+            match inner {
+                Stmt::World(nested) => return Err(CheckError {
+                    span: nested.name.span(),
+                    message: format!("world `{}` is declared inside of world `{}`", written(&nested.name.name), world.name.name),
+                    clarifying: String::from("worlds cannot be nested"),
+                    help: vec![]
+                }),
+                Stmt::Ty(ty) => {
+                    owners.insert((&world.name.name, written(&ty.name.name)));
+                },
+                _ => ()
+            }
+        }
+    }
+
+    let check_refs = |stmt: &Stmt, world: Option <&Ident>| -> Result <(), CheckError> {
+        let mut refs = vec![];
+        type_refs_in_stmt(stmt, &mut refs);
+
+        for ident in refs {
+            let name = written(&ident.name);
+
+            if world.is_some_and(|world| owners.contains(&(&world.name, name))) || shared.contains(ident.name.as_str()) {
+                continue
+            }
+
+            let Some(owner) = order.iter().find(|owner| owners.contains(&(&owner.name, name))) else { continue };
+
+            return Err(CheckError {
+                span: ident.span(),
+                message: format!("`{}` belongs to world `{}`", ident.name, owner.name),
+                clarifying: String::from(match world {
+                    Some(_) => "cannot be used from another world",
+                    None => "cannot be used outside of its world"
+                }),
+                help: vec![String::from("declare the type outside of any world to share it")]
+            })
+        }
+
+        Ok(())
+    };
+
+    for stmt in stmts {
+        match stmt {
+            Stmt::World(world) => for inner in &world.body {
+                check_refs(inner, Some(&world.name))?
+            },
+            Stmt::Channel(channel) => {
+                for end in [&channel.from, &channel.to] {
+                    if !worlds.contains_key(end.name.as_str()) {
+                        return Err(CheckError {
+                            span: end.span(),
+                            message: format!("no world named `{}`", end.name),
+                            clarifying: String::from("channels connect worlds"),
+                            help: vec![]
+                        })
+                    }
+                }
+
+                if channel.from.name == channel.to.name {
+                    return Err(CheckError {
+                        span: channel.to.span(),
+                        message: format!("channel from world `{}` to itself", channel.from.name),
+                        clarifying: String::from("expected another world"),
+                        help: vec![String::from("events inside of a world are sent directly")]
+                    })
+                }
+
+                check_refs(stmt, None)?;
+
+                // The worlds are not compiled into schedules, so there is nothing to deliver the events
+                return Err(CheckError {
+                    span: channel.from.span(),
+                    message: String::from("`channel` is not supported yet"),
+                    clarifying: String::from("worlds are not compiled into schedules"),
+                    help: vec![String::from("connect the worlds with `Universe::channel` of the ECS runtime")]
+                })
+            },
+            _ => check_refs(stmt, None)?
+        }
+    }
+
+    Ok(())
+}
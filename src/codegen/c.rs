@@ -0,0 +1,1100 @@
+//!
+//! The C backend
+//!
+//! Arrays, slices and tuples are lowered to structs, so they can be passed to and returned from functions by value:
+//!
+//! - `[int; 4]` becomes `struct { int items[4]; }`
+//!
+//! - `&[int]` becomes `struct { const int *items; size_t len; }`, and `&mut [int]` becomes `struct { int *items; size_t len; }`
+//!
+//! - `(int, float)` becomes `struct { int f0; float f1; }`
+//!
+//! References and pointers both become C pointers, which point to `const` unless they are `mut`: `&Vec2` becomes `const Vec2 *`.
+//!   `void` can only be pointed to: `*mut void` becomes `void *`
+//!
+//! A value of a function type is a closure: `fn(int) -> int` becomes `struct { int (*call)(void *, int); void *env; }`.
+//!   Lambdas are hoisted into static functions receiving their captured variables through `env`,
+//!   which is copied to the heap, so a closure may outlive the function it is created in. It is never freed.
+//!
+//! In the signatures of `extern clang` functions function types are plain C function pointers,
+//!   so C callbacks can be passed lambdas that capture nothing and named functions
+//!
+//! The declarations are generated from the statements and the bodies from their MIR. A temporary read once
+//!   is written where it is read, so calls nest as in the oko code, unless a statement comes in between:
+//!   then it is stored in a variable named after it, `_3`, to be evaluated first
+//!
+
+use std::collections::{HashMap, HashSet};
+use crate::check::layout;
+use crate::check::CheckError;
+use crate::check::ffi::exported_name;
+use crate::check::types::{fn_type, is_unit, is_void};
+use crate::check::visit::{self, params};
+use crate::mir::{Body, Constant, Local, LocalKind, Operand, Place, Program, Projection, Rvalue, Terminator};
+use crate::mono::mangle_kind;
+use crate::parse::signature::Signature;
+use crate::parse::stmt::{ExternFnStmt, FFILanguage, FnStmt, Stmt, TyStmt, TyStmtBody};
+use crate::parse::ty::{Indirection, IndirectionKind, Type, TypeKind};
+use crate::span::Span;
+
+const PRELUDE: &str = "#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n";
+
+const INDENT: &str = "    ";
+
+///
+/// Returns the C spelling of a builtin oko type
+///
+fn builtin(name: &str) -> Option <&'static str> {
+    Some(match name {
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "isize" => "ptrdiff_t",
+        "usize" => "size_t",
+        "int" => "int",
+        "float" => "float",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "bool",
+        "char" | "str" => "char",
+        _ => return None
+    })
+}
+
+///
+/// Joins a spelled type and a name into a declaration: `int *` and `x` become `int *x`
+///
+fn declaration(ty: &str, name: &str) -> String {
+    if ty.ends_with('*') {
+        format!("{ty}{name}")
+    } else {
+        format!("{ty} {name}")
+    }
+}
+
+///
+/// The attribute of a calling convention put before a prototype
+///
+fn convention(convention: Option <&str>) -> String {
+    convention.map(|convention| format!("__attribute__(({convention})) ")).unwrap_or_default()
+}
+
+fn deref(expr: String, times: usize) -> String {
+    if times == 0 {
+        expr
+    } else {
+        format!("({}{expr})", "*".repeat(times))
+    }
+}
+
+fn unsupported(span: Span, what: &str) -> CheckError {
+    CheckError {
+        span,
+        message: format!("{what} is not supported by the C backend yet"),
+        clarifying: String::from("used here"),
+        help: vec![]
+    }
+}
+
+///
+/// How function types are represented
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Abi {
+    ///
+    /// Closures: a function pointer and its environment
+    ///
+    Oko,
+
+    ///
+    /// Plain C function pointers
+    ///
+    C
+}
+
+///
+/// The names and the types of the parameters or the captured variables of a body
+///
+fn inputs(body: &Body, kind: LocalKind) -> Vec <(String, &Type)> {
+    body.locals
+        .iter()
+        .filter(|decl| decl.kind == kind)
+        .map(|decl| (decl.name.clone().expect("parameters and captured variables are named"), &decl.ty))
+        .collect()
+}
+
+///
+/// The body a function is being generated from
+///
+struct Frame <'b> {
+    body: &'b Body,
+
+    ///
+    /// How many times every local is read
+    ///
+    reads: Vec <usize>,
+
+    ///
+    /// The temporaries read once whose values are not written yet, in the order they are assigned.
+    ///   A value is written where its temporary is read, so the C code nests the calls as the oko code does
+    ///
+    pending: Vec <(Local, String)>,
+
+    ///
+    /// The temporaries of type `()` written as statements, which hold no value
+    ///
+    voids: HashSet <Local>,
+
+    ///
+    /// The lambdas stored in temporaries by the names of their bodies, which C callbacks receive without the closures
+    ///
+    closures: HashMap <Local, &'b str>,
+
+    ///
+    /// The value assigned to `_0`
+    ///
+    returned: Option <String>,
+
+    code: String
+}
+
+impl <'b> Frame <'b> {
+    fn new(body: &'b Body) -> Self {
+        let mut places = vec![];
+
+        for block in &body.blocks {
+            for statement in &block.statements {
+                match &statement.rvalue {
+                    Rvalue::Use(operand) | Rvalue::Variant(_, _, Some(operand)) => places.extend(operand.place()),
+                    Rvalue::Ref(_, place) => places.push(place),
+                    Rvalue::Struct(_, fields) => places.extend(fields.iter().filter_map(|(_, value)| value.place())),
+                    Rvalue::Closure(_, captures) => places.extend(captures.iter().filter_map(Operand::place)),
+                    Rvalue::Variant(_, _, None) => ()
+                }
+            }
+
+            if let Some(Terminator::Call { callee, args, .. }) = &block.terminator {
+                places.extend(callee.place());
+                places.extend(args.iter().filter_map(Operand::place))
+            }
+        }
+
+        let mut reads = vec![0; body.locals.len()];
+
+        for place in places {
+            reads[place.local.0] += 1;
+
+            for projection in &place.projection {
+                if let Projection::Index(index) = projection {
+                    reads[index.0] += 1
+                }
+            }
+        }
+
+        Self {
+            body,
+            reads,
+            pending: vec![],
+            voids: HashSet::new(),
+            closures: HashMap::new(),
+            returned: None,
+            code: String::new()
+        }
+    }
+
+    fn is_pending(&self, local: Local) -> bool {
+        self.pending.iter().any(|(pending, _)| *pending == local)
+    }
+
+    ///
+    /// Reads a local, taking the value of a temporary which is not written yet
+    ///
+    fn local(&mut self, local: Local) -> String {
+        if let Some(idx) = self.pending.iter().position(|(pending, _)| *pending == local) {
+            return self.pending.remove(idx).1
+        }
+
+        let decl = self.body.local(local);
+
+        match (decl.kind, &decl.name) {
+            _ if self.voids.contains(&local) => String::from("((void) 0)"),
+            (LocalKind::Param, Some(name)) => name.clone(),
+            (LocalKind::Capture, Some(name)) => format!("env->{name}"),
+            _ => format!("{local:?}")
+        }
+    }
+}
+
+#[derive(Default)]
+struct Generator <'a> {
+    tys: HashMap <&'a str, &'a TyStmt>,
+    fns: HashMap <&'a str, &'a Signature>,
+
+    ///
+    /// The foreign functions and the names of the C functions they link to
+    ///
+    externs: HashMap <&'a str, &'a str>,
+
+    ///
+    /// The functions exported by `extern clang fn` and `extern rust fn` and their names in C
+    ///
+    exports: HashMap <&'a str, &'a str>,
+
+    ///
+    /// Arrays, slices, tuples and closures in use, by their C names
+    ///
+    composites: HashMap <String, TypeKind>,
+
+    ///
+    /// Every struct type in order of appearance, declared ahead of the definitions
+    ///
+    forward: Vec <String>,
+
+    ///
+    /// The function pointer typedefs, which only need the structs to be declared
+    ///
+    fn_pointers: Vec <String>,
+    fn_pointer_names: HashSet <String>,
+
+    defined: HashSet <String>,
+    in_progress: HashSet <String>,
+    definitions: Vec <String>,
+
+    ///
+    /// Lambdas and wrappers of functions hoisted to the top level
+    ///
+    hoisted_prototypes: Vec <String>,
+    hoisted: Vec <String>,
+
+    ///
+    /// The C names of the lambdas by the names of their bodies
+    ///
+    lambdas: HashMap <String, String>,
+
+    ///
+    /// The wrappers already hoisted
+    ///
+    thunks: HashSet <String>
+}
+
+impl <'a> Generator <'a> {
+    fn collect(&mut self, stmts: &'a [Stmt], fns: &mut Vec <&'a FnStmt>, externs: &mut Vec <&'a ExternFnStmt>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Ty(ty) => {
+                    self.tys.insert(&ty.name.name, ty);
+                    self.forward.push(ty.name.name.clone())
+                },
+                Stmt::Fn(fun) => {
+                    if fun.export.is_some() {
+                        self.exports.insert(&fun.name.name, exported_name(fun));
+                    }
+
+                    self.fns.insert(&fun.name.name, &fun.sig);
+                    fns.push(fun)
+                },
+                Stmt::ExternFn(fun) => {
+                    self.fns.insert(&fun.name.name, &fun.sig);
+                    self.externs.insert(&fun.name.name, fun.link_name());
+                    externs.push(fun)
+                },
+                Stmt::World(world) => self.collect(&world.body, fns, externs),
+                Stmt::Macro(_) | Stmt::Channel(_) | Stmt::Const(_) | Stmt::Mod(_) | Stmt::Use(_) => (),
+                Stmt::Trait(_) | Stmt::Impl(_) => unreachable!("traits are resolved before the code generation")
+            }
+        }
+    }
+
+    ///
+    /// The C name of a function, which differs from the oko one for the foreign functions with `#[link_name]`
+    ///   and for the exported ones, which are named as written in their modules
+    ///
+    fn symbol <'b> (&self, name: &'b str) -> &'b str where 'a: 'b {
+        self.externs.get(name).or(self.exports.get(name)).copied().unwrap_or(name)
+    }
+
+    ///
+    /// Returns the C name of an array, a slice, a tuple or a closure and remembers to define it
+    ///
+    fn composite(&mut self, kind: &TypeKind) -> String {
+        let name = format!("oko_{}", mangle_kind(kind));
+
+        if !self.composites.contains_key(&name) {
+            self.composites.insert(name.clone(), kind.clone());
+            self.forward.push(name.clone())
+        }
+
+        name
+    }
+
+    ///
+    /// Returns the name of the typedef of a function pointer with the signature `sig`.
+    ///   With the oko ABI the pointer receives the closure environment first
+    ///
+    fn fn_pointer(&mut self, sig: &Signature, abi: Abi) -> Result <String, CheckError> {
+        let name = format!("oko_{}_{}", match abi {
+            Abi::Oko => "call",
+            Abi::C => "fnptr"
+        }, mangle_kind(&TypeKind::Fn(Box::new(sig.clone()))));
+
+        if self.fn_pointer_names.contains(&name) {
+            return Ok(name)
+        }
+
+        let ret = match &sig.return_ty {
+            Some(ty) if !is_unit(ty) => self.spell(ty, abi)?,
+            _ => String::from("void")
+        };
+
+        let mut params = sig.param_types().into_iter().map(|ty| self.spell(ty, abi)).collect::<Result <Vec <_>, _>>()?;
+
+        if abi == Abi::Oko {
+            params.insert(0, String::from("void *"))
+        }
+
+        let params = if params.is_empty() {
+            String::from("void")
+        } else {
+            params.join(", ")
+        };
+
+        self.fn_pointer_names.insert(name.clone());
+        self.fn_pointers.push(format!("typedef {ret} (*{name})({params});\n"));
+
+        Ok(name)
+    }
+
+    ///
+    /// Returns the C spelling of a type and the name of the struct that must be defined
+    ///   before the type can be used by value, if any
+    ///
+    fn spell_with_dependency(&mut self, ty: &Type, abi: Abi) -> Result <(String, Option <String>), CheckError> {
+        let spelled = match &ty.kind {
+            TypeKind::Named(named) => {
+                if named.generics.is_some() {
+                    return Err(unsupported(ty.span, "a generic type that is not an oko `ty`"))
+                }
+
+                match builtin(&named.name.last().name) {
+                    Some(builtin) => return Ok((String::from(builtin), None)),
+                    None if is_void(ty) => return Err(CheckError {
+                        span: ty.span,
+                        message: String::from("`void` can only be pointed to"),
+                        clarifying: String::from("used by value"),
+                        help: vec![String::from("use `*void` or `*mut void`")]
+                    }),
+                    None if self.tys.contains_key(named.name.last().name.as_str()) => named.name.last().name.clone(),
+                    None => return Err(CheckError {
+                        span: ty.span,
+                        message: format!("cannot find type `{}`", named.name.last().name),
+                        clarifying: String::from("not found"),
+                        help: vec![]
+                    })
+                }
+            },
+            // The reference is a part of the slice itself, only its mutability matters
+            TypeKind::Indirect(indirection, inner) if matches!(inner.kind, TypeKind::Slice(_)) => return Ok((match indirection.is_mutable {
+                true => self.composite(&TypeKind::Indirect(Indirection { kind: IndirectionKind::Ref, is_mutable: true }, inner.clone())),
+                false => self.composite(&inner.kind)
+            }, None)),
+            TypeKind::Indirect(indirection, inner) => {
+                let inner = if is_void(inner) {
+                    String::from("void")
+                } else {
+                    self.spell(inner, abi)?
+                };
+
+                return Ok((match (indirection.is_mutable, inner.ends_with('*')) {
+                    (true, _) => format!("{}*", declaration(&inner, "")),
+                    (false, true) => format!("{inner}const *"),
+                    (false, false) => format!("const {inner} *")
+                }, None))
+            },
+            TypeKind::Fn(sig) if abi == Abi::C => return Ok((self.fn_pointer(sig, Abi::C)?, None)),
+            TypeKind::Array(..) | TypeKind::Slice(_) | TypeKind::Tuple(_) | TypeKind::Fn(_) => self.composite(&ty.kind)
+        };
+
+        Ok((spelled.clone(), Some(spelled)))
+    }
+
+    fn spell(&mut self, ty: &Type, abi: Abi) -> Result <String, CheckError> {
+        Ok(self.spell_with_dependency(ty, abi)?.0)
+    }
+
+    ///
+    /// Spells a type used by value, defining the struct it depends on first
+    ///
+    fn spell_field(&mut self, ty: &Type, abi: Abi) -> Result <String, CheckError> {
+        let (spelled, dependency) = self.spell_with_dependency(ty, abi)?;
+
+        if let Some(dependency) = dependency {
+            if self.in_progress.contains(&dependency) {
+                return Err(CheckError {
+                    span: ty.span,
+                    message: format!("`{ty:?}` contains itself"),
+                    clarifying: String::from("a type of infinite size"),
+                    help: vec![String::from("use a pointer to break the cycle")]
+                })
+            }
+
+            self.define(&dependency)?
+        }
+
+        Ok(spelled)
+    }
+
+    ///
+    /// `union` for the unions declared with `ty`, `struct` for the other types
+    ///
+    fn keyword(&self, name: &str) -> &'static str {
+        match self.tys.get(name).map(|ty| &ty.body) {
+            Some(TyStmtBody::Union(_)) => "union",
+            _ => "struct"
+        }
+    }
+
+    fn define(&mut self, name: &str) -> Result <(), CheckError> {
+        if self.defined.contains(name) {
+            return Ok(())
+        }
+
+        self.in_progress.insert(name.to_string());
+
+        let mut fields = vec![];
+        let mut attributes = vec![];
+
+        if let Some(ty) = self.tys.get(name).copied() {
+            let layout = layout::layout(ty)?;
+
+            if layout.is_packed {
+                attributes.push(String::from("packed"))
+            }
+
+            if let Some(align) = layout.align {
+                attributes.push(format!("aligned({align})"))
+            }
+
+            match &ty.body {
+                TyStmtBody::Union(_) | TyStmtBody::Struct(_) => for field in ty.body.fields().unwrap_or_default() {
+                    let spelled = self.spell_field(&field.ty, Abi::Oko)?;
+                    fields.extend(field.names.iter().map(|name| declaration(&spelled, &name.name)))
+                },
+                TyStmtBody::Enum(body) => {
+                    let tags = body.fields.0
+                        .iter()
+                        .map(|variant| match &variant.discriminant {
+                            Some(discriminant) => format!("{INDENT}{INDENT}{name}_{} = {}", variant.name.name, discriminant.value),
+                            None => format!("{INDENT}{INDENT}{name}_{}", variant.name.name)
+                        })
+                        .collect::<Vec <_>>();
+
+                    fields.push(format!("enum {{\n{}\n{INDENT}}} tag", tags.join(",\n")));
+
+                    let mut attached = vec![];
+
+                    for variant in &body.fields.0 {
+                        if let Some(ty) = &variant.attached_type {
+                            let spelled = self.spell_field(ty, Abi::Oko)?;
+                            attached.push(format!("{INDENT}{INDENT}{};", declaration(&spelled, &variant.name.name)))
+                        }
+                    }
+
+                    if !attached.is_empty() {
+                        fields.push(format!("union {{\n{}\n{INDENT}}} value", attached.join("\n")))
+                    }
+                }
+            }
+        } else {
+            let kind = self.composites[name].clone();
+
+            match &kind {
+                TypeKind::Array(elem, len) => {
+                    let spelled = self.spell_field(elem, Abi::Oko)?;
+                    fields.push(declaration(&spelled, &format!("items[{len}]")))
+                },
+                TypeKind::Slice(elem) => {
+                    fields.push(self.slice_items(elem, false)?);
+                    fields.push(String::from("size_t len"))
+                },
+                TypeKind::Indirect(indirection, slice) => {
+                    let TypeKind::Slice(elem) = &slice.kind else {
+                        unreachable!("only mutable slices are composite indirections")
+                    };
+
+                    fields.push(self.slice_items(elem, indirection.is_mutable)?);
+                    fields.push(String::from("size_t len"))
+                },
+                TypeKind::Tuple(elems) => {
+                    for (idx, elem) in elems.0.iter().enumerate() {
+                        let spelled = self.spell_field(elem, Abi::Oko)?;
+                        fields.push(declaration(&spelled, &format!("f{idx}")))
+                    }
+
+                    if elems.0.is_empty() {
+                        fields.push(String::from("char unused"))
+                    }
+                },
+                TypeKind::Fn(sig) => {
+                    fields.push(declaration(&self.fn_pointer(sig, Abi::Oko)?, "call"));
+                    fields.push(String::from("void *env"))
+                },
+                TypeKind::Named(_) => unreachable!("named types are not composite")
+            }
+        }
+
+        let fields = fields.iter().map(|field| format!("{INDENT}{field};\n")).collect::<String>();
+        let attributes = if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" __attribute__(({}))", attributes.join(", "))
+        };
+
+        self.in_progress.remove(name);
+        self.defined.insert(name.to_string());
+        self.definitions.push(format!("{} {name} {{\n{fields}}}{attributes};\n", self.keyword(name)));
+
+        Ok(())
+    }
+
+    ///
+    /// The pointer to the items of a slice, to `const` items unless the slice is `mut`
+    ///
+    fn slice_items(&mut self, elem: &Type, is_mutable: bool) -> Result <String, CheckError> {
+        let items = Type {
+            kind: TypeKind::Indirect(Indirection { kind: IndirectionKind::Ptr, is_mutable }, Box::new(elem.clone())),
+            span: elem.span
+        };
+
+        Ok(declaration(&self.spell(&items, Abi::Oko)?, "items"))
+    }
+
+    fn return_type(&mut self, ty: Option <&Type>, abi: Abi) -> Result <String, CheckError> {
+        match ty {
+            Some(ty) if !is_unit(ty) => self.spell_field(ty, abi),
+            _ => Ok(String::from("void"))
+        }
+    }
+
+    ///
+    /// Returns the C parameter list, optionally preceded by the closure environment
+    ///
+    fn param_list(&mut self, params: &[(String, &Type)], abi: Abi, env: Option <&str>) -> Result <String, CheckError> {
+        let mut params = params
+            .iter()
+            .map(|(name, ty)| Ok(declaration(&self.spell_field(ty, abi)?, name)))
+            .collect::<Result <Vec <_>, CheckError>>()?;
+
+        if let Some(env) = env {
+            params.insert(0, declaration("void *", env))
+        }
+
+        Ok(if params.is_empty() {
+            String::from("void")
+        } else {
+            params.join(", ")
+        })
+    }
+
+    fn prototype(&mut self, name: &str, sig: &Signature, abi: Abi) -> Result <String, CheckError> {
+        let ret = self.return_type(sig.return_ty.as_ref(), abi)?;
+        let mut params = self.param_list(&params(sig), abi, None)?;
+
+        if sig.variadic.is_some() {
+            params += ", ..."
+        }
+
+        Ok(format!("{}({params})", declaration(&ret, name)))
+    }
+
+    ///
+    /// Writes a place. A borrowed temporary whose value is not written yet is put into a compound literal,
+    ///   which lives until the end of the enclosing block
+    ///
+    fn place(&mut self, place: &Place, is_borrowed: bool, frame: &mut Frame, program: &Program) -> Result <String, CheckError> {
+        let mut ty = frame.body.local(place.local).ty.clone();
+
+        let mut code = if is_borrowed && frame.is_pending(place.local) {
+            format!("({}[]) {{ {} }}[0]", self.spell(&ty, Abi::Oko)?, frame.local(place.local))
+        } else {
+            frame.local(place.local)
+        };
+
+        let mut derefs = 0;
+
+        for projection in &place.projection {
+            let projected = program.project(&ty, projection).expect("the MIR is validated before the code generation");
+            let base = core::mem::replace(&mut ty, projected);
+
+            code = match projection {
+                // The reference is a part of the slice itself
+                Projection::Deref => {
+                    derefs += usize::from(!matches!(ty.kind, TypeKind::Slice(_)));
+                    continue
+                },
+                Projection::Field(name) => format!("{}.{name}", deref(code, derefs)),
+                Projection::Index(index) => format!("{}.items[{}]", deref(code, derefs), frame.local(*index)),
+                Projection::ConstIndex(idx) if matches!(base.kind, TypeKind::Tuple(_)) => format!("{}.f{idx}", deref(code, derefs)),
+                Projection::ConstIndex(idx) => format!("{}.items[{idx}]", deref(code, derefs))
+            };
+
+            derefs = 0
+        }
+
+        Ok(deref(code, derefs))
+    }
+
+    fn operand(&mut self, operand: &Operand, frame: &mut Frame, program: &Program) -> Result <String, CheckError> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.place(place, false, frame, program),
+            Operand::Const(Constant::Int(value, _)) => Ok(value.to_string()),
+            Operand::Const(Constant::Fn(name)) => self.fn_value(name, self.fns[name.as_str()], frame.body.span),
+            Operand::Const(Constant::Unit) => Ok(String::from("((void) 0)"))
+        }
+    }
+
+    ///
+    /// Writes the value of an rvalue stored in a local of type `ty`
+    ///
+    fn rvalue(&mut self, rvalue: &Rvalue, ty: &Type, frame: &mut Frame, program: &Program) -> Result <String, CheckError> {
+        Ok(match rvalue {
+            Rvalue::Use(operand) => self.operand(operand, frame, program)?,
+            Rvalue::Ref(_, place) => format!("&{}", self.place(place, true, frame, program)?),
+            Rvalue::Struct(name, fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(field, value)| Ok(format!(".{field} = {}", self.operand(value, frame, program)?)))
+                    .collect::<Result <Vec <_>, CheckError>>()?;
+
+                format!("({} {name}) {{ {} }}", self.keyword(name), fields.join(", "))
+            },
+            Rvalue::Variant(name, variant, None) => format!("(struct {name}) {{ .tag = {name}_{variant} }}"),
+            Rvalue::Variant(name, variant, Some(value)) => format!("(struct {name}) {{ .tag = {name}_{variant}, .value.{variant} = {} }}", self.operand(value, frame, program)?),
+            Rvalue::Closure(lambda, captures) => {
+                let closure = self.spell_field(ty, Abi::Oko)?;
+                let name = self.lambdas[lambda].clone();
+
+                let env = if captures.is_empty() {
+                    String::from("NULL")
+                } else {
+                    let body = program.body(lambda).expect("closures are of lowered lambdas");
+
+                    // The environment is copied to the heap, so the closure may outlive the enclosing function
+                    let init = inputs(body, LocalKind::Capture)
+                        .into_iter()
+                        .zip(captures)
+                        .map(|((var, _), capture)| Ok(format!(".{var} = {}", self.operand(capture, frame, program)?)))
+                        .collect::<Result <Vec <_>, CheckError>>()?;
+
+                    format!("{name}_env_new((struct {name}_env) {{ {} }})", init.join(", "))
+                };
+
+                format!("({closure}) {{ .call = {name}, .env = {env} }}")
+            }
+        })
+    }
+
+    ///
+    /// Hoists a lambda into a static function receiving its captured variables through `env_`
+    ///
+    fn lambda(&mut self, body: &Body, program: &Program) -> Result <(), CheckError> {
+        let name = self.lambdas[&body.name].clone();
+        let captures = inputs(body, LocalKind::Capture);
+        let mut prelude = String::new();
+
+        if captures.is_empty() {
+            prelude += &format!("{INDENT}(void) env_;\n")
+        } else {
+            let mut fields = String::new();
+
+            for (var, ty) in &captures {
+                let spelled = self.spell_field(ty, Abi::Oko)?;
+                fields += &format!("{INDENT}{};\n", declaration(&spelled, var));
+            }
+
+            self.definitions.push(format!("struct {name}_env {{\n{fields}}};\n"));
+            prelude += &format!("{INDENT}struct {name}_env *env = env_;\n");
+
+            // Not `malloc`, which the program may declare itself with other types
+            let new = format!("static void *{name}_env_new(struct {name}_env env)");
+            self.hoisted_prototypes.push(format!("{new};\n"));
+            self.hoisted.push(format!("{new} {{\n{INDENT}struct {name}_env *copy = __builtin_malloc(sizeof env);\n{INDENT}*copy = env;\n{INDENT}return copy;\n}}\n"))
+        }
+
+        let ret = self.return_type(Some(&body.local(Local::RETURN).ty), Abi::Oko)?;
+        let params = self.param_list(&inputs(body, LocalKind::Param), Abi::Oko, Some("env_"))?;
+        let header = format!("static {}({params})", declaration(&ret, &name));
+
+        let code = self.body(body, program)?;
+
+        self.hoisted_prototypes.push(format!("{header};\n"));
+        self.hoisted.push(format!("{header} {{\n{prelude}{code}}}\n"));
+
+        Ok(())
+    }
+
+    ///
+    /// Lowers a named function used as a value to a closure, wrapping it to accept an environment
+    ///
+    fn fn_value(&mut self, name: &str, sig: &Signature, span: Span) -> Result <String, CheckError> {
+        let closure = self.spell_field(&fn_type(sig, span), Abi::Oko)?;
+        let thunk = format!("oko_thunk_{name}");
+
+        if self.thunks.insert(thunk.clone()) {
+            let abi = if self.externs.contains_key(name) { Abi::C } else { Abi::Oko };
+
+            if abi == Abi::C && sig.types().any(|ty| matches!(ty.kind, TypeKind::Fn(_))) {
+                return Err(unsupported(span, "using an extern function with callbacks as a value"))
+            }
+
+            if sig.variadic.is_some() {
+                return Err(unsupported(span, "using a variadic function as a value"))
+            }
+
+            let ret = self.return_type(sig.return_ty.as_ref(), Abi::Oko)?;
+            let params = self.param_list(&params(sig), Abi::Oko, Some("env"))?;
+            let header = format!("static {}({params})", declaration(&ret, &thunk));
+
+            let args = visit::params(sig).into_iter().map(|(name, _)| name).collect::<Vec <_>>().join(", ");
+            let call = format!("{}({args})", self.symbol(name));
+            let body = if ret == "void" {
+                format!("{INDENT}{call};\n")
+            } else {
+                format!("{INDENT}return {call};\n")
+            };
+
+            self.hoisted_prototypes.push(format!("{header};\n"));
+            self.hoisted.push(format!("{header} {{\n{INDENT}(void) env;\n{body}}}\n"))
+        }
+
+        Ok(format!("({closure}) {{ .call = {thunk}, .env = NULL }}"))
+    }
+
+    ///
+    /// Wraps a lambda which captures nothing into a plain C function of the signature `sig`, returning its name
+    ///
+    fn callback(&mut self, lambda: &str, sig: &Signature, program: &Program) -> Result <String, CheckError> {
+        let name = self.lambdas[lambda].clone();
+        let wrapper = format!("{name}_c");
+
+        if self.thunks.insert(wrapper.clone()) {
+            let params = inputs(program.body(lambda).expect("closures are of lowered lambdas"), LocalKind::Param);
+
+            let ret = self.return_type(sig.return_ty.as_ref(), Abi::C)?;
+            let param_list = self.param_list(&params, Abi::C, None)?;
+            let header = format!("static {}({param_list})", declaration(&ret, &wrapper));
+
+            let mut args = vec![String::from("NULL")];
+            args.extend(params.into_iter().map(|(name, _)| name));
+
+            let call = format!("{name}({})", args.join(", "));
+            let body = if ret == "void" {
+                format!("{INDENT}{call};\n")
+            } else {
+                format!("{INDENT}return {call};\n")
+            };
+
+            self.hoisted_prototypes.push(format!("{header};\n"));
+            self.hoisted.push(format!("{header} {{\n{body}}}\n"))
+        }
+
+        Ok(wrapper)
+    }
+
+    ///
+    /// Writes an argument of an `extern clang` function, passing functions as plain C function pointers
+    ///
+    fn c_arg(&mut self, arg: &Operand, param: &Type, frame: &mut Frame, program: &Program) -> Result <String, CheckError> {
+        let TypeKind::Fn(sig) = &param.kind else {
+            return self.operand(arg, frame, program)
+        };
+
+        if !param.is_pure() {
+            return self.operand(arg, frame, program)
+        }
+
+        match arg {
+            Operand::Const(Constant::Fn(name)) => Ok(self.symbol(name).to_string()),
+            Operand::Move(place) if frame.closures.contains_key(&place.local) => {
+                // Only the lambda is passed, its closure is dropped
+                frame.local(place.local);
+                self.callback(frame.closures[&place.local], sig, program)
+            },
+            _ => unreachable!("C callbacks are checked before the code generation")
+        }
+    }
+
+    ///
+    /// Writes a call of a named function or of a closure, which receives its environment first
+    ///
+    fn call(&mut self, callee: &Operand, args: &[Operand], frame: &mut Frame, program: &Program) -> Result <String, CheckError> {
+        let Operand::Const(Constant::Fn(name)) = callee else {
+            let closure = self.operand(callee, frame, program)?;
+            let mut args = args.iter().map(|arg| self.operand(arg, frame, program)).collect::<Result <Vec <_>, _>>()?;
+            args.insert(0, format!("{closure}.env"));
+
+            return Ok(format!("{closure}.call({})", args.join(", ")))
+        };
+
+        let sig = self.fns[name.as_str()];
+        let params = if self.externs.contains_key(name.as_str()) {
+            sig.param_types()
+        } else {
+            vec![]
+        };
+
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(idx, arg)| match params.get(idx) {
+                Some(param) => self.c_arg(arg, param, frame, program),
+                None => self.operand(arg, frame, program)
+            })
+            .collect::<Result <Vec <_>, _>>()?;
+
+        Ok(format!("{}({})", self.symbol(name), args.join(", ")))
+    }
+
+    ///
+    /// Writes a temporary which is read again later into a variable, or as a statement if it holds no value
+    ///
+    fn materialize(&mut self, local: Local, value: String, frame: &mut Frame) -> Result <(), CheckError> {
+        let ty = &frame.body.local(local).ty;
+
+        if is_unit(ty) {
+            frame.voids.insert(local);
+            frame.code += &format!("{INDENT}{value};\n")
+        } else {
+            frame.code += &format!("{INDENT}{} = {value};\n", declaration(&self.spell_field(ty, Abi::Oko)?, &format!("{local:?}")))
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Writes the temporaries whose values are not written yet into variables, so they are evaluated before what follows
+    ///
+    fn flush(&mut self, frame: &mut Frame) -> Result <(), CheckError> {
+        for (local, value) in core::mem::take(&mut frame.pending) {
+            self.materialize(local, value, frame)?
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Assigns a value to a local: the value of `_0` is returned, a temporary read once is written where it is read
+    ///   and one never read is a statement
+    ///
+    fn assign(&mut self, local: Local, value: String, frame: &mut Frame) -> Result <(), CheckError> {
+        match (frame.body.local(local).kind, frame.reads[local.0]) {
+            (LocalKind::Return, _) => frame.returned = Some(value),
+            (_, 0) => {
+                self.flush(frame)?;
+                frame.code += &format!("{INDENT}{value};\n")
+            },
+            (_, 1) => frame.pending.push((local, value)),
+            _ => {
+                self.flush(frame)?;
+                self.materialize(local, value, frame)?
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Generates the statements of a body. Its blocks follow each other, every one continuing in the next
+    ///
+    fn body(&mut self, body: &Body, program: &Program) -> Result <String, CheckError> {
+        let mut frame = Frame::new(body);
+
+        for block in &body.blocks {
+            for statement in &block.statements {
+                // The lowering only assigns to locals
+                let local = statement.place.local;
+                let value = self.rvalue(&statement.rvalue, &body.local(local).ty, &mut frame, program)?;
+
+                if let Rvalue::Closure(lambda, _) = &statement.rvalue {
+                    frame.closures.insert(local, lambda);
+                }
+
+                self.assign(local, value, &mut frame)?
+            }
+
+            match &block.terminator {
+                Some(Terminator::Call { callee, args, destination, .. }) => {
+                    let value = self.call(callee, args, &mut frame, program)?;
+                    self.assign(destination.local, value, &mut frame)?
+                },
+                Some(Terminator::Return) => {
+                    self.flush(&mut frame)?;
+
+                    if let Some(value) = frame.returned.take().filter(|_| !is_unit(&body.local(Local::RETURN).ty)) {
+                        frame.code += &format!("{INDENT}return {value};\n")
+                    }
+                },
+                Some(Terminator::Goto(_)) | None => ()
+            }
+        }
+
+        Ok(frame.code)
+    }
+
+    ///
+    /// Defines the types in `forward`, returning their declarations, the function pointer typedefs and the definitions
+    ///
+    fn types(&mut self) -> Result <[String; 3], CheckError> {
+        let mut idx = 0;
+
+        // Defining a struct may discover more composites, so `forward` grows while being iterated
+        while idx < self.forward.len() {
+            let name = self.forward[idx].clone();
+            self.define(&name)?;
+            idx += 1
+        }
+
+        let forward = self.forward
+            .iter()
+            .map(|name| format!("typedef {} {name} {name};\n", self.keyword(name)))
+            .collect::<String>();
+
+        Ok([forward, self.fn_pointers.concat(), self.definitions.join("\n")])
+    }
+
+    fn function(&mut self, fun: &FnStmt, program: &Program) -> Result <String, CheckError> {
+        let prototype = format!("{}{}", convention(fun.calling_convention()), self.prototype(self.symbol(&fun.name.name), &fun.sig, Abi::Oko)?);
+        let body = self.body(program.body(&fun.name.name).expect("every function is lowered to MIR"), program)?;
+
+        Ok(format!("{prototype} {{\n{body}}}\n"))
+    }
+}
+
+///
+/// Generates a C translation unit from monomorphized statements and the MIR they are lowered to.
+///   The declarations come from the statements and the bodies of the functions and the lambdas from the MIR
+///
+/// Items declared inside worlds are emitted alongside the top-level ones. Macros, channels and constants produce no code
+///
+pub fn generate(stmts: &[Stmt], program: &Program) -> Result <String, CheckError> {
+    let mut generator = Generator::default();
+    let (mut fns, mut externs) = (vec![], vec![]);
+
+    generator.collect(stmts, &mut fns, &mut externs);
+
+    // The lambdas are numbered in the order of their bodies
+    for body in program.bodies.iter().filter(|body| !generator.fns.contains_key(body.name.as_str())) {
+        let name = format!("oko_lambda_{}", generator.lambdas.len());
+        generator.lambdas.insert(body.name.clone(), name);
+    }
+
+    let mut prototypes = vec![];
+
+    for fun in externs {
+        prototypes.push(format!("extern {}{};\n", convention(fun.calling_convention()), generator.prototype(fun.link_name(), &fun.sig, Abi::C)?))
+    }
+
+    for fun in &fns {
+        prototypes.push(format!("{}{};\n", convention(fun.calling_convention()), generator.prototype(generator.symbol(&fun.name.name), &fun.sig, Abi::Oko)?))
+    }
+
+    let mut functions = fns.iter().map(|fun| generator.function(fun, program)).collect::<Result <Vec <_>, _>>()?;
+
+    for body in &program.bodies {
+        if generator.lambdas.contains_key(&body.name) {
+            generator.lambda(body, program)?
+        }
+    }
+
+    let [forward, fn_pointers, definitions] = generator.types()?;
+
+    prototypes.append(&mut generator.hoisted_prototypes);
+    functions.append(&mut generator.hoisted);
+
+    Ok([
+        String::from(PRELUDE),
+        forward,
+        fn_pointers,
+        definitions,
+        prototypes.concat(),
+        functions.join("\n")
+    ].into_iter().filter(|part| !part.is_empty()).collect::<Vec <_>>().join("\n"))
+}
+
+///
+/// Collects the `ty`s a type refers to, also through pointers and the fields of the `ty`s
+///
+pub fn reachable <'a> (ty: &'a Type, tys: &HashMap <&'a str, &'a TyStmt>, found: &mut Vec <String>) {
+    match &ty.kind {
+        TypeKind::Named(named) => if let Some(decl) = tys.get(named.name.last().name.as_str()) {
+            if found.contains(&decl.name.name) {
+                return
+            }
+
+            found.push(decl.name.name.clone());
+
+            match &decl.body {
+                TyStmtBody::Enum(body) => body.fields.0
+                    .iter()
+                    .filter_map(|variant| variant.attached_type.as_ref())
+                    .for_each(|ty| reachable(ty, tys, found)),
+                _ => decl.body
+                    .fields()
+                    .unwrap_or_default()
+                    .iter()
+                    .for_each(|field| reachable(&field.ty, tys, found))
+            }
+        },
+        TypeKind::Indirect(_, inner) | TypeKind::Array(inner, _) | TypeKind::Slice(inner) => reachable(inner, tys, found),
+        TypeKind::Tuple(elems) => elems.0.iter().for_each(|elem| reachable(elem, tys, found)),
+        TypeKind::Fn(sig) => sig.types().for_each(|ty| reachable(ty, tys, found))
+    }
+}
+
+///
+/// Generates a C header declaring the functions exported by `extern clang fn` and defining the `ty`s they use,
+///   or nothing if no function is exported. `name` names the include guard
+///
+pub fn header(stmts: &[Stmt], name: &str) -> Result <Option <String>, CheckError> {
+    let mut generator = Generator::default();
+    let (mut fns, mut externs) = (vec![], vec![]);
+
+    generator.collect(stmts, &mut fns, &mut externs);
+    fns.retain(|fun| fun.export == Some(FFILanguage::C));
+
+    if fns.is_empty() {
+        return Ok(None)
+    }
+
+    let mut found = vec![];
+
+    for fun in &fns {
+        fun.sig.types().for_each(|ty| reachable(ty, &generator.tys, &mut found))
+    }
+
+    generator.forward = found;
+
+    let prototypes = fns
+        .iter()
+        .map(|fun| Ok(format!("{}{};\n", convention(fun.calling_convention()), generator.prototype(generator.symbol(&fun.name.name), &fun.sig, Abi::C)?)))
+        .collect::<Result <String, CheckError>>()?;
+
+    let [forward, fn_pointers, definitions] = generator.types()?;
+    let guard = format!("{}_H", name.to_ascii_uppercase());
+
+    Ok(Some([
+        format!("#ifndef {guard}\n#define {guard}\n"),
+        String::from(PRELUDE),
+        String::from("#ifdef __cplusplus\nextern \"C\" {\n#endif\n"),
+        forward,
+        fn_pointers,
+        definitions,
+        prototypes,
+        String::from("#ifdef __cplusplus\n}\n#endif\n"),
+        String::from("#endif\n")
+    ].into_iter().filter(|part| !part.is_empty()).collect::<Vec <_>>().join("\n")))
+}
@@ -0,0 +1,6 @@
+//!
+//! This module turns checked and monomorphized statements into code in other languages
+//!
+
+pub mod c;
+pub mod rust;
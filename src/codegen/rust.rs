@@ -0,0 +1,287 @@
+//!
+//! The Rust bindings of the functions exported by `extern rust fn`
+//!
+//! The functions are defined by the C backend with the C ABI. The bindings declare them in `extern` blocks
+//!   and wrap each in a shim taking and returning Rust types, and define a `#[repr(C)]` struct or union
+//!   for every `ty` they use. A shim which takes a raw pointer is `unsafe`, as oko reads through it.
+//!
+//! References are passed as references, but returned and stored in fields as raw pointers,
+//!   so the bindings need no lifetimes
+//!
+
+use std::collections::HashMap;
+use crate::check::layout::layout;
+use crate::check::CheckError;
+use crate::driver::written;
+use crate::parse::stmt::{FFILanguage, FnStmt, Stmt, TyStmt, TyStmtBody};
+use crate::parse::ty::{IndirectionKind, Type, TypeKind};
+use crate::check::types::{is_unit, is_void};
+use crate::check::visit::params;
+use super::c::reachable;
+
+const INDENT: &str = "    ";
+
+///
+/// Words which can only be used as names of fields and parameters in Rust as raw identifiers
+///
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "continue", "dyn", "else", "enum", "false", "fn", "if", "in", "let", "loop",
+    "match", "move", "ref", "return", "static", "struct", "true", "type", "unsafe", "use", "where", "while", "yield"
+];
+
+///
+/// Where a type is written, which decides whether a reference stays one
+///
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Position {
+    Param,
+    Elsewhere
+}
+
+fn builtin(name: &str) -> Option <&'static str> {
+    Some(match name {
+        "i8" => "i8",
+        "i16" => "i16",
+        "i32" => "i32",
+        "i64" => "i64",
+        "u8" => "u8",
+        "u16" => "u16",
+        "u32" => "u32",
+        "u64" => "u64",
+        "isize" => "isize",
+        "usize" => "usize",
+        "int" => "core::ffi::c_int",
+        "float" | "f32" => "f32",
+        "f64" => "f64",
+        "bool" => "bool",
+        "char" | "str" => "core::ffi::c_char",
+        _ => return None
+    })
+}
+
+fn ident(name: &str) -> String {
+    match name {
+        "crate" | "super" | "Self" => format!("{name}_"),
+        name if KEYWORDS.contains(&name) => format!("r#{name}"),
+        name => name.to_string()
+    }
+}
+
+///
+/// The ABI of an `extern` block for a calling convention of `#[callconv(...)]`
+///
+fn abi(convention: Option <&str>) -> &str {
+    match convention {
+        Some("ms_abi") => "win64",
+        Some("sysv_abi") => "sysv64",
+        Some(convention) => convention,
+        None => "C"
+    }
+}
+
+///
+/// Whether passing the type lets oko read through a raw pointer
+///
+fn has_pointer(ty: &Type) -> bool {
+    match &ty.kind {
+        TypeKind::Indirect(indirection, _) if indirection.kind == IndirectionKind::Ptr => true,
+        TypeKind::Indirect(_, inner) => has_pointer(inner),
+        _ => false
+    }
+}
+
+fn spell(ty: &Type, position: Position, tys: &HashMap <&str, &TyStmt>) -> Result <String, CheckError> {
+    let no_equivalent = |help: &str| CheckError {
+        span: ty.span,
+        message: format!("`{ty:?}` has no Rust equivalent"),
+        clarifying: String::from("in a type used by Rust"),
+        help: vec![String::from(help)]
+    };
+
+    match &ty.kind {
+        TypeKind::Named(named) => {
+            let name = &named.name.last().name;
+
+            match builtin(name) {
+                Some(builtin) => Ok(String::from(builtin)),
+                None if tys.contains_key(name.as_str()) => Ok(name.clone()),
+                None => Err(CheckError {
+                    span: ty.span,
+                    message: format!("cannot find type `{name}`"),
+                    clarifying: String::from("not found"),
+                    help: vec![]
+                })
+            }
+        },
+        TypeKind::Indirect(_, inner) if matches!(inner.kind, TypeKind::Slice(_)) => Err(no_equivalent("use a pointer to the first element and the length")),
+        TypeKind::Indirect(indirection, inner) => {
+            let inner = if is_void(inner) {
+                String::from("core::ffi::c_void")
+            } else {
+                spell(inner, Position::Elsewhere, tys)?
+            };
+
+            Ok(match (indirection.kind, indirection.is_mutable) {
+                (IndirectionKind::Ref, false) if position == Position::Param => format!("&{inner}"),
+                (IndirectionKind::Ref, true) if position == Position::Param => format!("&mut {inner}"),
+                (_, false) => format!("*const {inner}"),
+                (_, true) => format!("*mut {inner}")
+            })
+        },
+        TypeKind::Array(elem, len) => Ok(format!("[{}; {len}]", spell(elem, Position::Elsewhere, tys)?)),
+        TypeKind::Slice(_) => Err(no_equivalent("use a pointer to the first element and the length")),
+        TypeKind::Tuple(_) => Err(no_equivalent("use a `#[repr(C)]` struct")),
+        TypeKind::Fn(_) => Err(no_equivalent("oko functions carry their captured variables, which Rust cannot receive"))
+    }
+}
+
+fn fields(decl: &TyStmt, tys: &HashMap <&str, &TyStmt>) -> Result <String, CheckError> {
+    let mut body = String::new();
+
+    for field in decl.body.fields().unwrap_or_default() {
+        let ty = spell(&field.ty, Position::Elsewhere, tys)?;
+        body += &field.names.iter().map(|name| format!("{INDENT}pub {}: {ty},\n", ident(&name.name))).collect::<String>()
+    }
+
+    Ok(body)
+}
+
+///
+/// Defines a `ty` as a `#[repr(C)]` struct or union. An enum is a struct of its tag and a union of the attached values,
+///   as the C backend lays it out, and its tags are constants of the struct
+///
+fn definition(decl: &TyStmt, tys: &HashMap <&str, &TyStmt>) -> Result <String, CheckError> {
+    let name = &decl.name.name;
+    let layout = layout(decl)?;
+    let mut reprs = vec![String::from("C")];
+
+    if layout.is_packed {
+        reprs.push(String::from("packed"))
+    }
+
+    if let Some(align) = layout.align {
+        reprs.push(format!("align({align})"))
+    }
+
+    let attributes = format!("#[repr({})]\n#[derive(Clone, Copy)]\n", reprs.join(", "));
+
+    let TyStmtBody::Enum(body) = &decl.body else {
+        let keyword = if matches!(decl.body, TyStmtBody::Union(_)) { "union" } else { "struct" };
+        return Ok(format!("{attributes}pub {keyword} {name} {{\n{}}}\n", fields(decl, tys)?))
+    };
+
+    let mut tags = String::new();
+    let mut attached = String::new();
+    let mut next = 0;
+
+    for variant in &body.fields.0 {
+        let value = variant.discriminant.as_ref().map_or(next, |discriminant| discriminant.value);
+        tags += &format!("{INDENT}pub const {}: core::ffi::c_uint = {value};\n", variant.name.name);
+        next = value + 1;
+
+        if let Some(ty) = &variant.attached_type {
+            attached += &format!("{INDENT}pub {}: {},\n", ident(&variant.name.name), spell(ty, Position::Elsewhere, tys)?)
+        }
+    }
+
+    let mut definition = if attached.is_empty() {
+        format!("{attributes}pub struct {name} {{\n{INDENT}pub tag: core::ffi::c_uint,\n}}\n")
+    } else {
+        format!(
+            "{attributes}pub struct {name} {{\n{INDENT}pub tag: core::ffi::c_uint,\n{INDENT}pub value: {name}Value,\n}}\n\n\
+            #[repr(C)]\n#[derive(Clone, Copy)]\npub union {name}Value {{\n{attached}}}\n"
+        )
+    };
+
+    definition += &format!("\nimpl {name} {{\n{tags}}}\n");
+
+    Ok(definition)
+}
+
+///
+/// Declares an exported function in an `extern` block and wraps it in a shim named as the function is in its module
+///
+fn shim(fun: &FnStmt, tys: &HashMap <&str, &TyStmt>) -> Result <(String, String), CheckError> {
+    let symbol = fun.link_name().unwrap_or(written(&fun.name.name));
+    let mut args = vec![];
+    let mut names = vec![];
+
+    for (name, ty) in params(&fun.sig) {
+        args.push(format!("{}: {}", ident(&name), spell(ty, Position::Param, tys)?));
+        names.push(ident(&name))
+    }
+
+    let ret = match &fun.sig.return_ty {
+        Some(ty) if !is_unit(ty) => format!(" -> {}", spell(ty, Position::Elsewhere, tys)?),
+        _ => String::new()
+    };
+
+    let args = args.join(", ");
+    let declaration = format!(
+        "{INDENT}unsafe extern \"{}\" {{\n{INDENT}{INDENT}pub fn {symbol}({args}){ret};\n{INDENT}}}\n",
+        abi(fun.calling_convention())
+    );
+
+    let safety = if fun.sig.param_types().into_iter().any(has_pointer) { "unsafe " } else { "" };
+    let shim = format!(
+        "pub {safety}fn {}({args}){ret} {{\n{INDENT}unsafe {{ ffi::{symbol}({}) }}\n}}\n",
+        ident(written(&fun.name.name)),
+        names.join(", ")
+    );
+
+    Ok((declaration, shim))
+}
+
+fn collect <'a> (stmts: &'a [Stmt], tys: &mut HashMap <&'a str, &'a TyStmt>, fns: &mut Vec <&'a FnStmt>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Ty(ty) => {
+                tys.insert(&ty.name.name, ty);
+            },
+            Stmt::Fn(fun) if fun.export == Some(FFILanguage::Rust) => fns.push(fun),
+            Stmt::World(world) => collect(&world.body, tys, fns),
+            _ => ()
+        }
+    }
+}
+
+///
+/// Generates a Rust module binding the functions exported by `extern rust fn`, or nothing if no function is.
+///   `name` is the name of the package, mentioned in the documentation of the module
+///
+pub fn bindings(stmts: &[Stmt], name: &str) -> Result <Option <String>, CheckError> {
+    let mut tys = HashMap::new();
+    let mut fns = vec![];
+
+    collect(stmts, &mut tys, &mut fns);
+
+    if fns.is_empty() {
+        return Ok(None)
+    }
+
+    let mut found = vec![];
+
+    for fun in &fns {
+        fun.sig.types().for_each(|ty| reachable(ty, &tys, &mut found))
+    }
+
+    let definitions = found
+        .iter()
+        .map(|name| definition(tys[name.as_str()], &tys))
+        .collect::<Result <Vec <_>, _>>()?;
+
+    let (declarations, shims): (Vec <_>, Vec <_>) = fns
+        .iter()
+        .map(|fun| shim(fun, &tys))
+        .collect::<Result <Vec <_>, _>>()?
+        .into_iter()
+        .unzip();
+
+    Ok(Some([
+        format!("//!\n//! The functions the oko package `{name}` exports to Rust, generated by `oko build`\n//!\n\n\
+            #![allow(non_camel_case_types, non_snake_case, non_upper_case_globals, dead_code, unused_imports)]\n"),
+        definitions.join("\n"),
+        format!("mod ffi {{\n{INDENT}use super::*;\n\n{}}}\n", declarations.join("\n")),
+        shims.join("\n")
+    ].into_iter().filter(|part| !part.is_empty()).collect::<Vec <_>>().join("\n")))
+}
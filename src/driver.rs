@@ -0,0 +1,1067 @@
+//!
+//! This module loads a program made of several files
+//!
+//! Every file is a module. `mod math` in `src/main.oko` declares the module stored in `src/math.oko`
+//!   (or `src/math/mod.oko`), so the files form a graph: a module declared in several places is still loaded once.
+//!
+//! Once loaded, the modules are flattened into a single list of statements: the items of every module
+//!   except the root one are renamed after the module (`math::length` becomes `math__length`)
+//!   and every path is replaced with the name it refers to.
+//!   Identifiers cannot contain `_`, so the new names never clash with user names.
+//!   The items of a dependency are also prefixed with the name of its package (`geometry__math__length`),
+//!   and the items of a world with the name of the world (`Game__Health`), so several worlds can declare items of the same names
+//!
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path as FsPath, PathBuf};
+use crate::check::traits::associated_name;
+use crate::check::visit::{for_each_body_mut, for_each_call_mut, for_each_signature_mut, for_each_type_mut};
+use crate::check::CheckError;
+use crate::error::{Error, Result};
+use crate::package::Package;
+use crate::parse::comments::remove_non_documenting_comments;
+use crate::parse::expr::{BlockExpr, Expr, FieldValue, StructExpr};
+use crate::parse::path::Path;
+use crate::parse::punctuated::Punctuated;
+use crate::parse::signature::{Arg, Signature};
+use crate::parse::span::Ident;
+use crate::parse::stmt::{MacroStmtBody, Stmt, TyStmtBody, UseStmt};
+use crate::parse::ty::{NamedType, Type, TypeKind, SELF};
+use crate::parse::x_times::Times;
+
+pub const EXTENSION: &str = "oko";
+
+///
+/// The separator of the module names and the item name in the name of an item of a module
+///
+const SEPARATOR: &str = "__";
+
+pub struct SourceFile {
+    pub filename: String,
+    pub code: String
+}
+
+///
+/// All the files of a program, indexed by [`crate::span::CursorPosition::file`]
+///
+#[derive(Default)]
+pub struct Sources {
+    pub files: Vec <SourceFile>
+}
+
+impl Sources {
+    ///
+    /// Turns a check error into a printable one, picking the file it occurred in
+    ///
+    pub fn error(&self, err: CheckError) -> Error {
+        let file = &self.files[err.span.start.file];
+        err.to_error(&file.code, file.filename.clone())
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum ItemKind {
+    Ty,
+    Fn,
+    Const,
+    World,
+    Trait
+}
+
+impl ItemKind {
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Ty => "type",
+            Self::Fn => "function",
+            Self::Const => "constant",
+            Self::World => "world",
+            Self::Trait => "trait"
+        }
+    }
+}
+
+///
+/// What a name refers to
+///
+#[derive(Clone)]
+enum Binding {
+    Module(usize),
+    Item {
+        kind: ItemKind,
+
+        ///
+        /// The name of the item in the flattened program
+        ///
+        name: String
+    }
+}
+
+impl Binding {
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Module(_) => "module",
+            Self::Item { kind, .. } => kind.describe()
+        }
+    }
+}
+
+struct Declared {
+    binding: Binding,
+    is_pub: bool
+}
+
+struct Module {
+    ///
+    /// Prepended to the names of the items: `math__`, empty for the root module
+    ///
+    prefix: String,
+
+    ///
+    /// The index of the package the module belongs to
+    ///
+    package: usize,
+
+    stmts: Vec <Stmt>,
+    items: HashMap <String, Declared>,
+    uses: Vec <UseStmt>
+}
+
+fn not_found(ident: &Ident, place: Option <&Ident>) -> CheckError {
+    CheckError {
+        span: ident.span(),
+        message: match place {
+            Some(module) => format!("cannot find `{}` in module `{}`", ident.name, module.name),
+            None => format!("cannot find `{}`", ident.name)
+        },
+        clarifying: String::from("not found"),
+        help: vec![]
+    }
+}
+
+fn unexpected(path: &Path, binding: &Binding, expected: &str) -> CheckError {
+    CheckError {
+        span: path.span(),
+        message: format!("expected {expected}, found {} `{path:?}`", binding.describe()),
+        clarifying: format!("not a {expected}"),
+        help: vec![]
+    }
+}
+
+fn rename(ident: &Ident, name: &str) -> Ident {
+    Ident::new(name.to_string(), ident.span().start)
+}
+
+#[derive(Default)]
+struct Loader {
+    sources: Sources,
+    modules: Vec <Module>,
+    by_path: HashMap <PathBuf, usize>,
+
+    ///
+    /// The files being loaded, to report cycles: the canonical paths and the paths as written
+    ///
+    loading: Vec <(PathBuf, PathBuf)>,
+
+    ///
+    /// The directory of the entry file of the package being loaded and the prefix of its items
+    ///
+    root_dir: PathBuf,
+    root_prefix: String,
+    package: usize,
+
+    ///
+    /// The root modules of the dependencies of every package, by the names the package gives them
+    ///
+    dependencies: Vec <HashMap <String, usize>>,
+
+    ///
+    /// The bindings of the `use` statements already resolved, by the module and the index of the import
+    ///
+    resolved: HashMap <(usize, usize), Binding>,
+
+    ///
+    /// The `use` statements being resolved, to report cycles
+    ///
+    resolving: Vec <(usize, usize)>,
+
+    ///
+    /// The variants of every enum by its name in the flattened program, with whether they take a value
+    ///
+    variants: HashMap <String, Vec <(String, bool)>>
+}
+
+impl Loader {
+    fn read(&mut self, path: &FsPath) -> Result <Vec <Stmt>> {
+        let filename = path.display().to_string();
+        let code = match std::fs::read_to_string(path) {
+            Ok(code) => remove_non_documenting_comments(code),
+            Err(err) => return Result(Err(Error::unreadable(filename, &err)))
+        };
+        let file = self.sources.files.len();
+        let stmts = crate::parse::parse_file(&code, &filename, file);
+
+        self.sources.files.push(SourceFile {
+            filename,
+            code
+        });
+
+        stmts
+    }
+
+    fn prefix(&self, path: &FsPath) -> String {
+        let relative = path.strip_prefix(&self.root_dir).unwrap_or(path).with_extension("");
+        let mut segments = relative.iter().map(|segment| segment.to_string_lossy().into_owned()).collect::<Vec <_>>();
+
+        if segments.last().is_some_and(|last| last == "mod") {
+            segments.pop();
+        }
+
+        segments.iter().fold(self.root_prefix.clone(), |prefix, segment| format!("{prefix}{segment}{SEPARATOR}"))
+    }
+
+    ///
+    /// Loads the module stored in `path` with all the modules it declares
+    ///
+    fn load(&mut self, path: PathBuf, is_root: bool) -> Result <usize> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+        if let Some(&idx) = self.by_path.get(&canonical) {
+            return Result(Ok(idx))
+        }
+
+        let stmts = self.read(&path)?;
+        let idx = self.modules.len();
+
+        self.modules.push(Module {
+            prefix: if is_root { self.root_prefix.clone() } else { self.prefix(&path) },
+            package: self.package,
+            stmts: vec![],
+            items: HashMap::new(),
+            uses: vec![]
+        });
+
+        self.by_path.insert(canonical.clone(), idx);
+        self.loading.push((canonical, path.clone()));
+
+        let dir = path.parent().map(FsPath::to_path_buf).unwrap_or_default();
+        let prefix = self.modules[idx].prefix.clone();
+        let mut items = HashMap::new();
+
+        for stmt in &stmts {
+            let (name, declared) = match stmt {
+                Stmt::Mod(module) => {
+                    let candidates = [
+                        dir.join(format!("{}.{EXTENSION}", module.name.name)),
+                        dir.join(&module.name.name).join(format!("mod.{EXTENSION}"))
+                    ];
+
+                    let Some(child) = candidates.iter().find(|candidate| candidate.is_file()) else {
+                        return Result(Err(self.sources.error(CheckError {
+                            span: module.name.span(),
+                            message: format!("cannot find module `{}`", module.name.name),
+                            clarifying: String::from("no file for the module"),
+                            help: vec![format!("create `{}` or `{}`", candidates[0].display(), candidates[1].display())]
+                        })))
+                    };
+
+                    let canonical = child.canonicalize().unwrap_or_else(|_| child.clone());
+
+                    if let Some(start) = self.loading.iter().position(|(loading, _)| *loading == canonical) {
+                        let cycle = self.loading[start..]
+                            .iter()
+                            .map(|(_, path)| path)
+                            .chain([child])
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec <_>>();
+
+                        return Result(Err(self.sources.error(CheckError {
+                            span: module.name.span(),
+                            message: format!("module `{}` depends on itself", module.name.name),
+                            clarifying: String::from("import cycle"),
+                            help: vec![format!("the cycle is {}", cycle.join(" -> "))]
+                        })))
+                    }
+
+                    let child = self.load(child.clone(), false)?;
+
+                    (&module.name, Declared {
+                        binding: Binding::Module(child),
+                        is_pub: module.is_pub
+                    })
+                },
+                Stmt::Ty(ty) => (&ty.name, Declared {
+                    binding: Binding::Item { kind: ItemKind::Ty, name: format!("{prefix}{}", ty.name.name) },
+                    is_pub: ty.is_pub
+                }),
+                Stmt::Fn(fun) => (&fun.name, Declared {
+                    binding: Binding::Item { kind: ItemKind::Fn, name: format!("{prefix}{}", fun.name.name) },
+                    is_pub: fun.is_pub
+                }),
+                // Foreign functions keep their names to be linked
+                Stmt::ExternFn(fun) => (&fun.name, Declared {
+                    binding: Binding::Item { kind: ItemKind::Fn, name: fun.name.name.clone() },
+                    is_pub: fun.is_pub
+                }),
+                Stmt::Const(constant) => (&constant.name, Declared {
+                    binding: Binding::Item { kind: ItemKind::Const, name: format!("{prefix}{}", constant.name.name) },
+                    is_pub: constant.is_pub
+                }),
+                Stmt::World(world) => (&world.name, Declared {
+                    binding: Binding::Item { kind: ItemKind::World, name: format!("{prefix}{}", world.name.name) },
+                    is_pub: false
+                }),
+                Stmt::Trait(tr) => (&tr.name, Declared {
+                    binding: Binding::Item { kind: ItemKind::Trait, name: format!("{prefix}{}", tr.name.name) },
+                    is_pub: tr.is_pub
+                }),
+                Stmt::Use(_) | Stmt::Macro(_) | Stmt::Channel(_) | Stmt::Impl(_) => continue
+            };
+
+            let is_module = matches!(declared.binding, Binding::Module(_));
+
+            if let Some(previous) = items.insert(name.name.clone(), declared) {
+                if is_module || matches!(previous.binding, Binding::Module(_)) {
+                    return Result(Err(self.sources.error(CheckError {
+                        span: name.span(),
+                        message: format!("`{}` is declared twice", name.name),
+                        clarifying: String::from("a module and an item cannot share a name"),
+                        help: vec![]
+                    })))
+                }
+            }
+        }
+
+        let mut uses = vec![];
+
+        for stmt in &stmts {
+            if let Stmt::Use(using) = stmt {
+                let name = using.path.last();
+
+                if items.contains_key(&name.name) || uses.iter().any(|other: &UseStmt| other.path.last().name == name.name) {
+                    return Result(Err(self.sources.error(CheckError {
+                        span: name.span(),
+                        message: format!("`{}` is already declared in this module", name.name),
+                        clarifying: String::from("imported here"),
+                        help: vec![]
+                    })))
+                }
+
+                uses.push((**using).clone())
+            }
+        }
+
+        self.loading.pop();
+        self.modules[idx].stmts = stmts;
+        self.modules[idx].items = items;
+        self.modules[idx].uses = uses;
+
+        Result(Ok(idx))
+    }
+
+    ///
+    /// Looks a name up among the items, the modules and the imports of a module, then among the dependencies of its package.
+    ///   From the outside only the `pub` ones are visible
+    ///
+    fn lookup(&mut self, module: usize, name: &Ident, from_outside: bool) -> core::result::Result <Option <Binding>, CheckError> {
+        let private = || CheckError {
+            span: name.span(),
+            message: format!("`{}` is private", name.name),
+            clarifying: String::from("not visible from here"),
+            help: vec![String::from("declare it with `pub`")]
+        };
+
+        if let Some(declared) = self.modules[module].items.get(&name.name) {
+            if from_outside && !declared.is_pub {
+                return Err(private())
+            }
+
+            return Ok(Some(declared.binding.clone()))
+        }
+
+        let using = self.modules[module]
+            .uses
+            .iter()
+            .enumerate()
+            .find(|(_, using)| using.path.last().name == name.name)
+            .map(|(idx, using)| (idx, using.clone()));
+
+        let Some((idx, using)) = using else {
+            if from_outside {
+                return Ok(None)
+            }
+
+            let package = self.modules[module].package;
+            return Ok(self.dependencies[package].get(&name.name).map(|&root| Binding::Module(root)))
+        };
+
+        if from_outside && !using.is_pub {
+            return Err(private())
+        }
+
+        if let Some(binding) = self.resolved.get(&(module, idx)) {
+            return Ok(Some(binding.clone()))
+        }
+
+        if self.resolving.contains(&(module, idx)) {
+            return Err(CheckError {
+                span: using.path.span(),
+                message: format!("the import of `{}` is cyclic", name.name),
+                clarifying: String::from("refers to itself through other imports"),
+                help: vec![]
+            })
+        }
+
+        self.resolving.push((module, idx));
+        let binding = self.resolve(module, &using.path);
+        self.resolving.pop();
+
+        let binding = binding?;
+        self.resolved.insert((module, idx), binding.clone());
+
+        Ok(Some(binding))
+    }
+
+    ///
+    /// Resolves a path written in `module`
+    ///
+    fn resolve(&mut self, module: usize, path: &Path) -> core::result::Result <Binding, CheckError> {
+        let first = &path.segments[0];
+        let mut binding = self.lookup(module, first, false)?.ok_or_else(|| not_found(first, None))?;
+
+        for (previous, segment) in path.segments.iter().zip(&path.segments[1..]) {
+            let Binding::Module(inner) = binding else {
+                return Err(CheckError {
+                    span: previous.span(),
+                    message: format!("`{}` is not a module", previous.name),
+                    clarifying: format!("a {}", binding.describe()),
+                    help: vec![]
+                })
+            };
+
+            binding = self.lookup(inner, segment, true)?.ok_or_else(|| not_found(segment, Some(previous)))?
+        }
+
+        Ok(binding)
+    }
+
+    fn flatten(&mut self) -> core::result::Result <Vec <Stmt>, CheckError> {
+        let mut output = vec![];
+
+        // The imports nothing refers to are resolved as well, so a broken one is still reported
+        for module in 0..self.modules.len() {
+            for idx in 0..self.modules[module].uses.len() {
+                let name = self.modules[module].uses[idx].path.last().clone();
+                self.lookup(module, &name, false)?;
+            }
+        }
+
+        for module in &self.modules {
+            variants(&module.stmts, &module.prefix, &mut self.variants)
+        }
+
+        for module in 0..self.modules.len() {
+            let stmts = core::mem::take(&mut self.modules[module].stmts);
+            let mut resolver = Resolver {
+                loader: self,
+                module,
+                world: HashMap::new(),
+                world_prefix: String::new(),
+                self_ty: None
+            };
+
+            let flattened = resolver.stmts(&stmts);
+            self.modules[module].stmts = stmts;
+
+            output.extend(flattened?)
+        }
+
+        let mut fns = HashSet::new();
+        associated_fns(&output, &mut fns)?;
+        associated_calls(&mut output, &fns, &self.variants)?;
+
+        Ok(output)
+    }
+}
+
+///
+/// Rewrites the statements of one module
+///
+struct Resolver <'a> {
+    loader: &'a mut Loader,
+    module: usize,
+
+    ///
+    /// The items of the world being rewritten, which shadow the items of the module
+    ///
+    world: HashMap <String, Binding>,
+
+    ///
+    /// The prefix of the names of the items of the world being rewritten, `Game__`
+    ///
+    world_prefix: String,
+
+    ///
+    /// The name of the type `Self` stands for inside of the `impl` being rewritten
+    ///
+    self_ty: Option <String>
+}
+
+impl Resolver <'_> {
+    fn name(&self, name: &str) -> String {
+        format!("{}{}{name}", self.loader.modules[self.module].prefix, self.world_prefix)
+    }
+
+    fn lookup(&mut self, path: &Path) -> core::result::Result <Option <Binding>, CheckError> {
+        if !path.is_qualified() {
+            if let Some(binding) = self.world.get(&path.last().name) {
+                return Ok(Some(binding.clone()))
+            }
+
+            return self.loader.lookup(self.module, path.last(), false)
+        }
+
+        self.loader.resolve(self.module, path).map(Some)
+    }
+
+    ///
+    /// Returns the new name of the item a path refers to, `None` if it is not an item declared in the program,
+    ///   like a builtin type or function
+    ///
+    fn item(&mut self, path: &Path, expected: ItemKind) -> core::result::Result <Option <String>, CheckError> {
+        match self.lookup(path)? {
+            Some(Binding::Item { kind, name }) if kind == expected => Ok(Some(name)),
+            Some(binding) => Err(unexpected(path, &binding, expected.describe())),
+            None if path.is_qualified() => Err(not_found(path.last(), None)),
+            None => Ok(None)
+        }
+    }
+
+    ///
+    /// Returns the path `TYPE::IDENT` with the new name of the type if a path refers to a function of a type,
+    ///   `Vec2::new` or `Self::new` inside of an `impl`
+    ///
+    fn associated(&mut self, path: &Path) -> core::result::Result <Option <Path>, CheckError> {
+        let (fun, ty) = path.segments.split_last().expect("a path cannot be empty");
+
+        let Some(last) = ty.last() else {
+            return Ok(None)
+        };
+
+        let name = match (ty, &self.self_ty) {
+            ([ty], Some(self_ty)) if ty.name == SELF => self_ty.clone(),
+            _ => match self.lookup(&Path { segments: ty.to_vec() })? {
+                Some(Binding::Item { kind: ItemKind::Ty, name }) => name,
+                _ => return Ok(None)
+            }
+        };
+
+        Ok(Some(Path {
+            segments: vec![rename(last, &name), fun.clone()]
+        }))
+    }
+
+    ///
+    /// Returns the new name of the enum a path refers to a variant of, with whether the variant takes a value:
+    ///   `Option::Some`, or `Some` if no item is named so and exactly one enum of the world, the module or its imports has such a variant
+    ///
+    fn variant(&mut self, path: &Path) -> core::result::Result <Option <(String, bool)>, CheckError> {
+        let name = &path.last().name;
+        let has_variant = |variants: &HashMap <String, Vec <(String, bool)>>, ty: &str| variants
+            .get(ty)
+            .and_then(|variants| variants.iter().find(|(variant, _)| variant == name))
+            .map(|&(_, has_value)| has_value);
+
+        if path.is_qualified() {
+            let Some(associated) = self.associated(path)? else {
+                return Ok(None)
+            };
+
+            let ty = &associated.segments[0].name;
+            return Ok(has_variant(&self.loader.variants, ty).map(|has_value| (ty.clone(), has_value)))
+        }
+
+        if self.lookup(path)?.is_some() {
+            return Ok(None)
+        }
+
+        let module = &self.loader.modules[self.module];
+        let mut tys = self.world
+            .values()
+            .chain(module.items.values().map(|declared| &declared.binding))
+            .filter_map(|binding| match binding {
+                Binding::Item { kind: ItemKind::Ty, name } => Some(name.clone()),
+                _ => None
+            })
+            .collect::<Vec <_>>();
+
+        let imports = module.uses.iter().map(|using| using.path.last().clone()).collect::<Vec <_>>();
+
+        // A broken import is reported where it is used
+        for import in &imports {
+            if let Ok(Some(Binding::Item { kind: ItemKind::Ty, name })) = self.loader.lookup(self.module, import, false) {
+                tys.push(name)
+            }
+        }
+
+        tys.sort();
+        tys.dedup();
+
+        let mut matching = tys
+            .into_iter()
+            .filter_map(|ty| has_variant(&self.loader.variants, &ty).map(|has_value| (ty, has_value)))
+            .collect::<Vec <_>>();
+
+        if matching.len() > 1 {
+            let enums = matching.iter().map(|(ty, _)| format!("`{}`", written(ty))).collect::<Vec <_>>();
+
+            return Err(CheckError {
+                span: path.span(),
+                message: format!("`{name}` is a variant of several enums: {}", enums.join(", ")),
+                clarifying: String::from("ambiguous variant"),
+                help: vec![format!("write the name of the enum: `{}::{name}`", written(&matching[0].0))]
+            })
+        }
+
+        Ok(matching.pop())
+    }
+
+    ///
+    /// Returns the enum literal a variable or a call constructs, `Option::None` or `Option::Some(5)`
+    ///
+    fn enum_literal(&mut self, expr: &Expr, bound: &HashSet <String>) -> core::result::Result <Option <StructExpr>, CheckError> {
+        let (path, args) = match expr {
+            Expr::Var(var) => (&var.name, None),
+            Expr::Call(call) if call.generics.is_none() => (&call.fun, Some(call.args.0.as_slice())),
+            _ => return Ok(None)
+        };
+
+        if !path.is_qualified() && bound.contains(&path.last().name) {
+            return Ok(None)
+        }
+
+        let Some((ty, has_value)) = self.variant(path)? else {
+            // A value named through an enum can only be one of its variants
+            if let (None, Some(associated)) = (args, self.associated(path)?) {
+                let ty = &associated.segments[0].name;
+
+                if let Some(variants) = self.loader.variants.get(ty) {
+                    return Err(CheckError {
+                        span: path.last().span(),
+                        message: format!("no variant `{}` in enum `{}`", path.last().name, written(ty)),
+                        clarifying: String::from("not found"),
+                        help: vec![format!("the variants of `{}` are {}", written(ty), variants.iter().map(|(variant, _)| format!("`{variant}`")).collect::<Vec <_>>().join(", "))]
+                    })
+                }
+            }
+
+            return Ok(None)
+        };
+
+        let variant = path.last();
+        let value = match (args, has_value) {
+            (Some([value]), true) => value.clone(),
+            (None | Some([]), false) => Expr::Block(Box::new(BlockExpr {
+                expressions: vec![]
+            })),
+            (_, true) => return Err(CheckError {
+                span: path.span(),
+                message: format!("variant `{}` of `{}` takes a value", variant.name, written(&ty)),
+                clarifying: String::from("expected exactly one value"),
+                help: vec![format!("construct it as `{}::{}(...)`", written(&ty), variant.name)]
+            }),
+            (Some(_), false) => return Err(CheckError {
+                span: path.span(),
+                message: format!("variant `{}` of `{}` takes no value", variant.name, written(&ty)),
+                clarifying: String::from("called with values"),
+                help: vec![format!("write it without parentheses: `{}::{}`", written(&ty), variant.name)]
+            })
+        };
+
+        Ok(Some(StructExpr {
+            name: Path::from(rename(&path.segments[0], &ty)),
+            fields: Punctuated(vec![FieldValue {
+                name: variant.clone(),
+                value
+            }])
+        }))
+    }
+
+    fn ty(&mut self, ty: &mut Type, generics: &HashSet <String>) -> core::result::Result <(), CheckError> {
+        for inner in ty.inner_mut() {
+            self.ty(inner, generics)?
+        }
+
+        match &mut ty.kind {
+            TypeKind::Named(NamedType { name, .. }) if name.is_qualified() || !generics.contains(&name.last().name) => {
+                if let Some(renamed) = self.item(name, ItemKind::Ty)? {
+                    *name = Path::from(rename(name.last(), &renamed))
+                }
+
+                Ok(())
+            },
+            TypeKind::Fn(sig) => self.counts(sig),
+            _ => Ok(())
+        }
+    }
+
+    fn counts(&mut self, sig: &mut Signature) -> core::result::Result <(), CheckError> {
+        for arg in &mut sig.args.0 {
+            if let Arg::Unnamed(unnamed) = arg {
+                if let Times::Const(name) = &unnamed.times {
+                    if let Some(renamed) = self.item(&Path::from(name.clone()), ItemKind::Const)? {
+                        unnamed.times = Times::Const(rename(name, &renamed))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &mut Expr, bound: &HashSet <String>) -> core::result::Result <(), CheckError> {
+        if let Some(mut lit) = self.enum_literal(expr, bound)? {
+            lit.fields.0.iter_mut().try_for_each(|field| self.expr(&mut field.value, bound))?;
+            *expr = Expr::Struct(Box::new(lit));
+
+            return Ok(())
+        }
+
+        match expr {
+            Expr::Lambda(lambda) => {
+                let mut bound = bound.clone();
+                bound.extend(params(&lambda.sig));
+                self.expr(&mut lambda.body, &bound)
+            },
+            Expr::Index(index) => {
+                self.expr(&mut index.base, bound)?;
+                self.expr(&mut index.index, bound)
+            },
+            Expr::Field(field) => self.expr(&mut field.base, bound),
+            Expr::MethodCall(call) => {
+                self.expr(&mut call.receiver, bound)?;
+                call.args.0.iter_mut().try_for_each(|arg| self.expr(arg, bound))
+            },
+            Expr::Call(call) => {
+                // `Vec2::new`, otherwise anything but calling a parameter of a function type
+                if let Some(associated) = self.associated(&call.fun)? {
+                    call.fun = associated
+                } else if call.fun.is_qualified() || !bound.contains(&call.fun.last().name) {
+                    if let Some(renamed) = self.item(&call.fun, ItemKind::Fn)? {
+                        call.fun = Path::from(rename(call.fun.last(), &renamed))
+                    }
+                }
+
+                call.args.0.iter_mut().try_for_each(|arg| self.expr(arg, bound))
+            },
+            Expr::Struct(lit) => {
+                if let (false, SELF, Some(self_ty)) = (lit.name.is_qualified(), lit.name.last().name.as_str(), &self.self_ty) {
+                    lit.name = Path::from(rename(lit.name.last(), self_ty))
+                } else if let Some(renamed) = self.item(&lit.name, ItemKind::Ty)? {
+                    lit.name = Path::from(rename(lit.name.last(), &renamed))
+                }
+
+                lit.fields.0.iter_mut().try_for_each(|field| self.expr(&mut field.value, bound))
+            },
+            Expr::Int(_) => Ok(()),
+            Expr::Var(var) => {
+                if var.name.is_qualified() {
+                    if let Some(renamed) = self.item(&var.name, ItemKind::Fn)? {
+                        var.name = Path::from(rename(var.name.last(), &renamed))
+                    }
+                } else if !bound.contains(&var.name.last().name) {
+                    if let Some(Binding::Item { kind: ItemKind::Fn, name }) = self.lookup(&var.name)? {
+                        var.name = Path::from(rename(var.name.last(), &name))
+                    }
+                }
+
+                Ok(())
+            },
+            Expr::Block(block) => block.expressions.iter_mut().try_for_each(|expr| self.expr(expr, bound))
+        }
+    }
+
+    ///
+    /// Rewrites the paths inside of a statement
+    ///
+    fn paths(&mut self, stmt: &mut Stmt) -> core::result::Result <(), CheckError> {
+        let generics = match stmt {
+            Stmt::Ty(ty) => ty.generics.iter().flat_map(|generics| generics.names()).map(str::to_string).collect(),
+            Stmt::Fn(fun) => fun.generics.iter().flat_map(|generics| generics.names()).map(str::to_string).collect(),
+            _ => HashSet::new()
+        };
+
+        let mut result = Ok(());
+
+        for_each_type_mut(stmt, &mut |ty| if result.is_ok() {
+            result = self.ty(ty, &generics)
+        });
+
+        for_each_signature_mut(stmt, &mut |sig| if result.is_ok() {
+            result = self.counts(sig)
+        });
+
+        result?;
+
+        match stmt {
+            Stmt::Fn(fun) => self.expr(&mut fun.body, &params(&fun.sig).collect()),
+            Stmt::Impl(imp) => {
+                if let Some(trait_name) = &mut imp.trait_name {
+                    if let Some(renamed) = self.item(trait_name, ItemKind::Trait)? {
+                        *trait_name = Path::from(rename(trait_name.last(), &renamed))
+                    }
+                }
+
+                self.self_ty = match &imp.ty.kind {
+                    TypeKind::Named(NamedType { name, generics: None }) => Some(name.last().name.clone()),
+                    _ => None
+                };
+
+                let result = imp.fns.iter_mut().try_for_each(|fun| self.expr(&mut fun.body, &params(&fun.sig).collect()));
+                self.self_ty = None;
+
+                result
+            },
+            Stmt::Macro(mac) => match &mut mac.body {
+                MacroStmtBody::Literal(lit) => self.expr(&mut lit.body, &HashSet::new())
+            },
+            Stmt::Channel(channel) => {
+                for end in [&mut channel.from, &mut channel.to] {
+                    if let Some(renamed) = self.item(&Path::from(end.clone()), ItemKind::World)? {
+                        *end = rename(end, &renamed)
+                    }
+                }
+
+                Ok(())
+            },
+            _ => Ok(())
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) -> core::result::Result <Vec <Stmt>, CheckError> {
+        let mut output = vec![];
+
+        for stmt in stmts {
+            let mut stmt = stmt.clone();
+
+            match &mut stmt {
+                Stmt::Mod(_) | Stmt::Use(_) => continue,
+                Stmt::Ty(ty) => ty.name = rename(&ty.name, &self.name(&ty.name.name)),
+                Stmt::Fn(fun) => fun.name = rename(&fun.name, &self.name(&fun.name.name)),
+                Stmt::Const(constant) => constant.name = rename(&constant.name, &self.name(&constant.name.name)),
+                Stmt::Trait(tr) => tr.name = rename(&tr.name, &self.name(&tr.name.name)),
+                Stmt::World(world) => {
+                    let prefix = format!("{}{SEPARATOR}", world.name.name);
+                    world.name = rename(&world.name, &self.name(&world.name.name));
+                    world.body = self.world(&world.body, prefix)?;
+                    output.push(stmt);
+                    continue
+                },
+                Stmt::ExternFn(_) | Stmt::Macro(_) | Stmt::Channel(_) | Stmt::Impl(_) => ()
+            }
+
+            self.paths(&mut stmt)?;
+            output.push(stmt)
+        }
+
+        Ok(output)
+    }
+
+    fn world(&mut self, body: &[Stmt], prefix: String) -> core::result::Result <Vec <Stmt>, CheckError> {
+        self.world_prefix = prefix;
+
+        for stmt in body {
+            let (name, kind) = match stmt {
+                Stmt::Mod(module) => return Err(CheckError {
+                    span: module.name.span(),
+                    message: String::from("a module cannot be declared inside of a world"),
+                    clarifying: String::from("declared here"),
+                    help: vec![String::from("declare it at the top level of the file")]
+                }),
+                Stmt::Use(using) => return Err(CheckError {
+                    span: using.path.span(),
+                    message: String::from("`use` is not allowed inside of a world"),
+                    clarifying: String::from("imported here"),
+                    help: vec![String::from("import it at the top level of the file")]
+                }),
+                Stmt::Ty(ty) => (&ty.name, ItemKind::Ty),
+                Stmt::Fn(fun) => (&fun.name, ItemKind::Fn),
+                Stmt::Const(constant) => (&constant.name, ItemKind::Const),
+                Stmt::Trait(tr) => (&tr.name, ItemKind::Trait),
+                _ => continue
+            };
+
+            self.world.insert(name.name.clone(), Binding::Item {
+                kind,
+                name: self.name(&name.name)
+            });
+        }
+
+        let body = self.stmts(body);
+        self.world.clear();
+        self.world_prefix.clear();
+
+        body
+    }
+}
+
+///
+/// Collects the variants of the enums declared in a module, including its worlds, by the new names of the enums
+///
+fn variants(stmts: &[Stmt], prefix: &str, variants: &mut HashMap <String, Vec <(String, bool)>>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Ty(ty) => if let TyStmtBody::Enum(body) = &ty.body {
+                let fields = body.fields.0
+                    .iter()
+                    .map(|field| (field.name.name.clone(), field.attached_type.is_some()))
+                    .collect();
+
+                variants.insert(format!("{prefix}{}", ty.name.name), fields);
+            },
+            Stmt::World(world) => self::variants(&world.body, &format!("{prefix}{}{SEPARATOR}", world.name.name), variants),
+            _ => ()
+        }
+    }
+}
+
+///
+/// Collects the functions of the `impl`s without a trait by the names of their types and their own names
+///
+fn associated_fns(stmts: &[Stmt], fns: &mut HashSet <(String, String)>) -> core::result::Result <(), CheckError> {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Impl(imp) if imp.trait_name.is_none() => {
+                let Some(ty) = imp.ty.name() else { continue };
+
+                for fun in &imp.fns {
+                    if !fns.insert((ty.name.clone(), fun.name.name.clone())) {
+                        return Err(CheckError {
+                            span: fun.name.span(),
+                            message: format!("function `{}` is defined twice for type `{}`", fun.name.name, written(&ty.name)),
+                            clarifying: String::from("redefined here"),
+                            help: vec![]
+                        })
+                    }
+                }
+            },
+            Stmt::World(world) => associated_fns(&world.body, fns)?,
+            _ => ()
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// The name of an item as written in its module: `math__Vec2` is `Vec2`
+///
+pub fn written(name: &str) -> &str {
+    name.rsplit(SEPARATOR).next().unwrap_or(name)
+}
+
+///
+/// Replaces the calls of the functions of types, `Vec2::new`, with the calls of the functions they are lowered to
+///
+fn associated_calls(stmts: &mut [Stmt], fns: &HashSet <(String, String)>, variants: &HashMap <String, Vec <(String, bool)>>) -> core::result::Result <(), CheckError> {
+    let mut result = Ok(());
+
+    for stmt in stmts.iter_mut() {
+        if let Stmt::World(world) = stmt {
+            associated_calls(&mut world.body, fns, variants)?;
+            continue
+        }
+
+        for_each_body_mut(stmt, &mut |body| for_each_call_mut(body, &mut |call| {
+            let [ty, fun] = call.fun.segments.as_slice() else { return };
+
+            if result.is_err() {
+                return
+            }
+
+            if fns.contains(&(ty.name.clone(), fun.name.clone())) {
+                call.fun = Path::from(rename(fun, &associated_name(&ty.name, &fun.name)))
+            } else if variants.contains_key(&ty.name) {
+                result = Err(CheckError {
+                    span: fun.span(),
+                    message: format!("no variant or function `{}` in enum `{}`", fun.name, written(&ty.name)),
+                    clarifying: String::from("not found"),
+                    help: vec![format!("the variants of `{}` are {}", written(&ty.name), variants[&ty.name].iter().map(|(variant, _)| format!("`{variant}`")).collect::<Vec <_>>().join(", "))]
+                })
+            } else {
+                result = Err(CheckError {
+                    span: fun.span(),
+                    message: format!("no function `{}` in type `{}`", fun.name, written(&ty.name)),
+                    clarifying: String::from("not found"),
+                    help: vec![format!("add it in an `impl {}` block", written(&ty.name))]
+                })
+            }
+        }));
+
+        result.clone()?
+    }
+
+    Ok(())
+}
+
+fn params(sig: &Signature) -> impl Iterator <Item = String> + '_ {
+    sig.args.0.iter().flat_map(|arg| match arg {
+        Arg::Named(named) => named.names.iter().map(|name| name.name.clone()).collect(),
+        Arg::Unnamed(_) => vec![]
+    })
+}
+
+impl Loader {
+    fn finish(mut self) -> Result <(Sources, Vec <Stmt>)> {
+        match self.flatten() {
+            Ok(stmts) => Result(Ok((self.sources, stmts))),
+            Err(err) => Result(Err(self.sources.error(err)))
+        }
+    }
+}
+
+///
+/// Loads the module stored in `filename` and every module it declares, directly or not,
+///   and flattens them into a single list of statements
+///
+pub fn load(filename: &str) -> Result <(Sources, Vec <Stmt>)> {
+    let path = PathBuf::from(filename);
+
+    let mut loader = Loader {
+        root_dir: path.parent().map(FsPath::to_path_buf).unwrap_or_default(),
+        dependencies: vec![HashMap::new()],
+        ..Loader::default()
+    };
+
+    loader.load(path, true)?;
+    loader.finish()
+}
+
+///
+/// Loads every package, as returned by [`crate::package::resolve`], and flattens them into a single list of statements.
+///   The items of the first package keep their names, the items of the others are prefixed with the name of the package
+///
+pub fn load_packages(packages: &[Package]) -> Result <(Sources, Vec <Stmt>)> {
+    let mut loader = Loader::default();
+    let mut roots = vec![];
+
+    for (idx, package) in packages.iter().enumerate() {
+        let entry = package.entry();
+
+        loader.root_dir = entry.parent().map(FsPath::to_path_buf).unwrap_or_default();
+        loader.root_prefix = if idx == 0 { String::new() } else { format!("{}{SEPARATOR}", package.manifest.name) };
+        loader.package = idx;
+
+        roots.push(loader.load(entry, true)?)
+    }
+
+    loader.dependencies = packages
+        .iter()
+        .map(|package| package.manifest.dependencies
+            .iter()
+            .zip(&package.dependencies)
+            .map(|(dependency, &idx)| (dependency.name.clone(), roots[idx]))
+            .collect())
+        .collect();
+
+    loader.finish()
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeSet;
+use crate::parse::signature::{Arg, Signature};
+use crate::parse::ty::Type;
+use super::component::{ComponentId, Components};
+use super::query::{Fetch, Mutability};
+
+pub type FetchIds = fn(&Components) -> Option <Vec <(ComponentId, Mutability)>>;
+
+///
+/// What a system declares it accesses
+///
+#[derive(Debug, Clone)]
+pub enum AccessDecl {
+    ///
+    /// Nothing is known about the system, so it can never run alongside others
+    ///
+    Exclusive,
+
+    ///
+    /// The access of a [`Fetch`] type, i.e. `(&Position, &mut Health)`
+    ///
+    Fetch(FetchIds),
+
+    ///
+    /// Components named by the `ty`s they originate from,
+    ///   i.e. taken from the query signature of an oko system
+    ///
+    Named(Vec <(String, Mutability)>)
+}
+
+impl AccessDecl {
+    pub fn of <Q: Fetch> () -> Self {
+        Self::Fetch(Q::ids)
+    }
+
+    ///
+    /// Derives the access from the types of a system query signature
+    ///
+    /// `fn movement(pos: &mut Position, vel: &Velocity)`
+    ///
+    /// `-------------^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+    ///
+    pub fn from_signature(sig: &Signature) -> Self {
+        Self::Named(sig.args.0.iter().flat_map(|arg| match arg {
+            Arg::Named(named) => vec![&named.ty; named.names.len()],
+            Arg::Unnamed(unnamed) => vec![&unnamed.it; unnamed.times.count()]
+        }).filter_map(|ty| Some((ty.base().name()?.name.clone(), Mutability::of(ty)))).collect())
+    }
+
+    pub fn resolve(&self, components: &Components) -> Access {
+        match self {
+            Self::Exclusive => Access {
+                exclusive: true,
+                ..Access::default()
+            },
+            Self::Fetch(ids) => Access::from_ids(ids(components).unwrap_or_default()),
+            Self::Named(names) => Access::from_ids(names
+                .iter()
+                .filter_map(|(name, mutability)| Some((components.id_by_name(name)?, *mutability)))
+                .collect())
+        }
+    }
+}
+
+impl Mutability {
+    ///
+    /// `&mut T` and `*mut T` are unique, everything else (`&T`, `T`) is shared.
+    ///   A mutable layer anywhere makes the access unique: `&*mut T`
+    ///
+    pub fn of(ty: &Type) -> Self {
+        if ty.indirections().iter().any(|indirection| indirection.is_mutable) {
+            Self::Unique
+        } else {
+            Self::Shared
+        }
+    }
+}
+
+///
+/// The resolved set of components a system reads and writes
+///
+#[derive(Debug, Clone, Default)]
+pub struct Access {
+    pub exclusive: bool,
+    pub reads: BTreeSet <ComponentId>,
+    pub writes: BTreeSet <ComponentId>
+}
+
+impl Access {
+    fn from_ids(ids: Vec <(ComponentId, Mutability)>) -> Self {
+        let mut access = Self::default();
+
+        for (id, mutability) in ids {
+            match mutability {
+                Mutability::Shared => access.reads.insert(id),
+                Mutability::Unique => access.writes.insert(id)
+            };
+        }
+
+        access
+    }
+
+    ///
+    /// Two systems conflict if either of them writes a component the other one touches
+    ///
+    pub fn conflicts_with(&self, other: &Access) -> bool {
+        self.exclusive
+            || other.exclusive
+            || !self.writes.is_disjoint(&other.writes)
+            || !self.writes.is_disjoint(&other.reads)
+            || !self.reads.is_disjoint(&other.writes)
+    }
+}
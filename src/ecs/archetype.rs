@@ -0,0 +1,231 @@
+use core::any::Any;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use super::component::{Component, ComponentId, Components};
+use super::entity::Entity;
+
+///
+/// A type-erased column of an archetype table
+///
+pub trait AnyColumn: Any + Send + Sync {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// Removes the element at `row`, replacing it with the last one
+    ///
+    fn swap_remove(&mut self, row: usize);
+
+    ///
+    /// Same as [`AnyColumn::swap_remove`], but returns the removed element
+    ///
+    fn swap_remove_boxed(&mut self, row: usize) -> Box <dyn Any + Send + Sync>;
+
+    ///
+    /// Pushes an element previously taken by [`AnyColumn::swap_remove_boxed`]
+    ///
+    fn push_boxed(&mut self, value: Box <dyn Any + Send + Sync>);
+
+    fn get(&self, row: usize) -> &dyn Any;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl Debug for dyn AnyColumn {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("Column(len: {})", self.len()))
+    }
+}
+
+///
+/// The concrete storage of the component `T`
+///
+#[derive(Debug)]
+pub struct Column <T> (pub Vec <T>);
+
+impl <T> Default for Column <T> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl <T: Component> AnyColumn for Column <T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        self.0.swap_remove(row);
+    }
+
+    fn swap_remove_boxed(&mut self, row: usize) -> Box <dyn Any + Send + Sync> {
+        Box::new(self.0.swap_remove(row))
+    }
+
+    fn push_boxed(&mut self, value: Box <dyn Any + Send + Sync>) {
+        self.0.push(*value.downcast().expect("column type mismatch"))
+    }
+
+    fn get(&self, row: usize) -> &dyn Any {
+        &self.0[row]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl dyn AnyColumn {
+    pub fn downcast_ref <T: Component> (&self) -> &Column <T> {
+        self.as_any().downcast_ref().expect("column type mismatch")
+    }
+
+    pub fn downcast_mut <T: Component> (&mut self) -> &mut Column <T> {
+        self.as_any_mut().downcast_mut().expect("column type mismatch")
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ArchetypeId(pub usize);
+
+///
+/// A table of all the entities that have exactly the same set of components
+///
+/// Every column is locked separately, so systems touching
+///   different components may access the same archetype simultaneously
+///
+#[derive(Debug)]
+pub struct Archetype {
+    pub id: ArchetypeId,
+
+    ///
+    /// Sorted, so the set can be used as a key
+    ///
+    pub components: Vec <ComponentId>,
+
+    pub columns: Vec <RwLock <Box <dyn AnyColumn>>>,
+
+    pub entities: Vec <Entity>
+}
+
+impl Archetype {
+    pub fn column_index(&self, id: ComponentId) -> Option <usize> {
+        self.components.binary_search(&id).ok()
+    }
+
+    pub fn column(&self, id: ComponentId) -> Option <&RwLock <Box <dyn AnyColumn>>> {
+        self.column_index(id).map(|idx| &self.columns[idx])
+    }
+
+    pub fn column_mut(&mut self, id: ComponentId) -> Option <&mut Box <dyn AnyColumn>> {
+        let idx = self.column_index(id)?;
+        Some(self.columns[idx].get_mut().expect("component column poisoned"))
+    }
+
+    pub fn contains(&self, id: ComponentId) -> bool {
+        self.column_index(id).is_some()
+    }
+
+    pub fn contains_all(&self, ids: impl IntoIterator <Item = ComponentId>) -> bool {
+        ids.into_iter().all(|id| self.contains(id))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    ///
+    /// Removes the row from every column.
+    ///
+    /// Returns the entity that was moved into `row` to fill the gap, if any
+    ///
+    pub fn swap_remove(&mut self, row: usize) -> Option <Entity> {
+        for column in &mut self.columns {
+            column.get_mut().expect("component column poisoned").swap_remove(row)
+        }
+
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
+    }
+}
+
+///
+/// All the archetypes of a world
+///
+#[derive(Debug, Default)]
+pub struct Archetypes {
+    archetypes: Vec <Archetype>,
+    by_components: HashMap <Vec <ComponentId>, ArchetypeId>
+}
+
+impl Archetypes {
+    ///
+    /// Returns the archetype with exactly `ids` components, creating it if needed
+    ///
+    pub fn get_or_create(&mut self, mut ids: Vec <ComponentId>, components: &Components) -> ArchetypeId {
+        ids.sort_unstable();
+        ids.dedup();
+
+        if let Some(id) = self.by_components.get(&ids) {
+            return *id
+        }
+
+        let id = ArchetypeId(self.archetypes.len());
+
+        self.archetypes.push(Archetype {
+            id,
+            columns: ids.iter().map(|component| RwLock::new((components.info(*component).new_column)())).collect(),
+            components: ids.clone(),
+            entities: vec![]
+        });
+        self.by_components.insert(ids, id);
+
+        id
+    }
+
+    pub fn get(&self, id: ArchetypeId) -> &Archetype {
+        &self.archetypes[id.0]
+    }
+
+    pub fn get_mut(&mut self, id: ArchetypeId) -> &mut Archetype {
+        &mut self.archetypes[id.0]
+    }
+
+    pub fn get_two_mut(&mut self, a: ArchetypeId, b: ArchetypeId) -> (&mut Archetype, &mut Archetype) {
+        assert_ne!(a, b, "cannot borrow the same archetype twice");
+
+        if a.0 < b.0 {
+            let (left, right) = self.archetypes.split_at_mut(b.0);
+            (&mut left[a.0], &mut right[0])
+        } else {
+            let (left, right) = self.archetypes.split_at_mut(a.0);
+            (&mut right[0], &mut left[b.0])
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator <Item = &Archetype> {
+        self.archetypes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.archetypes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.archetypes.is_empty()
+    }
+}
@@ -0,0 +1,81 @@
+use super::archetype::Archetype;
+use super::component::{Component, ComponentId, Components};
+use super::entity::Entity;
+use super::world::World;
+
+///
+/// A set of components that are spawned together
+///
+/// Implemented for tuples of up to 12 distinct components
+///
+pub trait Bundle: Send + Sync + 'static {
+    ///
+    /// Registers every component of the bundle and returns their ids
+    ///
+    fn register(components: &mut Components) -> Vec <ComponentId>;
+
+    ///
+    /// Pushes the components into the matching columns of `archetype`
+    ///
+    fn push(self, archetype: &mut Archetype, components: &Components);
+
+    ///
+    /// Adds the components to an already existing entity, replacing the ones it already has
+    ///
+    fn insert(self, world: &mut World, entity: Entity);
+}
+
+fn push_component <T: Component> (value: T, archetype: &mut Archetype, components: &Components) {
+    let id = components.id::<T>().expect("component of a bundle is not registered");
+
+    archetype.column_mut(id)
+        .expect("archetype does not match the bundle")
+        .downcast_mut::<T>()
+        .0
+        .push(value)
+}
+
+macro_rules! impl_bundle {
+    ($( $name:ident )*) => {
+        impl <$( $name: Component ),*> Bundle for ($( $name, )*) {
+            #[allow(unused_variables, unused_mut)]
+            fn register(components: &mut Components) -> Vec <ComponentId> {
+                let mut ids = vec![$( components.register::<$name>() ),*];
+                let len = ids.len();
+
+                ids.sort_unstable();
+                ids.dedup();
+
+                assert_eq!(ids.len(), len, "a bundle cannot contain the same component twice");
+
+                ids
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn push(self, archetype: &mut Archetype, components: &Components) {
+                let ($( $name, )*) = self;
+                $( push_component($name, archetype, components); )*
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn insert(self, world: &mut World, entity: Entity) {
+                let ($( $name, )*) = self;
+                $( world.insert(entity, $name); )*
+            }
+        }
+    };
+}
+
+impl_bundle!();
+impl_bundle!(A);
+impl_bundle!(A B);
+impl_bundle!(A B C);
+impl_bundle!(A B C D);
+impl_bundle!(A B C D E);
+impl_bundle!(A B C D E F);
+impl_bundle!(A B C D E F G);
+impl_bundle!(A B C D E F G H);
+impl_bundle!(A B C D E F G H I);
+impl_bundle!(A B C D E F G H I J);
+impl_bundle!(A B C D E F G H I J K);
+impl_bundle!(A B C D E F G H I J K L);
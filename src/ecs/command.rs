@@ -0,0 +1,87 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::Mutex;
+use super::bundle::Bundle;
+use super::component::Component;
+use super::entity::Entity;
+use super::world::World;
+
+pub type Command = Box <dyn FnOnce(&mut World) + Send>;
+
+///
+/// Structural changes requested while the world is shared, i.e. during query iteration
+///
+/// They are applied in order at the next stage boundary
+///
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Mutex <Vec <Command>>
+}
+
+impl Debug for CommandQueue {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("CommandQueue(len: {})", self.len()))
+    }
+}
+
+impl CommandQueue {
+    pub fn push(&self, command: Command) {
+        self.commands.lock().expect("command queue poisoned").push(command)
+    }
+
+    pub fn take(&self) -> Vec <Command> {
+        core::mem::take(&mut *self.commands.lock().expect("command queue poisoned"))
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.lock().expect("command queue poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+///
+/// A handle for deferring structural changes to a shared world
+///
+#[derive(Copy, Clone)]
+pub struct Commands <'w> {
+    world: &'w World
+}
+
+impl <'w> Commands <'w> {
+    pub fn new(world: &'w World) -> Self {
+        Self {
+            world
+        }
+    }
+
+    ///
+    /// Reserves an entity right away, its components are added at the stage boundary
+    ///
+    pub fn spawn <B: Bundle> (&self, bundle: B) -> Entity {
+        let entity = self.world.entities().reserve();
+
+        self.world.command_queue().push(Box::new(move |world| bundle.insert(world, entity)));
+
+        entity
+    }
+
+    pub fn insert <T: Component> (&self, entity: Entity, component: T) {
+        self.world.command_queue().push(Box::new(move |world| {
+            world.insert(entity, component);
+        }))
+    }
+
+    pub fn remove <T: Component> (&self, entity: Entity) {
+        self.world.command_queue().push(Box::new(move |world| {
+            world.remove::<T>(entity);
+        }))
+    }
+
+    pub fn despawn(&self, entity: Entity) {
+        self.world.command_queue().push(Box::new(move |world| {
+            world.despawn(entity);
+        }))
+    }
+}
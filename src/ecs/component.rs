@@ -0,0 +1,104 @@
+use core::any::{Any, TypeId};
+use std::collections::HashMap;
+use super::archetype::{AnyColumn, Column};
+
+///
+/// Anything that can be attached to an entity
+///
+/// In oko code every `ty` is a component
+///
+pub trait Component: Any + Send + Sync {}
+
+impl <T: Any + Send + Sync> Component for T {}
+
+///
+/// A dense index of a registered component
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ComponentId(pub usize);
+
+#[derive(Debug, Clone)]
+pub struct ComponentInfo {
+    pub id: ComponentId,
+
+    ///
+    /// The name of the `ty` this component originates from
+    ///
+    pub name: String,
+
+    pub type_id: TypeId,
+
+    ///
+    /// Creates an empty column able to store this component
+    ///
+    pub new_column: fn() -> Box <dyn AnyColumn>
+}
+
+///
+/// The registry of all the components known to a world
+///
+#[derive(Debug, Default)]
+pub struct Components {
+    infos: Vec <ComponentInfo>,
+    by_type: HashMap <TypeId, ComponentId>,
+    by_name: HashMap <String, ComponentId>
+}
+
+impl Components {
+    ///
+    /// Registers `T` under the name `name`, or returns its id if it is already registered
+    ///
+    pub fn register_named <T: Component> (&mut self, name: &str) -> ComponentId {
+        if let Some(id) = self.id::<T>() {
+            return id
+        }
+
+        let id = ComponentId(self.infos.len());
+
+        self.infos.push(ComponentInfo {
+            id,
+            name: name.to_string(),
+            type_id: TypeId::of::<T>(),
+            new_column: || Box::new(Column::<T>::default())
+        });
+        self.by_type.insert(TypeId::of::<T>(), id);
+        self.by_name.insert(name.to_string(), id);
+
+        id
+    }
+
+    ///
+    /// Registers `T` under its Rust type name
+    ///
+    pub fn register <T: Component> (&mut self) -> ComponentId {
+        self.register_named::<T>(core::any::type_name::<T>())
+    }
+
+    pub fn id <T: Component> (&self) -> Option <ComponentId> {
+        self.by_type.get(&TypeId::of::<T>()).copied()
+    }
+
+    pub fn id_by_type(&self, type_id: TypeId) -> Option <ComponentId> {
+        self.by_type.get(&type_id).copied()
+    }
+
+    pub fn id_by_name(&self, name: &str) -> Option <ComponentId> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn info(&self, id: ComponentId) -> &ComponentInfo {
+        &self.infos[id.0]
+    }
+
+    pub fn iter(&self) -> impl Iterator <Item = &ComponentInfo> {
+        self.infos.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.infos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.infos.is_empty()
+    }
+}
@@ -0,0 +1,175 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use core::sync::atomic::{AtomicU32, Ordering};
+use super::archetype::ArchetypeId;
+
+///
+/// A generational handle to an entity
+///
+/// The `index` of a despawned entity is reused, but with an incremented `generation`,
+///   so stale handles never refer to a newer entity
+///
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Entity {
+    pub index: u32,
+    pub generation: u32
+}
+
+impl Debug for Entity {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("Entity({}v{})", self.index, self.generation))
+    }
+}
+
+///
+/// Where the components of an entity live
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EntityLocation {
+    pub archetype: ArchetypeId,
+    pub row: usize
+}
+
+#[derive(Debug, Clone)]
+struct EntityMeta {
+    generation: u32,
+    location: Option <EntityLocation>
+}
+
+///
+/// The entity allocator
+///
+#[derive(Debug, Default)]
+pub struct Entities {
+    meta: Vec <EntityMeta>,
+    free: Vec <u32>,
+    alive: usize,
+
+    ///
+    /// How many entities were reserved through a shared reference since the last flush
+    ///
+    reserved: AtomicU32
+}
+
+impl Entities {
+    pub fn alloc(&mut self) -> Entity {
+        self.alive += 1;
+
+        if let Some(index) = self.free.pop() {
+            return Entity {
+                index,
+                generation: self.meta[index as usize].generation
+            }
+        }
+
+        let index = u32::try_from(self.meta.len()).expect("too many entities");
+
+        self.meta.push(EntityMeta {
+            generation: 0,
+            location: None
+        });
+
+        Entity {
+            index,
+            generation: 0
+        }
+    }
+
+    ///
+    /// Reserves an entity without mutable access to the allocator.
+    ///
+    /// The entity becomes alive only after [`Entities::flush_reserved`]
+    ///
+    pub fn reserve(&self) -> Entity {
+        let offset = self.reserved.fetch_add(1, Ordering::Relaxed);
+
+        Entity {
+            index: u32::try_from(self.meta.len()).ok().and_then(|len| len.checked_add(offset)).expect("too many entities"),
+            generation: 0
+        }
+    }
+
+    ///
+    /// Makes all the reserved entities alive, returning them
+    ///
+    pub fn flush_reserved(&mut self) -> Vec <Entity> {
+        let reserved = core::mem::take(self.reserved.get_mut());
+        let start = self.meta.len() as u32;
+
+        self.meta.extend((0..reserved).map(|_| EntityMeta {
+            generation: 0,
+            location: None
+        }));
+        self.alive += reserved as usize;
+
+        (start..start + reserved).map(|index| Entity {
+            index,
+            generation: 0
+        }).collect()
+    }
+
+    ///
+    /// Frees the entity, returning its last location if it was alive
+    ///
+    pub fn free(&mut self, entity: Entity) -> Option <EntityLocation> {
+        let meta = self.meta.get_mut(entity.index as usize)?;
+
+        if meta.generation != entity.generation {
+            return None
+        }
+
+        meta.generation = meta.generation.wrapping_add(1);
+        self.free.push(entity.index);
+        self.alive -= 1;
+
+        meta.location.take()
+    }
+
+    ///
+    /// The generation of every slot and the free slots in the order they will be reused
+    ///
+    pub fn state(&self) -> (Vec <u32>, Vec <u32>) {
+        (self.meta.iter().map(|meta| meta.generation).collect(), self.free.clone())
+    }
+
+    ///
+    /// Recreates the allocator from its [`Entities::state`].
+    ///
+    /// Every slot that is not free is alive, but has no location yet
+    ///
+    pub fn from_state(generations: &[u32], free: &[u32]) -> Self {
+        Self {
+            meta: generations.iter().map(|generation| EntityMeta {
+                generation: *generation,
+                location: None
+            }).collect(),
+            free: free.to_vec(),
+            alive: generations.len() - free.len(),
+            reserved: AtomicU32::new(0)
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.meta.get(entity.index as usize).is_some_and(|meta| meta.generation == entity.generation)
+    }
+
+    pub fn location(&self, entity: Entity) -> Option <EntityLocation> {
+        self.meta
+            .get(entity.index as usize)
+            .filter(|meta| meta.generation == entity.generation)
+            .and_then(|meta| meta.location)
+    }
+
+    pub fn set_location(&mut self, entity: Entity, location: EntityLocation) {
+        let meta = &mut self.meta[entity.index as usize];
+        debug_assert_eq!(meta.generation, entity.generation);
+        meta.location = Some(location)
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive == 0
+    }
+}
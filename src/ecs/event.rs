@@ -0,0 +1,76 @@
+use core::any::{Any, TypeId};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+///
+/// Anything that can be sent to callback systems
+///
+pub trait Event: Any + Send + Sync {}
+
+impl <T: Any + Send + Sync> Event for T {}
+
+///
+/// The builtin event sent before the first tick of a world
+///
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Startup;
+
+///
+/// The builtin event sent when a world stops running
+///
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Shutdown;
+
+///
+/// A sent, but not yet handled, event
+///
+#[derive(Debug)]
+pub struct EventRecord {
+    pub type_id: TypeId,
+    pub name: &'static str,
+    pub payload: Box <dyn Any + Send + Sync>
+}
+
+///
+/// The queue of pending events of a world
+///
+/// Events can be sent through a shared reference, so systems running in parallel may send them
+///
+#[derive(Debug, Default)]
+pub struct Events {
+    queue: Mutex <VecDeque <EventRecord>>
+}
+
+impl Events {
+    pub fn send <E: Event> (&self, event: E) {
+        self.push(EventRecord {
+            type_id: TypeId::of::<E>(),
+            name: core::any::type_name::<E>(),
+            payload: Box::new(event)
+        })
+    }
+
+    pub fn push(&self, record: EventRecord) {
+        self.queue.lock().expect("event queue poisoned").push_back(record)
+    }
+
+    pub fn pop(&self) -> Option <EventRecord> {
+        self.queue.lock().expect("event queue poisoned").pop_front()
+    }
+
+    pub fn for_each(&self, f: impl FnMut(&EventRecord)) {
+        self.queue.lock().expect("event queue poisoned").iter().for_each(f)
+    }
+
+    pub fn clear(&self) {
+        self.queue.lock().expect("event queue poisoned").clear()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.lock().expect("event queue poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,46 @@
+//!
+//! The runtime that executes worlds described in `ECS.md`
+//!
+//! Components are stored in archetypes (tables of entities sharing the same set of components),
+//!   entities are addressed with generational ids, and a [`Schedule`] executes
+//!   staged systems every tick and dispatches events to callback systems.
+//!
+//! Systems of the same stage that do not conflict on their declared component access
+//!   are executed in parallel, see [`ExecutionMode`]
+//!
+//! The state of a world can be saved into a [`Snapshot`] and restored from it
+//!
+//! Several worlds can be run together by a [`Universe`], exchanging events through channels
+//!
+//! The runtime is a library for Rust hosts: the worlds, components and systems are registered through its API,
+//!   and a world can be run headlessly for a fixed number of ticks via [`Schedule::run_for`].
+//!
+//! It is not driven by oko code: there is no interpreter, and the C backend compiles the functions
+//!   of a `world` statement as plain functions without registering them as systems.
+//!   Generating schedules from oko code is left for a later change
+//!
+
+pub mod entity;
+pub mod component;
+pub mod archetype;
+pub mod bundle;
+pub mod query;
+pub mod event;
+pub mod command;
+pub mod access;
+pub mod schedule;
+pub mod world;
+pub mod universe;
+pub mod snapshot;
+
+pub use entity::Entity;
+pub use component::{Component, ComponentId};
+pub use bundle::Bundle;
+pub use query::{Fetch, Query};
+pub use event::{Startup, Shutdown};
+pub use command::Commands;
+pub use access::AccessDecl;
+pub use schedule::{ExecutionMode, Schedule, SystemStatus};
+pub use world::World;
+pub use universe::{Sender, Universe};
+pub use snapshot::{Persistent, Schema, Snapshot};
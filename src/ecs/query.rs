@@ -0,0 +1,177 @@
+use core::marker::PhantomData;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use super::archetype::{AnyColumn, Archetype};
+use super::component::{Component, ComponentId, Components};
+use super::entity::Entity;
+use super::world::World;
+
+///
+/// How a query accesses a component, i.e. `&T` or `&mut T`
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Mutability {
+    Shared,
+    Unique
+}
+
+///
+/// Something that can be fetched from every row of an archetype
+///
+/// Implemented for `&T`, `&mut T` and tuples of up to 12 of them
+///
+pub trait Fetch {
+    type Item <'a>;
+
+    type Guard <'a>;
+
+    ///
+    /// The components accessed by the fetch, or `None` if any of them is not registered
+    ///   (and thus no entity can match)
+    ///
+    fn ids(components: &Components) -> Option <Vec <(ComponentId, Mutability)>>;
+
+    fn lock <'a> (archetype: &'a Archetype, components: &Components) -> Self::Guard <'a>;
+
+    fn get <'a, 'g: 'a> (guard: &'a mut Self::Guard <'g>, row: usize) -> Self::Item <'a>;
+}
+
+fn column_of <'a, T: Component> (archetype: &'a Archetype, components: &Components) -> &'a RwLock <Box <dyn AnyColumn>> {
+    components.id::<T>()
+        .and_then(|id| archetype.column(id))
+        .expect("archetype does not match the query")
+}
+
+impl <T: Component> Fetch for &T {
+    type Item <'a> = &'a T;
+
+    type Guard <'a> = RwLockReadGuard <'a, Box <dyn AnyColumn>>;
+
+    fn ids(components: &Components) -> Option <Vec <(ComponentId, Mutability)>> {
+        Some(vec![(components.id::<T>()?, Mutability::Shared)])
+    }
+
+    fn lock <'a> (archetype: &'a Archetype, components: &Components) -> Self::Guard <'a> {
+        column_of::<T>(archetype, components).read().expect("component column poisoned")
+    }
+
+    fn get <'a, 'g: 'a> (guard: &'a mut Self::Guard <'g>, row: usize) -> Self::Item <'a> {
+        &guard.downcast_ref::<T>().0[row]
+    }
+}
+
+impl <T: Component> Fetch for &mut T {
+    type Item <'a> = &'a mut T;
+
+    type Guard <'a> = RwLockWriteGuard <'a, Box <dyn AnyColumn>>;
+
+    fn ids(components: &Components) -> Option <Vec <(ComponentId, Mutability)>> {
+        Some(vec![(components.id::<T>()?, Mutability::Unique)])
+    }
+
+    fn lock <'a> (archetype: &'a Archetype, components: &Components) -> Self::Guard <'a> {
+        column_of::<T>(archetype, components).write().expect("component column poisoned")
+    }
+
+    fn get <'a, 'g: 'a> (guard: &'a mut Self::Guard <'g>, row: usize) -> Self::Item <'a> {
+        &mut guard.downcast_mut::<T>().0[row]
+    }
+}
+
+macro_rules! impl_fetch {
+    ($( $name:ident )+) => {
+        impl <$( $name: Fetch ),+> Fetch for ($( $name, )+) {
+            type Item <'a> = ($( $name::Item <'a>, )+);
+
+            type Guard <'a> = ($( $name::Guard <'a>, )+);
+
+            fn ids(components: &Components) -> Option <Vec <(ComponentId, Mutability)>> {
+                let mut ids = vec![];
+                $( ids.extend($name::ids(components)?); )+
+                Some(ids)
+            }
+
+            fn lock <'a> (archetype: &'a Archetype, components: &Components) -> Self::Guard <'a> {
+                ($( $name::lock(archetype, components), )+)
+            }
+
+            #[allow(non_snake_case)]
+            fn get <'a, 'g: 'a> (guard: &'a mut Self::Guard <'g>, row: usize) -> Self::Item <'a> {
+                let ($( $name, )+) = guard;
+                ($( $name::get($name, row), )+)
+            }
+        }
+    };
+}
+
+impl_fetch!(A);
+impl_fetch!(A B);
+impl_fetch!(A B C);
+impl_fetch!(A B C D);
+impl_fetch!(A B C D E);
+impl_fetch!(A B C D E F);
+impl_fetch!(A B C D E F G);
+impl_fetch!(A B C D E F G H);
+impl_fetch!(A B C D E F G H I);
+impl_fetch!(A B C D E F G H I J);
+impl_fetch!(A B C D E F G H I J K);
+impl_fetch!(A B C D E F G H I J K L);
+
+///
+/// Iterates over all the entities that have every component of `Q`
+///
+/// Columns are locked for the duration of the iteration over an archetype,
+///   so a query must not be nested inside another query writing the same component
+///
+pub struct Query <'w, Q: Fetch> {
+    world: &'w World,
+    ids: Option <Vec <(ComponentId, Mutability)>>,
+    marker: PhantomData <fn() -> Q>
+}
+
+impl <'w, Q: Fetch> Query <'w, Q> {
+    pub fn new(world: &'w World) -> Self {
+        let ids = Q::ids(world.components());
+
+        if let Some(ids) = &ids {
+            for (idx, (id, _)) in ids.iter().enumerate() {
+                assert!(ids[idx + 1..].iter().all(|(other, _)| other != id),
+                    "component `{}` is accessed twice in the same query", world.components().info(*id).name)
+            }
+        }
+
+        Self {
+            world,
+            ids,
+            marker: PhantomData
+        }
+    }
+
+    fn archetypes(&self) -> impl Iterator <Item = &'w Archetype> + '_ {
+        self.world
+            .archetypes()
+            .iter()
+            .filter(|archetype| match &self.ids {
+                Some(ids) => !archetype.is_empty() && archetype.contains_all(ids.iter().map(|(id, _)| *id)),
+                None => false
+            })
+    }
+
+    pub fn for_each(&self, mut f: impl FnMut(Entity, Q::Item <'_>)) {
+        for archetype in self.archetypes() {
+            let mut guard = Q::lock(archetype, self.world.components());
+
+            for (row, entity) in archetype.entities.iter().enumerate() {
+                f(*entity, Q::get(&mut guard, row))
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.archetypes().map(Archetype::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+}
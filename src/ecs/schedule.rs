@@ -0,0 +1,419 @@
+use core::any::{Any, TypeId};
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::num::NonZeroUsize;
+use std::collections::HashMap;
+use super::access::{Access, AccessDecl};
+use super::event::{Event, Shutdown, Startup};
+use super::query::Fetch;
+use super::world::World;
+
+///
+/// Whether a staged system wants to be executed on the next ticks
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum SystemStatus {
+    Continue,
+
+    ///
+    /// `no longer execute me`
+    ///
+    Finished
+}
+
+///
+/// What a staged system may return
+///
+pub trait SystemOutput {
+    fn into_status(self) -> SystemStatus;
+}
+
+impl SystemOutput for () {
+    fn into_status(self) -> SystemStatus {
+        SystemStatus::Continue
+    }
+}
+
+impl SystemOutput for SystemStatus {
+    fn into_status(self) -> SystemStatus {
+        self
+    }
+}
+
+pub type SystemFn = Box <dyn FnMut(&World) -> SystemStatus + Send>;
+
+pub type CallbackFn = Box <dyn FnMut(&World, &dyn Any) + Send>;
+
+///
+/// A system executed every tick as a part of its stage
+///
+pub struct StagedSystem {
+    pub name: String,
+    pub access: AccessDecl,
+    pub finished: bool,
+    run: SystemFn
+}
+
+impl StagedSystem {
+    pub fn run(&mut self, world: &World) {
+        if (self.run)(world) == SystemStatus::Finished {
+            self.finished = true
+        }
+    }
+}
+
+///
+/// A system called when an event of some type occurs
+///
+pub struct CallbackSystem {
+    pub name: String,
+    run: CallbackFn
+}
+
+///
+/// A named group of staged systems.
+///
+/// Stages are executed one after another, and at their boundaries
+///   deferred commands are applied and pending events are dispatched
+///
+pub struct Stage {
+    pub name: String,
+    pub systems: Vec <StagedSystem>
+}
+
+impl Stage {
+    ///
+    /// Splits the unfinished systems into batches that can be executed simultaneously.
+    ///
+    /// A system is put right after the last batch containing a system it conflicts with,
+    ///   so conflicting systems are always executed in the order they were added
+    ///
+    pub fn batches(&self, world: &World) -> Vec <Vec <usize>> {
+        let mut batches: Vec <Vec <usize>> = vec![];
+        let mut placed: Vec <(usize, usize, Access)> = vec![];
+
+        for (idx, system) in self.systems.iter().enumerate().filter(|(_, system)| !system.finished) {
+            let access = system.access.resolve(world.components());
+
+            let batch = placed
+                .iter()
+                .filter(|(_, _, other)| other.conflicts_with(&access))
+                .map(|(_, batch, _)| batch + 1)
+                .max()
+                .unwrap_or(0);
+
+            if batch == batches.len() {
+                batches.push(vec![])
+            }
+
+            batches[batch].push(idx);
+            placed.push((idx, batch, access))
+        }
+
+        batches
+    }
+}
+
+///
+/// How the systems of a stage are executed
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ExecutionMode {
+    ///
+    /// Every system is executed on the calling thread in the order it was added.
+    ///
+    /// Deterministic, so useful for debugging
+    ///
+    SingleThreaded,
+
+    ///
+    /// Non-conflicting systems are distributed over at most `threads` worker threads
+    ///
+    Parallel {
+        threads: NonZeroUsize
+    }
+}
+
+impl Default for ExecutionMode {
+    fn default() -> Self {
+        Self::Parallel {
+            threads: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+        }
+    }
+}
+
+///
+/// Which systems are batched together in every stage
+///
+#[derive(Debug, Clone)]
+pub struct ScheduleReport {
+    pub stages: Vec <(String, Vec <Vec <String>>)>
+}
+
+impl Display for ScheduleReport {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        for (stage, batches) in &self.stages {
+            writeln!(f, "stage `{stage}`:")?;
+
+            for (idx, batch) in batches.iter().enumerate() {
+                writeln!(f, "    batch {idx}: {}", batch.join(", "))?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// `when do I(world) call this system?`
+///
+#[derive(Default)]
+pub struct Schedule {
+    stages: Vec <Stage>,
+    callbacks: HashMap <TypeId, Vec <CallbackSystem>>,
+    mode: ExecutionMode,
+    started: bool,
+    shut_down: bool
+}
+
+impl Debug for Schedule {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        let mut map = f.debug_map();
+
+        for stage in &self.stages {
+            map.entry(&stage.name, &stage.systems.iter().map(|system| &system.name).collect::<Vec <_>>());
+        }
+
+        map.finish()
+    }
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_stage(&mut self, name: &str) -> &mut Self {
+        assert!(self.stage(name).is_none(), "stage `{name}` already exists");
+
+        self.stages.push(Stage {
+            name: name.to_string(),
+            systems: vec![]
+        });
+
+        self
+    }
+
+    pub fn stage(&self, name: &str) -> Option <&Stage> {
+        self.stages.iter().find(|stage| stage.name == name)
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn mode(&self) -> ExecutionMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: ExecutionMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    ///
+    /// Adds a system with an unknown access, so it is never executed alongside other systems
+    ///
+    pub fn add_system <O: SystemOutput> (&mut self, stage: &str, name: &str, system: impl FnMut(&World) -> O + Send + 'static) -> &mut Self {
+        self.add_system_with_access(stage, name, AccessDecl::Exclusive, system)
+    }
+
+    ///
+    /// Adds a system that accesses only the components of `Q`
+    ///
+    pub fn add_query_system <Q: Fetch, O: SystemOutput> (&mut self, stage: &str, name: &str, system: impl FnMut(&World) -> O + Send + 'static) -> &mut Self {
+        self.add_system_with_access(stage, name, AccessDecl::of::<Q>(), system)
+    }
+
+    pub fn add_system_with_access <O: SystemOutput> (&mut self, stage: &str, name: &str, access: AccessDecl, mut system: impl FnMut(&World) -> O + Send + 'static) -> &mut Self {
+        let stage = self.stages
+            .iter_mut()
+            .find(|it| it.name == stage)
+            .unwrap_or_else(|| panic!("no stage `{stage}` in the schedule"));
+
+        stage.systems.push(StagedSystem {
+            name: name.to_string(),
+            access,
+            finished: false,
+            run: Box::new(move |world| system(world).into_status())
+        });
+
+        self
+    }
+
+    ///
+    /// Adds a callback system that is called every time an event `E` is dispatched
+    ///
+    pub fn on <E: Event> (&mut self, name: &str, mut callback: impl FnMut(&World, &E) + Send + 'static) -> &mut Self {
+        self.callbacks.entry(TypeId::of::<E>()).or_default().push(CallbackSystem {
+            name: name.to_string(),
+            run: Box::new(move |world, event| callback(world, event.downcast_ref().expect("event type mismatch")))
+        });
+
+        self
+    }
+
+    ///
+    /// Handles all the pending events, including the ones sent by the callbacks themselves.
+    ///
+    /// Events without callbacks are dropped
+    ///
+    pub fn dispatch_events(&mut self, world: &World) {
+        while let Some(event) = world.events().pop() {
+            if let Some(callbacks) = self.callbacks.get_mut(&event.type_id) {
+                for callback in callbacks {
+                    (callback.run)(world, event.payload.as_ref())
+                }
+            }
+        }
+    }
+
+    ///
+    /// Applies the deferred commands and handles the pending events
+    ///
+    fn end_stage(&mut self, world: &mut World) {
+        world.apply_commands();
+
+        while !world.events().is_empty() {
+            self.dispatch_events(world);
+            world.apply_commands()
+        }
+    }
+
+    ///
+    /// Sends the [`Startup`] event, if it has not been sent yet
+    ///
+    pub fn startup(&mut self, world: &mut World) {
+        if !self.started {
+            self.started = true;
+            world.send(Startup);
+            self.end_stage(world)
+        }
+    }
+
+    ///
+    /// Sends the [`Shutdown`] event, if it has not been sent yet
+    ///
+    pub fn shutdown(&mut self, world: &mut World) {
+        if !self.shut_down {
+            self.shut_down = true;
+            world.send(Shutdown);
+            self.end_stage(world)
+        }
+    }
+
+    ///
+    /// Executes every stage once
+    ///
+    pub fn tick(&mut self, world: &mut World) {
+        self.startup(world);
+
+        for idx in 0..self.stages.len() {
+            self.run_stage(idx, world);
+            self.end_stage(world)
+        }
+
+        world.advance_tick()
+    }
+
+    fn run_stage(&mut self, idx: usize, world: &World) {
+        let stage = &mut self.stages[idx];
+
+        let threads = match self.mode {
+            ExecutionMode::SingleThreaded => {
+                for system in stage.systems.iter_mut().filter(|system| !system.finished) {
+                    system.run(world)
+                }
+
+                return
+            },
+            ExecutionMode::Parallel { threads } => threads.get()
+        };
+
+        let batches = stage.batches(world);
+        let mut slots = stage.systems.iter_mut().map(Some).collect::<Vec <_>>();
+
+        for batch in batches {
+            let mut systems = batch
+                .into_iter()
+                .map(|idx| slots[idx].take().expect("system is put into two batches"))
+                .collect::<Vec <_>>();
+
+            if systems.len() == 1 {
+                systems[0].run(world);
+                continue
+            }
+
+            let per_thread = systems.len().div_ceil(threads);
+
+            std::thread::scope(|scope| {
+                for chunk in systems.chunks_mut(per_thread) {
+                    scope.spawn(move || for system in chunk {
+                        system.run(world)
+                    });
+                }
+            })
+        }
+    }
+
+    ///
+    /// Describes which systems would be batched together if the world was ticked now
+    ///
+    pub fn report(&self, world: &World) -> ScheduleReport {
+        ScheduleReport {
+            stages: self.stages.iter().map(|stage| (stage.name.clone(), match self.mode {
+                ExecutionMode::SingleThreaded => stage.systems
+                    .iter()
+                    .filter(|system| !system.finished)
+                    .map(|system| vec![system.name.clone()])
+                    .collect(),
+                ExecutionMode::Parallel { .. } => stage.batches(world)
+                    .into_iter()
+                    .map(|batch| batch.into_iter().map(|idx| stage.systems[idx].name.clone()).collect())
+                    .collect()
+            })).collect()
+        }
+    }
+
+    ///
+    /// Executes exactly `ticks` ticks, unless the world asks to exit earlier.
+    ///
+    /// Does not shut the world down, so it can be inspected and run further
+    ///
+    pub fn run_for(&mut self, world: &mut World, ticks: u64) {
+        for _ in 0..ticks {
+            if world.is_exit_requested() {
+                break
+            }
+
+            self.tick(world)
+        }
+    }
+
+    ///
+    /// Runs the world until it asks to exit or every staged system is finished
+    ///
+    pub fn run(&mut self, world: &mut World) {
+        self.startup(world);
+
+        while !world.is_exit_requested() && !self.is_finished() {
+            self.tick(world)
+        }
+
+        self.shutdown(world)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.stages.iter().flat_map(|stage| &stage.systems).all(|system| system.finished)
+    }
+}
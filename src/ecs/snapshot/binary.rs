@@ -0,0 +1,261 @@
+//!
+//! The binary snapshot format
+//!
+//! All the integers are little-endian, strings and sequences are prefixed with their `u32` length
+//!
+//! ```text
+//! "OKOS" | format: u32 | fingerprint: u64 | schema: str | tick: u64
+//!   | generations: [u32] | free: [u32]
+//!   | entities: [index: u32 | generation: u32 | components: [name: str | VALUE]]
+//!   | events: [name: str | VALUE]
+//! ```
+//!
+//! Every `VALUE` starts with a tag byte:
+//!
+//! `0` bool: u8, `1` int: i64, `2` float: f64, `3` str, `4` entity: u32 u32,
+//!   `5` list: [VALUE], `6` struct: [name: str | VALUE], `7` enum: name: str | has_value: u8 | VALUE?
+//!
+
+use crate::ecs::entity::Entity;
+use super::{EntitySnapshot, Schema, Snapshot, SnapshotError, Value, FORMAT_VERSION};
+
+pub const MAGIC: &[u8; 4] = b"OKOS";
+
+struct Writer(Vec <u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value)
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend(value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend(value.to_le_bytes())
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("sequence is too long for a snapshot"))
+    }
+
+    fn str(&mut self, value: &str) {
+        self.len(value.len());
+        self.0.extend(value.as_bytes())
+    }
+
+    fn entity(&mut self, entity: Entity) {
+        self.u32(entity.index);
+        self.u32(entity.generation)
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Bool(bool) => {
+                self.u8(0);
+                self.u8(*bool as u8)
+            },
+            Value::Int(int) => {
+                self.u8(1);
+                self.u64(*int as u64)
+            },
+            Value::Float(float) => {
+                self.u8(2);
+                self.u64(float.to_bits())
+            },
+            Value::Str(str) => {
+                self.u8(3);
+                self.str(str)
+            },
+            Value::Entity(entity) => {
+                self.u8(4);
+                self.entity(*entity)
+            },
+            Value::List(list) => {
+                self.u8(5);
+                self.len(list.len());
+                list.iter().for_each(|value| self.value(value))
+            },
+            Value::Struct(fields) => {
+                self.u8(6);
+                self.len(fields.len());
+
+                for (name, value) in fields {
+                    self.str(name);
+                    self.value(value)
+                }
+            },
+            Value::Enum(variant, attached) => {
+                self.u8(7);
+                self.str(variant);
+                self.u8(attached.is_some() as u8);
+
+                if let Some(attached) = attached {
+                    self.value(attached)
+                }
+            }
+        }
+    }
+}
+
+struct Reader <'a> (&'a [u8]);
+
+impl Reader <'_> {
+    fn bytes <const N: usize> (&mut self) -> Result <[u8; N], SnapshotError> {
+        if self.0.len() < N {
+            return Err(SnapshotError::Malformed(String::from("unexpected end of data")))
+        }
+
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+
+        Ok(bytes.try_into().expect("slice has the requested length"))
+    }
+
+    fn u8(&mut self) -> Result <u8, SnapshotError> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result <u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn u64(&mut self) -> Result <u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    fn bool(&mut self) -> Result <bool, SnapshotError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(SnapshotError::Malformed(format!("invalid bool {other}")))
+        }
+    }
+
+    fn str(&mut self) -> Result <String, SnapshotError> {
+        let len = self.u32()? as usize;
+
+        if self.0.len() < len {
+            return Err(SnapshotError::Malformed(String::from("unexpected end of data")))
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        String::from_utf8(bytes.to_vec()).map_err(|_| SnapshotError::Malformed(String::from("invalid UTF-8 string")))
+    }
+
+    fn seq <T> (&mut self, mut item: impl FnMut(&mut Self) -> Result <T, SnapshotError>) -> Result <Vec <T>, SnapshotError> {
+        let len = self.u32()?;
+        (0..len).map(|_| item(self)).collect()
+    }
+
+    fn entity(&mut self) -> Result <Entity, SnapshotError> {
+        Ok(Entity {
+            index: self.u32()?,
+            generation: self.u32()?
+        })
+    }
+
+    fn value(&mut self) -> Result <Value, SnapshotError> {
+        Ok(match self.u8()? {
+            0 => Value::Bool(self.bool()?),
+            1 => Value::Int(self.u64()? as i64),
+            2 => Value::Float(f64::from_bits(self.u64()?)),
+            3 => Value::Str(self.str()?),
+            4 => Value::Entity(self.entity()?),
+            5 => Value::List(self.seq(Self::value)?),
+            6 => Value::Struct(self.seq(|reader| Ok((reader.str()?, reader.value()?)))?),
+            7 => {
+                let variant = self.str()?;
+
+                let attached = if self.bool()? {
+                    Some(Box::new(self.value()?))
+                } else {
+                    None
+                };
+
+                Value::Enum(variant, attached)
+            },
+            tag => return Err(SnapshotError::Malformed(format!("unknown value tag {tag}")))
+        })
+    }
+}
+
+impl Snapshot {
+    pub fn to_binary(&self) -> Vec <u8> {
+        let mut writer = Writer(MAGIC.to_vec());
+
+        writer.u32(FORMAT_VERSION);
+        writer.u64(self.schema.fingerprint());
+        writer.str(&self.schema.canonical());
+        writer.u64(self.tick);
+
+        writer.len(self.generations.len());
+        self.generations.iter().for_each(|generation| writer.u32(*generation));
+
+        writer.len(self.free.len());
+        self.free.iter().for_each(|index| writer.u32(*index));
+
+        writer.len(self.entities.len());
+
+        for entity in &self.entities {
+            writer.entity(entity.entity);
+            writer.len(entity.components.len());
+
+            for (name, value) in &entity.components {
+                writer.str(name);
+                writer.value(value)
+            }
+        }
+
+        writer.len(self.events.len());
+
+        for (name, value) in &self.events {
+            writer.str(name);
+            writer.value(value)
+        }
+
+        writer.0
+    }
+
+    pub fn from_binary(data: &[u8]) -> Result <Self, SnapshotError> {
+        let mut reader = Reader(data);
+
+        if &reader.bytes::<4>()? != MAGIC {
+            return Err(SnapshotError::Malformed(String::from("not an oko snapshot")))
+        }
+
+        let version = reader.u32()?;
+
+        if version != FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version))
+        }
+
+        let fingerprint = reader.u64()?;
+        let schema = Schema::from_canonical(&reader.str()?)?;
+
+        if schema.fingerprint() != fingerprint {
+            return Err(SnapshotError::Malformed(String::from("schema fingerprint does not match the schema")))
+        }
+
+        let snapshot = Self {
+            schema,
+            tick: reader.u64()?,
+            generations: reader.seq(Reader::u32)?,
+            free: reader.seq(Reader::u32)?,
+            entities: reader.seq(|reader| Ok(EntitySnapshot {
+                entity: reader.entity()?,
+                components: reader.seq(|reader| Ok((reader.str()?, reader.value()?)))?
+            }))?,
+            events: reader.seq(|reader| Ok((reader.str()?, reader.value()?)))?
+        };
+
+        if !reader.0.is_empty() {
+            return Err(SnapshotError::Malformed(String::from("trailing data")))
+        }
+
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,157 @@
+//!
+//! The JSON snapshot format
+//!
+//! Values are written as `true`, `5`, `5.0`, `"text"`, `[...]`,
+//!   `{"entity": "3v1"}`, `{"fields": {...}}` and `{"variant": "Some", "value": ...}`
+//!
+
+use serde_json::{json, Map, Value as Json};
+use crate::ecs::entity::Entity;
+use super::{EntitySnapshot, Schema, Snapshot, SnapshotError, Value, FORMAT_VERSION};
+
+fn malformed <T> (message: &str) -> Result <T, SnapshotError> {
+    Err(SnapshotError::Malformed(message.to_string()))
+}
+
+fn entity_to_json(entity: Entity) -> Json {
+    Json::String(format!("{}v{}", entity.index, entity.generation))
+}
+
+fn entity_from_json(json: &Json) -> Result <Entity, SnapshotError> {
+    let parsed = json
+        .as_str()
+        .and_then(|str| str.split_once('v'))
+        .and_then(|(index, generation)| Some(Entity {
+            index: index.parse().ok()?,
+            generation: generation.parse().ok()?
+        }));
+
+    match parsed {
+        Some(entity) => Ok(entity),
+        None => malformed("invalid entity")
+    }
+}
+
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::Bool(bool) => Json::Bool(*bool),
+        Value::Int(int) => json!(int),
+        Value::Float(float) => json!(float),
+        Value::Str(str) => Json::String(str.clone()),
+        Value::Entity(entity) => json!({ "entity": entity_to_json(*entity) }),
+        Value::List(list) => Json::Array(list.iter().map(value_to_json).collect()),
+        Value::Struct(fields) => json!({
+            "fields": fields.iter().map(|(name, value)| (name.clone(), value_to_json(value))).collect::<Map <_, _>>()
+        }),
+        Value::Enum(variant, attached) => match attached {
+            Some(attached) => json!({ "variant": variant, "value": value_to_json(attached) }),
+            None => json!({ "variant": variant })
+        }
+    }
+}
+
+fn value_from_json(json: &Json) -> Result <Value, SnapshotError> {
+    Ok(match json {
+        Json::Bool(bool) => Value::Bool(*bool),
+        Json::Number(number) => match number.as_i64() {
+            Some(int) if !number.is_f64() => Value::Int(int),
+            _ => Value::Float(number.as_f64().expect("a JSON number is representable as f64"))
+        },
+        Json::String(str) => Value::Str(str.clone()),
+        Json::Array(list) => Value::List(list.iter().map(value_from_json).collect::<Result <_, _>>()?),
+        Json::Object(object) => if let Some(entity) = object.get("entity") {
+            Value::Entity(entity_from_json(entity)?)
+        } else if let Some(Json::Object(fields)) = object.get("fields") {
+            Value::Struct(fields
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), value_from_json(value)?)))
+                .collect::<Result <_, _>>()?)
+        } else if let Some(Json::String(variant)) = object.get("variant") {
+            Value::Enum(variant.clone(), match object.get("value") {
+                Some(value) => Some(Box::new(value_from_json(value)?)),
+                None => None
+            })
+        } else {
+            return malformed("unknown object value")
+        },
+        Json::Null => return malformed("null value")
+    })
+}
+
+fn named_values_to_json(values: &[(String, Value)]) -> Json {
+    Json::Array(values.iter().map(|(name, value)| json!({ "type": name, "value": value_to_json(value) })).collect())
+}
+
+fn named_values_from_json(json: Option <&Json>) -> Result <Vec <(String, Value)>, SnapshotError> {
+    let Some(Json::Array(values)) = json else {
+        return malformed("expected an array of typed values")
+    };
+
+    values.iter().map(|value| match (value.get("type"), value.get("value")) {
+        (Some(Json::String(name)), Some(value)) => Ok((name.clone(), value_from_json(value)?)),
+        _ => malformed("expected a typed value")
+    }).collect()
+}
+
+fn u32s_from_json(json: Option <&Json>) -> Result <Vec <u32>, SnapshotError> {
+    let Some(Json::Array(values)) = json else {
+        return malformed("expected an array of integers")
+    };
+
+    values
+        .iter()
+        .map(|value| value.as_u64().and_then(|value| u32::try_from(value).ok()).map_or_else(|| malformed("expected an integer"), Ok))
+        .collect()
+}
+
+impl Snapshot {
+    pub fn to_json(&self) -> String {
+        let json = json!({
+            "format": FORMAT_VERSION,
+            "fingerprint": format!("{:016x}", self.schema.fingerprint()),
+            "schema": self.schema.canonical(),
+            "tick": self.tick,
+            "generations": self.generations,
+            "free": self.free,
+            "entities": self.entities.iter().map(|entity| json!({
+                "entity": entity_to_json(entity.entity),
+                "components": named_values_to_json(&entity.components)
+            })).collect::<Vec <_>>(),
+            "events": named_values_to_json(&self.events)
+        });
+
+        serde_json::to_string_pretty(&json).expect("snapshot is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result <Self, SnapshotError> {
+        let json: Json = serde_json::from_str(json).map_err(|err| SnapshotError::Malformed(err.to_string()))?;
+
+        let version = json.get("format").and_then(Json::as_u64).map_or_else(|| malformed("no format version"), Ok)?;
+
+        if version != FORMAT_VERSION as u64 {
+            return Err(SnapshotError::UnsupportedVersion(version as u32))
+        }
+
+        let schema = Schema::from_canonical(json.get("schema").and_then(Json::as_str).map_or_else(|| malformed("no schema"), Ok)?)?;
+
+        if json.get("fingerprint").and_then(Json::as_str) != Some(&format!("{:016x}", schema.fingerprint())) {
+            return malformed("schema fingerprint does not match the schema")
+        }
+
+        let Some(Json::Array(entities)) = json.get("entities") else {
+            return malformed("no entities")
+        };
+
+        Ok(Self {
+            schema,
+            tick: json.get("tick").and_then(Json::as_u64).map_or_else(|| malformed("no tick"), Ok)?,
+            generations: u32s_from_json(json.get("generations"))?,
+            free: u32s_from_json(json.get("free"))?,
+            entities: entities.iter().map(|entity| Ok(EntitySnapshot {
+                entity: entity_from_json(entity.get("entity").unwrap_or(&Json::Null))?,
+                components: named_values_from_json(entity.get("components"))?
+            })).collect::<Result <_, _>>()?,
+            events: named_values_from_json(json.get("events"))?
+        })
+    }
+}
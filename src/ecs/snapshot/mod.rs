@@ -0,0 +1,155 @@
+//!
+//! Saving the full state of a world and loading it back
+//!
+//! A [`Snapshot`] can be stored in a stable binary format ([`Snapshot::to_binary`])
+//!   or as JSON ([`Snapshot::to_json`])
+//!
+
+pub mod schema;
+pub mod value;
+pub mod binary;
+pub mod json;
+
+pub use schema::{Layout, Schema};
+pub use value::{Persistent, Value};
+
+use core::any::{Any, TypeId};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::collections::HashMap;
+use super::entity::Entity;
+
+///
+/// Bumped every time the binary or JSON representation changes
+///
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SnapshotError {
+    ///
+    /// The `ty` definitions changed since the snapshot was taken
+    ///
+    SchemaMismatch(Vec <String>),
+
+    ///
+    /// The snapshot was written by an incompatible version
+    ///
+    UnsupportedVersion(u32),
+
+    ///
+    /// The component or event is not a `ty` of the schema
+    ///
+    UnknownType(String),
+
+    ///
+    /// The component or event type was not registered as persistent
+    ///
+    NotPersistent(String),
+
+    InvalidValue {
+        ty: String,
+        expected: String
+    },
+
+    Malformed(String)
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::SchemaMismatch(differences) => write!(f, "snapshot schema mismatch: {}", differences.join(", ")),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported snapshot format version {version}, expected {FORMAT_VERSION}"),
+            Self::UnknownType(ty) => write!(f, "`{ty}` is not a `ty` of the schema"),
+            Self::NotPersistent(ty) => write!(f, "`{ty}` is not registered as persistent"),
+            Self::InvalidValue { ty, expected } => write!(f, "invalid value of `{ty}`: expected {expected}"),
+            Self::Malformed(message) => write!(f, "malformed snapshot: {message}")
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+///
+/// Converts values of a persistent type from and to [`Value`]s
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Codec {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub save: fn(&dyn Any) -> Value,
+    pub load: fn(&Value) -> Result <Box <dyn Any + Send + Sync>, SnapshotError>
+}
+
+impl Codec {
+    pub fn of <T: Persistent + Send + Sync> () -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: core::any::type_name::<T>(),
+            save: |value| value.downcast_ref::<T>().expect("codec type mismatch").to_value(),
+            load: |value| Ok(Box::new(T::from_value(value)?))
+        }
+    }
+}
+
+///
+/// The codecs of all the persistent components and events, keyed by the name of their `ty`
+///
+#[derive(Debug, Default)]
+pub struct Codecs {
+    by_type: HashMap <TypeId, String>,
+    by_name: HashMap <String, Codec>
+}
+
+impl Codecs {
+    pub fn register <T: Persistent + Send + Sync> (&mut self, name: &str) {
+        self.by_type.insert(TypeId::of::<T>(), name.to_string());
+        self.by_name.insert(name.to_string(), Codec::of::<T>());
+    }
+
+    pub fn by_type(&self, type_id: TypeId) -> Option <(&str, &Codec)> {
+        let name = self.by_type.get(&type_id)?;
+        Some((name, &self.by_name[name]))
+    }
+
+    pub fn by_name(&self, name: &str) -> Result <&Codec, SnapshotError> {
+        self.by_name.get(name).ok_or_else(|| SnapshotError::NotPersistent(name.to_string()))
+    }
+}
+
+///
+/// An entity with the values of all its components
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntitySnapshot {
+    pub entity: Entity,
+    pub components: Vec <(String, Value)>
+}
+
+///
+/// The full state of a world
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    ///
+    /// The schema the snapshot was taken with
+    ///
+    pub schema: Schema,
+
+    pub tick: u64,
+
+    ///
+    /// The generation of every entity slot, alive or not
+    ///
+    pub generations: Vec <u32>,
+
+    ///
+    /// The free entity slots, in the order they will be reused
+    ///
+    pub free: Vec <u32>,
+
+    pub entities: Vec <EntitySnapshot>,
+
+    ///
+    /// The events sent, but not yet handled, in the order they were sent
+    ///
+    pub events: Vec <(String, Value)>
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use crate::parse::stmt::{Stmt, TyStmt, TyStmtBody};
+use crate::parse::typed_variable::TypedVariables;
+use super::{SnapshotError, Value};
+
+///
+/// The layout of a single `ty`, i.e. its field names and types
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Layout {
+    ///
+    /// `ty Vec2 = x y: float` -- `[("x", "float"), ("y", "float")]`
+    ///
+    Struct(Vec <(String, String)>),
+
+    ///
+    /// `ty Bits = union i: int + f: float` -- `[("i", "int"), ("f", "float")]`, stored as a struct with one of the fields
+    ///
+    Union(Vec <(String, String)>),
+
+    ///
+    /// `ty Option = None | Some i32` -- `[("None", None), ("Some", Some("i32"))]`
+    ///
+    Enum(Vec <(String, Option <String>)>)
+}
+
+impl Layout {
+    pub fn of(ty: &TyStmt) -> Self {
+        let fields = |fields: &[TypedVariables]| fields
+            .iter()
+            .flat_map(|field| field.names.iter().map(|name| (name.name.clone(), format!("{:?}", field.ty))))
+            .collect();
+
+        match &ty.body {
+            TyStmtBody::Struct(body) => Self::Struct(fields(&body.fields.0)),
+            TyStmtBody::Union(body) => Self::Union(fields(&body.fields.0)),
+            TyStmtBody::Enum(body) => Self::Enum(body.fields.0
+                .iter()
+                .map(|field| (field.name.name.clone(), field.attached_type.as_ref().map(|ty| format!("{ty:?}"))))
+                .collect())
+        }
+    }
+
+    ///
+    /// Checks that the value has the shape of this layout
+    ///
+    pub fn validate(&self, ty: &str, value: &Value) -> Result <(), SnapshotError> {
+        let invalid = |expected: String| SnapshotError::InvalidValue {
+            ty: ty.to_string(),
+            expected
+        };
+
+        match (self, value) {
+            (Self::Struct(fields), Value::Struct(values)) => {
+                for (name, _) in fields {
+                    if !values.iter().any(|(other, _)| other == name) {
+                        return Err(invalid(format!("field `{name}`")))
+                    }
+                }
+
+                if let Some((extra, _)) = values.iter().find(|(name, _)| !fields.iter().any(|(other, _)| other == name)) {
+                    return Err(invalid(format!("no field `{extra}`")))
+                }
+
+                Ok(())
+            },
+            (Self::Union(fields), Value::Struct(values)) => match values.as_slice() {
+                [(name, _)] if fields.iter().any(|(other, _)| other == name) => Ok(()),
+                [(name, _)] => Err(invalid(format!("no field `{name}`"))),
+                _ => Err(invalid(String::from("exactly one field")))
+            },
+            (Self::Enum(variants), Value::Enum(variant, attached)) => match variants.iter().find(|(name, _)| name == variant) {
+                Some((_, ty)) if ty.is_some() == attached.is_some() => Ok(()),
+                Some((_, Some(ty))) => Err(invalid(format!("variant `{variant}` to carry `{ty}`"))),
+                Some((_, None)) => Err(invalid(format!("variant `{variant}` to carry nothing"))),
+                None => Err(invalid(format!("one of the variants {}", variants
+                    .iter()
+                    .map(|(name, _)| format!("`{name}`"))
+                    .collect::<Vec <_>>()
+                    .join(", "))))
+            },
+            (Self::Struct(_), _) => Err(invalid(String::from("a struct"))),
+            (Self::Union(_), _) => Err(invalid(String::from("a union"))),
+            (Self::Enum(_), _) => Err(invalid(String::from("an enum variant")))
+        }
+    }
+
+    ///
+    /// The kind of the layout followed by its fields: `struct x: float + y: float`, `enum None | Some i32`
+    ///
+    fn canonical(&self) -> String {
+        let join = |fields: &[(String, String)]| fields
+            .iter()
+            .map(|(name, ty)| format!("{name}: {ty}"))
+            .collect::<Vec <_>>()
+            .join(" + ");
+
+        let (kind, body) = match self {
+            Self::Struct(fields) => ("struct", join(fields)),
+            Self::Union(fields) => ("union", join(fields)),
+            Self::Enum(variants) => ("enum", variants
+                .iter()
+                .map(|(name, ty)| match ty {
+                    Some(ty) => format!("{name} {ty}"),
+                    None => name.clone()
+                })
+                .collect::<Vec <_>>()
+                .join(" | "))
+        };
+
+        if body.is_empty() {
+            String::from(kind)
+        } else {
+            format!("{kind} {body}")
+        }
+    }
+}
+
+///
+/// The layouts of all the types that can be stored in a snapshot
+///
+/// Snapshots remember the schema they were taken with, so loading a snapshot
+///   after the `ty` definitions have changed is detected
+///
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Schema {
+    pub types: BTreeMap <String, Layout>
+}
+
+impl Schema {
+    ///
+    /// Collects the `ty` statements, not descending into worlds
+    ///
+    pub fn from_stmts(stmts: &[Stmt]) -> Self {
+        let mut schema = Self::default();
+        schema.extend(stmts);
+        schema
+    }
+
+    ///
+    /// Collects the top-level `ty` statements and the ones declared inside the world `world`
+    ///
+    pub fn for_world(stmts: &[Stmt], world: &str) -> Self {
+        let mut schema = Self::from_stmts(stmts);
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::World(it) if it.name.name == world => schema.extend(&it.body),
+                _ => ()
+            }
+        }
+
+        schema
+    }
+
+    fn extend(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            if let Stmt::Ty(ty) = stmt {
+                self.types.insert(ty.name.name.clone(), Layout::of(ty));
+            }
+        }
+    }
+
+    pub fn layout(&self, ty: &str) -> Result <&Layout, SnapshotError> {
+        self.types.get(ty).ok_or_else(|| SnapshotError::UnknownType(ty.to_string()))
+    }
+
+    ///
+    /// The textual form of the schema, one `ty` per line, sorted by name: `ty Vec2 = struct x: float + y: float`
+    ///
+    pub fn canonical(&self) -> String {
+        self.types
+            .iter()
+            .map(|(name, layout)| format!("ty {name} = {}\n", layout.canonical()))
+            .collect()
+    }
+
+    ///
+    /// Parses the output of [`Schema::canonical`] back
+    ///
+    pub fn from_canonical(canonical: &str) -> Result <Self, SnapshotError> {
+        let malformed = || SnapshotError::Malformed(String::from("invalid schema"));
+
+        let mut types = BTreeMap::new();
+
+        for line in canonical.lines() {
+            let (name, body) = line.strip_prefix("ty ").and_then(|line| line.split_once(" = ")).ok_or_else(malformed)?;
+
+            let fields = |body: &str| body
+                .split(" + ")
+                .filter(|field| !field.is_empty())
+                .map(|field| field.split_once(": ").map(|(name, ty)| (name.to_string(), ty.to_string())).ok_or_else(malformed))
+                .collect::<Result <_, _>>();
+
+            let (kind, body) = body.split_once(' ').unwrap_or((body, ""));
+
+            let layout = match kind {
+                "struct" => Layout::Struct(fields(body)?),
+                "union" => Layout::Union(fields(body)?),
+                "enum" => Layout::Enum(body
+                    .split(" | ")
+                    .filter(|variant| !variant.is_empty())
+                    .map(|variant| match variant.split_once(' ') {
+                        Some((name, ty)) => (name.to_string(), Some(ty.to_string())),
+                        None => (variant.to_string(), None)
+                    })
+                    .collect()),
+                _ => return Err(malformed())
+            };
+
+            types.insert(name.to_string(), layout);
+        }
+
+        Ok(Self {
+            types
+        })
+    }
+
+    ///
+    /// The 64-bit FNV-1a hash of the canonical form
+    ///
+    pub fn fingerprint(&self) -> u64 {
+        self.canonical().bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+    }
+
+    ///
+    /// Describes how the `newer` schema differs from this one
+    ///
+    pub fn diff(&self, newer: &Schema) -> Vec <String> {
+        let mut differences = vec![];
+
+        for (name, layout) in &self.types {
+            match newer.types.get(name) {
+                None => differences.push(format!("`{name}` was removed")),
+                Some(other) if other != layout => differences.push(format!("`{name}` changed from `{}` to `{}`", layout.canonical(), other.canonical())),
+                _ => ()
+            }
+        }
+
+        for name in newer.types.keys().filter(|name| !self.types.contains_key(*name)) {
+            differences.push(format!("`{name}` was added"))
+        }
+
+        differences
+    }
+}
@@ -0,0 +1,205 @@
+use super::SnapshotError;
+use crate::ecs::entity::Entity;
+
+///
+/// A component or event value in a form independent of its Rust representation
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Entity(Entity),
+    List(Vec <Value>),
+
+    ///
+    /// The fields of a struct `ty`
+    ///
+    Struct(Vec <(String, Value)>),
+
+    ///
+    /// The variant of an enum `ty` and the value attached to it
+    ///
+    Enum(String, Option <Box <Value>>)
+}
+
+impl Value {
+    fn invalid <T> (expected: &str) -> Result <T, SnapshotError> {
+        Err(SnapshotError::InvalidValue {
+            ty: String::from(core::any::type_name::<T>()),
+            expected: expected.to_string()
+        })
+    }
+
+    ///
+    /// Returns the field `name` of a struct value
+    ///
+    pub fn field(&self, name: &str) -> Result <&Value, SnapshotError> {
+        match self {
+            Self::Struct(fields) => fields.iter().find(|(field, _)| field == name).map(|(_, value)| value).ok_or_else(|| SnapshotError::InvalidValue {
+                ty: String::from("struct"),
+                expected: format!("field `{name}`")
+            }),
+            _ => Err(SnapshotError::InvalidValue {
+                ty: String::from("struct"),
+                expected: String::from("a struct")
+            })
+        }
+    }
+
+    ///
+    /// Returns the variant name and the attached value of an enum value
+    ///
+    pub fn variant(&self) -> Result <(&str, Option <&Value>), SnapshotError> {
+        match self {
+            Self::Enum(variant, attached) => Ok((variant, attached.as_deref())),
+            _ => Err(SnapshotError::InvalidValue {
+                ty: String::from("enum"),
+                expected: String::from("an enum variant")
+            })
+        }
+    }
+}
+
+///
+/// A Rust type that can be saved in a snapshot
+///
+/// Use [`persistent_struct`](crate::persistent_struct) for structs
+///
+pub trait Persistent: Sized + 'static {
+    fn to_value(&self) -> Value;
+
+    fn from_value(value: &Value) -> Result <Self, SnapshotError>;
+}
+
+macro_rules! impl_persistent_int {
+    ($( $ty:ty )*) => {$(
+        impl Persistent for $ty {
+            fn to_value(&self) -> Value {
+                Value::Int(i64::try_from(*self).expect("integer does not fit into a snapshot"))
+            }
+
+            fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+                match value {
+                    Value::Int(int) => <$ty>::try_from(*int).or_else(|_| Value::invalid(concat!("an integer in range of `", stringify!($ty), "`"))),
+                    _ => Value::invalid("an integer")
+                }
+            }
+        }
+    )*};
+}
+
+impl_persistent_int!(i8 i16 i32 i64 u8 u16 u32 u64 isize usize);
+
+macro_rules! impl_persistent_float {
+    ($( $ty:ty )*) => {$(
+        impl Persistent for $ty {
+            fn to_value(&self) -> Value {
+                Value::Float(*self as f64)
+            }
+
+            fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+                match value {
+                    Value::Float(float) => Ok(*float as $ty),
+                    _ => Value::invalid("a float")
+                }
+            }
+        }
+    )*};
+}
+
+impl_persistent_float!(f32 f64);
+
+impl Persistent for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+        match value {
+            Value::Bool(bool) => Ok(*bool),
+            _ => Value::invalid("a bool")
+        }
+    }
+}
+
+impl Persistent for String {
+    fn to_value(&self) -> Value {
+        Value::Str(self.clone())
+    }
+
+    fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+        match value {
+            Value::Str(str) => Ok(str.clone()),
+            _ => Value::invalid("a string")
+        }
+    }
+}
+
+impl Persistent for Entity {
+    fn to_value(&self) -> Value {
+        Value::Entity(*self)
+    }
+
+    fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+        match value {
+            Value::Entity(entity) => Ok(*entity),
+            _ => Value::invalid("an entity")
+        }
+    }
+}
+
+impl <T: Persistent> Persistent for Vec <T> {
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(T::to_value).collect())
+    }
+
+    fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+        match value {
+            Value::List(list) => list.iter().map(T::from_value).collect(),
+            _ => Value::invalid("a list")
+        }
+    }
+}
+
+impl <T: Persistent> Persistent for Option <T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(value) => Value::Enum(String::from("Some"), Some(Box::new(value.to_value()))),
+            None => Value::Enum(String::from("None"), None)
+        }
+    }
+
+    fn from_value(value: &Value) -> Result <Self, SnapshotError> {
+        match value.variant()? {
+            ("Some", Some(value)) => Ok(Some(T::from_value(value)?)),
+            ("None", None) => Ok(None),
+            _ => Value::invalid("either `Some` with a value or `None`")
+        }
+    }
+}
+
+///
+/// Implements [`Persistent`] for a struct whose fields are all persistent
+///
+/// `persistent_struct!(Vec2 { x, y })`
+///
+#[macro_export]
+macro_rules! persistent_struct {
+    ($ty:ident { $( $field:ident ),* $(,)? }) => {
+        impl $crate::ecs::snapshot::Persistent for $ty {
+            fn to_value(&self) -> $crate::ecs::snapshot::Value {
+                $crate::ecs::snapshot::Value::Struct(vec![$(
+                    (String::from(stringify!($field)), $crate::ecs::snapshot::Persistent::to_value(&self.$field))
+                ),*])
+            }
+
+            fn from_value(value: &$crate::ecs::snapshot::Value) -> Result <Self, $crate::ecs::snapshot::SnapshotError> {
+                Ok(Self {$(
+                    $field: $crate::ecs::snapshot::Persistent::from_value(value.field(stringify!($field))?)?
+                ),*})
+            }
+        }
+    };
+}
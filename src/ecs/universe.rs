@@ -0,0 +1,171 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::{Arc, Mutex};
+use super::event::Event;
+use super::schedule::Schedule;
+use super::world::World;
+
+///
+/// A world together with the schedule running it
+///
+#[derive(Debug)]
+pub struct WorldEntry {
+    pub name: String,
+    pub world: World,
+    pub schedule: Schedule
+}
+
+///
+/// The sending end of a channel between two worlds
+///
+/// Sent events are buffered and delivered to the receiving world at the frame boundary
+///
+pub struct Sender <E: Event> {
+    buffer: Arc <Mutex <Vec <E>>>
+}
+
+impl <E: Event> Clone for Sender <E> {
+    fn clone(&self) -> Self {
+        Self {
+            buffer: Arc::clone(&self.buffer)
+        }
+    }
+}
+
+impl <E: Event> Sender <E> {
+    pub fn send(&self, event: E) {
+        self.buffer.lock().expect("channel poisoned").push(event)
+    }
+}
+
+trait AnyChannel: Send {
+    ///
+    /// Moves the buffered events into the event queue of the receiving world
+    ///
+    fn deliver(&mut self, worlds: &[WorldEntry]);
+}
+
+struct Channel <E: Event> {
+    to: usize,
+    buffer: Arc <Mutex <Vec <E>>>
+}
+
+impl <E: Event> AnyChannel for Channel <E> {
+    fn deliver(&mut self, worlds: &[WorldEntry]) {
+        let events = core::mem::take(&mut *self.buffer.lock().expect("channel poisoned"));
+        let world = &worlds[self.to].world;
+
+        for event in events {
+            world.send(event)
+        }
+    }
+}
+
+///
+/// Several independent worlds, each with its own schedule, connected with channels
+///
+/// Every frame each world is ticked once, then the events sent through channels
+///   are delivered, so they are handled by the receiving world on its next tick
+///
+#[derive(Default)]
+pub struct Universe {
+    worlds: Vec <WorldEntry>,
+    channels: Vec <Box <dyn AnyChannel>>
+}
+
+impl Debug for Universe {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.debug_struct("Universe")
+            .field("worlds", &self.worlds)
+            .field("channels", &self.channels.len())
+            .finish()
+    }
+}
+
+impl Universe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_world(&mut self, name: &str, world: World, schedule: Schedule) -> &mut Self {
+        assert!(self.position(name).is_none(), "world `{name}` already exists");
+
+        self.worlds.push(WorldEntry {
+            name: name.to_string(),
+            world,
+            schedule
+        });
+
+        self
+    }
+
+    fn position(&self, name: &str) -> Option <usize> {
+        self.worlds.iter().position(|entry| entry.name == name)
+    }
+
+    pub fn world(&self, name: &str) -> Option <&WorldEntry> {
+        self.worlds.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn world_mut(&mut self, name: &str) -> Option <&mut WorldEntry> {
+        self.worlds.iter_mut().find(|entry| entry.name == name)
+    }
+
+    pub fn worlds(&self) -> &[WorldEntry] {
+        &self.worlds
+    }
+
+    ///
+    /// Creates a channel delivering events `E` from the world `from` to the world `to`
+    ///
+    pub fn channel <E: Event> (&mut self, from: &str, to: &str) -> Sender <E> {
+        let from = self.position(from).unwrap_or_else(|| panic!("no world `{from}`"));
+        let to = self.position(to).unwrap_or_else(|| panic!("no world `{to}`"));
+
+        assert_ne!(from, to, "a channel must connect two different worlds");
+
+        let buffer = Arc::new(Mutex::new(vec![]));
+
+        self.channels.push(Box::new(Channel {
+            to,
+            buffer: Arc::clone(&buffer)
+        }));
+
+        Sender {
+            buffer
+        }
+    }
+
+    ///
+    /// Ticks every running world once and delivers the events sent through channels
+    ///
+    pub fn frame(&mut self) {
+        for entry in &mut self.worlds {
+            if !entry.world.is_exit_requested() {
+                entry.schedule.tick(&mut entry.world)
+            }
+        }
+
+        for channel in &mut self.channels {
+            channel.deliver(&self.worlds)
+        }
+    }
+
+    pub fn run_for(&mut self, frames: u64) {
+        for _ in 0..frames {
+            self.frame()
+        }
+    }
+
+    ///
+    /// Runs the worlds until every one of them asks to exit or finishes all its staged systems
+    ///
+    pub fn run(&mut self) {
+        while self.worlds.iter().any(|entry| !entry.world.is_exit_requested() && !entry.schedule.is_finished()) {
+            self.frame()
+        }
+
+        for entry in &mut self.worlds {
+            entry.schedule.shutdown(&mut entry.world)
+        }
+    }
+}
@@ -0,0 +1,482 @@
+use core::any::Any;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashSet;
+use super::archetype::{ArchetypeId, Archetypes};
+use super::bundle::Bundle;
+use super::command::{CommandQueue, Commands};
+use super::component::{Component, ComponentId, Components};
+use super::entity::{Entities, Entity, EntityLocation};
+use super::event::{Event, EventRecord, Events};
+use super::query::{Fetch, Query};
+use super::snapshot::{Codecs, EntitySnapshot, Persistent, Schema, Snapshot, SnapshotError};
+
+///
+/// An independent set of components, entities and events
+///
+/// Systems and callbacks live in a [`Schedule`](super::Schedule) that runs the world
+///
+#[derive(Debug, Default)]
+pub struct World {
+    entities: Entities,
+    components: Components,
+    archetypes: Archetypes,
+    events: Events,
+    commands: CommandQueue,
+    codecs: Codecs,
+    tick: u64,
+    exit: AtomicBool
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Registers the component `T` originating from the `ty` named `name`
+    ///
+    pub fn register_named <T: Component> (&mut self, name: &str) -> ComponentId {
+        self.components.register_named::<T>(name)
+    }
+
+    pub fn register <T: Component> (&mut self) -> ComponentId {
+        self.components.register::<T>()
+    }
+
+    ///
+    /// Registers the component `T` originating from the `ty` named `name` and allows saving it in snapshots
+    ///
+    pub fn register_persistent <T: Component + Persistent> (&mut self, name: &str) -> ComponentId {
+        self.codecs.register::<T>(name);
+        self.components.register_named::<T>(name)
+    }
+
+    ///
+    /// Allows saving the pending events `E` originating from the `ty` named `name` in snapshots
+    ///
+    pub fn register_persistent_event <E: Event + Persistent> (&mut self, name: &str) {
+        self.codecs.register::<E>(name)
+    }
+
+    ///
+    /// Saves the full state of the world.
+    ///
+    /// Every component and pending event must be registered as persistent and be a `ty` of `schema`
+    ///
+    pub fn snapshot(&self, schema: &Schema) -> Result <Snapshot, SnapshotError> {
+        let (generations, free) = self.entities.state();
+
+        let mut entities = vec![];
+
+        for archetype in self.archetypes.iter() {
+            let columns = archetype.columns
+                .iter()
+                .map(|column| column.read().expect("component column poisoned"))
+                .collect::<Vec <_>>();
+
+            let codecs = archetype.components.iter().map(|id| {
+                let info = self.components.info(*id);
+                self.codecs.by_type(info.type_id).ok_or_else(|| SnapshotError::NotPersistent(info.name.clone()))
+            }).collect::<Result <Vec <_>, _>>()?;
+
+            for (row, entity) in archetype.entities.iter().enumerate() {
+                let components = codecs.iter().zip(&columns).map(|((name, codec), column)| {
+                    let value = (codec.save)(column.get(row));
+                    schema.layout(name)?.validate(name, &value)?;
+                    Ok((name.to_string(), value))
+                }).collect::<Result <_, SnapshotError>>()?;
+
+                entities.push(EntitySnapshot {
+                    entity: *entity,
+                    components
+                })
+            }
+        }
+
+        entities.sort_by_key(|entity| entity.entity.index);
+
+        let mut events = vec![];
+        let mut error = None;
+
+        self.events.for_each(|record| match self.codecs.by_type(record.type_id) {
+            Some((name, codec)) => {
+                let value = (codec.save)(record.payload.as_ref());
+
+                match schema.layout(name).and_then(|layout| layout.validate(name, &value)) {
+                    Ok(()) => events.push((name.to_string(), value)),
+                    Err(err) => error = error.take().or(Some(err))
+                }
+            },
+            None => error = error.take().or(Some(SnapshotError::NotPersistent(record.name.to_string())))
+        });
+
+        if let Some(error) = error {
+            return Err(error)
+        }
+
+        Ok(Snapshot {
+            schema: schema.clone(),
+            tick: self.tick,
+            generations,
+            free,
+            entities,
+            events
+        })
+    }
+
+    ///
+    /// Replaces the state of the world with the one saved in the snapshot.
+    ///
+    /// Fails without touching the world if the snapshot was taken with a schema different from `schema`
+    ///
+    pub fn restore(&mut self, snapshot: &Snapshot, schema: &Schema) -> Result <(), SnapshotError> {
+        if snapshot.schema != *schema {
+            return Err(SnapshotError::SchemaMismatch(snapshot.schema.diff(schema)))
+        }
+
+        let mut rows = vec![];
+
+        for entity in &snapshot.entities {
+            let mut components = vec![];
+
+            for (name, value) in &entity.components {
+                schema.layout(name)?.validate(name, value)?;
+
+                let codec = self.codecs.by_name(name)?;
+                let id = self.components.id_by_type(codec.type_id).ok_or_else(|| SnapshotError::NotPersistent(name.clone()))?;
+
+                if components.iter().any(|(other, _)| *other == id) {
+                    return Err(SnapshotError::Malformed(format!("`{name}` is attached to {:?} twice", entity.entity)))
+                }
+
+                components.push((id, (codec.load)(value)?))
+            }
+
+            rows.push((entity.entity, components))
+        }
+
+        let events = snapshot.events.iter().map(|(name, value)| {
+            schema.layout(name)?.validate(name, value)?;
+            let codec = self.codecs.by_name(name)?;
+
+            Ok(EventRecord {
+                type_id: codec.type_id,
+                name: codec.type_name,
+                payload: (codec.load)(value)?
+            })
+        }).collect::<Result <Vec <_>, SnapshotError>>()?;
+
+        let free = snapshot.free.iter().copied().collect::<HashSet <_>>();
+
+        if free.len() != snapshot.free.len() || free.iter().any(|index| *index as usize >= snapshot.generations.len()) || rows.iter().any(|(entity, _)| {
+            snapshot.generations.get(entity.index as usize) != Some(&entity.generation) || free.contains(&entity.index)
+        }) {
+            return Err(SnapshotError::Malformed(String::from("entities do not match the allocator state")))
+        }
+
+        let mut saved = HashSet::new();
+
+        if let Some((entity, _)) = rows.iter().find(|(entity, _)| !saved.insert(entity.index)) {
+            return Err(SnapshotError::Malformed(format!("{entity:?} is saved twice")))
+        }
+
+        // Every slot that is not free is alive, so it must have been saved with its components
+        if let Some(index) = (0..snapshot.generations.len() as u32).find(|index| !free.contains(index) && !saved.contains(index)) {
+            return Err(SnapshotError::Malformed(format!("entity slot {index} is alive, but not saved")))
+        }
+
+        self.entities = Entities::from_state(&snapshot.generations, &snapshot.free);
+        self.archetypes = Archetypes::default();
+
+        for (entity, components) in rows {
+            let archetype_id = self.archetypes.get_or_create(components.iter().map(|(id, _)| *id).collect(), &self.components);
+            let archetype = self.archetypes.get_mut(archetype_id);
+
+            for (id, value) in components {
+                archetype.column_mut(id).expect("archetype does not contain the component").push_boxed(value)
+            }
+
+            archetype.entities.push(entity);
+
+            self.entities.set_location(entity, EntityLocation {
+                archetype: archetype_id,
+                row: archetype.len() - 1
+            })
+        }
+
+        self.events.clear();
+        events.into_iter().for_each(|record| self.events.push(record));
+
+        drop(self.commands.take());
+        self.tick = snapshot.tick;
+        self.exit.store(false, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    pub fn spawn <B: Bundle> (&mut self, bundle: B) -> Entity {
+        self.flush_reserved();
+
+        let ids = B::register(&mut self.components);
+        let archetype_id = self.archetypes.get_or_create(ids, &self.components);
+
+        let entity = self.entities.alloc();
+
+        let archetype = self.archetypes.get_mut(archetype_id);
+        let row = archetype.len();
+
+        bundle.push(archetype, &self.components);
+        archetype.entities.push(entity);
+
+        self.entities.set_location(entity, EntityLocation {
+            archetype: archetype_id,
+            row
+        });
+
+        entity
+    }
+
+    ///
+    /// Removes the entity with all its components.
+    ///
+    /// Returns `false` if the entity is already despawned
+    ///
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        self.flush_reserved();
+
+        let Some(location) = self.entities.free(entity) else {
+            return false
+        };
+
+        if let Some(moved) = self.archetypes.get_mut(location.archetype).swap_remove(location.row) {
+            self.entities.set_location(moved, location)
+        }
+
+        true
+    }
+
+    ///
+    /// Adds the component to the entity, replacing the old value if it already has one.
+    ///
+    /// Returns `false` if the entity is despawned
+    ///
+    pub fn insert <T: Component> (&mut self, entity: Entity, component: T) -> bool {
+        self.flush_reserved();
+
+        let Some(location) = self.entities.location(entity) else {
+            return false
+        };
+
+        let id = self.components.register::<T>();
+        let archetype = self.archetypes.get_mut(location.archetype);
+
+        if let Some(column) = archetype.column_mut(id) {
+            column.downcast_mut::<T>().0[location.row] = component;
+            return true
+        }
+
+        let mut ids = archetype.components.clone();
+        ids.push(id);
+
+        let target = self.archetypes.get_or_create(ids, &self.components);
+
+        self.archetypes
+            .get_mut(target)
+            .column_mut(id)
+            .expect("archetype does not contain the inserted component")
+            .downcast_mut::<T>()
+            .0
+            .push(component);
+
+        self.move_entity(entity, location, target);
+
+        true
+    }
+
+    ///
+    /// Removes the component from the entity, returning it
+    ///
+    pub fn remove <T: Component> (&mut self, entity: Entity) -> Option <T> {
+        self.flush_reserved();
+
+        let location = self.entities.location(entity)?;
+        let id = self.components.id::<T>()?;
+        let archetype = self.archetypes.get(location.archetype);
+
+        if !archetype.contains(id) {
+            return None
+        }
+
+        let ids = archetype.components.iter().copied().filter(|other| *other != id).collect();
+        let target = self.archetypes.get_or_create(ids, &self.components);
+
+        self.move_entity(entity, location, target)
+            .into_iter()
+            .find(|(removed, _)| *removed == id)
+            .map(|(_, value)| *value.downcast().expect("column type mismatch"))
+    }
+
+    ///
+    /// Moves the entity into the `target` archetype, carrying over every component both archetypes have.
+    ///
+    /// Components absent in `target` are returned
+    ///
+    fn move_entity(&mut self, entity: Entity, location: EntityLocation, target: ArchetypeId) -> Vec <(ComponentId, Box <dyn Any + Send + Sync>)> {
+        let (source, destination) = self.archetypes.get_two_mut(location.archetype, target);
+        let mut leftovers = vec![];
+
+        for (idx, id) in source.components.iter().enumerate() {
+            let value = source.columns[idx].get_mut().expect("component column poisoned").swap_remove_boxed(location.row);
+
+            match destination.column_mut(*id) {
+                Some(column) => column.push_boxed(value),
+                None => leftovers.push((*id, value))
+            }
+        }
+
+        source.entities.swap_remove(location.row);
+        let moved = source.entities.get(location.row).copied();
+
+        destination.entities.push(entity);
+        let row = destination.len() - 1;
+
+        if let Some(moved) = moved {
+            self.entities.set_location(moved, location)
+        }
+
+        self.entities.set_location(entity, EntityLocation {
+            archetype: target,
+            row
+        });
+
+        leftovers
+    }
+
+    ///
+    /// Makes the entities reserved by [`Commands::spawn`] alive and empty
+    ///
+    fn flush_reserved(&mut self) {
+        let reserved = self.entities.flush_reserved();
+
+        if reserved.is_empty() {
+            return
+        }
+
+        let empty = self.archetypes.get_or_create(vec![], &self.components);
+        let archetype = self.archetypes.get_mut(empty);
+
+        for entity in reserved {
+            self.entities.set_location(entity, EntityLocation {
+                archetype: empty,
+                row: archetype.len()
+            });
+            archetype.entities.push(entity)
+        }
+    }
+
+    pub fn commands(&self) -> Commands <'_> {
+        Commands::new(self)
+    }
+
+    pub fn command_queue(&self) -> &CommandQueue {
+        &self.commands
+    }
+
+    ///
+    /// Applies all the deferred structural changes in the order they were requested
+    ///
+    pub fn apply_commands(&mut self) {
+        self.flush_reserved();
+
+        for command in self.commands.take() {
+            command(self)
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    pub fn has <T: Component> (&self, entity: Entity) -> bool {
+        match (self.entities.location(entity), self.components.id::<T>()) {
+            (Some(location), Some(id)) => self.archetypes.get(location.archetype).contains(id),
+            _ => false
+        }
+    }
+
+    pub fn query <Q: Fetch> (&self) -> Query <'_, Q> {
+        Query::new(self)
+    }
+
+    ///
+    /// Calls `f` with the component `T` of `entity`, if it has one
+    ///
+    pub fn read <T: Component, R> (&self, entity: Entity, f: impl FnOnce(&T) -> R) -> Option <R> {
+        let location = self.entities.location(entity)?;
+        let column = self.archetypes.get(location.archetype).column(self.components.id::<T>()?)?;
+        let column = column.read().expect("component column poisoned");
+
+        Some(f(&column.downcast_ref::<T>().0[location.row]))
+    }
+
+    ///
+    /// Calls `f` with the mutable component `T` of `entity`, if it has one
+    ///
+    pub fn write <T: Component, R> (&self, entity: Entity, f: impl FnOnce(&mut T) -> R) -> Option <R> {
+        let location = self.entities.location(entity)?;
+        let column = self.archetypes.get(location.archetype).column(self.components.id::<T>()?)?;
+        let mut column = column.write().expect("component column poisoned");
+
+        Some(f(&mut column.downcast_mut::<T>().0[location.row]))
+    }
+
+    pub fn send <E: Event> (&self, event: E) {
+        self.events.send(event)
+    }
+
+    ///
+    /// Asks the schedule running this world to stop after the current tick
+    ///
+    pub fn exit(&self) {
+        self.exit.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_exit_requested(&self) -> bool {
+        self.exit.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// How many ticks have been completed
+    ///
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn advance_tick(&mut self) {
+        self.tick += 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn entities(&self) -> &Entities {
+        &self.entities
+    }
+
+    pub fn components(&self) -> &Components {
+        &self.components
+    }
+
+    pub fn archetypes(&self) -> &Archetypes {
+        &self.archetypes
+    }
+
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+}
@@ -1,9 +1,11 @@
-use crate::span::Span;
+use crate::span::{CursorPosition, Span};
+use std::io::IsTerminal;
 use std::process::{ExitCode, Termination};
 use core::fmt::Display;
 use core::ops::{Try, FromResidual, ControlFlow};
 use core::convert::Infallible;
 use owo_colors::*;
+use serde_json::{json, Value as Json};
 
 #[repr(transparent)]
 pub struct Result <T> (pub core::result::Result <T, Error>);
@@ -45,32 +47,118 @@ impl Termination for Result <()> {
     }
 }
 
+///
+/// How serious a diagnostic is. Only errors stop the compilation
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+
+    ///
+    /// Additional information about the previous diagnostic
+    ///
+    Note
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note"
+        }
+    }
+
+    ///
+    /// The color of the label, the underscoring and the clarifying message, and the color of the lines of code
+    ///
+    fn colors(self) -> (AnsiColors, AnsiColors) {
+        match self {
+            Self::Error => (AnsiColors::BrightRed, AnsiColors::Red),
+            Self::Warning => (AnsiColors::BrightYellow, AnsiColors::Yellow),
+            Self::Note => (AnsiColors::BrightCyan, AnsiColors::Cyan)
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Error {
+    pub severity: Severity,
+
+    ///
+    /// Identifies the kind of the diagnostic, such as the name of a lint
+    ///
+    pub code: Option <String>,
+
     pub span: Span,
     pub message: String,
     pub spanned: String,
     pub clarifying: String,
     pub help: Vec <String>,
     pub filename: String,
-    pub code: String
+
+    ///
+    /// The contents of the file
+    ///
+    pub source: String
 }
 
-impl Termination for Error {
-    fn report(self) -> ExitCode {
+impl Error {
+    pub fn with_severity(self, severity: Severity) -> Self {
+        Self {
+            severity,
+            ..self
+        }
+    }
+
+    pub fn with_code(self, code: Option <String>) -> Self {
+        Self {
+            code,
+            ..self
+        }
+    }
+
+    ///
+    /// The error of a file which cannot be read, pointing at its start
+    ///
+    pub fn unreadable(filename: String, err: &std::io::Error) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: None,
+            span: Span::EOF.eof_or(""),
+            message: format!("cannot read `{filename}`"),
+            spanned: Span::EOF.apply(""),
+            clarifying: err.to_string(),
+            help: vec![],
+            filename,
+            source: String::new()
+        }
+    }
+
+    ///
+    /// The diagnostic as it is shown to a user, colored
+    ///
+    fn render(&self) -> String {
+        let (accent, line) = self.severity.colors();
         let ladjust = " ".repeat(self.span.start.line.max(self.span.end.line).to_string().len() + 1);
 
-        print!("{}{} ", "error".bright_red().bold(), ":".bold());
-        print_with_style_and_green_if_asterisks(&self.message, |v| print!("{}", v.bold()));
-        println!("{}", ":".bold());
+        let label = match &self.code {
+            Some(code) => format!("{}[{code}]", self.severity.label()),
+            None => self.severity.label().to_string()
+        };
 
-        println!("{}{} {}:{:?}", &ladjust[1..], "-->".blue().bold(), self.filename, self.span.start);
-        println!("{ladjust}{}", "|".blue().bold());
+        let mut rendered = format!("{}{} ", label.color(accent).bold(), ":".bold());
+        rendered += &style_with_green_if_asterisks(&self.message, &|v| v.bold().to_string());
+        rendered += &format!("{}\n", ":".bold());
 
-        for (linenum, line) in self.spanned.split('\n').enumerate() {
+        rendered += &format!("{}{} {}:{:?}\n", &ladjust[1..], "-->".blue().bold(), self.filename, self.span.start);
+        rendered += &format!("{ladjust}{}\n", "|".blue().bold());
+
+        for (linenum, spanned) in self.spanned.split('\n').enumerate() {
             let idx = linenum + self.span.start.line;
             let idx_stringified = idx.to_string();
-            let full_line = self.code.split('\n').nth(idx - 1).unwrap();
+            let full_line = self.source.split('\n').nth(idx - 1).unwrap();
 
             let ladjust2 = " ".repeat(ladjust.len() - idx_stringified.len() - 1);
 
@@ -80,42 +168,228 @@ impl Termination for Error {
                 String::new()
             };
 
-            let underscoring_len = if line == "<EOF>" {
+            let underscoring_len = if spanned == "<EOF>" {
                 1
             } else {
-                line.len()
+                spanned.len()
             };
 
-            print!("{ladjust2}{idx} {stick} {line}\n{ladjust}{stick}{circumflex_ladjsust}{underscoring} ",
+            rendered += &format!("{ladjust2}{idx} {stick} {full_line}\n{ladjust}{stick}{circumflex_ladjsust}{underscoring} ",
                 idx = idx_stringified.blue().bold(),
                 stick = "|".blue().bold(),
-                line = full_line.red(),
-                underscoring = "^".repeat(underscoring_len).bright_red().bold())
+                full_line = full_line.color(line),
+                underscoring = "^".repeat(underscoring_len).color(accent).bold())
         }
 
-        print_with_style_and_green_if_asterisks(&self.clarifying, |v| print!("{}", v.bright_red().bold()));
-        println!();
+        rendered += &style_with_green_if_asterisks(&self.clarifying, &|v| v.color(accent).bold().to_string());
+        rendered + "\n"
+    }
+
+    ///
+    /// The byte offset of a position in the file
+    ///
+    fn offset(&self, position: CursorPosition) -> usize {
+        let before = self.source.split('\n').take(position.line - 1).map(|line| line.len() + 1).sum::<usize>();
+        let line = self.source[before.min(self.source.len())..].split('\n').next().unwrap_or("");
+
+        before + line.char_indices().nth(position.column - 1).map_or(line.len(), |(idx, _)| idx)
+    }
 
+    fn json_span(&self, label: &str, is_primary: bool) -> Json {
+        let Span { start, end } = self.span;
+
+        json!({
+            "file": self.filename,
+            "line_start": start.line,
+            "column_start": start.column,
+            "line_end": end.line,
+            "column_end": end.column,
+            "byte_start": self.offset(start),
+            "byte_end": self.offset(end),
+            "is_primary": is_primary,
+            "label": label
+        })
+    }
+
+    ///
+    /// The diagnostic as a JSON object, with the places the notes point at as its secondary spans
+    ///
+    fn to_json(&self, notes: &[&Error]) -> Json {
+        let mut spans = vec![self.json_span(&self.clarifying, true)];
+        spans.extend(notes.iter().map(|note| note.json_span(&note.message, false)));
+
+        json!({
+            "severity": self.severity.label(),
+            "code": self.code,
+            "message": self.message,
+            "spans": spans,
+            "help": self.help
+        })
+    }
+}
+
+impl Termination for Error {
+    fn report(self) -> ExitCode {
+        Emitter::default().emit(&[self]);
         ExitCode::FAILURE
     }
 }
 
-type Printer = fn(&dyn Display);
+///
+/// How diagnostics are printed
+///
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Format {
+    ///
+    /// Text with the lines of code the diagnostics point at
+    ///
+    #[default]
+    Human,
+
+    ///
+    /// A JSON object per line, for editors and CI
+    ///
+    Json
+}
+
+///
+/// When human-readable diagnostics are colored
+///
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ColorChoice {
+    ///
+    /// When the standard error is a terminal
+    ///
+    #[default]
+    Auto,
+    Always,
+    Never
+}
+
+///
+/// Prints diagnostics to the standard error
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Emitter {
+    pub format: Format,
+    pub is_colored: bool
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self::new(Format::default(), ColorChoice::default())
+    }
+}
+
+impl Emitter {
+    pub fn new(format: Format, color: ColorChoice) -> Self {
+        Self {
+            format,
+            is_colored: match color {
+                ColorChoice::Auto => std::io::stderr().is_terminal(),
+                ColorChoice::Always => true,
+                ColorChoice::Never => false
+            }
+        }
+    }
+
+    ///
+    /// The text as it is printed: without the colors unless they are enabled
+    ///
+    fn styled(&self, colored: String) -> String {
+        if self.is_colored {
+            colored
+        } else {
+            uncolored(&colored)
+        }
+    }
+
+    fn print(&self, colored: String) {
+        eprint!("{}", self.styled(colored))
+    }
+
+    ///
+    /// The diagnostics as they are printed. In JSON, the notes are the secondary spans of the diagnostic before them
+    ///
+    pub fn formatted(&self, diagnostics: &[Error]) -> String {
+        match self.format {
+            Format::Human => diagnostics.iter().map(|diagnostic| self.styled(diagnostic.render())).collect(),
+            Format::Json => {
+                let mut formatted = String::new();
+                let mut idx = 0;
+
+                while idx < diagnostics.len() {
+                    let notes = diagnostics[idx + 1..]
+                        .iter()
+                        .take_while(|note| note.severity == Severity::Note)
+                        .collect::<Vec <_>>();
+
+                    formatted += &format!("{}\n", diagnostics[idx].to_json(&notes));
+                    idx += notes.len() + 1
+                }
+
+                formatted
+            }
+        }
+    }
+
+    ///
+    /// Prints the diagnostics to the standard error
+    ///
+    pub fn emit(&self, diagnostics: &[Error]) {
+        eprint!("{}", self.formatted(diagnostics))
+    }
+
+    ///
+    /// Prints an error that does not point at any code
+    ///
+    pub fn fail(&self, message: &str) -> ExitCode {
+        match self.format {
+            Format::Human => self.print(format!("{}{} {}\n", "error".bright_red().bold(), ":".bold(), message.bold())),
+            Format::Json => eprintln!("{}", json!({
+                "severity": Severity::Error.label(),
+                "code": null,
+                "message": message,
+                "spans": [],
+                "help": []
+            }))
+        }
+
+        ExitCode::FAILURE
+    }
+}
+
+///
+/// Removes the escape sequences of colors and styles
+///
+fn uncolored(colored: &str) -> String {
+    let mut uncolored = String::with_capacity(colored.len());
+    let mut rest = colored;
+
+    while let Some(start) = rest.find('\x1b') {
+        uncolored += &rest[..start];
+        rest = rest[start..].find('m').map_or("", |end| &rest[start + end + 1..])
+    }
+
+    uncolored + rest
+}
+
+type Styler <'a> = &'a dyn Fn(&dyn Display) -> String;
 
-fn print_with_style_and_green_if_asterisks(message: &str, default: Printer) {
+fn style_with_green_if_asterisks(message: &str, default: Styler) -> String {
     if let Some(start) = message.find('`') {
         let extra = start + '`'.len_utf8();
 
         let end = message[extra..].find('`').unwrap_or(message.len() - extra) + extra;
 
-        print_and_use_another_printer_on_span(message, start, end, default, |v| print!("{}", v.green().bold()));
+        style_and_use_another_styler_on_span(message, start, end, default, &|v| v.green().bold().to_string())
     } else {
         default(&message)
     }
 }
 
-fn print_and_use_another_printer_on_span(message: &str, start: usize, end: usize, default: Printer, special: Printer) {
-    default(&&message[..start]);
-    special(&&message[start..=end]);
-    default(&&message[end + message[end..].chars().next().map(char::len_utf8).unwrap_or(0)..])
+fn style_and_use_another_styler_on_span(message: &str, start: usize, end: usize, default: Styler, special: Styler) -> String {
+    default(&&message[..start])
+        + &special(&&message[start..=end])
+        + &default(&&message[end + message[end..].chars().next().map(char::len_utf8).unwrap_or(0)..])
 }
@@ -1,5 +1,13 @@
 #![feature(try_trait_v2)]
 
 pub mod parse;
+pub mod check;
+pub mod mono;
+pub mod codegen;
+pub mod mir;
+pub mod bindgen;
+pub mod driver;
+pub mod package;
 pub mod error;
 pub mod span;
+pub mod ecs;
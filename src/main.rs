@@ -1,5 +1,314 @@
-fn main() -> oko::error::Result <()> {
-    println!("{:#?}", oko::parse::parse("src/core.oko")?);
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use oko::check::lints::{Level, Levels, Lint};
+use oko::error::{ColorChoice, Emitter, Error, Format, Severity};
+use oko::package::{self, Package, DEFAULT_ENTRY, MANIFEST};
+use oko::parse::stmt::Stmt;
 
-    oko::error::Result(Ok(()))
+const USAGE: &str = "\
+usage:
+    oko new NAME                  creates the package NAME in a new directory
+    oko build OPTIONS...          compiles the current package
+    oko run OPTIONS... ARGS...    compiles and runs the current package
+    oko bindgen OPTIONS... HEADER prints the `extern clang` declarations of a C header
+
+options:
+    -A LINT                       allows the lint
+    -W LINT                       makes the lint warn
+    -D LINT                       makes the lint an error
+    --error-format=human|json     prints the diagnostics as text or as a JSON object per line
+    --color=auto|always|never     colors the text, by default when the standard error is a terminal
+    --emit=mir                    also writes the MIR of the functions to `target/NAME.mir`";
+
+///
+/// The directory of a package the build artifacts are stored in
+///
+const TARGET: &str = "target";
+
+const MAIN: &str = "\
+extern clang fn putchar(c: int) -> int
+
+fn main() -> int {
+    putchar(79)
+    putchar(75)
+    putchar(10)
+    0
+}
+";
+
+fn fail(message: &str) -> ExitCode {
+    Emitter::default().fail(message)
+}
+
+fn new(name: &str) -> ExitCode {
+    if !package::is_valid_name(name) {
+        return fail(&format!("`{name}` is not a valid package name, use only letters and digits, starting with a letter"))
+    }
+
+    let dir = Path::new(name);
+
+    if dir.exists() {
+        return fail(&format!("`{name}` already exists"))
+    }
+
+    let entry = dir.join(DEFAULT_ENTRY);
+    let written = std::fs::create_dir_all(entry.parent().unwrap_or(dir))
+        .and_then(|_| std::fs::write(dir.join(MANIFEST), format!("[package]\nname = \"{name}\"\n\n[dependencies]\n")))
+        .and_then(|_| std::fs::write(&entry, MAIN));
+
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => fail(&format!("failed to create `{name}`: {err}"))
+    }
+}
+
+///
+/// The flags of `oko build` and `oko run`
+///
+#[derive(Default)]
+struct Options {
+    levels: Levels,
+    emitter: Emitter,
+    emit_mir: bool
+}
+
+///
+/// Reads the leading flags, returning them and the rest of the arguments
+///
+fn options(mut args: &[String]) -> Result <(Options, &[String]), ExitCode> {
+    let mut levels = Levels::default();
+    let (mut format, mut color) = (Format::default(), ColorChoice::default());
+    let mut emit_mir = false;
+
+    loop {
+        match args {
+            [flag, name, rest @ ..] if matches!(flag.as_str(), "-A" | "-W" | "-D") => {
+                let level = match flag.as_str() {
+                    "-A" => Level::Allow,
+                    "-W" => Level::Warn,
+                    _ => Level::Deny
+                };
+
+                let Some(lint) = Lint::from_name(name) else {
+                    let names = Lint::ALL.iter().map(|lint| format!("`{}`", lint.name())).collect::<Vec <_>>().join(", ");
+                    return Err(Emitter::new(format, color).fail(&format!("unknown lint `{name}`, the lints are {names}")))
+                };
+
+                levels.set(lint, level);
+                args = rest
+            },
+            [flag, rest @ ..] if flag == "--emit=mir" => {
+                emit_mir = true;
+                args = rest
+            },
+            [flag, rest @ ..] if flag.starts_with("--error-format=") || flag.starts_with("--color=") => {
+                match flag.split_once('=') {
+                    Some((_, "human")) => format = Format::Human,
+                    Some((_, "json")) => format = Format::Json,
+                    Some((_, "auto")) => color = ColorChoice::Auto,
+                    Some((_, "always")) => color = ColorChoice::Always,
+                    Some((_, "never")) => color = ColorChoice::Never,
+                    _ => return Err(Emitter::new(format, color).fail(&format!("unknown value of `{flag}`")))
+                }
+
+                args = rest
+            },
+            _ => break
+        }
+    }
+
+    Ok((Options {
+        levels,
+        emitter: Emitter::new(format, color),
+        emit_mir
+    }, args))
+}
+
+///
+/// The C code of the packages, the bindings of the functions they export and the MIR, if any
+///
+struct Compiled {
+    code: String,
+    header: Option <String>,
+    rust: Option <String>,
+    mir: Option <String>,
+    has_main: bool
+}
+
+///
+/// Compiles the packages into C, returning the code if there are no errors and the diagnostics either way.
+///   The bodies are checked and lowered to MIR, which the C code of the functions is generated from
+///
+fn compile(packages: &[Package], options: &Options) -> (Option <Compiled>, Vec <Error>) {
+    let (sources, mut stmts) = match oko::driver::load_packages(packages).0 {
+        Ok(loaded) => loaded,
+        Err(err) => return (None, vec![err])
+    };
+
+    let mut diagnostics = oko::check::check(&stmts, &sources, &options.levels);
+
+    if diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error) {
+        return (None, diagnostics)
+    }
+
+    oko::check::consts::resolve(&mut stmts);
+
+    let has_main = stmts.iter().any(|stmt| matches!(stmt, Stmt::Fn(fun) if fun.name.name == "main"));
+    let compiled = oko::mono::monomorphize(&stmts)
+        .and_then(|stmts| oko::check::traits::resolve(&stmts))
+        .and_then(|stmts| oko::check::bodies::check(&stmts).map(|_| stmts))
+        .and_then(|stmts| {
+            let program = oko::mir::lower(&stmts)?;
+            oko::mir::validate(&program).unwrap_or_else(|err| panic!("a checked program is lowered to invalid MIR: {}", err.message));
+
+            Ok(Compiled {
+                code: oko::codegen::c::generate(&stmts, &program)?,
+                header: oko::codegen::c::header(&stmts, &packages[0].manifest.name)?,
+                rust: oko::codegen::rust::bindings(&stmts, &packages[0].manifest.name)?,
+                mir: options.emit_mir.then(|| format!("{program:?}")),
+                has_main
+            })
+        });
+
+    match compiled {
+        Ok(compiled) => (Some(compiled), diagnostics),
+        Err(err) => {
+            diagnostics.push(sources.error(err));
+            (None, diagnostics)
+        }
+    }
+}
+
+///
+/// Compiles the package the current directory belongs to, returning the path to the executable,
+///   or to the object file if the package has no `main` function.
+///   The C header and the Rust bindings of the exported functions and the MIR are written next to the C code
+///
+fn build(options: &Options) -> Result <(PathBuf, bool), ExitCode> {
+    let fail = |message: &str| options.emitter.fail(message);
+
+    let cwd = std::env::current_dir().expect("failed to get the current directory");
+    let Some(dir) = package::find(&cwd) else {
+        return Err(fail(&format!("cannot find `{MANIFEST}` in `{}` or any of its parents", cwd.display())))
+    };
+
+    let packages = package::resolve(&dir).0.map_err(|err| {
+        options.emitter.emit(&[err]);
+        ExitCode::FAILURE
+    })?;
+
+    let (compiled, diagnostics) = compile(&packages, options);
+    options.emitter.emit(&diagnostics);
+
+    let Some(compiled) = compiled else {
+        return Err(ExitCode::FAILURE)
+    };
+
+    let name = &packages[0].manifest.name;
+    let target = dir.join(TARGET);
+    let source = target.join(format!("{name}.c"));
+    let binary = target.join(if compiled.has_main { name.clone() } else { format!("{name}.o") });
+
+    if let Err(err) = std::fs::create_dir_all(&target).and_then(|_| std::fs::write(&source, compiled.code)) {
+        return Err(fail(&format!("failed to write `{}`: {err}", source.display())))
+    }
+
+    for (output, extension) in [(compiled.header, "h"), (compiled.rust, "rs"), (compiled.mir, "mir")] {
+        let Some(output) = output else { continue };
+        let path = target.join(format!("{name}.{extension}"));
+
+        if let Err(err) = std::fs::write(&path, output) {
+            return Err(fail(&format!("failed to write `{}`: {err}", path.display())))
+        }
+    }
+
+    let mut links = vec![];
+
+    for link in packages.iter().flat_map(|package| &package.manifest.links) {
+        if !links.contains(&link) {
+            links.push(link)
+        }
+    }
+
+    let cc = std::env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let mut command = Command::new(&cc);
+
+    // Without `main` there is nothing to run, so the code is compiled to be linked into another program
+    if compiled.has_main {
+        command.arg(&source).arg("-o").arg(&binary).args(links.iter().map(|link| format!("-l{link}")));
+    } else {
+        command.arg("-c").arg(&source).arg("-o").arg(&binary);
+    }
+
+    match command.status() {
+        Ok(status) if status.success() => Ok((binary, compiled.has_main)),
+        Ok(_) => Err(fail(&format!("`{cc}` failed to compile `{}`", source.display()))),
+        Err(err) => Err(fail(&format!("failed to run `{cc}`: {err}")))
+    }
+}
+
+fn run(args: &[String]) -> ExitCode {
+    let (options, args) = match options(args) {
+        Ok(options) => options,
+        Err(code) => return code
+    };
+
+    let binary = match build(&options) {
+        Ok((binary, true)) => binary,
+        Ok((_, false)) => return options.emitter.fail("the package has no `main` function to run"),
+        Err(code) => return code
+    };
+
+    match Command::new(&binary).args(args).status() {
+        // Exit codes beyond a byte keep failing rather than wrapping, possibly to 0
+        Ok(status) => ExitCode::from(status.code().map_or(1, |code| if code == 0 { 0 } else { code.clamp(1, u8::MAX.into()) as u8 })),
+        Err(err) => options.emitter.fail(&format!("failed to run `{}`: {err}", binary.display()))
+    }
+}
+
+///
+/// Prints the declarations of a C header, warning about what is skipped
+///
+fn bindgen(options: &Options, header: &str) -> ExitCode {
+    let code = match std::fs::read_to_string(header) {
+        Ok(code) => code,
+        Err(err) => return options.emitter.fail(&format!("failed to read `{header}`: {err}"))
+    };
+
+    match oko::bindgen::generate(&code) {
+        Ok(bindings) => {
+            let warnings = bindings.warnings
+                .into_iter()
+                .map(|warning| warning.to_error(&code, header.to_string()).with_severity(Severity::Warning))
+                .collect::<Vec <_>>();
+
+            options.emitter.emit(&warnings);
+            print!("{}", bindings.code);
+            ExitCode::SUCCESS
+        },
+        Err(err) => {
+            options.emitter.emit(&[err.to_error(&code, header.to_string())]);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec <_>>();
+
+    match args.first().map(String::as_str) {
+        Some("new") if args.len() == 2 => new(&args[1]),
+        Some("build") => match options(&args[1..]) {
+            Ok((options, [])) => build(&options).map_or_else(|code| code, |_| ExitCode::SUCCESS),
+            Ok(_) => fail(USAGE),
+            Err(code) => code
+        },
+        Some("run") => run(&args[1..]),
+        Some("bindgen") => match options(&args[1..]) {
+            Ok((options, [header])) => bindgen(&options, header),
+            Ok(_) => fail(USAGE),
+            Err(code) => code
+        },
+        _ => fail(USAGE)
+    }
 }
@@ -0,0 +1,438 @@
+use std::collections::{HashMap, HashSet};
+use crate::check::CheckError;
+use crate::check::types::{fn_type, is_unit, named, return_type, unit};
+use crate::check::visit::{free_vars, params};
+use crate::parse::expr::{Adjustment, Expr, FieldExpr, IndexExpr, LambdaExpr, MethodCallExpr, StructExpr};
+use crate::parse::signature::Signature;
+use crate::parse::stmt::{FnStmt, Stmt, TyStmtBody};
+use crate::parse::ty::{Indirection, IndirectionKind, Type, TypeKind};
+use crate::span::Span;
+use super::*;
+
+fn cannot_infer(span: Span, what: &str) -> CheckError {
+    CheckError {
+        span,
+        message: format!("cannot infer the type of {what}"),
+        clarifying: String::from("while lowering to MIR"),
+        help: vec![]
+    }
+}
+
+///
+/// Builds the body of one function or lambda
+///
+struct Builder <'a> {
+    program: &'a Program,
+    name: String,
+    locals: Vec <LocalDecl>,
+    blocks: Vec <BasicBlock>,
+
+    ///
+    /// The locals of the parameters and the captured variables by their names
+    ///
+    vars: HashMap <String, Local>,
+
+    ///
+    /// The bodies of the lambdas written in the body and in those lambdas
+    ///
+    lambdas: Vec <Body>,
+
+    lambda_count: usize
+}
+
+impl <'a> Builder <'a> {
+    fn new(program: &'a Program, name: String, sig: &Signature, span: Span) -> Self {
+        let mut builder = Self {
+            program,
+            name,
+            locals: vec![],
+            blocks: vec![BasicBlock {
+                statements: vec![],
+                terminator: None
+            }],
+            vars: HashMap::new(),
+            lambdas: vec![],
+            lambda_count: 0
+        };
+
+        builder.local(LocalKind::Return, return_type(sig, span), None);
+
+        for (name, ty) in params(sig) {
+            let local = builder.local(LocalKind::Param, ty.clone(), Some(name.clone()));
+            builder.vars.insert(name, local);
+        }
+
+        builder
+    }
+
+    fn local(&mut self, kind: LocalKind, ty: Type, name: Option <String>) -> Local {
+        self.locals.push(LocalDecl {
+            kind,
+            ty,
+            name
+        });
+
+        Local(self.locals.len() - 1)
+    }
+
+    fn temp(&mut self, ty: Type) -> Local {
+        self.local(LocalKind::Temp, ty, None)
+    }
+
+    fn current(&mut self) -> &mut BasicBlock {
+        self.blocks.last_mut().expect("a body has a block")
+    }
+
+    fn assign(&mut self, place: Place, rvalue: Rvalue, span: Span) {
+        self.current().statements.push(Statement {
+            place,
+            rvalue,
+            span
+        })
+    }
+
+    ///
+    /// Ends the current block and starts the next one, returning its id
+    ///
+    fn terminate(&mut self, terminator: impl FnOnce(BlockId) -> Terminator) -> BlockId {
+        let next = BlockId(self.blocks.len());
+        self.current().terminator = Some(terminator(next));
+        self.blocks.push(BasicBlock {
+            statements: vec![],
+            terminator: None
+        });
+
+        next
+    }
+
+    ///
+    /// Stores an rvalue in a new temporary, which is moved out when read
+    ///
+    fn store(&mut self, rvalue: Rvalue, ty: Type, span: Span) -> (Operand, Type) {
+        let temp = self.temp(ty.clone());
+        self.assign(temp.into(), rvalue, span);
+
+        (Operand::Move(temp.into()), ty)
+    }
+
+    fn place_type(&self, place: &Place, span: Span) -> Result <Type, CheckError> {
+        self.program.place_type(&self.locals, place).map_err(|message| CheckError {
+            span,
+            message,
+            clarifying: String::from("while lowering to MIR"),
+            help: vec![]
+        })
+    }
+
+    ///
+    /// Lowers an expression denoting a place in memory, or stores its value in a temporary
+    ///
+    fn place(&mut self, expr: &Expr) -> Result <(Place, Type), CheckError> {
+        match expr {
+            Expr::Var(var) if self.vars.contains_key(&var.name.last().name) => {
+                let local = self.vars[&var.name.last().name];
+                Ok((local.into(), self.locals[local.0].ty.clone()))
+            },
+            Expr::Field(field) => self.field(field),
+            Expr::Index(index) => self.index(index),
+            expr => {
+                let (operand, ty) = self.expr(expr, None)?;
+
+                match operand {
+                    Operand::Move(place) => Ok((place, ty)),
+                    operand => {
+                        let temp = self.temp(ty.clone());
+                        self.assign(temp.into(), Rvalue::Use(operand), expr.span());
+                        Ok((temp.into(), ty))
+                    }
+                }
+            }
+        }
+    }
+
+    ///
+    /// Adds a dereference for every reference and pointer a place is behind
+    ///
+    fn deref(&self, mut place: Place, mut ty: Type, span: Span) -> Result <(Place, Type), CheckError> {
+        while let TypeKind::Indirect(..) = ty.kind {
+            place.projection.push(Projection::Deref);
+            ty = self.place_type(&place, span)?
+        }
+
+        Ok((place, ty))
+    }
+
+    fn field(&mut self, field: &FieldExpr) -> Result <(Place, Type), CheckError> {
+        let (base, ty) = self.place(&field.base)?;
+        let (mut place, _) = self.deref(base, ty, field.span)?;
+
+        place.projection.push(Projection::Field(field.field.name.clone()));
+        let ty = self.place_type(&place, field.span)?;
+
+        Ok((place, ty))
+    }
+
+    fn index(&mut self, index: &IndexExpr) -> Result <(Place, Type), CheckError> {
+        let (base, ty) = self.place(&index.base)?;
+        let (mut place, ty) = self.deref(base, ty, index.span)?;
+
+        let projection = match (&index.index, &ty.kind) {
+            (Expr::Int(int), TypeKind::Array(..) | TypeKind::Tuple(_)) => Projection::ConstIndex(int.value),
+            (index, _) => match self.expr(index, None)? {
+                (Operand::Copy(Place { local, projection }) | Operand::Move(Place { local, projection }), _) if projection.is_empty() => Projection::Index(local),
+                (operand, ty) => {
+                    let temp = self.temp(ty);
+                    self.assign(temp.into(), Rvalue::Use(operand), index.span());
+                    Projection::Index(temp)
+                }
+            }
+        };
+
+        place.projection.push(projection);
+        let ty = self.place_type(&place, index.span)?;
+
+        Ok((place, ty))
+    }
+
+    ///
+    /// Calls `callee`, lowering the arguments with the types of the parameters expected,
+    ///   and continues in a new block
+    ///
+    fn call(&mut self, callee: Operand, sig: &Signature, mut args: Vec <Operand>, exprs: &[Expr], span: Span) -> Result <(Operand, Type), CheckError> {
+        let param_types = sig.param_types();
+
+        for (idx, expr) in exprs.iter().enumerate() {
+            args.push(self.expr(expr, param_types.get(args.len().max(idx)).copied())?.0)
+        }
+
+        let ty = return_type(sig, span);
+        let destination = self.temp(ty.clone());
+
+        self.terminate(|target| Terminator::Call {
+            callee: Box::new(callee),
+            args,
+            destination: destination.into(),
+            target,
+            span
+        });
+
+        Ok((Operand::Move(destination.into()), ty))
+    }
+
+    fn method_call(&mut self, call: &MethodCallExpr) -> Result <(Operand, Type), CheckError> {
+        let dispatch = call.dispatch.as_ref().expect("method calls are dispatched before the lowering");
+        let Some(sig) = self.program.fns.get(&dispatch.fun.name) else {
+            return Err(cannot_infer(call.span, "the method"))
+        };
+
+        let receiver = match dispatch.adjustment {
+            Adjustment::None => self.expr(&call.receiver, None)?.0,
+            Adjustment::Deref(times) => {
+                let (mut place, _) = self.place(&call.receiver)?;
+                place.projection.extend((0..times).map(|_| Projection::Deref));
+                Operand::Copy(place)
+            },
+            Adjustment::Ref => {
+                let (place, ty) = self.place(&call.receiver)?;
+                let is_mutable = matches!(sig.param_types().first().map(|param| &param.kind), Some(TypeKind::Indirect(indirection, _)) if indirection.is_mutable);
+                let borrowed = Type {
+                    kind: TypeKind::Indirect(Indirection {
+                        kind: IndirectionKind::Ref,
+                        is_mutable
+                    }, Box::new(ty)),
+                    span: call.span
+                };
+
+                self.store(Rvalue::Ref(is_mutable, place), borrowed, call.span).0
+            }
+        };
+
+        let sig = sig.clone();
+        self.call(Operand::Const(Constant::Fn(dispatch.fun.name.clone())), &sig, vec![receiver], &call.args.0, call.span)
+    }
+
+    fn literal(&mut self, lit: &StructExpr, span: Span) -> Result <(Operand, Type), CheckError> {
+        let name = &lit.name.last().name;
+        let ty = named(name, lit.name.span());
+
+        if let Some(TyStmtBody::Enum(body)) = self.program.tys.get(name).map(|ty| &ty.body) {
+            let value = &lit.fields.0[0];
+            let attached = body.fields.0
+                .iter()
+                .find(|variant| variant.name.name == value.name.name)
+                .and_then(|variant| variant.attached_type.clone());
+
+            let operand = match (&value.value, attached) {
+                (Expr::Block(block), _) if block.expressions.is_empty() => None,
+                (value, attached) => Some(self.expr(value, attached.as_ref())?.0)
+            };
+
+            return Ok(self.store(Rvalue::Variant(name.clone(), value.name.name.clone(), operand), ty, span))
+        }
+
+        let mut fields = vec![];
+
+        for field in &lit.fields.0 {
+            let expected = self.program.field_type(&ty, &field.name.name).cloned();
+            fields.push((field.name.name.clone(), self.expr(&field.value, expected.as_ref())?.0))
+        }
+
+        Ok(self.store(Rvalue::Struct(name.clone(), fields), ty, span))
+    }
+
+    fn lambda(&mut self, lambda: &LambdaExpr) -> Result <(Operand, Type), CheckError> {
+        let name = format!("{}::lambda{}", self.name, self.lambda_count);
+        let mut builder = Builder::new(self.program, name.clone(), &lambda.sig, lambda.span);
+
+        let bound = params(&lambda.sig).into_iter().map(|(name, _)| name).collect::<HashSet <_>>();
+        let mut used = vec![];
+        free_vars(&lambda.body, &bound, &mut used);
+
+        let mut captures = vec![];
+
+        for var in used.into_iter().filter(|var| self.vars.contains_key(var)) {
+            let outer = self.vars[&var];
+            let local = builder.local(LocalKind::Capture, self.locals[outer.0].ty.clone(), Some(var.clone()));
+
+            builder.vars.insert(var, local);
+            captures.push(Operand::Copy(outer.into()))
+        }
+
+        builder.ret(&lambda.body, &lambda.sig, lambda.span)?;
+        self.lambdas.append(&mut builder.finish(lambda.span));
+        self.lambda_count += 1;
+
+        Ok(self.store(Rvalue::Closure(name, captures), fn_type(&lambda.sig, lambda.span), lambda.span))
+    }
+
+    ///
+    /// Returns the body followed by the bodies of its lambdas
+    ///
+    fn finish(self, span: Span) -> Vec <Body> {
+        let mut bodies = vec![Body {
+            name: self.name,
+            locals: self.locals,
+            blocks: self.blocks,
+            span
+        }];
+
+        bodies.extend(self.lambdas);
+        bodies
+    }
+
+    fn expr(&mut self, expr: &Expr, expected: Option <&Type>) -> Result <(Operand, Type), CheckError> {
+        match expr {
+            Expr::Var(var) => {
+                let name = &var.name.last().name;
+
+                if let Some(local) = self.vars.get(name) {
+                    return Ok((Operand::Copy((*local).into()), self.locals[local.0].ty.clone()))
+                }
+
+                match self.program.fns.get(name) {
+                    Some(sig) => Ok((Operand::Const(Constant::Fn(name.clone())), fn_type(sig, var.name.span()))),
+                    None => Err(cannot_infer(var.name.span(), &format!("`{name}`")))
+                }
+            },
+            Expr::Int(int) => {
+                let ty = expected.cloned().unwrap_or_else(|| named("int", int.span));
+                Ok((Operand::Const(Constant::Int(int.value, ty.clone())), ty))
+            },
+            Expr::Field(_) | Expr::Index(_) => {
+                let (place, ty) = self.place(expr)?;
+                Ok((Operand::Copy(place), ty))
+            },
+            Expr::Call(call) => {
+                let name = &call.fun.last().name;
+
+                if let Some(local) = self.vars.get(name).copied() {
+                    let TypeKind::Fn(sig) = self.locals[local.0].ty.kind.clone() else {
+                        return Err(cannot_infer(call.fun.span(), &format!("the call of `{name}`")))
+                    };
+
+                    return self.call(Operand::Copy(local.into()), &sig, vec![], &call.args.0, call.fun.span())
+                }
+
+                let Some(sig) = self.program.fns.get(name).cloned() else {
+                    return Err(cannot_infer(call.fun.span(), &format!("the call of `{name}`")))
+                };
+
+                self.call(Operand::Const(Constant::Fn(name.clone())), &sig, vec![], &call.args.0, call.fun.span())
+            },
+            Expr::MethodCall(call) => self.method_call(call),
+            Expr::Struct(lit) => self.literal(lit, lit.name.span()),
+            Expr::Lambda(lambda) => self.lambda(lambda),
+            Expr::Block(block) => {
+                let Some((last, exprs)) = block.expressions.split_last() else {
+                    return Ok((Operand::Const(Constant::Unit), unit(Span::EOF)))
+                };
+
+                for expr in exprs {
+                    self.expr(expr, None)?;
+                }
+
+                self.expr(last, expected)
+            }
+        }
+    }
+
+    ///
+    /// Lowers the body of a function, storing its value in `_0` and returning
+    ///
+    fn ret(&mut self, body: &Expr, sig: &Signature, span: Span) -> Result <(), CheckError> {
+        let returns = sig.return_ty.as_ref().is_some_and(|ty| !is_unit(ty));
+        let (value, _) = self.expr(body, sig.return_ty.as_ref().filter(|_| returns))?;
+
+        let value = if returns { value } else { Operand::Const(Constant::Unit) };
+
+        self.assign(Local::RETURN.into(), Rvalue::Use(value), span);
+        self.current().terminator = Some(Terminator::Return);
+
+        Ok(())
+    }
+}
+
+fn collect(stmts: &[Stmt], program: &mut Program, fns: &mut Vec <FnStmt>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Ty(ty) if ty.generics.is_none() => {
+                program.tys.insert(ty.name.name.clone(), (**ty).clone());
+            },
+            Stmt::Fn(fun) if fun.generics.is_none() => {
+                program.fns.insert(fun.name.name.clone(), fun.sig.clone());
+                fns.push((**fun).clone())
+            },
+            Stmt::ExternFn(fun) => {
+                program.fns.insert(fun.name.name.clone(), fun.sig.clone());
+            },
+            Stmt::World(world) => collect(&world.body, program, fns),
+            _ => ()
+        }
+    }
+}
+
+///
+/// Lowers monomorphized statements with resolved traits and checked bodies to MIR, a body for every function and lambda
+///
+pub fn lower(stmts: &[Stmt]) -> Result <Program, CheckError> {
+    let mut program = Program {
+        tys: HashMap::new(),
+        fns: HashMap::new(),
+        bodies: vec![]
+    };
+
+    let mut fns = vec![];
+    collect(stmts, &mut program, &mut fns);
+
+    let mut bodies = vec![];
+
+    for fun in &fns {
+        let mut builder = Builder::new(&program, fun.name.name.clone(), &fun.sig, fun.name.span());
+        builder.ret(&fun.body, &fun.sig, fun.name.span())?;
+        bodies.append(&mut builder.finish(fun.name.span()))
+    }
+
+    program.bodies = bodies;
+
+    Ok(program)
+}
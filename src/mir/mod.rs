@@ -0,0 +1,429 @@
+//!
+//! The mid-level IR, lowered from checked and monomorphized statements
+//!
+//! A function is a control-flow graph of basic blocks. A block is a list of assignments ended by a terminator:
+//!   a call, which continues in another block once it returns, a jump, or the return from the function.
+//!   Every value lives in a typed local: `_0` is the returned value, the parameters follow it,
+//!   then the captured variables of a lambda, then the temporaries.
+//!
+//! An operand reads a place, a local with projections through pointers, fields and elements.
+//!   A temporary is read once, so it is moved, a variable is copied.
+//!   Taking a method receiver by reference borrows it: `_3 = &_1`
+//!
+//! Every build lowers the checked bodies to MIR and validates it, then the C backend generates the bodies
+//!   of the functions from it. The checks reject every program which lowers to invalid MIR,
+//!   so a failed validation is a bug of the compiler
+//!
+
+pub mod lower;
+pub mod validate;
+
+use std::collections::HashMap;
+use core::fmt::{Debug, Formatter, Result as FmtResult, Write};
+use crate::parse::signature::Signature;
+use crate::parse::stmt::TyStmt;
+use crate::parse::ty::{Type, TypeKind};
+use crate::span::Span;
+
+pub use lower::lower;
+pub use validate::validate;
+
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Local(pub usize);
+
+impl Local {
+    pub const RETURN: Local = Local(0);
+}
+
+impl Debug for Local {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("_{}", self.0))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BlockId(pub usize);
+
+impl Debug for BlockId {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("bb{}", self.0))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum LocalKind {
+    Return,
+    Param,
+
+    ///
+    /// A variable of the enclosing function used by a lambda
+    ///
+    Capture,
+
+    Temp
+}
+
+#[derive(Clone)]
+pub struct LocalDecl {
+    pub kind: LocalKind,
+    pub ty: Type,
+
+    ///
+    /// The name of a parameter or a captured variable
+    ///
+    pub name: Option <String>
+}
+
+#[derive(Clone)]
+pub enum Projection {
+    ///
+    /// Reads through a reference or a pointer: `(*_1)`
+    ///
+    Deref,
+
+    Field(String),
+
+    ///
+    /// An element of an array or a slice at the index held by a local: `_1[_2]`
+    ///
+    Index(Local),
+
+    ///
+    /// An element of an array or a tuple at a constant index: `_1[0]`
+    ///
+    ConstIndex(usize)
+}
+
+#[derive(Clone)]
+pub struct Place {
+    pub local: Local,
+    pub projection: Vec <Projection>
+}
+
+impl From <Local> for Place {
+    fn from(local: Local) -> Self {
+        Self {
+            local,
+            projection: vec![]
+        }
+    }
+}
+
+impl Debug for Place {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        let mut place = format!("{:?}", self.local);
+
+        for projection in &self.projection {
+            place = match projection {
+                Projection::Deref => format!("(*{place})"),
+                Projection::Field(name) => format!("{place}.{name}"),
+                Projection::Index(index) => format!("{place}[{index:?}]"),
+                Projection::ConstIndex(index) => format!("{place}[{index}]")
+            }
+        }
+
+        f.write_str(&place)
+    }
+}
+
+#[derive(Clone)]
+pub enum Constant {
+    Int(usize, Type),
+
+    ///
+    /// A named function used as a value or called
+    ///
+    Fn(String),
+
+    Unit
+}
+
+#[derive(Clone)]
+pub enum Operand {
+    Copy(Place),
+    Move(Place),
+    Const(Constant)
+}
+
+impl Operand {
+    ///
+    /// The place the operand reads, if it is not a constant
+    ///
+    pub fn place(&self) -> Option <&Place> {
+        match self {
+            Self::Copy(place) | Self::Move(place) => Some(place),
+            Self::Const(_) => None
+        }
+    }
+}
+
+impl Debug for Operand {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Copy(place) => f.write_fmt(format_args!("copy {place:?}")),
+            Self::Move(place) => f.write_fmt(format_args!("move {place:?}")),
+            Self::Const(Constant::Int(value, ty)) => f.write_fmt(format_args!("const {value}: {ty:?}")),
+            Self::Const(Constant::Fn(name)) => f.write_fmt(format_args!("const fn {name}")),
+            Self::Const(Constant::Unit) => f.write_str("const ()")
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Rvalue {
+    Use(Operand),
+
+    ///
+    /// A borrow of a place, `mut` for `&mut`
+    ///
+    Ref(bool, Place),
+
+    ///
+    /// A struct or a union literal, a union initializes one field
+    ///
+    Struct(String, Vec <(String, Operand)>),
+
+    ///
+    /// An enum literal: the type, the variant and its attached value
+    ///
+    Variant(String, String, Option <Operand>),
+
+    ///
+    /// A lambda: the body it is lowered to and the values of its captured variables
+    ///
+    Closure(String, Vec <Operand>)
+}
+
+impl Debug for Rvalue {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Use(operand) => operand.fmt(f),
+            Self::Ref(is_mutable, place) => f.write_fmt(format_args!("&{}{place:?}", if *is_mutable { "mut " } else { "" })),
+            Self::Struct(name, fields) => {
+                let fields = fields.iter().map(|(field, value)| format!("{field}: {value:?}")).collect::<Vec <_>>();
+                f.write_fmt(format_args!("{name} {{ {} }}", fields.join(", ")))
+            },
+            Self::Variant(name, variant, Some(value)) => f.write_fmt(format_args!("{name}::{variant}({value:?})")),
+            Self::Variant(name, variant, None) => f.write_fmt(format_args!("{name}::{variant}")),
+            Self::Closure(body, captures) => f.write_fmt(format_args!("closure {body} {captures:?}"))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Statement {
+    pub place: Place,
+    pub rvalue: Rvalue,
+    pub span: Span
+}
+
+impl Debug for Statement {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("{:?} = {:?}", self.place, self.rvalue))
+    }
+}
+
+#[derive(Clone)]
+pub enum Terminator {
+    Goto(BlockId),
+
+    ///
+    /// Calls `callee`, a named function or a closure, stores the result in `destination` and continues at `target`
+    ///
+    Call {
+        callee: Box <Operand>,
+        args: Vec <Operand>,
+        destination: Place,
+        target: BlockId,
+        span: Span
+    },
+
+    Return
+}
+
+impl Debug for Terminator {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Goto(target) => f.write_fmt(format_args!("goto -> {target:?}")),
+            Self::Call { callee, args, destination, target, .. } => {
+                let callee = match &**callee {
+                    Operand::Const(Constant::Fn(name)) => name.clone(),
+                    callee => format!("({callee:?})")
+                };
+                let args = args.iter().map(|arg| format!("{arg:?}")).collect::<Vec <_>>();
+
+                f.write_fmt(format_args!("{destination:?} = {callee}({}) -> {target:?}", args.join(", ")))
+            },
+            Self::Return => f.write_str("return")
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BasicBlock {
+    pub statements: Vec <Statement>,
+
+    ///
+    /// `None` only while the block is being built
+    ///
+    pub terminator: Option <Terminator>
+}
+
+impl BasicBlock {
+    ///
+    /// The blocks the control may continue to after this one
+    ///
+    pub fn successors(&self) -> Vec <BlockId> {
+        match &self.terminator {
+            Some(Terminator::Goto(target) | Terminator::Call { target, .. }) => vec![*target],
+            Some(Terminator::Return) | None => vec![]
+        }
+    }
+}
+
+///
+/// A function or a lambda
+///
+#[derive(Clone)]
+pub struct Body {
+    pub name: String,
+    pub locals: Vec <LocalDecl>,
+    pub blocks: Vec <BasicBlock>,
+    pub span: Span
+}
+
+impl Body {
+    ///
+    /// The locals holding values when the body is entered: the parameters and the captured variables
+    ///
+    pub fn inputs(&self) -> impl Iterator <Item = Local> + '_ {
+        self.locals
+            .iter()
+            .enumerate()
+            .filter(|(_, decl)| matches!(decl.kind, LocalKind::Param | LocalKind::Capture))
+            .map(|(idx, _)| Local(idx))
+    }
+
+    pub fn local(&self, local: Local) -> &LocalDecl {
+        &self.locals[local.0]
+    }
+}
+
+impl Debug for Body {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        let params = self.locals
+            .iter()
+            .enumerate()
+            .filter(|(_, decl)| decl.kind == LocalKind::Param)
+            .map(|(idx, decl)| format!("_{idx}: {:?}", decl.ty))
+            .collect::<Vec <_>>();
+
+        f.write_fmt(format_args!("fn {}({}) -> {:?} {{\n", self.name, params.join(", "), self.locals[0].ty))?;
+
+        for (idx, decl) in self.locals.iter().enumerate() {
+            f.write_fmt(format_args!("    let _{idx}: {:?}", decl.ty))?;
+
+            match (&decl.name, decl.kind) {
+                (Some(name), LocalKind::Capture) => f.write_fmt(format_args!(" // captured {name}"))?,
+                (Some(name), _) => f.write_fmt(format_args!(" // {name}"))?,
+                (None, _) => ()
+            }
+
+            f.write_char('\n')?
+        }
+
+        for (idx, block) in self.blocks.iter().enumerate() {
+            f.write_fmt(format_args!("\n    bb{idx}: {{\n"))?;
+
+            for statement in &block.statements {
+                f.write_fmt(format_args!("        {statement:?}\n"))?
+            }
+
+            match &block.terminator {
+                Some(terminator) => f.write_fmt(format_args!("        {terminator:?}\n"))?,
+                None => f.write_str("        <unterminated>\n")?
+            }
+
+            f.write_str("    }\n")?
+        }
+
+        f.write_str("}\n")
+    }
+}
+
+///
+/// The bodies of a program with the types and the signatures they refer to
+///
+pub struct Program {
+    pub tys: HashMap <String, TyStmt>,
+
+    ///
+    /// The signatures of every named function, the foreign ones too
+    ///
+    pub fns: HashMap <String, Signature>,
+
+    pub bodies: Vec <Body>
+}
+
+impl Program {
+    pub fn body(&self, name: &str) -> Option <&Body> {
+        self.bodies.iter().find(|body| body.name == name)
+    }
+
+    ///
+    /// The type of a field of a struct or a union
+    ///
+    pub fn field_type(&self, ty: &Type, field: &str) -> Option <&Type> {
+        let decl = self.tys.get(ty.name()?.name.as_str())?;
+
+        decl.body
+            .fields()?
+            .iter()
+            .find(|fields| fields.names.iter().any(|name| name.name == field))
+            .map(|fields| &fields.ty)
+    }
+
+    ///
+    /// The type of the place a projection leads to from a place of type `ty`
+    ///
+    pub fn project(&self, ty: &Type, projection: &Projection) -> Result <Type, String> {
+        let projected = match (projection, &ty.kind) {
+            (Projection::Deref, TypeKind::Indirect(_, inner)) => Some(&**inner),
+            (Projection::Field(name), TypeKind::Named(_)) => self.field_type(ty, name),
+            (Projection::Index(_), TypeKind::Array(elem, _) | TypeKind::Slice(elem)) => Some(&**elem),
+            (Projection::ConstIndex(idx), TypeKind::Array(elem, len)) => (idx < len).then_some(&**elem),
+            (Projection::ConstIndex(idx), TypeKind::Tuple(elems)) => elems.0.get(*idx),
+            _ => None
+        };
+
+        projected.cloned().ok_or_else(|| match projection {
+            Projection::Deref => format!("cannot dereference `{ty:?}`"),
+            Projection::Field(name) => format!("`{ty:?}` has no field `{name}`"),
+            Projection::Index(_) => format!("cannot index into `{ty:?}`"),
+            Projection::ConstIndex(idx) => format!("`{ty:?}` has no element {idx}")
+        })
+    }
+
+    pub fn place_type(&self, locals: &[LocalDecl], place: &Place) -> Result <Type, String> {
+        let Some(decl) = locals.get(place.local.0) else {
+            return Err(format!("`{:?}` is not declared", place.local))
+        };
+
+        place.projection.iter().try_fold(decl.ty.clone(), |ty, projection| self.project(&ty, projection))
+    }
+}
+
+impl Debug for Program {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        for (idx, body) in self.bodies.iter().enumerate() {
+            if idx > 0 {
+                f.write_char('\n')?
+            }
+
+            body.fmt(f)?
+        }
+
+        Ok(())
+    }
+}
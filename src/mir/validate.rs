@@ -0,0 +1,351 @@
+use std::collections::HashSet;
+use crate::check::CheckError;
+use crate::check::types::{coerces, fn_type, is_numeric, named, return_type, same, unit};
+use crate::parse::stmt::TyStmtBody;
+use crate::parse::ty::{IndirectionKind, Type, TypeKind};
+use super::*;
+
+fn mismatch(expected: &Type, found: &Type) -> Result <(), String> {
+    if coerces(expected, found) {
+        Ok(())
+    } else {
+        Err(format!("expected `{expected:?}`, found `{found:?}`"))
+    }
+}
+
+///
+/// The locals which hold values at a point of a body
+///
+#[derive(Clone, PartialEq, Eq)]
+struct State {
+    ///
+    /// Assigned on every path to the point
+    ///
+    init: HashSet <Local>,
+
+    ///
+    /// Moved out on some path to the point
+    ///
+    moved: HashSet <Local>
+}
+
+impl State {
+    fn join(&self, other: &State) -> State {
+        State {
+            init: self.init.intersection(&other.init).copied().collect(),
+            moved: self.moved.union(&other.moved).copied().collect()
+        }
+    }
+
+    fn read(&self, local: Local) -> Result <(), String> {
+        if self.moved.contains(&local) {
+            Err(format!("`{local:?}` is used after it is moved"))
+        } else if !self.init.contains(&local) {
+            Err(format!("`{local:?}` is used before it is assigned"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn place(&self, place: &Place) -> Result <(), String> {
+        self.read(place.local)?;
+
+        place.projection.iter().try_for_each(|projection| match projection {
+            Projection::Index(index) => self.read(*index),
+            _ => Ok(())
+        })
+    }
+
+    fn operand(&mut self, operand: &Operand) -> Result <(), String> {
+        match operand {
+            Operand::Copy(place) => self.place(place),
+            Operand::Move(place) => {
+                self.place(place)?;
+
+                if place.projection.is_empty() {
+                    self.moved.insert(place.local);
+                }
+
+                Ok(())
+            },
+            Operand::Const(_) => Ok(())
+        }
+    }
+
+    fn assign(&mut self, place: &Place) -> Result <(), String> {
+        if place.projection.is_empty() {
+            self.init.insert(place.local);
+            self.moved.remove(&place.local);
+            Ok(())
+        } else {
+            self.place(place)
+        }
+    }
+
+    fn rvalue(&mut self, rvalue: &Rvalue) -> Result <(), String> {
+        match rvalue {
+            Rvalue::Use(operand) | Rvalue::Variant(_, _, Some(operand)) => self.operand(operand),
+            Rvalue::Ref(_, place) => self.place(place),
+            Rvalue::Struct(_, fields) => fields.iter().try_for_each(|(_, value)| self.operand(value)),
+            Rvalue::Closure(_, captures) => captures.iter().try_for_each(|capture| self.operand(capture)),
+            Rvalue::Variant(_, _, None) => Ok(())
+        }
+    }
+
+    ///
+    /// Runs through a block, returning the state at its end
+    ///
+    fn block(mut self, block: &BasicBlock) -> Result <State, String> {
+        for statement in &block.statements {
+            self.rvalue(&statement.rvalue)?;
+            self.assign(&statement.place)?
+        }
+
+        match &block.terminator {
+            Some(Terminator::Call { callee, args, destination, .. }) => {
+                self.operand(callee)?;
+                args.iter().try_for_each(|arg| self.operand(arg))?;
+                self.assign(destination)?
+            },
+            Some(Terminator::Return) => self.read(Local::RETURN)?,
+            Some(Terminator::Goto(_)) | None => ()
+        }
+
+        Ok(self)
+    }
+}
+
+struct Validator <'a> {
+    program: &'a Program,
+    body: &'a Body
+}
+
+impl Validator <'_> {
+    fn place_type(&self, place: &Place) -> Result <Type, String> {
+        let ty = self.program.place_type(&self.body.locals, place)?;
+
+        for projection in &place.projection {
+            if let Projection::Index(index) = projection {
+                let index = &self.body.locals.get(index.0).ok_or_else(|| format!("`{index:?}` is not declared"))?.ty;
+
+                if !is_numeric(index) {
+                    return Err(format!("index of type `{index:?}`, which is not an integer"))
+                }
+            }
+        }
+
+        Ok(ty)
+    }
+
+    fn operand_type(&self, operand: &Operand) -> Result <Type, String> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.place_type(place),
+            Operand::Const(Constant::Int(_, ty)) if is_numeric(ty) => Ok(ty.clone()),
+            Operand::Const(Constant::Int(0, ty)) if matches!(&ty.kind, TypeKind::Indirect(indirection, _) if indirection.kind == IndirectionKind::Ptr) => Ok(ty.clone()),
+            Operand::Const(Constant::Int(_, ty)) => Err(format!("an integer constant of type `{ty:?}`")),
+            Operand::Const(Constant::Fn(name)) => match self.program.fns.get(name) {
+                Some(sig) => Ok(fn_type(sig, self.body.span)),
+                None => Err(format!("`{name}` is not a function"))
+            },
+            Operand::Const(Constant::Unit) => Ok(unit(self.body.span))
+        }
+    }
+
+    fn rvalue(&self, rvalue: &Rvalue, expected: &Type) -> Result <(), String> {
+        match rvalue {
+            Rvalue::Use(operand) => mismatch(expected, &self.operand_type(operand)?),
+            Rvalue::Ref(is_mutable, place) => match &expected.kind {
+                TypeKind::Indirect(indirection, inner) if indirection.kind == IndirectionKind::Ref && indirection.is_mutable == *is_mutable => {
+                    let found = self.place_type(place)?;
+
+                    if same(inner, &found) {
+                        Ok(())
+                    } else {
+                        Err(format!("expected a borrow of `{inner:?}`, found one of `{found:?}`"))
+                    }
+                },
+                _ => Err(format!("a borrow stored in `{expected:?}`"))
+            },
+            Rvalue::Struct(name, fields) => {
+                let Some(decl) = self.program.tys.get(name) else {
+                    return Err(format!("`{name}` is not a type"))
+                };
+
+                mismatch(expected, &named(name, self.body.span))?;
+
+                let declared = match &decl.body {
+                    TyStmtBody::Enum(_) => return Err(format!("enum `{name}` initialized as a struct")),
+                    body => body.fields().unwrap_or_default().iter().flat_map(|fields| &fields.names).count()
+                };
+
+                match (&decl.body, fields.len()) {
+                    (TyStmtBody::Union(_), 1) => (),
+                    (TyStmtBody::Union(_), _) => return Err(format!("union `{name}` initialized with {} fields", fields.len())),
+                    (_, len) if len != declared || fields.iter().map(|(field, _)| field).collect::<HashSet <_>>().len() != len => {
+                        return Err(format!("struct `{name}` is not initialized field by field"))
+                    },
+                    _ => ()
+                }
+
+                fields.iter().try_for_each(|(field, value)| match self.program.field_type(expected, field) {
+                    Some(ty) => mismatch(ty, &self.operand_type(value)?),
+                    None => Err(format!("`{name}` has no field `{field}`"))
+                })
+            },
+            Rvalue::Variant(name, variant, value) => {
+                let Some(TyStmtBody::Enum(body)) = self.program.tys.get(name).map(|decl| &decl.body) else {
+                    return Err(format!("`{name}` is not an enum"))
+                };
+
+                mismatch(expected, &named(name, self.body.span))?;
+
+                let Some(declared) = body.fields.0.iter().find(|declared| &declared.name.name == variant) else {
+                    return Err(format!("`{name}` has no variant `{variant}`"))
+                };
+
+                match (&declared.attached_type, value) {
+                    (Some(ty), Some(value)) => mismatch(ty, &self.operand_type(value)?),
+                    (None, None) => Ok(()),
+                    _ => Err(format!("variant `{name}::{variant}` initialized with a wrong attached value"))
+                }
+            },
+            Rvalue::Closure(name, captures) => {
+                let Some(lambda) = self.program.body(name) else {
+                    return Err(format!("`{name}` is not a body"))
+                };
+
+                let TypeKind::Fn(sig) = &expected.kind else {
+                    return Err(format!("a closure stored in `{expected:?}`"))
+                };
+
+                let inputs = |kind| lambda.locals.iter().filter(move |decl| decl.kind == kind).map(|decl| &decl.ty);
+
+                let params = inputs(LocalKind::Param).collect::<Vec <_>>();
+                let sig_params = sig.param_types();
+
+                if params.len() != sig_params.len() || !params.iter().zip(&sig_params).all(|(param, sig_param)| same(param, sig_param)) {
+                    return Err(format!("closure `{name}` stored in `{expected:?}`, which has other parameters"))
+                }
+
+                mismatch(&return_type(sig, self.body.span), &lambda.local(Local::RETURN).ty)?;
+
+                let captured = inputs(LocalKind::Capture).collect::<Vec <_>>();
+
+                if captured.len() != captures.len() {
+                    return Err(format!("closure `{name}` captures {} variables, given {}", captured.len(), captures.len()))
+                }
+
+                captured.into_iter().zip(captures).try_for_each(|(ty, capture)| mismatch(ty, &self.operand_type(capture)?))
+            }
+        }
+    }
+
+    fn terminator(&self, terminator: &Terminator) -> Result <(), String> {
+        let Terminator::Call { callee, args, destination, .. } = terminator else {
+            return Ok(())
+        };
+
+        let callee = self.operand_type(callee)?;
+
+        let TypeKind::Fn(sig) = &callee.kind else {
+            return Err(format!("call of `{callee:?}`, which is not a function"))
+        };
+
+        let params = sig.param_types();
+
+        if args.len() < params.len() || (args.len() > params.len() && sig.variadic.is_none()) {
+            return Err(format!("call of `{callee:?}` with {} arguments", args.len()))
+        }
+
+        for (param, arg) in params.iter().zip(args) {
+            mismatch(param, &self.operand_type(arg)?)?
+        }
+
+        for arg in &args[params.len()..] {
+            self.operand_type(arg)?;
+        }
+
+        mismatch(&self.place_type(destination)?, &return_type(sig, self.body.span))
+    }
+
+    fn validate(&self) -> Result <(), String> {
+        let body = self.body;
+
+        if body.locals.first().map(|decl| decl.kind) != Some(LocalKind::Return) {
+            return Err(String::from("`_0` is not the returned value"))
+        }
+
+        if body.blocks.is_empty() {
+            return Err(String::from("no blocks"))
+        }
+
+        for (idx, block) in body.blocks.iter().enumerate() {
+            let at = |message: String| format!("bb{idx}: {message}");
+
+            let Some(terminator) = &block.terminator else {
+                return Err(at(String::from("the block is not terminated")))
+            };
+
+            if let Some(target) = block.successors().into_iter().find(|target| target.0 >= body.blocks.len()) {
+                return Err(at(format!("jump to `{target:?}`, which does not exist")))
+            }
+
+            for statement in &block.statements {
+                let ty = self.place_type(&statement.place).map_err(at)?;
+                self.rvalue(&statement.rvalue, &ty).map_err(|message| at(format!("`{statement:?}`: {message}")))?
+            }
+
+            self.terminator(terminator).map_err(|message| at(format!("`{terminator:?}`: {message}")))?
+        }
+
+        // The states at the entries of the blocks, reached in any order until they settle
+        let mut states: Vec <Option <State>> = vec![None; body.blocks.len()];
+        let mut pending = vec![BlockId(0)];
+
+        states[0] = Some(State {
+            init: body.inputs().collect(),
+            moved: HashSet::new()
+        });
+
+        while let Some(block) = pending.pop() {
+            let state = states[block.0].clone().expect("pending blocks are reached");
+            let at = |message: String| format!("{block:?}: {message}");
+            let exit = state.block(&body.blocks[block.0]).map_err(at)?;
+
+            for successor in body.blocks[block.0].successors() {
+                let joined = match &states[successor.0] {
+                    Some(entry) => entry.join(&exit),
+                    None => exit.clone()
+                };
+
+                if states[successor.0].as_ref() != Some(&joined) {
+                    states[successor.0] = Some(joined);
+                    pending.push(successor)
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Checks that the MIR is well formed: the blocks are terminated and jump to existing blocks,
+///   the types of the assigned values, the arguments and the results of calls match,
+///   and every local is assigned before it is read and not read after it is moved
+///
+pub fn validate(program: &Program) -> Result <(), CheckError> {
+    for body in &program.bodies {
+        Validator {
+            program,
+            body
+        }.validate().map_err(|message| CheckError {
+            span: body.span,
+            message: format!("invalid MIR of `{}`: {message}", body.name),
+            clarifying: String::from("lowered from here"),
+            help: vec![]
+        })?
+    }
+
+    Ok(())
+}
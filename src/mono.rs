@@ -0,0 +1,337 @@
+//!
+//! This module replaces generic `ty`s and `fn`s with their concrete instances
+//!
+//! Every use of a generic item with concrete generic arguments (`Vec2 <float>`, `id<int>(x)`)
+//!   produces a copy of the item with the parameters substituted, named after the item and its arguments.
+//!   The generic items themselves are dropped
+//!
+
+use std::collections::{HashMap, HashSet};
+use crate::check::visit::{for_each_call_mut, for_each_type_mut};
+use crate::check::CheckError;
+use crate::parse::expr::Expr;
+use crate::parse::generics::GenericParams;
+use crate::parse::path::Path;
+use crate::parse::span::Ident;
+use crate::parse::stmt::{MacroStmtBody, Stmt, WorldStmt};
+use crate::parse::ty::{IndirectionKind, NamedType, Type, TypeKind};
+
+///
+/// How deep instances may require other instances before the instantiation is considered infinite
+///
+const MAX_DEPTH: usize = 64;
+
+///
+/// Mangles the name of an instance
+///
+/// Every argument is mangled with [`mangle_type`], so `Pair <&int, Vec2 <float>>` becomes `Pair_R3int_11Vec2_5float`.
+///   Identifiers cannot contain `_`, so the mangled names never clash with user names
+///
+pub fn mangle(name: &str, args: &[Type]) -> String {
+    let mut mangled = String::from(name);
+
+    for arg in args {
+        mangled.push('_');
+        mangled.push_str(&mangle_type(arg))
+    }
+
+    mangled
+}
+
+///
+/// Mangles a type into an identifier-friendly string
+///
+/// The length-prefixed name of a named type, `A<len>_` and the element of an array, `S` and the element of a slice,
+///   `T<count>_` and the fields of a tuple, `F<count>_`, the parameters and the return type of a function.
+///   Every indirection is written as `R`, `RM`, `P` or `PM` followed by the type behind it:
+///   `&[int; 4]` becomes `RA4_3int`
+///
+pub fn mangle_type(ty: &Type) -> String {
+    mangle_kind(&ty.kind)
+}
+
+///
+/// Mangles the kind of a type, see [`mangle_type`]
+///
+pub fn mangle_kind(kind: &TypeKind) -> String {
+    let mut mangled = String::new();
+
+    match kind {
+        TypeKind::Named(named) => {
+            let name = match &named.generics {
+                Some(generics) => mangle(&named.name.last().name, &generics.0),
+                None => named.name.last().name.clone()
+            };
+
+            mangled.push_str(&name.len().to_string());
+            mangled.push_str(&name)
+        },
+        TypeKind::Array(elem, len) => {
+            mangled.push_str(&format!("A{len}_"));
+            mangled.push_str(&mangle_type(elem))
+        },
+        TypeKind::Slice(elem) => {
+            mangled.push('S');
+            mangled.push_str(&mangle_type(elem))
+        },
+        TypeKind::Tuple(elems) => {
+            mangled.push_str(&format!("T{}_", elems.0.len()));
+            elems.0.iter().for_each(|elem| mangled.push_str(&mangle_type(elem)))
+        },
+        TypeKind::Fn(sig) => {
+            let params = sig.param_types();
+
+            mangled.push_str(&format!("F{}_", params.len()));
+            params.into_iter().for_each(|param| mangled.push_str(&mangle_type(param)));
+
+            match &sig.return_ty {
+                Some(ty) => mangled.push_str(&mangle_type(ty)),
+                None => mangled.push_str("T0_")
+            }
+        },
+        TypeKind::Indirect(indirection, inner) => {
+            mangled.push(match indirection.kind {
+                IndirectionKind::Ref => 'R',
+                IndirectionKind::Ptr => 'P'
+            });
+
+            if indirection.is_mutable {
+                mangled.push('M')
+            }
+
+            mangled.push_str(&mangle_type(inner))
+        }
+    }
+
+    mangled
+}
+
+///
+/// A generic item and the scope its instances are placed into
+///
+#[derive(Clone, Copy)]
+struct Generic <'a> {
+    stmt: &'a Stmt,
+    generics: &'a GenericParams,
+    scope: usize
+}
+
+#[derive(Default)]
+struct Monomorphizer <'a> {
+    ///
+    /// The instances of every scope: `0` is the top level, the rest are worlds in order
+    ///
+    instances: Vec <Vec <Stmt>>,
+
+    created: HashSet <(usize, String)>,
+
+    ///
+    /// The generic `ty`s and `fn`s visible from the scope being processed
+    ///
+    tys: HashMap <&'a str, Generic <'a>>,
+    fns: HashMap <&'a str, Generic <'a>>
+}
+
+impl <'a> Monomorphizer <'a> {
+    fn declare(&mut self, stmts: &'a [Stmt], scope: usize) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Ty(ty) => if let Some(generics) = &ty.generics {
+                    self.tys.insert(&ty.name.name, Generic { stmt, generics, scope });
+                } else {
+                    self.tys.remove(ty.name.name.as_str());
+                },
+                Stmt::Fn(fun) => if let Some(generics) = &fun.generics {
+                    self.fns.insert(&fun.name.name, Generic { stmt, generics, scope });
+                } else {
+                    self.fns.remove(fun.name.name.as_str());
+                },
+                _ => ()
+            }
+        }
+    }
+
+    ///
+    /// Returns the name of the instance of `name` with `args`, creating the instance if needed
+    ///
+    fn instance(&mut self, is_fn: bool, name: &Ident, args: &[Type], depth: usize) -> Result <Option <String>, CheckError> {
+        let generic = match if is_fn { self.fns.get(name.name.as_str()) } else { self.tys.get(name.name.as_str()) } {
+            Some(generic) => generic,
+            None => return Ok(None)
+        };
+
+        let (stmt, generics, scope) = (generic.stmt, generic.generics, generic.scope);
+        let mangled = mangle(&name.name, args);
+
+        if !self.created.insert((scope, mangled.clone())) {
+            return Ok(Some(mangled))
+        }
+
+        if depth >= MAX_DEPTH {
+            return Err(CheckError {
+                span: name.span(),
+                message: format!("the instantiation of `{}` never ends", name.name),
+                clarifying: format!("requires more than {MAX_DEPTH} nested instances"),
+                help: vec![String::from("a generic item cannot use itself with ever-growing generic arguments")]
+            })
+        }
+
+        let params = generics.names().zip(args).collect::<HashMap <_, _>>();
+        let mut instance = stmt.clone();
+
+        match &mut instance {
+            Stmt::Ty(ty) => {
+                ty.name = Ident::new(mangled.clone(), ty.name.span().start);
+                ty.generics = None
+            },
+            Stmt::Fn(fun) => {
+                fun.name = Ident::new(mangled.clone(), fun.name.span().start);
+                fun.generics = None
+            },
+            _ => unreachable!("only `ty`s and `fn`s are generic")
+        }
+
+        for_each_type_mut(&mut instance, &mut |ty| substitute_params(ty, &params));
+
+        self.rewrite(&mut instance, depth + 1)?;
+
+        self.instances[scope].push(instance);
+
+        Ok(Some(mangled))
+    }
+
+    ///
+    /// Replaces the uses of generic items in `ty` with their instances
+    ///
+    fn rewrite_type(&mut self, ty: &mut Type, depth: usize) -> Result <(), CheckError> {
+        for inner in ty.inner_mut() {
+            self.rewrite_type(inner, depth)?
+        }
+
+        let TypeKind::Named(NamedType { name, generics: Some(generics) }) = &mut ty.kind else {
+            return Ok(())
+        };
+
+        if let Some(mangled) = self.instance(false, name.last(), &generics.0, depth)? {
+            ty.kind = TypeKind::Named(NamedType {
+                name: Path::from(Ident::new(mangled, name.span().start)),
+                generics: None
+            })
+        }
+
+        Ok(())
+    }
+
+    fn rewrite_calls(&mut self, expr: &mut Expr, depth: usize) -> Result <(), CheckError> {
+        let mut result = Ok(());
+
+        for_each_call_mut(expr, &mut |call| {
+            if result.is_err() {
+                return
+            }
+
+            let Some(generics) = &call.generics else { return };
+
+            result = match self.instance(true, call.fun.last(), &generics.0, depth) {
+                Ok(Some(mangled)) => {
+                    call.fun = Path::from(Ident::new(mangled, call.fun.span().start));
+                    call.generics = None;
+                    Ok(())
+                },
+                Ok(None) => Ok(()),
+                Err(err) => Err(err)
+            }
+        });
+
+        result
+    }
+
+    fn rewrite(&mut self, stmt: &mut Stmt, depth: usize) -> Result <(), CheckError> {
+        let mut result = Ok(());
+
+        for_each_type_mut(stmt, &mut |ty| if result.is_ok() {
+            result = self.rewrite_type(ty, depth)
+        });
+
+        result?;
+
+        match stmt {
+            Stmt::Fn(fun) => self.rewrite_calls(&mut fun.body, depth),
+            Stmt::Impl(imp) => imp.fns.iter_mut().try_for_each(|fun| self.rewrite_calls(&mut fun.body, depth)),
+            Stmt::Macro(mac) => match &mut mac.body {
+                MacroStmtBody::Literal(lit) => self.rewrite_calls(&mut lit.body, depth)
+            },
+            _ => Ok(())
+        }
+    }
+
+    fn process(&mut self, stmts: &'a [Stmt], scope: usize) -> Result <Vec <Stmt>, CheckError> {
+        let outer = (self.tys.clone(), self.fns.clone());
+
+        self.declare(stmts, scope);
+
+        let mut output = vec![];
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::Ty(ty) if ty.generics.is_some() => (),
+                Stmt::Fn(fun) if fun.generics.is_some() => (),
+                Stmt::World(world) => {
+                    self.instances.push(vec![]);
+                    let world_scope = self.instances.len() - 1;
+
+                    let mut body = self.process(&world.body, world_scope)?;
+                    body.append(&mut self.instances[world_scope]);
+
+                    output.push(Stmt::World(Box::new(WorldStmt {
+                        attrs: world.attrs.clone(),
+                        name: world.name.clone(),
+                        body
+                    })))
+                },
+                _ => {
+                    let mut stmt = stmt.clone();
+                    self.rewrite(&mut stmt, 0)?;
+                    output.push(stmt)
+                }
+            }
+        }
+
+        (self.tys, self.fns) = outer;
+
+        Ok(output)
+    }
+}
+
+///
+/// Replaces the generic parameters used in `ty` with the corresponding arguments
+///
+pub fn substitute_params(ty: &mut Type, params: &HashMap <&str, &Type>) {
+    for inner in ty.inner_mut() {
+        substitute_params(inner, params)
+    }
+
+    if let TypeKind::Named(NamedType { name, generics: None }) = &ty.kind {
+        if let Some(arg) = params.get(name.last().name.as_str()) {
+            // The indirections around the parameter stay in place: `&T` with `T` = `*int` becomes `&*int`
+            ty.kind = arg.kind.clone()
+        }
+    }
+}
+
+///
+/// Returns the statements with every generic item replaced by the instances it is used with
+///
+/// Instances of a generic item are placed at the end of the scope (the top level or a world) the item is declared in
+///
+pub fn monomorphize(stmts: &[Stmt]) -> Result <Vec <Stmt>, CheckError> {
+    let mut monomorphizer = Monomorphizer {
+        instances: vec![vec![]],
+        ..Default::default()
+    };
+
+    let mut output = monomorphizer.process(stmts, 0)?;
+    output.append(&mut monomorphizer.instances[0]);
+
+    Ok(output)
+}
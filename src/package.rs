@@ -0,0 +1,341 @@
+//!
+//! This module reads packages: directories with an `oko.toml` manifest
+//!
+//! ```toml
+//! [package]
+//! name = "game"
+//! entry = "src/main.oko"
+//! links = ["m", "SDL2"]
+//!
+//! [dependencies]
+//! geometry = { path = "../geometry" }
+//! ```
+//!
+//! `entry` is the root module of the package, `src/main.oko` by default. `links` are the C libraries
+//!   the `extern clang` functions of the package come from. A dependency is another package in a local directory,
+//!   its public items are reachable from every module of the dependent package: `geometry::Vec2`
+//!
+
+use core::ops::Range;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, Item, TableLike};
+use crate::check::CheckError;
+use crate::error::{Error, Result};
+use crate::span::Span;
+
+pub const MANIFEST: &str = "oko.toml";
+
+pub const DEFAULT_ENTRY: &str = "src/main.oko";
+
+///
+/// A package the current one depends on
+///
+/// `geometry = { path = "../geometry" }`
+///
+pub struct Dependency {
+    ///
+    /// The name the dependent package refers to the dependency by
+    ///
+    pub name: String,
+
+    ///
+    /// The directory of the dependency, relative to the manifest
+    ///
+    pub path: PathBuf,
+
+    span: Span
+}
+
+pub struct Manifest {
+    pub name: String,
+    pub entry: PathBuf,
+    pub links: Vec <String>,
+    pub dependencies: Vec <Dependency>,
+    name_span: Span,
+    entry_span: Span
+}
+
+///
+/// Whether `name` can name a package or a dependency: it is used in paths and in the generated code,
+///   so only letters and digits starting with a letter are allowed
+///
+pub fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|char: char| char.is_ascii_alphabetic()) && name.chars().all(|char| char.is_ascii_alphanumeric())
+}
+
+///
+/// Reads the parts of a manifest, reporting errors at the right places of its code
+///
+struct Reader <'a> {
+    code: &'a str
+}
+
+impl Reader <'_> {
+    ///
+    /// The span of a part of the manifest, cut at the end of its first line
+    ///
+    fn span(&self, range: Option <Range <usize>>) -> Span {
+        range.map_or(Span::EOF, |range| {
+            let line_end = self.code[range.start..].find('\n').map_or(self.code.len(), |idx| range.start + idx);
+            let span = Span::of_range(self.code, range.start..range.end.min(line_end), 0);
+
+            // Errors at the end of a line point to the symbol after it
+            if span.start == span.end {
+                return Span::with_extra_column(span.start, 1)
+            }
+
+            span
+        })
+    }
+
+    fn error(&self, range: Option <Range <usize>>, message: String, clarifying: &str) -> CheckError {
+        CheckError {
+            span: self.span(range),
+            message,
+            clarifying: String::from(clarifying),
+            help: vec![]
+        }
+    }
+
+    fn table <'t> (&self, item: &'t Item, name: &str) -> core::result::Result <&'t dyn TableLike, CheckError> {
+        item.as_table_like().ok_or_else(|| self.error(item.span(), format!("`{name}` must be a table"), "not a table"))
+    }
+
+    fn string(&self, item: &Item, name: &str) -> core::result::Result <String, CheckError> {
+        item.as_str()
+            .map(str::to_string)
+            .ok_or_else(|| self.error(item.span(), format!("`{name}` must be a string"), "not a string"))
+    }
+
+    fn ident(&self, name: &str, range: Option <Range <usize>>, what: &str) -> core::result::Result <(), CheckError> {
+        if is_valid_name(name) {
+            return Ok(())
+        }
+
+        Err(CheckError {
+            help: vec![String::from("use only letters and digits, starting with a letter")],
+            ..self.error(range, format!("`{name}` is not a valid {what} name"), "invalid name")
+        })
+    }
+
+    fn check_keys(&self, table: &dyn TableLike, known: &[&str], place: &str) -> core::result::Result <(), CheckError> {
+        match table.iter().find(|(key, _)| !known.contains(key)) {
+            Some((key, _)) => Err(CheckError {
+                help: vec![format!("the known keys are {}", known.iter().map(|key| format!("`{key}`")).collect::<Vec <_>>().join(", "))],
+                ..self.error(table.key(key).and_then(|key| key.span()), format!("unknown key `{key}` in {place}"), "unknown key")
+            }),
+            None => Ok(())
+        }
+    }
+
+    fn manifest(&self) -> core::result::Result <Manifest, CheckError> {
+        let document = ImDocument::parse(self.code).map_err(|err| self.error(err.span(), err.message().trim().replace('\n', ": "), "invalid TOML"))?;
+        let root = document.as_table();
+
+        self.check_keys(root, &["package", "dependencies"], "the manifest")?;
+
+        let package = root.get("package").ok_or_else(|| CheckError {
+            help: vec![String::from("add `[package]` with the `name` of the package")],
+            ..self.error(None, String::from("the manifest has no `[package]` table"), "missing table")
+        })?;
+        let package = self.table(package, "package")?;
+
+        self.check_keys(package, &["name", "entry", "links"], "`[package]`")?;
+
+        let (name, name_span) = match package.get("name") {
+            Some(item) => {
+                let name = self.string(item, "name")?;
+                self.ident(&name, item.span(), "package")?;
+                (name, self.span(item.span()))
+            },
+            None => return Err(self.error(root.key("package").and_then(|key| key.span()), String::from("the package has no `name`"), "in this table"))
+        };
+
+        let (entry, entry_span) = match package.get("entry") {
+            Some(item) => (PathBuf::from(self.string(item, "entry")?), self.span(item.span())),
+            None => (PathBuf::from(DEFAULT_ENTRY), name_span)
+        };
+
+        let links = match package.get("links") {
+            Some(item) => {
+                let array = item.as_array().ok_or_else(|| self.error(item.span(), String::from("`links` must be an array of strings"), "not an array"))?;
+
+                array.iter()
+                    .map(|link| link.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| self.error(link.span(), String::from("a library must be a string"), "not a string")))
+                    .collect::<core::result::Result <_, _>>()?
+            },
+            None => vec![]
+        };
+
+        let mut dependencies = vec![];
+
+        if let Some(item) = root.get("dependencies") {
+            let table = self.table(item, "dependencies")?;
+
+            for (name, item) in table.iter() {
+                let key_span = table.key(name).and_then(|key| key.span());
+                self.ident(name, key_span.clone(), "dependency")?;
+
+                let dependency = self.table(item, name)?;
+                self.check_keys(dependency, &["path"], &format!("the dependency `{name}`"))?;
+
+                let path = dependency.get("path").ok_or_else(|| CheckError {
+                    help: vec![format!("point it to the directory of the package: `{name} = {{ path = \"../{name}\" }}`")],
+                    ..self.error(key_span.clone(), format!("the dependency `{name}` has no `path`"), "only local packages are supported")
+                })?;
+
+                dependencies.push(Dependency {
+                    name: name.to_string(),
+                    path: PathBuf::from(self.string(path, "path")?),
+                    span: self.span(path.span())
+                })
+            }
+        }
+
+        Ok(Manifest {
+            name,
+            entry,
+            links,
+            dependencies,
+            name_span,
+            entry_span
+        })
+    }
+}
+
+impl Manifest {
+    pub fn parse(code: &str) -> core::result::Result <Self, CheckError> {
+        Reader { code }.manifest()
+    }
+}
+
+pub struct Package {
+    pub dir: PathBuf,
+    pub manifest: Manifest,
+
+    ///
+    /// The indices of the dependencies among all the packages, in the order of [`Manifest::dependencies`]
+    ///
+    pub dependencies: Vec <usize>
+}
+
+impl Package {
+    pub fn entry(&self) -> PathBuf {
+        self.dir.join(&self.manifest.entry)
+    }
+}
+
+#[derive(Default)]
+struct Graph {
+    packages: Vec <Package>,
+    by_dir: HashMap <PathBuf, usize>,
+    by_name: HashMap <String, usize>,
+
+    ///
+    /// The packages being read, to report cycles
+    ///
+    loading: Vec <PathBuf>
+}
+
+impl Graph {
+    fn visit(&mut self, dir: PathBuf) -> Result <usize> {
+        let filename = dir.join(MANIFEST);
+        let code = match std::fs::read_to_string(&filename) {
+            Ok(code) => code,
+            Err(err) => return Result(Err(Error::unreadable(filename.display().to_string(), &err)))
+        };
+        let error = |err: CheckError| Result(Err(err.to_error(&code, filename.display().to_string())));
+
+        let manifest = match Manifest::parse(&code) {
+            Ok(manifest) => manifest,
+            Err(err) => return error(err)
+        };
+
+        if let Some(&other) = self.by_name.get(&manifest.name) {
+            return error(CheckError {
+                span: manifest.name_span,
+                message: format!("two packages are named `{}`", manifest.name),
+                clarifying: String::from("the name is taken"),
+                help: vec![format!("the other one is in `{}`", self.packages[other].dir.display())]
+            })
+        }
+
+        if !dir.join(&manifest.entry).is_file() {
+            return error(CheckError {
+                span: manifest.entry_span,
+                message: format!("cannot find the entry file `{}`", manifest.entry.display()),
+                clarifying: String::from("of this package"),
+                help: vec![format!("create `{}`", dir.join(&manifest.entry).display())]
+            })
+        }
+
+        let idx = self.packages.len();
+        self.by_dir.insert(dir.clone(), idx);
+        self.by_name.insert(manifest.name.clone(), idx);
+        self.loading.push(dir.clone());
+
+        self.packages.push(Package {
+            dir: dir.clone(),
+            manifest,
+            dependencies: vec![]
+        });
+
+        for nth in 0..self.packages[idx].manifest.dependencies.len() {
+            let dependency = &self.packages[idx].manifest.dependencies[nth];
+            let (name, span) = (dependency.name.clone(), dependency.span);
+            let path = dir.join(&dependency.path);
+
+            let canonical = match path.canonicalize() {
+                Ok(canonical) if canonical.join(MANIFEST).is_file() => canonical,
+                _ => return error(CheckError {
+                    span,
+                    message: format!("cannot find `{MANIFEST}` in `{}`", path.display()),
+                    clarifying: String::from("not a package"),
+                    help: vec![]
+                })
+            };
+
+            if self.loading.contains(&canonical) {
+                return error(CheckError {
+                    span,
+                    message: format!("package `{name}` depends on itself"),
+                    clarifying: String::from("dependency cycle"),
+                    help: vec![]
+                })
+            }
+
+            let dependency = match self.by_dir.get(&canonical) {
+                Some(&dependency) => dependency,
+                None => self.visit(canonical)?
+            };
+
+            self.packages[idx].dependencies.push(dependency)
+        }
+
+        self.loading.pop();
+
+        Result(Ok(idx))
+    }
+}
+
+///
+/// Finds the package containing `dir`: the closest directory with a manifest, `dir` itself or one of its parents
+///
+pub fn find(dir: &Path) -> Option <PathBuf> {
+    dir.ancestors().find(|dir| dir.join(MANIFEST).is_file()).map(Path::to_path_buf)
+}
+
+///
+/// Reads the package in `dir` with every package it depends on, directly or not. The package in `dir` is the first one
+///
+pub fn resolve(dir: &Path) -> Result <Vec <Package>> {
+    let mut graph = Graph::default();
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+
+    graph.visit(dir)?;
+
+    Result(Ok(graph.packages))
+}
@@ -0,0 +1,212 @@
+use super::span::{DoubleQuotedString, Ident};
+use super::punctuated::Punctuated;
+use super::stream::{Parse, ParseStream, ParseStreamError, Result};
+use crate::span::Span;
+
+///
+/// A literal value inside of an attribute
+///
+/// `#[repr(align(8))]`
+///
+/// `--------------^--`
+///
+#[derive(Debug, Clone)]
+pub enum Lit {
+    Int(usize, Span),
+    Str(DoubleQuotedString)
+}
+
+impl Lit {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Int(_, span) => *span,
+            Self::Str(string) => string.span()
+        }
+    }
+}
+
+impl Parse for Lit {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        stream.trim();
+
+        if stream.code.starts_with('"') {
+            return stream.double_quoted_string().map(Self::Str)
+        }
+
+        let start = stream.cursor;
+        let value = stream.number_usize().map_err(|err| err.with_custom_expected(String::from("a number or a string")))?;
+
+        Ok(Self::Int(value, Span {
+            start,
+            end: stream.cursor
+        }))
+    }
+}
+
+///
+/// The contents of an attribute, also nested into the list of another one
+///
+/// `#[repr(C, align(8))]`
+///
+/// `--^^^^^^^^^^^^^^^^-`
+///
+#[derive(Debug, Clone)]
+pub enum Meta {
+    ///
+    /// `packed`
+    ///
+    Word(Ident),
+
+    ///
+    /// `align(8)`
+    ///
+    List(Ident, Punctuated <Meta, ',', true>),
+
+    ///
+    /// `name = "puts"`
+    ///
+    NameValue(Ident, Lit),
+
+    ///
+    /// `8`
+    ///
+    Lit(Lit)
+}
+
+impl Meta {
+    ///
+    /// The name of the attribute, `None` for a literal
+    ///
+    pub fn name(&self) -> Option <&Ident> {
+        match self {
+            Self::Word(name) | Self::List(name, _) | Self::NameValue(name, _) => Some(name),
+            Self::Lit(_) => None
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Word(name) | Self::List(name, _) | Self::NameValue(name, _) => name.span(),
+            Self::Lit(lit) => lit.span()
+        }
+    }
+}
+
+///
+/// The name of an attribute, which unlike an [`Ident`] may contain `_`: `link_name`
+///
+fn name(stream: &mut ParseStream) -> Result <Ident> {
+    stream.trim();
+
+    if !stream.code.starts_with(|char: char| char.is_alphabetic()) {
+        return Err(ParseStreamError {
+            span: Span::with_extra_column(stream.cursor, 1),
+            parsing_depth: stream.depth,
+            expected: String::from("the name of an attribute"),
+            help: vec![]
+        })
+    }
+
+    let end = stream.code.find(|char: char| !char.is_alphanumeric() && char != '_').unwrap_or(stream.code.len());
+    let name = Ident::new(stream.code[..end].to_string(), stream.cursor);
+    stream.offset_by(end);
+
+    Ok(name)
+}
+
+impl Parse for Meta {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let mut clone = stream.clone();
+
+        if let Ok(lit) = Lit::parse(&mut clone) {
+            *stream = clone;
+            return Ok(Self::Lit(lit))
+        }
+
+        let name = name(stream)?;
+
+        if stream.clone().punct("(").is_ok() {
+            return Ok(Self::List(name, stream.embraced('(', ')')?))
+        }
+
+        if stream.punct("=").is_ok() {
+            return Ok(Self::NameValue(name, Lit::parse(stream)?))
+        }
+
+        Ok(Self::Word(name))
+    }
+}
+
+///
+/// An attribute of an item
+///
+/// `#[repr(C, packed)]`
+///
+/// `^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub meta: Meta,
+    pub span: Span
+}
+
+impl Parse for Attribute {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        stream.trim();
+
+        let start = stream.cursor;
+        stream.punct("#")?;
+        stream.punct("[")?;
+        let meta = Meta::parse(stream)?;
+        stream.punct("]")?;
+
+        Ok(Self {
+            meta,
+            span: Span {
+                start,
+                end: stream.cursor
+            }
+        })
+    }
+}
+
+///
+/// Parses the attributes written before an item
+///
+pub fn attributes(stream: &mut ParseStream) -> Result <Vec <Attribute>> {
+    let mut attrs = vec![];
+
+    loop {
+        let mut clone = stream.clone();
+        clone.trim();
+
+        if !clone.code.starts_with("#[") {
+            return Ok(attrs)
+        }
+
+        attrs.push(Attribute::parse(stream)?)
+    }
+}
+
+///
+/// The string of the attribute `name = "..."`: `puts` of `#[link_name = "puts"]`
+///
+pub fn string_value <'a> (attrs: &'a [Attribute], name: &str) -> Option <&'a str> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(ident, Lit::Str(string)) if ident.name == name => Some(string.value.as_str()),
+        _ => None
+    })
+}
+
+///
+/// The only word listed by the attribute `name`: `stdcall` of `#[callconv(stdcall)]`
+///
+pub fn list_word <'a> (attrs: &'a [Attribute], name: &str) -> Option <&'a str> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::List(ident, list) if ident.name == name => match list.0.as_slice() {
+            [Meta::Word(word)] => Some(word.name.as_str()),
+            _ => None
+        },
+        _ => None
+    })
+}
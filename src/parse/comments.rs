@@ -1,5 +1,5 @@
-pub const COMMENT_START: &'static str = "#(";
-pub const COMMENT_END: &'static str = ")#";
+pub const COMMENT_START: &str = "#(";
+pub const COMMENT_END: &str = ")#";
 
 pub fn remove_non_documenting_comments(mut code: String) -> String {
     while let Some(start) = code.find(COMMENT_START) {
@@ -7,13 +7,13 @@ macro_rules! define_parseable_enum {
 
         FIELDS: $( $field:ident )*
     ) => {
-        use crate::parse::stream::*;
+        use $crate::parse::stream::*;
 
         #[allow(unused_imports)]
-        use crate::parse::punctuated::*;
+        use $crate::parse::punctuated::*;
 
         #[allow(unused_imports)]
-        use crate::parse::span::*;
+        use $crate::parse::span::*;
 
         $(
             #[allow(non_snake_case)]
@@ -32,8 +32,13 @@ macro_rules! define_parseable_enum {
             fn parse(stream: &mut ParseStream) -> Result <Self> {
 
                 $(
-                    let c_like_concat::concat!($field, _error) = match c_like_concat::concat!($field, $name::parse(stream)) {
-                        Ok(x) => return Ok(Self::$field(Box::new(x))),
+                    let mut clone = stream.clone();
+
+                    let c_like_concat::concat!($field, _error) = match c_like_concat::concat!($field, $name::parse(&mut clone)) {
+                        Ok(x) => {
+                            *stream = clone;
+                            return Ok(Self::$field(Box::new(x)))
+                        },
                         Err(err) => err
                     };
                 )*
@@ -1,20 +1,32 @@
+use crate::parse::path::Path;
+use crate::parse::ty::Type;
 use super::*;
 
+///
+/// The function call
+///
+/// `length<Vec2>(v)`
+///
+/// `^^^^^^^^^^^^^^^`
+///
 #[derive(Debug, Clone)]
 pub struct CallExpr {
-    pub fun: Ident,
+    pub fun: Path,
+    pub generics: Option <Punctuated <Type, ','>>,
     pub args: Punctuated <Expr, ',', true>
 }
 
 impl Parse for CallExpr {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
-        let fun = Ident::parse(stream)?;
+        let fun = Path::parse(stream)?;
+        let generics = stream.embraced('<', '>').ok();
         stream.punct("(")?;
         let args = Punctuated::parse(stream)?;
         stream.punct(")")?;
 
         Ok(Self {
             fun,
+            generics,
             args
         })
     }
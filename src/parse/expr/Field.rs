@@ -0,0 +1,39 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult, Write};
+use crate::span::Span;
+use super::*;
+
+///
+/// Access to a field of a struct, also through references and pointers to it
+///
+/// `player.position.x`
+///
+/// `^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct FieldExpr {
+    pub base: Expr,
+    pub field: Ident,
+    pub span: Span
+}
+
+impl Debug for FieldExpr {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        self.base.fmt(f)?;
+        f.write_char('.')?;
+        f.write_str(&self.field.name)
+    }
+}
+
+impl Parse for FieldExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        match Expr::postfix(stream)? {
+            Expr::Field(field) => Ok(*field),
+            _ => Err(ParseStreamError {
+                span: Span::with_extra_column(stream.cursor, 1),
+                parsing_depth: stream.depth,
+                expected: String::from("`.` and the name of a field"),
+                help: vec![]
+            })
+        }
+    }
+}
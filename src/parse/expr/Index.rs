@@ -0,0 +1,30 @@
+use crate::span::Span;
+use super::*;
+
+///
+/// Indexing into an array, a slice or a tuple
+///
+/// `grid[row][3]`
+///
+/// `^^^^^^^^^^^^`
+///
+#[derive(Debug, Clone)]
+pub struct IndexExpr {
+    pub base: Expr,
+    pub index: Expr,
+    pub span: Span
+}
+
+impl Parse for IndexExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        match Expr::postfix(stream)? {
+            Expr::Index(index) => Ok(*index),
+            _ => Err(ParseStreamError {
+                span: Span::with_extra_column(stream.cursor, 1),
+                parsing_depth: stream.depth,
+                expected: String::from("`[`"),
+                help: vec![]
+            })
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::span::Span;
+use super::*;
+
+///
+/// A non-negative integer literal
+///
+/// `cells[3]`
+///
+/// `------^-`
+///
+#[derive(Debug, Clone)]
+pub struct IntExpr {
+    pub value: usize,
+    pub span: Span
+}
+
+impl Parse for IntExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        stream.trim();
+
+        let start = stream.cursor;
+        let value = stream.number_usize()?;
+
+        Ok(Self {
+            value,
+            span: Span {
+                start,
+                end: stream.cursor
+            }
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use crate::parse::signature::Signature;
+use crate::span::Span;
+use super::*;
+
+///
+/// An anonymous function, which may use the variables of the function it is written in
+///
+/// `apply(values, fn(x: int) -> int = add(x, offset))`
+///
+/// `--------------^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^-`
+///
+#[derive(Debug, Clone)]
+pub struct LambdaExpr {
+    pub sig: Signature,
+    pub body: Expr,
+    pub span: Span
+}
+
+impl Parse for LambdaExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        stream.trim();
+
+        let start = stream.cursor;
+        stream.keyword("fn")?;
+        let sig = Signature::parse(stream)?;
+        let body = Expr::single_or_block(stream)?;
+
+        Ok(Self {
+            sig,
+            body,
+            span: Span {
+                start,
+                end: stream.cursor
+            }
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult, Write};
+use crate::span::Span;
+use super::*;
+
+///
+/// How the receiver of a method call is turned into the first argument of the method
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Adjustment {
+    None,
+
+    ///
+    /// The method takes a reference or a pointer to the receiver
+    ///
+    Ref,
+
+    ///
+    /// The receiver is behind that many references or pointers more than the method takes
+    ///
+    Deref(usize)
+}
+
+///
+/// The function a method call is dispatched to
+///
+#[derive(Debug, Clone)]
+pub struct Dispatch {
+    pub fun: Ident,
+    pub adjustment: Adjustment
+}
+
+///
+/// The method call
+///
+/// `position.show(2)`
+///
+/// `^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct MethodCallExpr {
+    pub receiver: Expr,
+    pub method: Ident,
+    pub args: Punctuated <Expr, ',', true>,
+    pub span: Span,
+
+    ///
+    /// Filled by [`crate::check::traits::resolve`] once the type of the receiver is known
+    ///
+    pub dispatch: Option <Dispatch>
+}
+
+impl Debug for MethodCallExpr {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        self.receiver.fmt(f)?;
+        f.write_char('.')?;
+        f.write_str(&self.method.name)?;
+        f.write_char('(')?;
+        self.args.debug(f)?;
+        f.write_char(')')
+    }
+}
+
+impl Parse for MethodCallExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        match Expr::postfix(stream)? {
+            Expr::MethodCall(call) => Ok(*call),
+            _ => Err(ParseStreamError {
+                span: Span::with_extra_column(stream.cursor, 1),
+                parsing_depth: stream.depth,
+                expected: String::from("`.` and a method call"),
+                help: vec![]
+            })
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::parse::path::Path;
+use super::*;
+
+///
+/// A single field initializer of the struct literal
+///
+/// `Health { value: full() }`
+///
+/// `---------^^^^^^^^^^^^^--`
+///
+#[derive(Debug, Clone)]
+pub struct FieldValue {
+    pub name: Ident,
+    pub value: Expr
+}
+
+impl Parse for FieldValue {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let name = Ident::parse(stream)?;
+        stream.punct(":")?;
+        let value = Expr::parse(stream)?;
+
+        Ok(Self {
+            name,
+            value
+        })
+    }
+}
+
+///
+/// The struct literal
+///
+/// `stats::Health { value: full() }`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Debug, Clone)]
+pub struct StructExpr {
+    pub name: Path,
+    pub fields: Punctuated <FieldValue, ',', true>
+}
+
+impl Parse for StructExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let name = Path::parse(stream)?;
+        let fields = stream.embraced('{', '}')?;
+
+        Ok(Self {
+            name,
+            fields
+        })
+    }
+}
@@ -0,0 +1,28 @@
+use crate::parse::path::Path;
+use super::*;
+
+///
+/// A variable used as an expression, or a function, or a variant of an enum without a value
+///
+/// `length(v)`
+///
+/// `-------^-`
+///
+/// `Option::None`
+///
+/// `^^^^^^^^^^^^`
+///
+#[derive(Debug, Clone)]
+pub struct VarExpr {
+    pub name: Path
+}
+
+impl Parse for VarExpr {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let name = Path::parse(stream)?;
+
+        Ok(Self {
+            name
+        })
+    }
+}
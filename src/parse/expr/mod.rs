@@ -1,9 +1,18 @@
+use crate::span::Span;
+
 crate::define_parseable_enum! {
     NAME = Expr
 
     FIELDS:
 
+    Lambda
+    MethodCall
+    Field
+    Index
     Call
+    Struct
+    Int
+    Var
     Block
 }
 
@@ -15,4 +24,106 @@ impl Expr {
             Expr::Block(Box::new(BlockExpr::parse(stream)?))
         })
     }
+
+    ///
+    /// The span an error about the expression points at, the one of its first expression for a block
+    ///
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Var(var) => var.name.span(),
+            Expr::Int(int) => int.span,
+            Expr::Field(field) => field.span,
+            Expr::Index(index) => index.span,
+            Expr::Call(call) => call.fun.span(),
+            Expr::MethodCall(call) => call.span,
+            Expr::Struct(lit) => lit.name.span(),
+            Expr::Lambda(lambda) => lambda.span,
+            Expr::Block(block) => block.expressions.first().map_or(Span::EOF, Expr::span)
+        }
+    }
+
+    ///
+    /// Parses an operand followed by any number of indexings, field accesses and method calls
+    ///
+    /// `grid[1].cells[0].show()`
+    ///
+    fn postfix(stream: &mut ParseStream) -> Result <Self> {
+        stream.trim();
+
+        let start = stream.cursor;
+        let mut expr = Self::operand(stream)?;
+
+        let span = |stream: &ParseStream| Span {
+            start,
+            end: stream.cursor
+        };
+
+        loop {
+            if stream.punct("[").is_ok() {
+                let index = Expr::parse(stream)?;
+                stream.punct("]")?;
+
+                expr = Expr::Index(Box::new(IndexExpr {
+                    base: expr,
+                    index,
+                    span: span(stream)
+                }))
+            } else if stream.punct(".").is_ok() {
+                let name = Ident::parse(stream)?;
+
+                if stream.clone().punct("(").is_err() {
+                    expr = Expr::Field(Box::new(FieldExpr {
+                        base: expr,
+                        field: name,
+                        span: span(stream)
+                    }));
+
+                    continue
+                }
+
+                let args = stream.embraced('(', ')')?;
+
+                expr = Expr::MethodCall(Box::new(MethodCallExpr {
+                    receiver: expr,
+                    method: name,
+                    args,
+                    span: span(stream),
+                    dispatch: None
+                }))
+            } else {
+                return Ok(expr)
+            }
+        }
+    }
+
+    ///
+    /// Parses the expression a postfix operation applies to
+    ///
+    fn operand(stream: &mut ParseStream) -> Result <Self> {
+        macro_rules! operands {
+            ($( $variant:ident($ty:ident) )*) => {$(
+                let mut clone = stream.clone();
+
+                if let Ok(operand) = $ty::parse(&mut clone) {
+                    *stream = clone;
+                    return Ok(Expr::$variant(Box::new(operand)))
+                }
+            )*};
+        }
+
+        operands! {
+            Call(CallExpr)
+            Struct(StructExpr)
+            Int(IntExpr)
+            Var(VarExpr)
+            Block(BlockExpr)
+        }
+
+        Err(ParseStreamError {
+            span: Span::with_extra_column(stream.cursor, 1),
+            parsing_depth: stream.depth,
+            expected: String::from("an expression"),
+            help: vec![]
+        })
+    }
 }
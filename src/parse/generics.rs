@@ -0,0 +1,67 @@
+use super::punctuated::Punctuated;
+use super::span::Ident;
+use super::stream::{Parse, ParseStream, Result};
+use core::fmt::{Debug, Formatter, Result as FmtResult, Write};
+
+///
+/// The generic parameters of a `ty` or a `fn`
+///
+/// `ty Vec2 <T> = x y: T`
+///
+/// `--------^^^---------`
+///
+#[derive(Clone)]
+pub struct GenericParams {
+    pub params: Punctuated <Ident, ','>
+}
+
+impl GenericParams {
+    pub fn len(&self) -> usize {
+        self.params.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.0.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator <Item = &str> {
+        self.params.0.iter().map(|param| param.name.as_str())
+    }
+
+    ///
+    /// Parses the parameters if the next token is `<`, failing on a malformed list instead of skipping it
+    ///
+    pub fn optional(stream: &mut ParseStream) -> Result <Option <Self>> {
+        let mut clone = stream.clone();
+        clone.trim();
+
+        if !clone.code.starts_with('<') {
+            return Ok(None)
+        }
+
+        Self::parse(stream).map(Some)
+    }
+}
+
+impl Debug for GenericParams {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_char('<')?;
+        for (idx, param) in self.params.0.iter().enumerate() {
+            if idx != 0 {
+                f.write_str(", ")?
+            }
+            f.write_str(&param.name)?
+        }
+        f.write_char('>')
+    }
+}
+
+impl Parse for GenericParams {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let params = stream.embraced('<', '>')?;
+
+        Ok(Self {
+            params
+        })
+    }
+}
@@ -7,11 +7,14 @@ pub mod span;
 pub mod stmt;
 pub mod expr;
 pub mod ty;
+pub mod generics;
 pub mod typed_variable;
 pub mod punctuated;
 pub mod signature;
+pub mod path;
 pub mod x_times;
 pub mod comments;
+pub mod attribute;
 
 #[macro_use]
 pub mod define_parseable_enum;
@@ -20,18 +23,39 @@ use crate::error::Result;
 use stream::{Parse, ParseStream};
 use stmt::Stmt;
 
+///
+/// Reads the file and removes all the non-documenting comments from it
+///
+pub fn read(filename: &str) -> String {
+    let code = std::fs::read_to_string(filename).expect("failed to read file");
+    comments::remove_non_documenting_comments(code)
+}
+
 ///
 /// Transforms the source code into a sequence of statements
 ///
 pub fn parse(filename: &str) -> Result <Vec <Stmt>> {
-    let code = std::fs::read_to_string(filename).expect("failed to read file");
-    let code = comments::remove_non_documenting_comments(code);
-    let mut stream = ParseStream::new(&code);
+    parse_code(&read(filename), filename)
+}
+
+///
+/// Same as [`parse`], but the code is already [`read`]
+///
+pub fn parse_code(code: &str, filename: &str) -> Result <Vec <Stmt>> {
+    parse_file(code, filename, 0)
+}
+
+///
+/// Same as [`parse_code`], but the spans point into the file with the index `file`.
+///   Used when several files are loaded, see [`crate::driver`]
+///
+pub fn parse_file(code: &str, filename: &str, file: usize) -> Result <Vec <Stmt>> {
+    let mut stream = ParseStream::in_file(code, file);
 
     let mut vec = vec![];
 
     while !stream.is_empty() {
-        let stmt = Result(Stmt::parse(&mut stream).map_err(|err| err.to_error(&code, filename.to_string())))?;
+        let stmt = Result(Stmt::parse(&mut stream).map_err(|err| err.to_error(code, filename.to_string())))?;
 
         stream.trim();
 
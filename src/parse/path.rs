@@ -0,0 +1,73 @@
+use super::span::Ident;
+use super::stream::{Parse, ParseStream, Result};
+use crate::span::Span;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+///
+/// A possibly qualified name of an item
+///
+/// `math::vec::length`
+///
+/// `^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct Path {
+    ///
+    /// Never empty
+    ///
+    pub segments: Vec <Ident>
+}
+
+impl Path {
+    ///
+    /// The name of the item itself
+    ///
+    /// `math::vec::length`
+    ///
+    /// `-----------^^^^^^`
+    ///
+    pub fn last(&self) -> &Ident {
+        self.segments.last().expect("a path cannot be empty")
+    }
+
+    pub fn is_qualified(&self) -> bool {
+        self.segments.len() > 1
+    }
+
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.segments[0].span().start,
+            end: self.last().span().end
+        }
+    }
+}
+
+impl From <Ident> for Path {
+    fn from(ident: Ident) -> Self {
+        Self {
+            segments: vec![ident]
+        }
+    }
+}
+
+impl Debug for Path {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        let segments = self.segments.iter().map(|segment| segment.name.as_str()).collect::<Vec <_>>();
+
+        f.write_str(&segments.join("::"))
+    }
+}
+
+impl Parse for Path {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let mut segments = vec![Ident::parse(stream)?];
+
+        while stream.punct("::").is_ok() {
+            segments.push(Ident::parse(stream)?)
+        }
+
+        Ok(Self {
+            segments
+        })
+    }
+}
@@ -48,6 +48,8 @@ impl <T: Parse, const P: char, const IS_ZERO_ALLOWED: bool> Parse for Punctuated
 
         loop {
             let mut clone = stream.clone();
+            clone.trim();
+            let start = clone.depth;
 
             let value = match T::parse(&mut clone) {
                 Ok(ok) => {
@@ -55,8 +57,13 @@ impl <T: Parse, const P: char, const IS_ZERO_ALLOWED: bool> Parse for Punctuated
                     ok
                 },
                 Err(err) => if vec.is_empty() && !IS_ZERO_ALLOWED {
-                    let expected = format!("at least one {}", err.expected);
+                    // "a number" becomes "at least one number"
+                    let item = ["a ", "an ", "the "].iter().find_map(|article| err.expected.strip_prefix(article)).unwrap_or(&err.expected);
+                    let expected = format!("at least one {item}");
                     return Err(err.with_custom_expected(expected))
+                } else if err.parsing_depth.0 > start.0 {
+                    // The element is there, but malformed
+                    return Err(err)
                 } else {
                     break
                 }
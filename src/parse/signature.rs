@@ -1,7 +1,9 @@
+use super::path::Path;
 use super::punctuated::Punctuated;
+use super::span::Ident;
 use super::typed_variable::TypedVariables;
 use super::x_times::XTimes;
-use super::ty::Type;
+use super::ty::{Indirection, NamedType, Type, TypeKind, SELF};
 use super::stream::{Parse, ParseStream, ParseStreamError, Result};
 use crate::span::Span;
 use core::fmt::{Debug, Formatter, Result as FmtResult, Write};
@@ -22,14 +24,99 @@ impl Debug for Arg {
     }
 }
 
+impl Arg {
+    ///
+    /// Parses the receiver of a method written without its type: `self`, `&self` and `&mut self`
+    ///   are the same as `self: Self`, `self: &Self` and `self: &mut Self`
+    ///
+    fn receiver(stream: &mut ParseStream) -> Result <Self> {
+        stream.trim();
+
+        let start = stream.cursor;
+        let indirection = Indirection::parse(stream).ok();
+        let name = Ident::parse(stream)?;
+
+        if name.name != "self" || stream.clone().punct(":").is_ok() {
+            return Err(ParseStreamError {
+                span: name.span(),
+                parsing_depth: stream.depth,
+                expected: String::from("`self`"),
+                help: vec![]
+            })
+        }
+
+        let span = Span {
+            start,
+            end: stream.cursor
+        };
+
+        let mut ty = Type {
+            kind: TypeKind::Named(NamedType {
+                name: Path::from(Ident::new(String::from(SELF), name.span().start)),
+                generics: None
+            }),
+            span
+        };
+
+        if let Some(indirection) = indirection {
+            ty = Type {
+                kind: TypeKind::Indirect(indirection, Box::new(ty)),
+                span
+            }
+        }
+
+        Ok(Self::Named(TypedVariables {
+            attrs: vec![],
+            names: vec![name],
+            ty
+        }))
+    }
+}
+
 impl Parse for Arg {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
-        if let Ok(ok) = TypedVariables::parse(stream) {
-            return Ok(Self::Named(ok))
+        let start = {
+            let mut clone = stream.clone();
+            clone.trim();
+            clone.depth
+        };
+
+        let mut clone = stream.clone();
+
+        if let Ok(receiver) = Self::receiver(&mut clone) {
+            *stream = clone;
+            return Ok(receiver)
         }
 
-        if let Ok(ok) = XTimes::parse(stream) {
-            return Ok(Self::Unnamed(ok))
+        let mut clone = stream.clone();
+
+        let named_error = match TypedVariables::parse(&mut clone) {
+            Ok(ok) => {
+                *stream = clone;
+                return Ok(Self::Named(ok))
+            },
+            Err(err) => err
+        };
+
+        let mut clone = stream.clone();
+
+        let unnamed_error = match XTimes::parse(&mut clone) {
+            Ok(ok) => {
+                *stream = clone;
+                return Ok(Self::Unnamed(ok))
+            },
+            Err(err) => err
+        };
+
+        // An error after the first token is more precise than the general one, `int x -3` for example
+        let deepest = if unnamed_error.parsing_depth.0 >= named_error.parsing_depth.0 {
+            unnamed_error
+        } else {
+            named_error
+        };
+
+        if deepest.parsing_depth.0 > start.0 {
+            return Err(deepest)
         }
 
         Err(ParseStreamError {
@@ -44,13 +131,87 @@ impl Parse for Arg {
 #[derive(Clone)]
 pub struct Signature {
     pub args: Punctuated <Arg, ',', true>,
+
+    ///
+    /// The span of `...` after the parameters of a variadic function: `(format: *char, ...)`
+    ///
+    pub variadic: Option <Span>,
+
     pub return_ty: Option <Type>
 }
 
+impl Signature {
+    ///
+    /// The types of all the parameters and the return type
+    ///
+    pub fn types(&self) -> impl Iterator <Item = &Type> {
+        self.args.0.iter().map(|arg| match arg {
+            Arg::Named(named) => &named.ty,
+            Arg::Unnamed(unnamed) => &unnamed.it
+        }).chain(self.return_ty.as_ref())
+    }
+
+    ///
+    /// The type of every single parameter, so `(x y: int, float x 2)` has four of them
+    ///
+    pub fn param_types(&self) -> Vec <&Type> {
+        self.args.0.iter().flat_map(|arg| match arg {
+            Arg::Named(named) => vec![&named.ty; named.names.len()],
+            Arg::Unnamed(unnamed) => vec![&unnamed.it; unnamed.times.count()]
+        }).collect()
+    }
+
+    pub fn types_mut(&mut self) -> impl Iterator <Item = &mut Type> {
+        self.args.0.iter_mut().map(|arg| match arg {
+            Arg::Named(named) => &mut named.ty,
+            Arg::Unnamed(unnamed) => &mut unnamed.it
+        }).chain(self.return_ty.as_mut())
+    }
+
+    ///
+    /// Parses the parenthesized parameters, which may end with `...`
+    ///
+    fn params(stream: &mut ParseStream) -> Result <(Punctuated <Arg, ',', true>, Option <Span>)> {
+        let mut clone = stream.clone();
+        clone.trim();
+
+        if !clone.code.starts_with('(') {
+            return stream.embraced('(', ')').map(|args| (args, None))
+        }
+
+        clone.offset_by(1);
+
+        let args = Punctuated::parse(&mut clone)?;
+
+        clone.trim();
+
+        let start = clone.cursor;
+        let variadic = clone.punct("...").ok().map(|_| Span {
+            start,
+            end: clone.cursor
+        });
+
+        clone.punct(")").map_err(|err| err.with_custom_expected(String::from(if variadic.is_some() {
+            "the closing delimiter - `)`"
+        } else {
+            "the closing delimiter - `)` or `...`"
+        })))?;
+
+        *stream = clone;
+
+        Ok((args, variadic))
+    }
+}
+
 impl Debug for Signature {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
         f.write_char('(')?;
         self.args.debug(f)?;
+
+        if self.variadic.is_some() {
+            f.write_str(if self.args.0.is_empty() { "..." } else { ", ..." })?
+        }
+
         f.write_char(')')?;
 
         if let Some(ty) = &self.return_ty {
@@ -64,7 +225,7 @@ impl Debug for Signature {
 
 impl Parse for Signature {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
-        let args = stream.embraced('(', ')')?;
+        let (args, variadic) = Self::params(stream)?;
 
         let return_ty = if stream.punct("->").is_ok() {
             Some(Type::parse(stream)?)
@@ -74,6 +235,7 @@ impl Parse for Signature {
 
         Ok(Self {
             args,
+            variadic,
             return_ty
         })
     }
@@ -0,0 +1,46 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::parse::ty::Type;
+use super::*;
+
+///
+/// The `channel` statement.
+///
+/// Lets the world `from` send events of type `message` to the world `to`.
+/// They are delivered at the frame boundary
+///
+/// `channel Game -> Ui: Damage`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct ChannelStmt {
+    pub attrs: Vec <Attribute>,
+    pub from: Ident,
+    pub to: Ident,
+    pub message: Type
+}
+
+impl Debug for ChannelStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_fmt(format_args!("channel {} -> {}: {:?}", self.from.name, self.to.name, self.message))
+    }
+}
+
+impl Parse for ChannelStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        stream.keyword("channel")?;
+        let from = Ident::parse(stream)?;
+        stream.punct("->")?;
+        let to = Ident::parse(stream)?;
+        stream.punct(":")?;
+        let message = Type::parse(stream)?;
+
+        Ok(Self {
+            attrs,
+            from,
+            to,
+            message
+        })
+    }
+}
@@ -0,0 +1,47 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use super::*;
+
+///
+/// The `const` statement.
+///
+/// Names a compile-time integer, which can be used as a count of repeated parameters
+///
+/// `const N = 16`
+///
+/// `^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct ConstStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
+    pub name: Ident,
+    pub value: usize
+}
+
+impl Debug for ConstStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        if self.is_pub {
+            f.write_str("pub ")?
+        }
+
+        f.write_fmt(format_args!("const {} = {}", self.name.name, self.value))
+    }
+}
+
+impl Parse for ConstStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
+        stream.keyword("const")?;
+        let name = Ident::parse(stream)?;
+        stream.punct("=")?;
+        let value = stream.number_usize()?;
+
+        Ok(Self {
+            attrs,
+            is_pub,
+            name,
+            value
+        })
+    }
+}
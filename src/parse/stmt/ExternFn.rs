@@ -1,32 +1,55 @@
 use super::*;
+use super::super::attribute::{list_word, string_value};
 use super::super::signature::Signature;
 use crate::span::Span;
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
 pub enum FFILanguage {
-    C
+    C,
+
+    ///
+    /// Rust through its `extern "C"` functions, which have the C ABI
+    ///
+    Rust
 }
 
 impl Debug for FFILanguage {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
         f.write_str(match self {
-            Self::C => "clang"
+            Self::C => "clang",
+            Self::Rust => "rust"
         })
     }
 }
 
+impl FFILanguage {
+    ///
+    /// The name of the language as it is written in prose: `C`, `Rust`
+    ///
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::C => "C",
+            Self::Rust => "Rust"
+        }
+    }
+}
+
 impl Parse for FFILanguage {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
         if stream.keyword("clang").is_ok() {
             return Ok(Self::C)
         }
 
+        if stream.keyword("rust").is_ok() {
+            return Ok(Self::Rust)
+        }
+
         Err(ParseStreamError {
             span: Span::with_extra_column(stream.cursor, 1),
             parsing_depth: stream.depth,
-            expected: String::from("`clang`"),
+            expected: String::from("`clang` or `rust`"),
             help: vec![]
         })
     }
@@ -34,6 +57,8 @@ impl Parse for FFILanguage {
 
 #[derive(Clone)]
 pub struct ExternFnStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
     pub lang: FFILanguage,
     pub name: Ident,
     pub sig: Signature
@@ -41,6 +66,9 @@ pub struct ExternFnStmt {
 
 impl Debug for ExternFnStmt {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        if self.is_pub {
+            f.write_str("pub ")?
+        }
         f.write_str("extern ")?;
         self.lang.fmt(f)?;
         f.write_str(" fn ")?;
@@ -51,15 +79,45 @@ impl Debug for ExternFnStmt {
     }
 }
 
+impl ExternFnStmt {
+    ///
+    /// The name of the C function, given by `#[link_name = "..."]` or the name of the statement
+    ///
+    pub fn link_name(&self) -> &str {
+        string_value(&self.attrs, "link_name").unwrap_or(&self.name.name)
+    }
+
+    ///
+    /// The calling convention given by `#[callconv(...)]`, `None` for the default one of the C compiler
+    ///
+    pub fn calling_convention(&self) -> Option <&str> {
+        list_word(&self.attrs, "callconv")
+    }
+}
+
 impl Parse for ExternFnStmt {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
         stream.keyword("extern")?;
         let lang = FFILanguage::parse(stream)?;
         stream.keyword("fn")?;
         let name = Ident::parse(stream)?;
         let sig = Signature::parse(stream)?;
 
+        // `extern clang fn` with a body is an exported `FnStmt`, which reports the errors in the body
+        if stream.clone().punct("=").is_ok() || stream.clone().punct("{").is_ok() {
+            return Err(ParseStreamError {
+                span: Span::with_extra_column(stream.cursor, 1),
+                parsing_depth: stream.depth,
+                expected: String::from("a foreign function without a body"),
+                help: vec![]
+            })
+        }
+
         Ok(Self {
+            attrs,
+            is_pub,
             lang,
             name,
             sig
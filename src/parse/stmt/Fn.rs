@@ -1,20 +1,42 @@
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 use std::fmt::Write;
+use crate::parse::attribute::{list_word, string_value};
+use crate::parse::generics::GenericParams;
 use crate::parse::signature::Signature;
 use crate::parse::expr::Expr;
 use super::*;
 
 #[derive(Clone)]
 pub struct FnStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
+
+    ///
+    /// The language the function is exported to with the C ABI: `extern clang fn`, `extern rust fn`
+    ///
+    pub export: Option <FFILanguage>,
+
     pub name: Ident,
+    pub generics: Option <GenericParams>,
     pub sig: Signature,
     pub body: Expr
 }
 
 impl Debug for FnStmt {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        if self.is_pub {
+            f.write_str("pub ")?
+        }
+        if let Some(lang) = self.export {
+            f.write_str("extern ")?;
+            lang.fmt(f)?;
+            f.write_char(' ')?
+        }
         f.write_str("fn ")?;
         f.write_str(&self.name.name)?;
+        if let Some(generics) = &self.generics {
+            generics.fmt(f)?
+        }
         self.sig.fmt(f)?;
         f.write_char(' ')?;
         self.body.fmt(f)?;
@@ -23,15 +45,43 @@ impl Debug for FnStmt {
     }
 }
 
+impl FnStmt {
+    ///
+    /// The name an exported function is given in C by `#[link_name = "..."]`
+    ///
+    pub fn link_name(&self) -> Option <&str> {
+        string_value(&self.attrs, "link_name")
+    }
+
+    ///
+    /// The calling convention of an exported function given by `#[callconv(...)]`
+    ///
+    pub fn calling_convention(&self) -> Option <&str> {
+        list_word(&self.attrs, "callconv")
+    }
+}
+
 impl Parse for FnStmt {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
+        let export = if stream.keyword("extern").is_ok() {
+            Some(FFILanguage::parse(stream)?)
+        } else {
+            None
+        };
         stream.keyword("fn")?;
         let name = Ident::parse(stream)?;
+        let generics = GenericParams::optional(stream)?;
         let sig = Signature::parse(stream)?;
         let body = Expr::single_or_block(stream)?;
 
         Ok(Self {
+            attrs,
+            is_pub,
+            export,
             name,
+            generics,
             sig,
             body
         })
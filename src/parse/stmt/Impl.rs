@@ -0,0 +1,79 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::parse::path::Path;
+use crate::parse::ty::{NamedType, Type, TypeKind};
+use super::*;
+
+///
+/// The `impl` statement.
+///
+/// Implements the methods of a trait for a type, or adds functions of its own to a `ty` type when there is no trait.
+///   Inside the functions `Self` is the type
+///
+/// `impl Display for Vec2 { fn show(&self) -> int = 0 }`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+///
+/// `impl Vec2 { fn new(x y: int) -> Self = Self { x: x, y: y } }`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct ImplStmt {
+    pub attrs: Vec <Attribute>,
+    pub trait_name: Option <Path>,
+    pub ty: Type,
+    pub fns: Vec <FnStmt>
+}
+
+impl Debug for ImplStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_str("impl ")?;
+
+        if let Some(trait_name) = &self.trait_name {
+            trait_name.fmt(f)?;
+            f.write_str(" for ")?
+        }
+
+        self.ty.fmt(f)?;
+        f.write_str(" ")?;
+        f.debug_list().entries(&self.fns).finish()
+    }
+}
+
+impl Parse for ImplStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        stream.keyword("impl")?;
+        let ty = Type::parse(stream)?;
+
+        let (trait_name, ty) = if stream.keyword("for").is_ok() {
+            let TypeKind::Named(NamedType { name, generics: None }) = ty.kind else {
+                return Err(ParseStreamError {
+                    span: ty.span,
+                    parsing_depth: stream.depth,
+                    expected: String::from("the name of a trait"),
+                    help: vec![]
+                })
+            };
+
+            (Some(name), Type::parse(stream)?)
+        } else {
+            (None, ty)
+        };
+
+        stream.punct("{")?;
+
+        let mut fns = vec![];
+
+        while stream.punct("}").is_err() {
+            fns.push(FnStmt::parse(stream)?);
+        }
+
+        Ok(Self {
+            attrs,
+            trait_name,
+            ty,
+            fns
+        })
+    }
+}
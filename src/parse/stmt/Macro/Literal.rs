@@ -1,7 +1,8 @@
 use crate::parse::expr::Expr;
 use crate::parse::signature::{Arg, Signature};
-use crate::parse::ty::{Ptrs, Refs, Type};
+use crate::parse::ty::{Indirection, IndirectionKind, Type};
 use crate::span::Span;
+use crate::parse::x_times::Times;
 use super::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -85,7 +86,7 @@ impl Parse for LiteralMacroStmtBody {
                     &typed.ty
                 }
                 Arg::Unnamed(unnamed) => {
-                    if unnamed.times != 1 {
+                    if !matches!(unnamed.times, Times::Count(1)) {
                         return Err(ParseStreamError {
                             span: unnamed.it.span,
                             parsing_depth: stream.depth,
@@ -98,13 +99,19 @@ impl Parse for LiteralMacroStmtBody {
                 }
             };
 
-            let ty = if ty.refs == Refs::SINGLE && ty.ptrs == Ptrs::ZERO && ty.name.name == "str" {
+            let name = ty.base().name().map(|name| name.name.as_str());
+            let shared_ref = Indirection {
+                kind: IndirectionKind::Ref,
+                is_mutable: false
+            };
+
+            let ty = if ty.indirections() == [shared_ref] && name == Some("str") {
                 LiteralType::String
-            } else if ty.is_pure() && ty.name.name == "char" {
+            } else if ty.is_pure() && name == Some("char") {
                 LiteralType::Char
-            } else if ty.is_pure() && ty.name.name == "float" {
+            } else if ty.is_pure() && name == Some("float") {
                 LiteralType::Float
-            } else if ty.is_pure() && ty.name.name == "int" {
+            } else if ty.is_pure() && name == Some("int") {
                 LiteralType::Int
             } else {
                 return Err(ParseStreamError {
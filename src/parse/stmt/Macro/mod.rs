@@ -1,3 +1,5 @@
+use crate::parse::attribute::{attributes, Attribute};
+
 crate::define_parseable_enum! {
     NAME = MacroStmtBody
 
@@ -8,15 +10,18 @@ crate::define_parseable_enum! {
 
 #[derive(Debug, Clone)]
 pub struct MacroStmt {
+    pub attrs: Vec <Attribute>,
     pub body: MacroStmtBody
 }
 
 impl Parse for MacroStmt {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
         stream.keyword("macro")?;
         let body = MacroStmtBody::parse(stream)?;
 
         Ok(Self {
+            attrs,
             body
         })
     }
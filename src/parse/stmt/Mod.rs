@@ -0,0 +1,43 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use super::*;
+
+///
+/// The `mod` statement.
+///
+/// Declares the module stored in the file `name.oko` (or `name/mod.oko`) next to the current one
+///
+/// `pub mod math`
+///
+/// `^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct ModStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
+    pub name: Ident
+}
+
+impl Debug for ModStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        if self.is_pub {
+            f.write_str("pub ")?
+        }
+
+        f.write_fmt(format_args!("mod {}", self.name.name))
+    }
+}
+
+impl Parse for ModStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
+        stream.keyword("mod")?;
+        let name = Ident::parse(stream)?;
+
+        Ok(Self {
+            attrs,
+            is_pub,
+            name
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::parse::signature::Signature;
+use super::*;
+
+///
+/// A method a trait requires. The first parameter is the receiver, its type is written with `Self`
+///
+/// `trait Display { fn show(self: &Self) -> int }`
+///
+/// `----------------^^^^^^^^^^^^^^^^^^^^^^^^^^^^--`
+///
+#[derive(Clone)]
+pub struct TraitMethod {
+    pub attrs: Vec <Attribute>,
+    pub name: Ident,
+    pub sig: Signature
+}
+
+impl Debug for TraitMethod {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_str("fn ")?;
+        f.write_str(&self.name.name)?;
+        self.sig.fmt(f)
+    }
+}
+
+impl Parse for TraitMethod {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        stream.keyword("fn")?;
+        let name = Ident::parse(stream)?;
+        let sig = Signature::parse(stream)?;
+
+        Ok(Self {
+            attrs,
+            name,
+            sig
+        })
+    }
+}
+
+///
+/// The `trait` statement.
+///
+/// Declares methods the types implementing the trait provide
+///
+/// `trait Display { fn show(self: &Self) -> int }`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct TraitStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
+    pub name: Ident,
+    pub methods: Vec <TraitMethod>
+}
+
+impl Debug for TraitStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        if self.is_pub {
+            f.write_str("pub ")?
+        }
+
+        f.write_str("trait ")?;
+        f.write_str(&self.name.name)?;
+        f.write_str(" ")?;
+        f.debug_list().entries(&self.methods).finish()
+    }
+}
+
+impl Parse for TraitStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
+        stream.keyword("trait")?;
+        let name = Ident::parse(stream)?;
+        stream.punct("{")?;
+
+        let mut methods = vec![];
+
+        while stream.punct("}").is_err() {
+            methods.push(TraitMethod::parse(stream)?);
+        }
+
+        Ok(Self {
+            attrs,
+            is_pub,
+            name,
+            methods
+        })
+    }
+}
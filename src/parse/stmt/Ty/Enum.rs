@@ -1,4 +1,5 @@
 use super::*;
+use super::super::super::expr::IntExpr;
 use super::super::super::ty::Type;
 
 ///
@@ -10,20 +11,52 @@ use super::super::super::ty::Type;
 ///
 /// `-------------------^^^^^^^^`
 ///
+/// The attached type is written on the same line as the name of the variant
+///
 #[derive(Debug, Clone)]
 pub struct Field {
+    pub attrs: Vec <Attribute>,
     pub name: Ident,
-    pub attached_type: Option <Type>
+    pub attached_type: Option <Type>,
+
+    ///
+    /// The value of the tag of the variant
+    ///
+    /// `ty Key = Up = 38 | Down = 40`
+    ///
+    /// `--------------^^-----------^^`
+    ///
+    pub discriminant: Option <IntExpr>
 }
 
 impl Parse for Field {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
         let name = Ident::parse(stream)?;
-        let attached_type = Type::parse(stream).ok();
+
+        let mut clone = stream.clone();
+        clone.trim();
+        let is_same_line = clone.cursor.line == stream.cursor.line;
+
+        let attached_type = match Type::parse(&mut clone) {
+            Ok(ty) if is_same_line => {
+                *stream = clone;
+                Some(ty)
+            },
+            _ => None
+        };
+
+        let discriminant = if stream.punct("=").is_ok() {
+            Some(IntExpr::parse(stream)?)
+        } else {
+            None
+        };
 
         Ok(Self {
+            attrs,
             name,
-            attached_type
+            attached_type,
+            discriminant
         })
     }
 }
@@ -35,6 +68,14 @@ impl Parse for Field {
 ///
 /// `------------^^^^^^^^^^^^^^^`
 ///
+/// The variants may continue on the next lines, each one starting with `|`
+///
+/// `ty Option =`
+///
+/// `    | None`
+///
+/// `    | Some i32`
+///
 #[derive(Debug, Clone)]
 pub struct EnumTyStmtBody {
     pub fields: Punctuated <Field, '|'>
@@ -42,6 +83,7 @@ pub struct EnumTyStmtBody {
 
 impl Parse for EnumTyStmtBody {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
+        stream.punct("|").ok();
         let fields = Punctuated::parse(stream)?;
         stream.newline()?;
 
@@ -4,13 +4,23 @@ use super::super::super::typed_variable::TypedVariables;
 ///
 /// The struct variant of the `ty` statement body.
 ///
-/// `ty Vec2 = x: T + y: T`
+/// `ty Vec2 <T> = x: T + y: T`
 ///
-/// `----------^^^^^^^^^^^`
+/// `--------------^^^^^^^^^^^`
 ///
-/// `ty Vec2 = x y: T`
+/// `ty Vec2 <T> = x y: T`
 ///
-/// `----------^^^^^^`
+/// `--------------^^^^^^`
+///
+/// The block form lists the fields on separate lines, optionally separated by `+`
+///
+/// `ty Player = {`
+///
+/// `    health: u8`
+///
+/// `    position: Vec2`
+///
+/// `}`
 ///
 #[derive(Debug, Clone)]
 pub struct StructTyStmtBody {
@@ -19,7 +29,15 @@ pub struct StructTyStmtBody {
 
 impl Parse for StructTyStmtBody {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
-        let fields = Punctuated::parse(stream)?;
+        let fields = if stream.punct("{").is_ok() {
+            let fields = stream.one_or_more::<BlockField>().map_err(|err| err.with_custom_expected(String::from("at least one field")))?;
+            stream.punct("}")?;
+
+            Punctuated(fields.into_iter().map(|field| field.0).collect())
+        } else {
+            Punctuated::parse(stream)?
+        };
+
         stream.newline()?;
 
         Ok(Self {
@@ -27,3 +45,17 @@ impl Parse for StructTyStmtBody {
         })
     }
 }
+
+///
+/// A field of the block form, with the optional `+` after it
+///
+struct BlockField(TypedVariables);
+
+impl Parse for BlockField {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let field = TypedVariables::parse(stream)?;
+        stream.punct("+").ok();
+
+        Ok(Self(field))
+    }
+}
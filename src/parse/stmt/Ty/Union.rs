@@ -0,0 +1,26 @@
+use super::*;
+use super::super::super::typed_variable::TypedVariables;
+
+///
+/// The union variant of the `ty` statement body: the fields share the same memory,
+///   written the same way as the fields of a struct
+///
+/// `ty Bits = union i: int + f: float`
+///
+/// `----------^^^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Debug, Clone)]
+pub struct UnionTyStmtBody {
+    pub fields: Punctuated <TypedVariables, '+'>
+}
+
+impl Parse for UnionTyStmtBody {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        stream.keyword("union")?;
+        let body = StructTyStmtBody::parse(stream)?;
+
+        Ok(Self {
+            fields: body.fields
+        })
+    }
+}
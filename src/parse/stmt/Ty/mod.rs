@@ -1,3 +1,7 @@
+use crate::parse::attribute::{attributes, Attribute};
+use crate::parse::generics::GenericParams;
+use crate::parse::typed_variable::TypedVariables;
+
 crate::define_parseable_enum! {
     ///
     /// The `ty` statement body.
@@ -6,19 +10,37 @@ crate::define_parseable_enum! {
     ///
     /// `------------^^^^^^^^^^^^^^^`
     ///
-    /// `ty Vec2 = x: T + y: T`
+    /// `ty Vec2 <T> = x: T + y: T`
+    ///
+    /// `--------------^^^^^^^^^^^`
+    ///
+    /// `ty Bits = union i: int + f: float`
     ///
-    /// `----------^^^^^^^^^^^`
+    /// `----------^^^^^^^^^^^^^^^^^^^^^^^`
     ///
 
     NAME = TyStmtBody
 
     FIELDS:
 
+    Union
     Struct
     Enum
 }
 
+impl TyStmtBody {
+    ///
+    /// The fields of a struct or a union, `None` for an enum
+    ///
+    pub fn fields(&self) -> Option <&[TypedVariables]> {
+        match self {
+            Self::Union(body) => Some(&body.fields.0),
+            Self::Struct(body) => Some(&body.fields.0),
+            Self::Enum(_) => None
+        }
+    }
+}
+
 ///
 /// The `ty` statement.
 ///
@@ -26,21 +48,32 @@ crate::define_parseable_enum! {
 ///
 /// `^^^^^^^^^^^^^^^^^^^^^^^^^^^`
 ///
+/// The attributes before it describe the layout: `#[repr(C, packed, align(8))]`
+///
 #[derive(Debug, Clone)]
 pub struct TyStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
     pub name: Ident,
+    pub generics: Option <GenericParams>,
     pub body: TyStmtBody
 }
 
 impl Parse for TyStmt {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
         stream.keyword("ty")?;
         let name = Ident::parse(stream)?;
+        let generics = GenericParams::optional(stream)?;
         stream.punct("=")?;
         let body = TyStmtBody::parse(stream)?;
 
         Ok(Self {
+            attrs,
+            is_pub,
             name,
+            generics,
             body
         })
     }
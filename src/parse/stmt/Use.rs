@@ -0,0 +1,46 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::parse::path::Path;
+use super::*;
+
+///
+/// The `use` statement.
+///
+/// Brings an item or a module into the scope under its own name.
+///   With `pub` the item can also be reached through the current module
+///
+/// `use math::vec::length`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct UseStmt {
+    pub attrs: Vec <Attribute>,
+    pub is_pub: bool,
+    pub path: Path
+}
+
+impl Debug for UseStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        if self.is_pub {
+            f.write_str("pub ")?
+        }
+
+        f.write_str("use ")?;
+        self.path.fmt(f)
+    }
+}
+
+impl Parse for UseStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        let is_pub = stream.keyword("pub").is_ok();
+        stream.keyword("use")?;
+        let path = Path::parse(stream)?;
+
+        Ok(Self {
+            attrs,
+            is_pub,
+            path
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use super::*;
+
+///
+/// The `world` statement.
+///
+/// Everything declared inside belongs to the world and cannot be referenced from the outside
+///
+/// `world Game { ty Health = value: u8 }`
+///
+/// `^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct WorldStmt {
+    pub attrs: Vec <Attribute>,
+    pub name: Ident,
+    pub body: Vec <Stmt>
+}
+
+impl Debug for WorldStmt {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_str("world ")?;
+        f.write_str(&self.name.name)?;
+        f.write_str(" ")?;
+        f.debug_list().entries(&self.body).finish()
+    }
+}
+
+impl Parse for WorldStmt {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let attrs = attributes(stream)?;
+        stream.keyword("world")?;
+        let name = Ident::parse(stream)?;
+        stream.punct("{")?;
+
+        let mut body = vec![];
+
+        while stream.punct("}").is_err() {
+            body.push(Stmt::parse(stream)?);
+        }
+
+        Ok(Self {
+            attrs,
+            name,
+            body
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use crate::parse::attribute::{attributes, Attribute};
+
 crate::define_parseable_enum! {
     NAME = Stmt
 
@@ -7,4 +9,32 @@ crate::define_parseable_enum! {
     Ty
     ExternFn
     Macro
+    World
+    Channel
+    Const
+    Mod
+    Use
+    Trait
+    Impl
+}
+
+impl Stmt {
+    ///
+    /// The attributes written before the statement
+    ///
+    pub fn attrs(&self) -> &[Attribute] {
+        match self {
+            Self::Fn(fun) => &fun.attrs,
+            Self::Ty(ty) => &ty.attrs,
+            Self::ExternFn(fun) => &fun.attrs,
+            Self::Macro(mac) => &mac.attrs,
+            Self::World(world) => &world.attrs,
+            Self::Channel(channel) => &channel.attrs,
+            Self::Const(constant) => &constant.attrs,
+            Self::Mod(module) => &module.attrs,
+            Self::Use(using) => &using.attrs,
+            Self::Trait(tr) => &tr.attrs,
+            Self::Impl(imp) => &imp.attrs
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use super::span::{Ident, DoubleQuotedString};
 use crate::span::{CursorPosition, Span};
 use super::punctuated::Punctuated;
-use crate::error::Error;
+use crate::error::{Error, Severity};
 
 pub type Result <T> = core::result::Result <T, ParseStreamError>;
 
@@ -56,13 +56,15 @@ impl ParseStreamError {
         let spanned = self.span.apply(code);
 
         Error {
+            severity: Severity::Error,
+            code: None,
             span: self.span.eof_or(code),
             message: format!("unexpected token `{spanned}`"),
             spanned,
             clarifying: format!("expected {}", self.expected),
             help: self.help,
             filename,
-            code: code.to_string()
+            source: code.to_string()
         }
     }
 
@@ -74,9 +76,16 @@ impl ParseStreamError {
 
 impl <'a> ParseStream <'a> {
     pub const fn new(code: &'a str) -> Self {
+        Self::in_file(code, 0)
+    }
+
+    ///
+    /// Creates a stream whose spans point into the file with the index `file`
+    ///
+    pub const fn in_file(code: &'a str, file: usize) -> Self {
         Self {
             code,
-            cursor: CursorPosition::default(),
+            cursor: CursorPosition::in_file(file),
             depth: ParsingDepth(0)
         }
     }
@@ -85,7 +94,7 @@ impl <'a> ParseStream <'a> {
         self.trim();
 
         let non_numeric = self.code.find(|char: char| !char.is_numeric()).unwrap_or(self.code.len());
-        return if let Ok(ok) = self.code[..non_numeric].parse::<u8>() {
+        if let Ok(ok) = self.code[..non_numeric].parse::<u8>() {
             self.offset_by(ok.to_string().len());
             Ok(ok)
         } else {
@@ -96,16 +105,53 @@ impl <'a> ParseStream <'a> {
                 help: vec![]
             })
         }
+    }
+
+    ///
+    /// Parses a non-negative integer, telling negative and too large numbers apart from non-numbers
+    ///
+    pub fn number_usize(&mut self) -> Result <usize> {
+        self.trim();
+
+        let is_negative = self.code.starts_with('-') && self.code[1..].starts_with(|char: char| char.is_ascii_digit());
+        let skipped = if is_negative { 1 } else { 0 };
+        let non_numeric = self.code[skipped..].find(|char: char| !char.is_ascii_digit()).map_or(self.code.len(), |idx| idx + skipped);
+        let span = Span::with_extra_column(self.cursor, non_numeric.max(1));
+
+        if is_negative {
+            return Err(ParseStreamError {
+                span,
+                parsing_depth: self.depth,
+                expected: String::from("a non-negative number"),
+                help: vec![]
+            })
+        }
 
+        if let Ok(ok) = self.code[..non_numeric].parse::<usize>() {
+            self.offset_by(non_numeric);
+            Ok(ok)
+        } else if non_numeric != 0 {
+            Err(ParseStreamError {
+                span,
+                parsing_depth: self.depth,
+                expected: format!("a number that is at most {}", usize::MAX),
+                help: vec![]
+            })
+        } else {
+            Err(ParseStreamError {
+                span,
+                parsing_depth: self.depth,
+                expected: String::from("an `usize` number"),
+                help: vec![]
+            })
+        }
     }
 
+    ///
+    /// Expects nothing but whitespace up to the end of the line, or of the code on its last line
+    ///
     pub fn newline(&mut self) -> Result <()> {
-        let newline = self.code.find(|char: char| char == LINE_SEPARATOR).ok_or_else(|| ParseStreamError {
-            span: Span::with_extra_column(self.cursor, self.code.len()),
-            parsing_depth: self.depth,
-            expected: String::from("newline"),
-            help: vec![]
-        })?;
+        let newline = self.code.find(LINE_SEPARATOR).unwrap_or(self.code.len());
 
         if let Some(wrong) = self.code[..newline].find(|char: char| !char.is_whitespace()) {
             Err(ParseStreamError {
@@ -149,11 +195,10 @@ impl <'a> ParseStream <'a> {
     }
 
     pub fn embraced <T: Parse, const P: char, const IS_ZERO_ALLOWED: bool> (&mut self, open_delim: char, close_delim: char) -> Result <Punctuated <T, P, IS_ZERO_ALLOWED>> {
-        self.trim();
-
-        if self.code.starts_with(open_delim) {
-            let mut clone = self.clone();
+        let mut clone = self.clone();
+        clone.trim();
 
+        if clone.code.starts_with(open_delim) {
             clone.offset_by(open_delim.len_utf8());
 
             let punctuated = Punctuated::parse(&mut clone)?;
@@ -176,7 +221,7 @@ impl <'a> ParseStream <'a> {
         }
 
         Err(ParseStreamError {
-            span: Span::with_extra_column(self.cursor, 1),
+            span: Span::with_extra_column(clone.cursor, 1),
             parsing_depth: self.depth,
             expected: format!("`{open_delim} ... {close_delim}` block"),
             help: vec![]
@@ -254,9 +299,11 @@ impl <'a> ParseStream <'a> {
         puncts! {
             self,
 
+            "::"
             ":"
             ";"
 
+            "..."
             "."
             ","
 
@@ -287,6 +334,8 @@ impl <'a> ParseStream <'a> {
             "|"
 
             "!"
+
+            "#"
         }
 
         Err(ParseStreamError {
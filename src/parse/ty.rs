@@ -1,122 +1,324 @@
+use super::path::Path;
 use super::span::Ident;
-use super::stream::{Parse, ParseStream, Result, ParseStreamError};
+use super::punctuated::Punctuated;
+use super::signature::Signature;
+use super::stream::{Parse, ParseStream, Result};
 use crate::span::Span;
 use core::fmt::{Debug, Formatter, Result as FmtResult, Write};
 
+///
+/// The name of the type a trait or an `impl` is for, usable inside of them
+///
+pub const SELF: &str = "Self";
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum IndirectionKind {
+    ///
+    /// `&T`
+    ///
+    Ref,
+
+    ///
+    /// `*T`
+    ///
+    Ptr
+}
+
+///
+/// A single layer of indirection
+///
+/// `&mut *int`
+///
+/// `^^^^-----`
+///
 #[derive(Copy, Clone, Eq, PartialEq)]
-pub struct Muts <const SYMBOL: char> {
-    pub len: u8,
-    pub muts: u8
+pub struct Indirection {
+    pub kind: IndirectionKind,
+    pub is_mutable: bool
+}
+
+impl Debug for Indirection {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_char(match self.kind {
+            IndirectionKind::Ref => '&',
+            IndirectionKind::Ptr => '*'
+        })?;
+
+        if self.is_mutable {
+            f.write_str("mut ")?
+        }
+
+        Ok(())
+    }
+}
+
+impl Parse for Indirection {
+    fn parse(stream: &mut ParseStream) -> Result <Self> {
+        let kind = if stream.punct("&").is_ok() {
+            IndirectionKind::Ref
+        } else {
+            stream.punct("*").map_err(|err| err.with_custom_expected(String::from("`&` or `*`")))?;
+            IndirectionKind::Ptr
+        };
+
+        let is_mutable = stream.keyword("mut").is_ok();
+
+        Ok(Self {
+            kind,
+            is_mutable
+        })
+    }
 }
 
-impl <const SYMBOL: char> Muts <SYMBOL> {
-    pub const ZERO: Muts <SYMBOL> = Muts {
-        len: 0,
-        muts: 0
-    };
+///
+/// A type named by an identifier, possibly with generic arguments
+///
+/// `Vec2 <float>`
+///
+/// `^^^^^^^^^^^^`
+///
+#[derive(Clone)]
+pub struct NamedType {
+    pub name: Path,
 
-    pub const SINGLE: Muts <SYMBOL> = Muts {
-        len: 1,
-        muts: 0
-    };
+    ///
+    /// `Vec2 <float>`
+    ///
+    /// `-----^^^^^^^`
+    ///
+    pub generics: Option <Punctuated <Type, ','>>
 }
 
-impl <const SYMBOL: char> Debug for Muts <SYMBOL> {
+impl Debug for NamedType {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
-        for i in 0..self.len {
-            f.write_char(SYMBOL)?;
+        self.name.fmt(f)?;
+
+        if let Some(generics) = &self.generics {
+            f.write_char('<')?;
+            generics.debug(f)?;
+            f.write_char('>')?
+        }
 
-            let is_mutable = ((self.muts >> i) & 1) != 0;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub enum TypeKind {
+    ///
+    /// `Vec2 <float>`
+    ///
+    Named(NamedType),
 
-            if is_mutable {
-                f.write_str("mut")?;
+    ///
+    /// A fixed-size array
+    ///
+    /// `[int; 4]`
+    ///
+    Array(Box <Type>, usize),
 
-                if i + 1 != self.len {
-                    f.write_char(' ')?
+    ///
+    /// A dynamically sized view into a sequence, used behind a reference or a pointer
+    ///
+    /// `&[int]`
+    ///
+    /// `-^^^^^`
+    ///
+    Slice(Box <Type>),
+
+    ///
+    /// `(int, float)`
+    ///
+    /// `()` is the empty tuple
+    ///
+    Tuple(Punctuated <Type, ',', true>),
+
+    ///
+    /// A function or a lambda
+    ///
+    /// `fn(int) -> int`
+    ///
+    Fn(Box <Signature>),
+
+    ///
+    /// A reference or a pointer to another type. The layers nest freely: `&*mut &int`
+    ///
+    Indirect(Indirection, Box <Type>)
+}
+
+impl Debug for TypeKind {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Named(named) => named.fmt(f),
+            Self::Array(elem, len) => f.write_fmt(format_args!("[{elem:?}; {len}]")),
+            Self::Slice(elem) => f.write_fmt(format_args!("[{elem:?}]")),
+            Self::Tuple(elems) => {
+                f.write_char('(')?;
+                elems.debug(f)?;
+
+                if elems.0.len() == 1 {
+                    f.write_char(',')?
                 }
+
+                f.write_char(')')
+            },
+            Self::Fn(sig) => {
+                f.write_str("fn")?;
+                sig.fmt(f)
+            },
+            Self::Indirect(indirection, inner) => {
+                indirection.fmt(f)?;
+                inner.fmt(f)
             }
         }
-
-        Ok(())
     }
 }
 
-impl <const SYMBOL: char> Parse for Muts <SYMBOL> {
+impl Parse for TypeKind {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
-        let mut len = 0;
-        let mut muts = 0b0000_0000;
-
-        let mut buf = [0; 4];
-        let symbol = SYMBOL.encode_utf8(&mut buf);
-
-        while stream.punct(symbol).is_ok() {
-            if len >= u8::BITS as u8 {
-                return Err(ParseStreamError {
-                    span: Span::with_shrunk_column(stream.cursor, 1),
-                    parsing_depth: stream.depth,
-                    expected: format!("not an extra `{SYMBOL}` token"),
-                    help: vec![]
-                })
-            }
+        if stream.punct("[").is_ok() {
+            let elem = Box::new(Type::parse(stream)?);
 
-            if stream.keyword("mut").is_ok() {
-                muts |= 1 << len;
+            if stream.punct("]").is_ok() {
+                return Ok(Self::Slice(elem))
             }
 
-            len += 1
+            stream.punct(";")?;
+            let len = stream.number_usize()?;
+            stream.punct("]")?;
+
+            return Ok(Self::Array(elem, len))
         }
 
-        Ok(Self {
-            len,
-            muts
-        })
-    }
-}
+        if let Ok(elems) = stream.embraced('(', ')') {
+            return Ok(Self::Tuple(elems))
+        }
 
-pub type Ptrs = Muts <'*'>;
+        if stream.keyword("fn").is_ok() {
+            return Ok(Self::Fn(Box::new(Signature::parse(stream)?)))
+        }
 
-pub type Refs = Muts <'&'>;
+        let name = Path::parse(stream)?;
+        let generics = stream.embraced('<', '>').ok();
+
+        Ok(Self::Named(NamedType {
+            name,
+            generics
+        }))
+    }
+}
 
 #[derive(Clone)]
 pub struct Type {
-    pub refs: Refs,
-    pub ptrs: Ptrs,
-    pub name: Ident,
+    pub kind: TypeKind,
     pub span: Span
 }
 
 impl Type {
+    ///
+    /// Whether the type is used by value, not through a reference or a pointer
+    ///
     pub fn is_pure(&self) -> bool {
-        self.refs == Refs::ZERO && self.ptrs == Ptrs::ZERO
+        !matches!(self.kind, TypeKind::Indirect(..))
+    }
+
+    ///
+    /// The name of the type if it is a named one
+    ///
+    pub fn name(&self) -> Option <&Ident> {
+        match &self.kind {
+            TypeKind::Named(named) => Some(named.name.last()),
+            _ => None
+        }
+    }
+
+    ///
+    /// The layers of indirection of the type from the outermost one
+    ///
+    /// `&mut *int` gives `&mut` and `*`
+    ///
+    pub fn indirections(&self) -> Vec <Indirection> {
+        let mut indirections = vec![];
+        let mut ty = self;
+
+        while let TypeKind::Indirect(indirection, inner) = &ty.kind {
+            indirections.push(*indirection);
+            ty = inner
+        }
+
+        indirections
+    }
+
+    ///
+    /// The type behind all the layers of indirection
+    ///
+    /// `&mut *int` gives `int`
+    ///
+    pub fn base(&self) -> &Type {
+        match &self.kind {
+            TypeKind::Indirect(_, inner) => inner.base(),
+            _ => self
+        }
+    }
+
+    ///
+    /// The types this one is directly made of: generic arguments, array and slice elements, tuple fields,
+    ///   parameters and the return type of a function, the type behind a reference or a pointer
+    ///
+    pub fn inner(&self) -> Vec <&Type> {
+        match &self.kind {
+            TypeKind::Named(named) => named.generics.iter().flat_map(|generics| generics.0.iter()).collect(),
+            TypeKind::Array(elem, _) | TypeKind::Slice(elem) | TypeKind::Indirect(_, elem) => vec![elem],
+            TypeKind::Tuple(elems) => elems.0.iter().collect(),
+            TypeKind::Fn(sig) => sig.types().collect()
+        }
+    }
+
+    pub fn inner_mut(&mut self) -> Vec <&mut Type> {
+        match &mut self.kind {
+            TypeKind::Named(named) => named.generics.iter_mut().flat_map(|generics| generics.0.iter_mut()).collect(),
+            TypeKind::Array(elem, _) | TypeKind::Slice(elem) | TypeKind::Indirect(_, elem) => vec![elem],
+            TypeKind::Tuple(elems) => elems.0.iter_mut().collect(),
+            TypeKind::Fn(sig) => sig.types_mut().collect()
+        }
     }
 }
 
 impl Debug for Type {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
-        self.refs.fmt(f)?;
-        self.ptrs.fmt(f)?;
-        f.write_str(&self.name.name)?;
-
-        Ok(())
+        self.kind.fmt(f)
     }
 }
 
 impl Parse for Type {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
-        let start = stream.cursor;
+        let start = {
+            let mut clone = stream.clone();
+            clone.trim();
+            clone.cursor
+        };
 
-        let refs = Refs::parse(stream)?;
-        let ptrs = Ptrs::parse(stream)?;
-        let name = Ident::parse(stream)?;
+        if let Ok(indirection) = Indirection::parse(stream) {
+            let inner = Type::parse(stream)?;
+            let span = Span {
+                start,
+                end: inner.span.end
+            };
+
+            return Ok(Self {
+                kind: TypeKind::Indirect(indirection, Box::new(inner)),
+                span
+            })
+        }
+
+        let kind = TypeKind::parse(stream)?;
         let span = Span {
             start,
             end: stream.cursor
         };
 
         Ok(Self {
-            refs,
-            ptrs,
-            name,
+            kind,
             span
         })
     }
@@ -1,3 +1,4 @@
+use super::attribute::{attributes, Attribute};
 use super::span::Ident;
 use super::ty::Type;
 use super::stream::{Parse, ParseStream, Result};
@@ -18,8 +19,11 @@ use crate::span::Span;
 ///
 /// `^^^^^^`
 ///
+/// Attributes before the names apply to every one of them: `#[deprecated] x y: T`
+///
 #[derive(Clone)]
 pub struct TypedVariables {
+    pub attrs: Vec <Attribute>,
     pub names: Vec <Ident>,
     pub ty: Type
 }
@@ -52,12 +56,14 @@ impl Parse for TypedVariables {
     fn parse(stream: &mut ParseStream) -> Result <Self> {
         let mut clone = stream.clone();
 
+        let attrs = attributes(&mut clone)?;
         let names = clone.one_or_more()?;
         clone.punct(":")?;
         let ty = Type::parse(&mut clone)?;
         *stream = clone;
 
         Ok(Self {
+            attrs,
             names,
             ty
         })
@@ -1,19 +1,68 @@
+use super::span::Ident;
 use super::stream::{Parse, ParseStream, Result};
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 use crate::parse::stream::ParseStreamError;
 use crate::span::Span;
 
+///
+/// How many times something is repeated
+///
+/// `int x 300`, `int x N`
+///
+/// `------^^^`, `------^`
+///
+///
+/// The largest count of repetitions, `int x 65535`. The repeated parameters are expanded one by one,
+///   so an unbounded count would exhaust the memory
+///
+pub const MAX_COUNT: usize = 65535;
+
+#[derive(Clone)]
+pub enum Times {
+    Count(usize),
+
+    ///
+    /// A `const` declared elsewhere, replaced with its value after the checks
+    ///
+    Const(Ident)
+}
+
+impl Times {
+    ///
+    /// The number of repetitions
+    ///
+    /// # Panics
+    ///
+    /// If the count is still a named constant, that is, [`crate::check::consts::resolve`] has not been called
+    ///
+    pub fn count(&self) -> usize {
+        match self {
+            Self::Count(count) => *count,
+            Self::Const(name) => panic!("the constant `{}` is not resolved", name.name)
+        }
+    }
+}
+
+impl Debug for Times {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Count(count) => count.fmt(f),
+            Self::Const(name) => f.write_str(&name.name)
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct XTimes <T: Parse, const IS_NO_X_ALLOWED: bool = false> {
     pub it: T,
-    pub times: u8
+    pub times: Times
 }
 
 impl <T: Parse + Debug, const IS_NO_X_ALLOWED: bool> Debug for XTimes <T, IS_NO_X_ALLOWED> {
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
         self.it.fmt(f)?;
 
-        if self.times != 1 {
+        if !matches!(self.times, Times::Count(1)) {
             f.write_str(" x ")?;
             self.times.fmt(f)?;
         }
@@ -26,10 +75,28 @@ impl <T: Parse, const IS_NO_X_ALLOWED: bool> Parse for XTimes <T, IS_NO_X_ALLOWE
     fn parse(stream: &mut ParseStream) -> Result <Self> {
         let it = T::parse(stream)?;
         let times = match stream.keyword("x") {
-            Ok(_) => {
-                let start = stream.cursor;
+            Ok(_) => if let Ok(name) = Ident::parse(stream) {
+                Times::Const(name)
+            } else {
+                let (start, digits) = {
+                    let mut clone = stream.clone();
+                    clone.trim();
+                    (clone.cursor, clone.code.find(|char: char| !char.is_ascii_digit()).unwrap_or(clone.code.len()))
+                };
+
+                let too_large = ParseStreamError {
+                    span: Span::with_extra_column(start, digits),
+                    parsing_depth: stream.depth,
+                    expected: format!("a count of at most {MAX_COUNT}"),
+                    help: vec![String::from("the count is too large, pass an array or a pointer instead")]
+                };
 
-                let result = stream.number_u8()?;
+                let result = match stream.number_usize() {
+                    Ok(count) if count > MAX_COUNT => return Err(too_large),
+                    // Only a number overflowing `usize` fails after a digit
+                    Err(_) if digits != 0 => return Err(too_large),
+                    result => result?
+                };
 
                 if result == 0 {
                     return Err(ParseStreamError {
@@ -38,15 +105,15 @@ impl <T: Parse, const IS_NO_X_ALLOWED: bool> Parse for XTimes <T, IS_NO_X_ALLOWE
                             end: stream.cursor
                         },
                         parsing_depth: stream.depth,
-                        expected: String::from("non-zero amount"),
-                        help: vec![]
+                        expected: String::from("a count of at least 1"),
+                        help: vec![String::from("remove the parameter instead of repeating it zero times")]
                     })
                 }
 
-                result
+                Times::Count(result)
             },
             Err(err) => if IS_NO_X_ALLOWED {
-                1
+                Times::Count(1)
             } else {
                 return Err(err)
             }
@@ -1,4 +1,5 @@
 use core::fmt::{Debug, Result, Formatter};
+use core::ops::Range;
 
 ///
 /// The position of an item in file
@@ -13,7 +14,12 @@ pub struct CursorPosition {
     ///
     /// Starts from 1
     ///
-    pub column: usize
+    pub column: usize,
+
+    ///
+    /// The index of the file among the loaded ones, see [`crate::driver::Sources`]
+    ///
+    pub file: usize
 }
 
 impl Debug for CursorPosition {
@@ -25,27 +31,52 @@ impl Debug for CursorPosition {
 impl CursorPosition {
     pub const EOF: CursorPosition = CursorPosition {
         line: 0,
-        column: 0
+        column: 0,
+        file: 0
     };
 
     pub const fn default() -> Self {
+        Self::in_file(0)
+    }
+
+    pub const fn in_file(file: usize) -> Self {
         Self {
             line: 1,
-            column: 1
+            column: 1,
+            file
         }
     }
 
+    ///
+    /// The position of the byte at `offset` in `code`
+    ///
+    pub fn at_offset(code: &str, offset: usize, file: usize) -> Self {
+        code[..offset].chars().fold(Self::in_file(file), |position, char| if char == '\n' {
+            Self {
+                line: position.line + 1,
+                column: 1,
+                ..position
+            }
+        } else {
+            position.extend_column_by(1)
+        })
+    }
+
+    pub const fn is_eof(self) -> bool {
+        self.line == 0
+    }
+
     pub const fn extend_column_by(self, len: usize) -> Self {
         Self {
-            line: self.line,
-            column: self.column + len
+            column: self.column + len,
+            ..self
         }
     }
 
     pub const fn shrink_column_by(self, len: usize) -> Self {
         Self {
-            line: self.line,
-            column: self.column - len
+            column: self.column - len,
+            ..self
         }
     }
 }
@@ -90,18 +121,30 @@ impl Span {
         }
     }
 
+    ///
+    /// The span of the bytes in `range` of `code`
+    ///
+    pub fn of_range(code: &str, range: Range <usize>, file: usize) -> Self {
+        Self {
+            start: CursorPosition::at_offset(code, range.start, file),
+            end: CursorPosition::at_offset(code, range.end, file)
+        }
+    }
+
     pub fn eof_or(self, code: &str) -> Self {
-        if self.start == CursorPosition::EOF {
+        if self.start.is_eof() {
             let (idx, last_line) = code.split('\n').enumerate().last().unwrap_or_default();
 
             return Self {
                 start: CursorPosition {
                     line: idx + 1,
-                    column: last_line.len() + 1
+                    column: last_line.len() + 1,
+                    file: self.start.file
                 },
                 end: CursorPosition {
                     line: idx + 1,
-                    column: last_line.len() + 2
+                    column: last_line.len() + 2,
+                    file: self.start.file
                 }
             }
         }
@@ -110,7 +153,7 @@ impl Span {
     }
 
     pub fn apply(self, code: &str) -> String {
-        if self.start == CursorPosition::EOF {
+        if self.start.is_eof() {
             return String::from("<EOF>")
         }
 
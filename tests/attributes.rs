@@ -0,0 +1,76 @@
+mod common;
+
+use oko::error::Severity;
+use oko::parse::stmt::{Stmt, TyStmtBody};
+use oko::parse::signature::Arg;
+
+///
+/// The warnings which are not lints
+///
+fn warnings(code: &str) -> Vec <String> {
+    common::diagnostics(&format!("{code}\n\nfn main() -> int = 0\n"))
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Warning && diagnostic.code.is_none())
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn attributes_are_carried_in_the_ast() {
+    let stmts = oko::parse::parse_code("#[inline]\nfn f(#[unused] x: int) -> int = x\n\nty A = {\n    #[deprecated = \"use y\"]\n    x: int\n}\n", "test.oko").0.unwrap();
+
+    let Stmt::Fn(fun) = &stmts[0] else { panic!("not a function") };
+    assert_eq!(fun.attrs[0].meta.name().unwrap().name, "inline");
+
+    let Arg::Named(param) = &fun.sig.args.0[0] else { panic!("not a named parameter") };
+    assert_eq!(param.attrs[0].meta.name().unwrap().name, "unused");
+
+    let Stmt::Ty(ty) = &stmts[1] else { panic!("not a type") };
+    let TyStmtBody::Struct(body) = &ty.body else { panic!("not a struct") };
+    assert_eq!(oko::parse::attribute::string_value(&body.fields.0[0].attrs, "deprecated"), Some("use y"));
+}
+
+#[test]
+fn unknown_attributes_are_ignored_with_a_warning() {
+    assert_eq!(
+        warnings("#[frobnicate]\nfn f(#[also] x: int) -> int = x\n\nty A = {\n    #[what(1)]\n    y: int\n}"),
+        ["unknown attribute `frobnicate`", "unknown attribute `also`", "unknown attribute `what`"]
+    );
+    assert_eq!(error("#[frobnicate]\nfn f() -> int = 0"), None);
+}
+
+#[test]
+fn known_attributes_are_written_in_their_forms() {
+    for (code, message) in [
+        ("#[inline = \"x\"]\nfn f() -> int = 0", "malformed attribute `inline`"),
+        ("#[link_name]\nextern clang fn puts(s: *u8) -> int", "malformed attribute `link_name`"),
+        ("#[derive(Debug = \"x\")]\nty A = x: int", "malformed attribute `derive`"),
+        ("#[deprecated(soon)]\nfn f() -> int = 0", "malformed attribute `deprecated`")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
+
+#[test]
+fn known_attributes_are_written_on_their_targets() {
+    for (code, message) in [
+        ("#[inline]\nty A = x: int", "attribute `inline` cannot be used on a type"),
+        ("#[repr(C)]\nfn f() -> int = 0", "attribute `repr` cannot be used on a function"),
+        ("fn f(#[inline] x: int) -> int = x", "attribute `inline` cannot be used on a parameter"),
+        ("#[link_name = \"g\"]\nfn f() -> int = 0", "attribute `link_name` cannot be used on a function")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+
+    assert_eq!(error("#[deprecated = \"old\"]\nfn f() -> int = 0\n\n#[inline]\nfn g() -> int = 0"), None);
+}
+
+#[test]
+fn attributes_start_with_a_name() {
+    assert_eq!(error("#[1]\nfn f() -> int = 0").as_deref(), Some("an attribute must start with its name"));
+    assert_eq!(common::diagnostics("#[]\nfn main() -> int = 0\n").remove(0).clarifying, "expected the name of an attribute");
+}
@@ -0,0 +1,57 @@
+mod common;
+
+fn bindings(header: &str) -> String {
+    oko::bindgen::generate(header).unwrap().code
+}
+
+const STRING_H: &str = "
+typedef unsigned long size_t;
+
+extern void *memcpy (void *__restrict __dest, const void *__restrict __src, size_t __n);
+extern int strcmp (const char *__s1, const char *__s2);
+extern char *strcpy (char *__restrict __dest, const char *__restrict __src);
+extern size_t strlen (const char *__s);
+";
+
+#[test]
+fn pointers_keep_their_const_ness() {
+    let code = bindings(STRING_H);
+
+    assert!(code.contains("extern clang fn memcpy(dest: *mut void, src: *void, n: usize) -> *mut void\n"), "{code}");
+    assert!(code.contains("extern clang fn strcmp(s1: *char, s2: *char) -> int\n"), "{code}");
+    assert!(code.contains("extern clang fn strcpy(dest: *mut char, src: *char) -> *mut char\n"), "{code}");
+    assert!(code.contains("extern clang fn strlen(s: *char) -> usize\n"), "{code}");
+}
+
+#[test]
+fn extern_declarations_point_to_const() {
+    let c = common::compile(&format!("{}\nfn main() -> int = 0\n", bindings(STRING_H))).unwrap();
+
+    assert!(c.contains("extern void *memcpy(void *dest, const void *src, size_t n);\n"), "{c}");
+    assert!(c.contains("extern int strcmp(const char *s1, const char *s2);\n"), "{c}");
+    assert!(c.contains("extern char *strcpy(char *dest, const char *src);\n"), "{c}");
+    assert!(c.contains("extern size_t strlen(const char *s);\n"), "{c}");
+}
+
+///
+/// The declarations of a libc header agree with the ones the C compiler knows the builtin functions by
+///
+#[test]
+fn libc_declarations_match_the_builtin_ones() {
+    let c = common::compile(&format!("{}\nfn main() -> int = 0\n", bindings(include_str!("fixtures/string.h")))).unwrap();
+    let dir = common::write(&[("string.c", &c)]);
+
+    let output = common::cc(&dir, &["-Werror", "-c", "string.c", "-o", "string.o"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn void_can_only_be_pointed_to() {
+    assert_eq!(
+        common::compile("extern clang fn f(x: void)\n\nfn main() -> int = 0\n"),
+        Err(String::from("`void` can only be pointed to"))
+    );
+
+    let c = common::compile("extern clang fn f(x: **mut void)\n\nfn main() -> int = 0\n").unwrap();
+    assert!(c.contains("extern void f(void *const *x);\n"), "{c}");
+}
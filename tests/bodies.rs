@@ -0,0 +1,79 @@
+mod common;
+
+fn error(code: &str) -> Option <String> {
+    common::resolve(&format!("{code}\n\nfn main() -> int = 0\n")).err()
+}
+
+#[test]
+fn unknown_values_and_functions_are_reported() {
+    assert_eq!(error("fn f(x: int) -> int = y").as_deref(), Some("cannot find value `y` in this scope"));
+    assert_eq!(error("fn f() -> int = g(1)").as_deref(), Some("cannot find function `g` in this scope"));
+    assert_eq!(error("fn f(x: int) -> int = x(1)").as_deref(), Some("`x` of type `int` is not a function"));
+}
+
+#[test]
+fn calls_take_as_many_arguments_as_declared() {
+    assert_eq!(
+        error("fn f(x: int) -> int = x\n\nfn g() -> int = f(1, 2)").as_deref(),
+        Some("function `f` takes 1 argument(s), but 2 were given")
+    );
+    assert_eq!(
+        error("fn apply(f: fn(int) -> int) -> int = f()").as_deref(),
+        Some("function `f` takes 1 argument(s), but 0 were given")
+    );
+    assert_eq!(
+        error("extern clang fn printf(format: *u8, ...) -> int\n\nfn f() -> int = printf()").as_deref(),
+        Some("function `printf` takes at least 1 argument(s), but 0 were given")
+    );
+    assert!(error("extern clang fn printf(format: *u8, ...) -> int\n\nfn f() -> int = printf(0, 1, 2)").is_none());
+}
+
+///
+/// The line and the columns of the code the first error found by checking the bodies points at
+///
+fn span(code: &str) -> (usize, usize, usize) {
+    let (sources, mut stmts) = common::load(&[("main.oko", code)]).0.unwrap();
+    oko::check::consts::resolve(&mut stmts);
+
+    let stmts = oko::mono::monomorphize(&stmts)
+        .and_then(|stmts| oko::check::traits::resolve(&stmts))
+        .unwrap();
+
+    let span = sources.error(oko::check::bodies::check(&stmts).unwrap_err()).span;
+    (span.start.line, span.start.column, span.end.column)
+}
+
+#[test]
+fn the_errors_point_at_the_expression() {
+    assert_eq!(span("fn f(x: int) -> int = x\n\nfn main() -> int = f(1, 2)\n"), (3, 20, 21));
+    assert_eq!(span("fn main() -> int = y\n"), (1, 20, 21));
+    assert_eq!(span("fn len(p: *int) -> int = 0\n\nfn main() -> int = len(77)\n"), (3, 24, 26));
+}
+
+#[test]
+fn values_of_other_types_are_rejected() {
+    for (code, message) in [
+        ("fn f(a: &[int; 4]) -> &[int] = a", "expected `&[int]`, found `&[int; 4]`"),
+        ("ty Vec2 = x y: int\n\nfn len(v: &Vec2) -> int = v.x\n\nfn g() -> int = len(Vec2 { x: 1, y: 2 })", "expected `&Vec2`, found `Vec2`"),
+        ("fn len(p: *int) -> int = 0\n\nfn f() -> int = len(1)", "expected `*int`, found `int`"),
+        ("ty Vec2 = x y: int\n\nfn f(v: Vec2) -> Vec2 = Vec2 { x: v, y: 1 }", "expected `int`, found `Vec2`"),
+        ("fn apply(f: fn(int) -> int) -> int = f(1)\n\nfn g() -> int = apply(fn(x: i8) -> int = 0)", "expected `fn(int) -> int`, found `fn(x: i8) -> int`")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+
+    // a mutable reference is also a shared one
+    assert!(error("fn len(v: &int) -> int = 0\n\nfn f(v: &mut int) -> int = len(v)").is_none());
+}
+
+#[test]
+fn indices_are_checked() {
+    for (code, message) in [
+        ("fn f(x: int) -> int = x[0]", "cannot index into a value of type `int`"),
+        ("fn f(p: (int, int), i: int) -> int = p[i]", "a tuple can only be indexed by an integer literal"),
+        ("fn f(a: [int; 2]) -> int = a[2]", "index 2 is out of bounds for `[int; 2]`"),
+        ("ty Vec2 = x y: int\n\nfn f(a: [int; 2], v: Vec2) -> int = a[v]", "an index must be an integer, found `Vec2`")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
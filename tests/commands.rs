@@ -0,0 +1,93 @@
+use oko::ecs::{Schedule, World};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Health(u8);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Poisoned;
+
+#[test]
+fn commands_are_deferred_to_the_stage_boundary() {
+    let mut world = World::new();
+    let entity = world.spawn((Health(3),));
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage("update").add_stage("check");
+
+    schedule.add_system("update", "poison", move |world: &World| {
+        world.query::<&Health>().for_each(|entity, _| world.commands().insert(entity, Poisoned));
+        assert!(!world.has::<Poisoned>(entity));
+        assert_eq!(world.command_queue().len(), 1)
+    });
+
+    schedule.add_system("check", "poisoned", move |world: &World| {
+        assert!(world.has::<Poisoned>(entity));
+        assert!(world.command_queue().is_empty())
+    });
+
+    schedule.run_for(&mut world, 1);
+}
+
+#[test]
+fn entities_are_despawned_while_iterating_over_a_query() {
+    let mut world = World::new();
+
+    for value in 0..6 {
+        world.spawn((Health(value),));
+    }
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage("update");
+    schedule.add_system("update", "cull", |world: &World| world.query::<&Health>().for_each(|entity, health| if health.0 % 2 == 0 {
+        world.commands().despawn(entity)
+    }));
+
+    schedule.run_for(&mut world, 1);
+
+    let mut left = vec![];
+    world.query::<&Health>().for_each(|_, health| left.push(health.0));
+    left.sort();
+
+    assert_eq!(left, [1, 3, 5]);
+    assert_eq!(world.len(), 3);
+}
+
+#[test]
+fn spawned_entities_are_reserved_right_away() {
+    let mut world = World::new();
+    let commands = world.commands();
+
+    let first = commands.spawn((Health(1),));
+    let second = commands.spawn((Health(2), Poisoned));
+
+    assert_ne!(first, second);
+    assert!(!world.contains(first));
+
+    world.apply_commands();
+
+    assert_eq!(world.read::<Health, _>(first, |health| health.0), Some(1));
+    assert!(world.has::<Poisoned>(second));
+    assert!(!world.has::<Poisoned>(first));
+}
+
+#[test]
+fn commands_are_applied_in_order() {
+    let mut world = World::new();
+    let entity = world.spawn((Health(1),));
+
+    let commands = world.commands();
+    commands.insert(entity, Health(2));
+    commands.remove::<Health>(entity);
+    commands.insert(entity, Health(3));
+    world.apply_commands();
+
+    assert_eq!(world.read::<Health, _>(entity, |health| health.0), Some(3));
+
+    let commands = world.commands();
+    commands.despawn(entity);
+    commands.insert(entity, Poisoned);
+    world.apply_commands();
+
+    assert!(!world.contains(entity));
+    assert!(world.is_empty());
+}
@@ -0,0 +1,109 @@
+//!
+//! Helpers shared by the tests: each test crate uses only some of them
+//!
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use oko::check::lints::Levels;
+use oko::error::{Error, Severity};
+use oko::parse::stmt::Stmt;
+
+static DIRS: AtomicUsize = AtomicUsize::new(0);
+
+///
+/// Writes the files into a new temporary directory, returning it
+///
+pub fn write(files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("oko-test-{}-{}", std::process::id(), DIRS.fetch_add(1, Ordering::Relaxed)));
+
+    for (name, code) in files {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, code).unwrap()
+    }
+
+    dir
+}
+
+///
+/// Loads `main.oko` of the files, or the error of loading it
+///
+pub fn load(files: &[(&str, &str)]) -> oko::error::Result <(oko::driver::Sources, Vec <Stmt>)> {
+    let main = write(files).join("main.oko");
+    oko::driver::load(main.to_str().unwrap())
+}
+
+///
+/// Loads and checks `main.oko` of the files with the lint levels, returning every diagnostic
+///
+pub fn diagnostics_with(files: &[(&str, &str)], levels: &Levels) -> Vec <Error> {
+    match load(files).0 {
+        Ok((sources, stmts)) => oko::check::check(&stmts, &sources, levels),
+        Err(err) => vec![err]
+    }
+}
+
+pub fn diagnostics(code: &str) -> Vec <Error> {
+    diagnostics_with(&[("main.oko", code)], &Levels::default())
+}
+
+///
+/// The message of the first error found by loading and checking the code
+///
+pub fn error(code: &str) -> Option <String> {
+    diagnostics(code)
+        .into_iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+        .map(|diagnostic| diagnostic.message)
+}
+
+///
+/// Loads, checks, monomorphizes `main.oko` of the files, resolves its traits and checks its bodies, returning the message of the first error otherwise
+///
+pub fn resolve_files(files: &[(&str, &str)]) -> Result <Vec <Stmt>, String> {
+    let (sources, mut stmts) = load(files).0.map_err(|err| err.message)?;
+    let diagnostics = oko::check::check(&stmts, &sources, &Levels::default());
+
+    if let Some(err) = diagnostics.into_iter().find(|diagnostic| diagnostic.severity == Severity::Error) {
+        return Err(err.message)
+    }
+
+    oko::check::consts::resolve(&mut stmts);
+
+    oko::mono::monomorphize(&stmts)
+        .and_then(|stmts| oko::check::traits::resolve(&stmts))
+        .and_then(|stmts| oko::check::bodies::check(&stmts).map(|_| stmts))
+        .map_err(|err| err.message)
+}
+
+pub fn resolve(code: &str) -> Result <Vec <Stmt>, String> {
+    resolve_files(&[("main.oko", code)])
+}
+
+///
+/// Compiles `main.oko` of the files into C through its MIR as `oko build` does,
+///   returning the message of the first error otherwise
+///
+pub fn compile_files(files: &[(&str, &str)]) -> Result <String, String> {
+    let stmts = resolve_files(files)?;
+
+    let program = oko::mir::lower(&stmts).map_err(|err| err.message)?;
+    oko::mir::validate(&program).unwrap_or_else(|err| panic!("a checked program is lowered to invalid MIR: {}", err.message));
+
+    oko::codegen::c::generate(&stmts, &program).map_err(|err| err.message)
+}
+
+pub fn compile(code: &str) -> Result <String, String> {
+    compile_files(&[("main.oko", code)])
+}
+
+///
+/// Runs the C compiler given by `CC` in the directory. The tests calling it need one
+///
+pub fn cc(dir: &Path, args: &[&str]) -> Output {
+    let cc = std::env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    Command::new(&cc).args(args).current_dir(dir).output().unwrap_or_else(|err| panic!("cannot run the C compiler `{cc}`: {err}, set `CC` to one"))
+}
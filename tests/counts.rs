@@ -0,0 +1,47 @@
+mod common;
+
+use oko::parse::span::Ident;
+use oko::parse::punctuated::Punctuated;
+use oko::parse::stream::{Parse, ParseStream};
+use oko::parse::x_times::MAX_COUNT;
+
+fn clarifying(code: &str) -> String {
+    common::diagnostics(&format!("{code}\n\nfn main() -> int = 0\n")).remove(0).clarifying
+}
+
+#[test]
+fn counts_are_limited() {
+    assert_eq!(MAX_COUNT, 65535);
+
+    for count in ["65536", "4294967296", "99999999999999999999999"] {
+        assert_eq!(clarifying(&format!("fn f(int x {count}) -> int = 0")), "expected a count of at most 65535", "{count}")
+    }
+
+    assert_eq!(clarifying("fn f(int x 0) -> int = 0"), "expected a count of at least 1");
+    assert_eq!(clarifying("fn f(int x -1) -> int = 0"), "expected a non-negative number");
+}
+
+#[test]
+fn the_largest_count_is_expanded() {
+    let c = common::compile("fn f(int x 65535) -> int = 0\n\nfn main() -> int = 0\n").unwrap();
+    assert!(c.contains("int f(int arg0, int arg1, "), "{c}");
+    assert!(c.contains("int arg65534) {"), "{c}");
+}
+
+#[test]
+fn constant_counts_are_limited() {
+    assert_eq!(
+        common::error("const N = 70000\n\nfn f(int x N) -> int = 0\n\nfn main() -> int = 0\n").as_deref(),
+        Some("`N` is 70000, but a count must be at most 65535")
+    );
+    assert_eq!(
+        common::error("const N = 0\n\nfn f(int x N) -> int = 0\n\nfn main() -> int = 0\n").as_deref(),
+        Some("`N` is zero, but a count must be at least 1")
+    );
+}
+
+#[test]
+fn empty_lists_expect_at_least_one_item() {
+    let err = Punctuated::<Ident, ','>::parse(&mut ParseStream::new(")")).unwrap_err();
+    assert_eq!(err.expected, "at least one ident");
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use oko::error::{ColorChoice, Emitter, Format};
+use serde_json::Value as Json;
+
+///
+/// A parameter shadowing a function: a warning followed by a note
+///
+const SHADOWING: &str = "pub fn f(x: int) -> int = 0\n\npub fn g(f: int) -> int = f\n\nfn main() -> int = 0\n";
+
+fn json(code: &str) -> Vec <Json> {
+    Emitter::new(Format::Json, ColorChoice::Never)
+        .formatted(&common::diagnostics(code))
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn json_diagnostics_are_objects_per_line() {
+    let diagnostics = json(SHADOWING);
+    assert_eq!(diagnostics.len(), 1);
+
+    let diagnostic = &diagnostics[0];
+
+    assert_eq!(diagnostic["severity"], "warning");
+    assert_eq!(diagnostic["code"], "shadowing");
+    assert_eq!(diagnostic["message"], "parameter `f` shadows the function `f`");
+    assert_eq!(diagnostic["help"], serde_json::json!(["rename the parameter", "allow it with `#[allow(shadowing)]`"]));
+}
+
+#[test]
+fn notes_are_secondary_spans() {
+    let spans = json(SHADOWING)[0]["spans"].as_array().unwrap().clone();
+    assert_eq!(spans.len(), 2);
+
+    let primary = &spans[0];
+
+    assert!(primary["file"].as_str().unwrap().ends_with("main.oko"));
+    assert_eq!(primary["is_primary"], true);
+    assert_eq!(primary["label"], "the function cannot be called here");
+    assert_eq!((&primary["line_start"], &primary["column_start"]), (&Json::from(3), &Json::from(10)));
+    assert_eq!((&primary["byte_start"], &primary["byte_end"]), (&Json::from(38), &Json::from(39)));
+    assert_eq!(&SHADOWING[38..39], "f");
+
+    let secondary = &spans[1];
+
+    assert_eq!(secondary["is_primary"], false);
+    assert_eq!(secondary["label"], "the shadowed function `f` is declared here");
+    assert_eq!((&secondary["line_start"], &secondary["column_start"]), (&Json::from(1), &Json::from(8)));
+    assert_eq!(secondary["byte_start"], 7);
+}
+
+#[test]
+fn errors_have_no_code() {
+    let diagnostic = &json("ty A = x: int + x: int\n\nfn main() -> int = 0\n")[0];
+
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["code"], Json::Null);
+    assert_eq!(diagnostic["message"], "field `x` is declared twice in `A`");
+}
+
+#[test]
+fn human_diagnostics_are_colored_on_demand() {
+    let diagnostics = common::diagnostics(SHADOWING);
+
+    let plain = Emitter::new(Format::Human, ColorChoice::Never).formatted(&diagnostics);
+
+    assert!(plain.starts_with("warning[shadowing]: parameter `f` shadows the function `f`:\n"), "{plain}");
+    assert!(plain.contains("3 | pub fn g(f: int) -> int = f\n  |          ^^ the function cannot be called here\n"), "{plain}");
+    assert!(plain.contains("note: the shadowed function `f` is declared here:\n"), "{plain}");
+    assert!(!plain.contains('\x1b'));
+
+    let colored = Emitter::new(Format::Human, ColorChoice::Always).formatted(&diagnostics);
+    assert!(colored.contains('\x1b'));
+}
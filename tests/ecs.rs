@@ -0,0 +1,179 @@
+use std::sync::{Arc, Mutex};
+use oko::ecs::{Entity, Schedule, Shutdown, Startup, SystemStatus, World};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position(i32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Velocity(i32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Health(u8);
+
+struct Hit(Entity);
+
+fn positions(world: &World) -> Vec <i32> {
+    let mut positions = vec![];
+    world.query::<&Position>().for_each(|_, position| positions.push(position.0));
+    positions.sort();
+    positions
+}
+
+#[test]
+fn spawned_entities_are_queried_by_their_components() {
+    let mut world = World::new();
+
+    world.spawn((Position(1), Velocity(2)));
+    world.spawn((Position(5),));
+    world.spawn((Velocity(7), Health(3)));
+
+    assert_eq!(world.len(), 3);
+    assert_eq!(world.query::<&Position>().count(), 2);
+    assert_eq!(world.query::<(&Position, &Velocity)>().count(), 1);
+    assert_eq!(world.query::<&Velocity>().count(), 2);
+    assert_eq!(positions(&world), [1, 5]);
+}
+
+#[test]
+fn despawned_entities_are_not_reused_by_stale_handles() {
+    let mut world = World::new();
+
+    let first = world.spawn((Health(1),));
+    assert!(world.despawn(first));
+    assert!(!world.despawn(first));
+
+    let second = world.spawn((Health(2),));
+
+    assert_eq!(second.index, first.index);
+    assert_ne!(second.generation, first.generation);
+    assert!(!world.contains(first));
+    assert_eq!(world.read::<Health, _>(first, |health| health.0), None);
+    assert_eq!(world.read::<Health, _>(second, |health| health.0), Some(2));
+}
+
+#[test]
+fn despawning_keeps_the_other_entities_of_the_archetype() {
+    let mut world = World::new();
+
+    let entities = (0..4).map(|idx| world.spawn((Position(idx),))).collect::<Vec <_>>();
+    world.despawn(entities[1]);
+
+    assert_eq!(positions(&world), [0, 2, 3]);
+
+    for (idx, entity) in entities.iter().enumerate().filter(|(idx, _)| *idx != 1) {
+        assert_eq!(world.read::<Position, _>(*entity, |position| position.0), Some(idx as i32))
+    }
+}
+
+#[test]
+fn components_are_inserted_and_removed() {
+    let mut world = World::new();
+    let entity = world.spawn((Position(1),));
+
+    assert!(world.insert(entity, Velocity(3)));
+    assert!(world.has::<Velocity>(entity));
+    assert_eq!(world.query::<(&Position, &Velocity)>().count(), 1);
+
+    assert!(world.insert(entity, Velocity(4)));
+    assert_eq!(world.read::<Velocity, _>(entity, |velocity| velocity.0), Some(4));
+
+    assert_eq!(world.remove::<Velocity>(entity), Some(Velocity(4)));
+    assert_eq!(world.remove::<Velocity>(entity), None);
+    assert!(!world.has::<Velocity>(entity));
+    assert_eq!(world.read::<Position, _>(entity, |position| position.0), Some(1));
+}
+
+#[test]
+fn staged_systems_run_every_tick_in_order() {
+    let mut world = World::new();
+    let entity = world.spawn((Position(0), Velocity(2)));
+    let order = Arc::new(Mutex::new(vec![]));
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage("update").add_stage("render");
+
+    let log = order.clone();
+    schedule.add_system("update", "movement", move |world: &World| {
+        world.query::<(&mut Position, &Velocity)>().for_each(|_, (position, velocity)| position.0 += velocity.0);
+        log.lock().unwrap().push("movement")
+    });
+
+    let log = order.clone();
+    schedule.add_system("render", "draw", move |_: &World| log.lock().unwrap().push("draw"));
+
+    schedule.run_for(&mut world, 3);
+
+    assert_eq!(world.tick(), 3);
+    assert_eq!(world.read::<Position, _>(entity, |position| position.0), Some(6));
+    assert_eq!(*order.lock().unwrap(), ["movement", "draw", "movement", "draw", "movement", "draw"]);
+}
+
+#[test]
+fn finished_systems_are_no_longer_executed() {
+    let mut world = World::new();
+    let calls = Arc::new(Mutex::new(0));
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage("update");
+
+    let counter = calls.clone();
+    schedule.add_system("update", "twice", move |_: &World| {
+        let mut calls = counter.lock().unwrap();
+        *calls += 1;
+
+        if *calls == 2 { SystemStatus::Finished } else { SystemStatus::Continue }
+    });
+
+    schedule.run(&mut world);
+
+    assert_eq!(*calls.lock().unwrap(), 2);
+    assert_eq!(world.tick(), 2);
+    assert!(schedule.is_finished());
+}
+
+#[test]
+fn exit_stops_a_headless_run_early() {
+    let mut world = World::new();
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage("update");
+    schedule.add_system("update", "quit", |world: &World| if world.tick() == 1 {
+        world.exit()
+    });
+
+    schedule.run_for(&mut world, 10);
+
+    assert_eq!(world.tick(), 2);
+    assert!(world.is_exit_requested());
+}
+
+#[test]
+fn events_are_dispatched_to_callbacks_at_stage_boundaries() {
+    let mut world = World::new();
+    let target = world.spawn((Health(10),));
+    let lifecycle = Arc::new(Mutex::new(vec![]));
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage("update");
+    schedule.add_system("update", "attack", move |world: &World| world.send(Hit(target)));
+    schedule.on::<Hit>("damage", |world, hit| {
+        world.write::<Health, _>(hit.0, |health| health.0 -= 1);
+    });
+
+    let log = lifecycle.clone();
+    schedule.on::<Startup>("start", move |_, _| log.lock().unwrap().push("startup"));
+
+    let log = lifecycle.clone();
+    schedule.on::<Shutdown>("stop", move |_, _| log.lock().unwrap().push("shutdown"));
+
+    schedule.run_for(&mut world, 4);
+
+    assert_eq!(world.read::<Health, _>(target, |health| health.0), Some(6));
+    assert!(world.events().is_empty());
+    assert_eq!(*lifecycle.lock().unwrap(), ["startup"]);
+
+    schedule.shutdown(&mut world);
+    schedule.shutdown(&mut world);
+
+    assert_eq!(*lifecycle.lock().unwrap(), ["startup", "shutdown"]);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+const PHYSICS: &str = "
+#[repr(C)]
+ty Vec2 = x y: f32
+
+#[repr(C)]
+ty Body = position: Vec2 + parent: &Body
+
+#[link_name = \"physics_length\"]
+extern clang fn length(v: &Vec2) -> f32 = v.x
+
+extern clang fn push(body: &mut Body, by: *Vec2) -> f32 = by.x
+
+fn helper() -> int = 0
+";
+
+fn header(code: &str) -> Result <Option <String>, String> {
+    oko::codegen::c::header(&common::resolve(code)?, "physics").map_err(|err| err.message)
+}
+
+#[test]
+fn headers_declare_the_exported_functions_and_their_types() {
+    let header = header(PHYSICS).unwrap().unwrap();
+
+    assert!(header.starts_with("#ifndef PHYSICS_H\n#define PHYSICS_H\n"), "{header}");
+    assert!(header.contains("#ifdef __cplusplus\nextern \"C\" {\n#endif\n"), "{header}");
+    assert!(header.contains("struct Body {\n    Vec2 position;\n    const Body *parent;\n};"), "{header}");
+    assert!(header.contains("float physics_length(const Vec2 *v);\n"), "{header}");
+    assert!(header.contains("float push(Body *body, const Vec2 *by);\n"), "{header}");
+    assert!(!header.contains("helper"), "{header}");
+
+    assert_eq!(self::header("fn main() -> int = 0\n"), Ok(None));
+}
+
+#[test]
+fn definitions_match_the_header() {
+    let c = common::compile(PHYSICS).unwrap();
+
+    assert!(c.contains("float physics_length(const Vec2 *v) {\n    return (*v).x;"), "{c}");
+    assert!(c.contains("float push(Body *body, const Vec2 *by) {"), "{c}");
+}
+
+///
+/// C code holding pointers to `const` calls the exported functions without casts,
+///   and the prototypes of the definitions agree with the header
+///
+#[test]
+fn exported_functions_are_called_from_c() {
+    let header = header(PHYSICS).unwrap().unwrap();
+    let c = common::compile(PHYSICS).unwrap();
+
+    let prototypes = c
+        .lines()
+        .filter(|line| line.starts_with("float ") && line.ends_with(");"))
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+
+    assert_eq!(prototypes.lines().count(), 2, "{c}");
+
+    let caller = format!("#include \"physics.h\"\n\n{prototypes}\nfloat call(const Vec2 *v, Body *b) {{\n    push(b, v);\n    return physics_length(v);\n}}\n");
+    let dir = common::write(&[("physics.h", &header), ("caller.c", &caller)]);
+
+    let output = common::cc(&dir, &["-Werror", "-Wall", "-c", "caller.c"]);
+    assert!(output.status.success(), "{caller}\n{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn exported_signatures_are_c_types() {
+    for (code, message) in [
+        ("extern clang fn f(x: [int; 2]) -> int = 0", "`[int; 2]` has no C equivalent"),
+        ("extern clang fn f(x: (int, int)) -> int = 0", "`(int, int)` has no C equivalent"),
+        ("ty A = x: int\n\nextern clang fn f(a: &A) -> int = 0", "`A` is used by C, but its layout is not fixed"),
+        ("extern clang fn f <T> (x: &T) -> int = 0", "exported function `f` is generic")
+    ] {
+        assert_eq!(common::error(&format!("{code}\n\nfn main() -> int = 0\n")).as_deref(), Some(message), "{code}")
+    }
+
+    assert_eq!(
+        common::error("extern clang fn f() -> int = 0\n\n#[link_name = \"f\"]\nextern clang fn g() -> int = 0\n\nfn main() -> int = 0\n").as_deref(),
+        Some("the C function `f` is defined twice")
+    );
+}
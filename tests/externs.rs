@@ -0,0 +1,81 @@
+mod common;
+
+const PRINT: &str = "
+#[link_name = \"printf\"]
+extern clang fn print(format: *char, ...) -> int
+";
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn variadic_functions_take_any_arguments_after_the_declared_ones() {
+    let c = common::compile(&format!("{PRINT}\nfn main() -> int = print(0, 1, 2)\n")).unwrap();
+
+    assert!(c.contains("extern int printf(const char *format, ...);\n"), "{c}");
+    assert!(c.contains("return printf(0, 1, 2);"), "{c}");
+}
+
+#[test]
+fn only_foreign_functions_are_variadic() {
+    for code in [
+        "fn f(x: int, ...) -> int = x",
+        "extern clang fn f(x: int, ...) -> int = x",
+        "fn f(g: fn(int, ...)) -> int = 0"
+    ] {
+        assert_eq!(error(code).as_deref(), Some("only foreign functions can be variadic"), "{code}")
+    }
+
+    assert_eq!(
+        error("extern clang fn f(...) -> int").as_deref(),
+        Some("variadic function `f` has no parameters before `...`")
+    );
+    assert_eq!(error("extern rust fn f(x: int, ...) -> int").as_deref(), Some("Rust function `f` cannot be variadic"));
+}
+
+#[test]
+fn variadic_functions_cannot_be_values() {
+    assert_eq!(
+        common::compile(&format!("{PRINT}\nfn apply(f: fn(*char) -> int) -> int = 0\n\nfn main() -> int = apply(print)\n")),
+        Err(String::from("using a variadic function as a value is not supported yet"))
+    );
+}
+
+#[test]
+fn calling_conventions_are_attributes_of_the_prototypes() {
+    let c = common::compile("
+#[callconv(stdcall)]
+extern clang fn sleep(ms: u32)
+
+#[callconv(cdecl)]
+extern clang fn log(level: int, ...)
+
+fn main() -> int = 0
+").unwrap();
+
+    assert!(c.contains("extern __attribute__((stdcall)) void sleep(uint32_t ms);\n"), "{c}");
+    assert!(c.contains("extern __attribute__((cdecl)) void log(int level, ...);\n"), "{c}");
+}
+
+#[test]
+fn calling_conventions_are_checked() {
+    for (code, message) in [
+        ("#[callconv(pascal)]\nextern clang fn f()", "unknown calling convention `pascal`"),
+        ("#[callconv(stdcall, cdecl)]\nextern clang fn f()", "`callconv` takes one calling convention"),
+        ("#[callconv(stdcall)]\n#[callconv(cdecl)]\nextern clang fn f()", "`f` has two calling conventions"),
+        ("#[callconv(stdcall)]\nextern clang fn f(x: int, ...)", "variadic function `f` cannot use the `stdcall` calling convention")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
+
+#[test]
+fn link_names_are_c_names() {
+    for (code, message) in [
+        ("#[link_name = \"put s\"]\nextern clang fn f()", "`put s` is not a name of a C function"),
+        ("#[link_name = \"a\"]\n#[link_name = \"b\"]\nextern clang fn f()", "`f` has two link names")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
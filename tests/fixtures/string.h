@@ -0,0 +1,23 @@
+/* The declarations of glibc's <string.h> the C compilers know as builtins, without the preprocessor directives */
+
+typedef unsigned long size_t;
+
+extern void *memcpy (void *__restrict __dest, const void *__restrict __src, size_t __n);
+extern void *memmove (void *__dest, const void *__src, size_t __n);
+extern void *memset (void *__s, int __c, size_t __n);
+extern int memcmp (const void *__s1, const void *__s2, size_t __n);
+extern void *memchr (const void *__s, int __c, size_t __n);
+
+extern char *strcpy (char *__restrict __dest, const char *__restrict __src);
+extern char *strncpy (char *__restrict __dest, const char *__restrict __src, size_t __n);
+extern char *strcat (char *__restrict __dest, const char *__restrict __src);
+extern char *strncat (char *__restrict __dest, const char *__restrict __src, size_t __n);
+extern int strcmp (const char *__s1, const char *__s2);
+extern int strncmp (const char *__s1, const char *__s2, size_t __n);
+extern char *strchr (const char *__s, int __c);
+extern char *strrchr (const char *__s, int __c);
+extern size_t strspn (const char *__s, const char *__accept);
+extern size_t strcspn (const char *__s, const char *__reject);
+extern char *strpbrk (const char *__s, const char *__accept);
+extern char *strstr (const char *__haystack, const char *__needle);
+extern size_t strlen (const char *__s);
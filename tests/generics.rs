@@ -0,0 +1,101 @@
+mod common;
+
+use oko::parse::stmt::Stmt;
+
+const PAIR: &str = "
+ty Pair <T> = first second: T
+
+fn id <T> (x: T) -> T = x
+";
+
+fn clarifying(code: &str) -> String {
+    common::diagnostics(code).remove(0).clarifying
+}
+
+#[test]
+fn malformed_generic_lists_are_reported() {
+    assert_eq!(clarifying("ty V <T = x: T\n"), "expected the second closing delimiter - `>`");
+    assert_eq!(clarifying("fn f <> (x: int) -> int = x\n"), "expected at least one ident");
+}
+
+#[test]
+fn generic_lists_may_end_with_a_comma() {
+    assert_eq!(common::error("fn id <T,> (x: T) -> T = x\n\nfn main(x: int) -> int = id<int>(x)\n"), None);
+}
+
+#[test]
+fn generic_arguments_match_the_parameters() {
+    for (code, message) in [
+        ("fn main(x: int) -> int = id(x)", "cannot infer the generic arguments of `id`"),
+        ("fn main(x: int) -> int = id<int, int>(x)", "wrong number of generic arguments for `id`"),
+        ("fn f(p: Pair) -> Pair = p", "wrong number of generic arguments for `Pair`"),
+        ("fn f <T> (x: T<int>) -> int = x", "generic parameter `T` cannot take generic arguments"),
+        ("fn f <T, T> (x: T) -> T = x", "generic parameter `T` is declared twice")
+    ] {
+        assert_eq!(common::error(&format!("{PAIR}\n{code}\n")).as_deref(), Some(message), "{code}")
+    }
+}
+
+///
+/// The names of the items left after the monomorphization
+///
+fn items(code: &str) -> Vec <String> {
+    common::resolve(code).unwrap().iter().filter_map(|stmt| match stmt {
+        Stmt::Fn(fun) => Some(fun.name.name.clone()),
+        Stmt::Ty(ty) => Some(ty.name.name.clone()),
+        _ => None
+    }).collect()
+}
+
+#[test]
+fn every_set_of_arguments_is_a_separate_instance() {
+    let items = items(&format!("{PAIR}
+fn swap <T> (p: Pair<T>) -> Pair<T> = p
+
+fn ints(p: Pair<int>) -> Pair<int> = swap<int>(p)
+
+fn main(f: float, i: int) -> int {{
+    id<float>(f)
+    id<int>(i)
+}}
+"));
+
+    for instance in ["Pair_3int", "swap_3int", "id_5float", "id_3int", "ints", "main"] {
+        assert!(items.contains(&String::from(instance)), "{instance} in {items:?}")
+    }
+
+    // the generic items themselves are left out
+    for generic in ["Pair", "swap", "id"] {
+        assert!(!items.contains(&String::from(generic)), "{generic} in {items:?}")
+    }
+}
+
+#[test]
+fn unused_instances_are_left_out() {
+    let items = items(&format!("{PAIR}\nfn main(x: int) -> int = id<int>(x)\n"));
+    assert_eq!(items, ["main", "id_3int"]);
+}
+
+#[test]
+fn instances_are_emitted_as_c_items() {
+    let c = common::compile(&format!("{PAIR}
+fn swap <T> (p: Pair<T>) -> Pair<T> = p
+
+fn ints(p: Pair<int>) -> Pair<int> = swap<int>(p)
+
+fn main() -> int {{
+    id<float>(1)
+    id<int>(1)
+}}
+")).unwrap();
+
+    assert!(c.contains("struct Pair_3int {\n    int first;\n    int second;\n};"), "{c}");
+    assert!(c.contains("Pair_3int swap_3int(Pair_3int p) {"), "{c}");
+    assert!(c.contains("float id_5float(float x) {"), "{c}");
+    assert!(c.contains("int id_3int(int x) {"), "{c}");
+    assert!(c.contains("return id_3int(1);"), "{c}");
+
+    // the generic items themselves are not emitted
+    assert!(!c.contains(" id("), "{c}");
+    assert!(!c.contains("struct Pair {"), "{c}");
+}
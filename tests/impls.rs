@@ -0,0 +1,71 @@
+mod common;
+
+const VEC2: &str = "
+ty Vec2 = x y: int
+
+impl Vec2 {
+    fn new(x y: int) -> Self = Self { x: x, y: y }
+    fn sum(&self) -> int = self.x
+    fn bump(&mut self) -> int = self.y
+}
+";
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{VEC2}\n{code}\n\nfn main() -> int = 0\n"))
+}
+
+fn compile(code: &str) -> Result <String, String> {
+    common::compile(&format!("{VEC2}\n{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn functions_of_types_are_prefixed_with_the_type() {
+    let c = compile("fn f() -> int = Vec2::new(1, 2).x").unwrap();
+
+    assert!(c.contains("Vec2 Vec2_new(int x, int y) {\n    return (struct Vec2) { .x = x, .y = y };"), "{c}");
+    assert!(c.contains("return Vec2_new(1, 2).x;"), "{c}");
+}
+
+#[test]
+fn receivers_are_borrowed_and_dereferenced() {
+    let c = compile("
+fn f(v: Vec2, r: &&Vec2, m: &mut Vec2) -> int {
+    v.sum()
+    r.sum()
+    m.bump()
+    m.sum()
+}").unwrap();
+
+    assert!(c.contains("    Vec2_sum(&v);\n    Vec2_sum((*r));\n    Vec2_bump(m);\n    return Vec2_sum(m);"), "{c}");
+}
+
+#[test]
+fn receivers_are_not_made_mutable() {
+    assert_eq!(
+        compile("fn f(v: &Vec2) -> int = v.bump()"),
+        Err(String::from("the receiver of type `&Vec2` cannot be passed as `&mut Vec2`"))
+    );
+}
+
+#[test]
+fn fields_and_functions_must_exist() {
+    assert_eq!(compile("fn f(v: Vec2) -> int = v.z"), Err(String::from("no field `z` in type `Vec2`")));
+    assert_eq!(compile("fn f(v: Vec2) -> int = v.nope()"), Err(String::from("no method `nope` for type `Vec2`")));
+    assert_eq!(error("fn f() -> int = Vec2::old(1, 2).x").as_deref(), Some("no function `old` in type `Vec2`"));
+}
+
+#[test]
+fn impls_are_checked() {
+    assert_eq!(
+        error("impl Vec2 {\n    fn sum(&self) -> int = 0\n}").as_deref(),
+        Some("function `sum` is defined twice for type `Vec2`")
+    );
+    assert_eq!(
+        error("impl Nope {\n}").as_deref(),
+        Some("functions can only be added to types declared with `ty`, not to `Nope`")
+    );
+    assert_eq!(
+        error("impl Vec2 {\n    fn g <T> (x: T) -> int = 0\n}").as_deref(),
+        Some("function `g` of an `impl` cannot be generic")
+    );
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use oko::parse::stream::{Parse, ParseStream};
+use oko::parse::ty::{IndirectionKind, Type, TypeKind};
+
+fn parse(ty: &str) -> Type {
+    Type::parse(&mut ParseStream::new(ty)).unwrap()
+}
+
+#[test]
+fn indirections_nest_in_any_order() {
+    for ty in ["*mut &*int", "&*mut &mut int", "**&&*Vec2<float>"] {
+        assert_eq!(format!("{:?}", parse(ty)), ty)
+    }
+
+    let TypeKind::Indirect(outer, inner) = parse("*mut &int").kind else { panic!("not an indirection") };
+    let TypeKind::Indirect(next, _) = inner.kind else { panic!("not an indirection") };
+
+    assert_eq!((outer.kind, outer.is_mutable), (IndirectionKind::Ptr, true));
+    assert_eq!((next.kind, next.is_mutable), (IndirectionKind::Ref, false));
+}
+
+#[test]
+fn indirections_are_not_limited_in_depth() {
+    let ty = format!("{}int", "&*".repeat(20));
+    assert_eq!(format!("{:?}", parse(&ty)), ty);
+
+    let c = common::compile(&format!("fn deep(x: {ty}) -> {ty} = x\n\nfn main() -> int = 0\n")).unwrap();
+    let spelled = format!("const int {}*", "*const ".repeat(39));
+    assert!(c.contains(&format!("{spelled}deep({spelled}x)")), "{c}");
+}
+
+#[test]
+fn every_indirection_is_a_c_pointer() {
+    let c = common::compile("fn deep(x: *mut &*int) -> *mut &*int = x\n\nfn main() -> int = 0\n").unwrap();
+    assert!(c.contains("const int *const **deep(const int *const **x) {\n    return x;"), "{c}");
+
+    let c = common::compile("fn deep(x: &mut *mut int) -> &mut *mut int = x\n\nfn main() -> int = 0\n").unwrap();
+    assert!(c.contains("int **deep(int **x) {"), "{c}");
+}
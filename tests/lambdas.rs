@@ -0,0 +1,62 @@
+mod common;
+
+const SORT: &str = "
+extern clang fn qsort(base: *mut u8, count: usize, size: usize, compare: fn(*u8, *u8) -> int)
+";
+
+#[test]
+fn functions_and_lambdas_are_passed_as_closures() {
+    let c = common::compile("
+fn apply(f: fn(int) -> int, x: int) -> int = f(x)
+
+fn twice(x: int) -> int = x
+
+fn offset(k: int) -> int = apply(fn(x: int) -> int = k, 1)
+
+fn main() -> int = apply(twice, 2)
+").unwrap();
+
+    assert!(c.contains("struct oko_F1_3int3int {\n    oko_call_F1_3int3int call;\n    void *env;\n};"), "{c}");
+    assert!(c.contains("int apply(oko_F1_3int3int f, int x) {\n    return f.call(f.env, x);"), "{c}");
+
+    // a lambda carries the variables it captures in its environment
+    assert!(c.contains("struct oko_lambda_0_env {\n    int k;\n};"), "{c}");
+    assert!(c.contains("struct oko_lambda_0_env *env = env_;\n    return env->k;"), "{c}");
+
+    // a named function is wrapped into a thunk without an environment
+    assert!(c.contains("apply((oko_F1_3int3int) { .call = oko_thunk_twice, .env = NULL }, 2)"), "{c}");
+    assert!(c.contains("static int oko_thunk_twice(void *env, int x) {"), "{c}");
+}
+
+#[test]
+fn captured_variables_outlive_the_function() {
+    let c = common::compile("
+fn id(f: fn(int) -> int) -> fn(int) -> int = f
+
+fn mk(y: int) -> fn(int) -> int = id(fn(x: int) -> int = y)
+
+fn returned(k: int) -> fn(int) -> int = fn(x: int) -> int = k
+
+fn call(f: fn(int) -> int) -> int = f(2)
+
+fn main() -> int = call(mk(1))
+").unwrap();
+
+    // the environment is copied to the heap instead of living on the stack of `mk`
+    assert!(c.contains("return id((oko_F1_3int3int) { .call = oko_lambda_0, .env = oko_lambda_0_env_new((struct oko_lambda_0_env) { .y = y }) });"), "{c}");
+    assert!(c.contains("static void *oko_lambda_0_env_new(struct oko_lambda_0_env env) {\n    struct oko_lambda_0_env *copy = __builtin_malloc(sizeof env);\n    *copy = env;\n    return copy;\n}"), "{c}");
+    assert!(c.contains(".env = oko_lambda_1_env_new((struct oko_lambda_1_env) { .k = k })"), "{c}");
+}
+
+#[test]
+fn c_callbacks_are_plain_function_pointers() {
+    let c = common::compile(&format!("{SORT}\nfn f() = qsort(0, 0, 0, fn(a: *u8, b: *u8) -> int = 0)\n\nfn main() -> int = 0\n")).unwrap();
+
+    assert!(c.contains("typedef int (*oko_fnptr_F2_"), "{c}");
+    assert!(c.contains("qsort(0, 0, 0, oko_lambda_0_c)"), "{c}");
+
+    assert_eq!(
+        common::compile(&format!("{SORT}\nfn f(k: int) = qsort(0, 0, 0, fn(a: *u8, b: *u8) -> int = k)\n\nfn main() -> int = 0\n")),
+        Err(String::from("a lambda passed to C cannot capture variables"))
+    );
+}
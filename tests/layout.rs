@@ -0,0 +1,67 @@
+mod common;
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn layouts_are_lowered_to_attributes() {
+    let c = common::compile("
+#[repr(C, packed)]
+ty Packed = a: u8 + b: int
+
+#[repr(align(16))]
+ty Aligned = a: u8
+
+ty Bits = union i: int + f: float
+
+fn f(p: Packed, a: Aligned, b: Bits) -> int = 0
+
+fn main() -> int = 0
+").unwrap();
+
+    assert!(c.contains("struct Packed {\n    uint8_t a;\n    int b;\n} __attribute__((packed));"), "{c}");
+    assert!(c.contains("struct Aligned {\n    uint8_t a;\n} __attribute__((aligned(16)));"), "{c}");
+    assert!(c.contains("typedef union Bits Bits;"), "{c}");
+    assert!(c.contains("union Bits {\n    int i;\n    float f;\n};"), "{c}");
+}
+
+#[test]
+fn discriminants_follow_the_previous_variant() {
+    let c = common::compile("ty Key = Up = 38 | Down | Left = 10\n\nfn f(k: Key) -> int = 0\n\nfn main() -> int = 0\n").unwrap();
+    assert!(c.contains("        Key_Up = 38,\n        Key_Down,\n        Key_Left = 10\n"), "{c}");
+}
+
+#[test]
+fn layouts_are_checked() {
+    for (code, message) in [
+        ("#[repr(align(3))]\nty A = a: u8", "alignment 3 is not a power of two"),
+        ("#[repr(packed, align(4))]\nty A = a: u8", "type `A` cannot be both packed and aligned"),
+        ("#[repr(Rust)]\nty A = a: u8", "unknown representation `Rust`"),
+        ("ty K = A = 1 | B = 1", "discriminant 1 of `B` is already used by `A`"),
+        ("ty K = A = 2147483648", "discriminant of `A` is too large")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
+
+#[test]
+fn fields_and_variants_are_declared_once() {
+    for (code, message) in [
+        ("ty A = x y: int + x: float", "field `x` is declared twice in `A`"),
+        ("ty A = union i: int + i: float", "field `i` is declared twice in `A`"),
+        ("ty A = {\n    x: int\n    y x: int\n}", "field `x` is declared twice in `A`"),
+        ("ty Shape =\n    | Empty\n    | Empty", "variant `Empty` is declared twice in `Shape`"),
+        ("world Game {\n    ty A = x x: int\n}", "field `x` is declared twice in `A`")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
+
+#[test]
+fn the_second_declaration_is_reported() {
+    let diagnostic = common::diagnostics("ty A = x: int + y: int + x: float\n\nfn main() -> int = 0\n").remove(0);
+
+    assert_eq!(diagnostic.spanned, "x:");
+    assert_eq!(diagnostic.span.start.column, 26);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use oko::check::lints::{Level, Levels, Lint};
+use oko::error::Severity;
+
+///
+/// The severities, the lints and the messages of the diagnostics of the code checked with the levels
+///
+fn linted_with(code: &str, levels: &Levels) -> Vec <(Severity, Option <String>, String)> {
+    common::diagnostics_with(&[("main.oko", &format!("{code}\n\nfn main() -> int = 0\n"))], levels)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.severity, diagnostic.code, diagnostic.message))
+        .collect()
+}
+
+fn linted(code: &str) -> Vec <(Severity, Option <String>, String)> {
+    linted_with(code, &Levels::default())
+}
+
+fn warning(lint: &str, message: &str) -> (Severity, Option <String>, String) {
+    (Severity::Warning, Some(String::from(lint)), String::from(message))
+}
+
+#[test]
+fn unused_fns_are_not_reachable_from_the_roots() {
+    assert_eq!(linted("fn f() -> int = 0"), [warning("unused_fns", "function `f` is never used")]);
+
+    // the roots are `main`, public and exported functions, methods and worlds
+    assert_eq!(linted("\
+fn a() -> int = 0
+fn b() -> int = a()
+pub fn c() -> int = b()
+extern clang fn d() -> int = 0
+
+ty Vec2 = x y: int
+
+impl Vec2 {
+    fn e(&self) -> int = g()
+}
+
+fn g() -> int = 0
+
+world W {
+    fn h() -> int = 0
+}"), []);
+
+    // a function only called by an unused one is unused as well
+    assert_eq!(linted("fn a() -> int = b()\nfn b() -> int = 0"), [
+        warning("unused_fns", "function `a` is never used"),
+        warning("unused_fns", "function `b` is never used")
+    ]);
+}
+
+#[test]
+fn unused_tys_are_never_referred_to() {
+    assert_eq!(linted("ty A = x: int\n\nty B = a: &B"), [
+        warning("unused_tys", "type `A` is never used"),
+        warning("unused_tys", "type `B` is never used")
+    ]);
+    assert_eq!(linted("ty A = x: int\n\npub ty B = x: int\n\npub fn f(a: A) -> int = 0"), []);
+}
+
+#[test]
+fn unused_macros_are_reported() {
+    assert_eq!(
+        linted("macro suffix \"px\" (x: int) -> int = x"),
+        [warning("unused_macros", "suffix macro `px` is never used")]
+    );
+}
+
+#[test]
+fn shadowing_parameters_are_reported_with_a_note() {
+    let diagnostics = linted("pub fn f(x: int) -> int = 0\n\npub fn g(f: int) -> int = f");
+
+    assert_eq!(diagnostics[0], warning("shadowing", "parameter `f` shadows the function `f`"));
+    assert_eq!(diagnostics[1], (Severity::Note, None, String::from("the shadowed function `f` is declared here")));
+
+    assert_eq!(
+        linted("fn apply(g: fn(int) -> int) -> int = g(0)\n\npub fn f(x: int) -> int = apply(fn(x: int) -> int = x)")[0],
+        warning("shadowing", "parameter `x` shadows a parameter of an enclosing function")
+    );
+}
+
+#[test]
+fn type_names_are_upper_camel_case() {
+    let diagnostics = common::diagnostics_with(&[("main.oko", "pub ty vec2 = x y: int\n\nfn main() -> int = 0\n")], &Levels::default());
+
+    assert_eq!(diagnostics[0].message, "type `vec2` should have an upper camel case name");
+    assert_eq!(diagnostics[0].help, ["rename it to `Vec2`", "allow it with `#[allow(non_camel_case_tys)]`"]);
+}
+
+#[test]
+fn levels_are_set_by_flags() {
+    let mut levels = Levels::default();
+
+    levels.set(Lint::UnusedFns, Level::Deny);
+    levels.set(Lint::UnusedTys, Level::Allow);
+
+    assert_eq!(linted_with("fn f() -> int = 0\n\nty A = x: int", &levels), [
+        (Severity::Error, Some(String::from("unused_fns")), String::from("function `f` is never used"))
+    ])
+}
+
+#[test]
+fn attributes_override_the_flags() {
+    let mut levels = Levels::default();
+    levels.set(Lint::UnusedFns, Level::Deny);
+
+    assert_eq!(linted_with("#[allow(unused_fns)]\nfn f() -> int = 0", &levels), []);
+    assert_eq!(linted_with("#[warn(unused_fns)]\nfn f() -> int = 0", &Levels::default()).len(), 1);
+    assert_eq!(
+        linted("#[deny(unused_tys)]\nty A = x: int")[0],
+        (Severity::Error, Some(String::from("unused_tys")), String::from("type `A` is never used"))
+    );
+
+    // the attributes of an item override the ones of its world
+    assert_eq!(linted("#[deny(non_camel_case_tys)]\nworld W {\n    #[allow(non_camel_case_tys)]\n    pub ty a = x: int\n    pub ty b = x: int\n}")
+        .into_iter()
+        .map(|(severity, _, message)| (severity, message))
+        .collect::<Vec <_>>(), [(Severity::Error, String::from("type `b` should have an upper camel case name"))]);
+}
+
+#[test]
+fn unknown_lints_are_ignored_with_a_warning() {
+    assert_eq!(
+        linted("#[allow(unused_everything)]\npub fn f() -> int = 0"),
+        [(Severity::Warning, None, String::from("unknown lint `unused_everything`"))]
+    );
+}
+
+#[test]
+fn uses_of_deprecated_items_are_reported() {
+    assert_eq!(linted("#[deprecated = \"use g\"]\npub fn f() -> int = 0\n\npub fn first(a: int, b: int) -> int = a\n\npub fn g() -> int = first(f(), f())"), [
+        warning("deprecated", "use of deprecated function `f`: use g"),
+        warning("deprecated", "use of deprecated function `f`: use g")
+    ]);
+
+    assert_eq!(linted("#[deprecated]\npub ty A = x: int\n\npub fn f(a: A) -> int = 0"), [warning("deprecated", "use of deprecated type `A`")]);
+
+    assert_eq!(linted("pub ty A = #[deprecated] x y: int\n\npub ty B = None | #[deprecated] Some int\n\npub fn f() -> int = A { x: 0, y: 0 }.x\n\npub fn g() -> B = B::Some(0)"), [
+        warning("deprecated", "use of deprecated field `A::x`"),
+        warning("deprecated", "use of deprecated field `A::y`"),
+        warning("deprecated", "use of deprecated variant `B::Some`")
+    ]);
+
+    // deprecated items may use each other, and the lint can be allowed like the others
+    assert_eq!(linted("#[deprecated]\npub fn f() -> int = 0\n\n#[deprecated]\npub fn g() -> int = f()\n\n#[allow(deprecated)]\npub fn h() -> int = g()"), []);
+}
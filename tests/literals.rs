@@ -0,0 +1,73 @@
+mod common;
+
+const TYPES: &str = "
+ty Vec2 = x y: int
+
+ty Shape =
+    | Empty
+    | Circle int
+
+ty Bits = union i: int + f: float
+";
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{TYPES}\n{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn literals_are_lowered_to_compound_literals() {
+    let c = common::compile(&format!("{TYPES}
+fn vec() -> Vec2 = Vec2 {{ x: 1, y: 2 }}
+
+fn circle() -> Shape = Shape::Circle(5)
+
+fn empty() -> Shape = Empty
+
+fn bits() -> Bits = Bits {{ i: 5 }}
+
+fn named() -> Shape = Shape {{ Circle: 5 }}
+
+fn main() -> int = 0
+")).unwrap();
+
+    assert!(c.contains("return (struct Vec2) { .x = 1, .y = 2 };"), "{c}");
+    assert!(c.contains("Shape circle(void) {\n    return (struct Shape) { .tag = Shape_Circle, .value.Circle = 5 };"), "{c}");
+    assert!(c.contains("Shape empty(void) {\n    return (struct Shape) { .tag = Shape_Empty };"), "{c}");
+    assert!(c.contains("return (union Bits) { .i = 5 };"), "{c}");
+    assert!(c.contains("Shape named(void) {\n    return (struct Shape) { .tag = Shape_Circle, .value.Circle = 5 };"), "{c}");
+}
+
+#[test]
+fn struct_literals_initialize_every_field_once() {
+    assert_eq!(error("fn f() -> Vec2 = Vec2 { x: 1 }").as_deref(), Some("missing field `y` in the literal of `Vec2`"));
+    assert_eq!(error("fn f() -> Vec2 = Vec2 { x: 1, x: 2, y: 3 }").as_deref(), Some("field `x` is initialized twice"));
+    assert_eq!(error("fn f() -> Vec2 = Vec2 { x: 1, y: 2, z: 3 }").as_deref(), Some("no field `z` in type `Vec2`"));
+}
+
+#[test]
+fn union_and_enum_literals_initialize_one_field() {
+    assert_eq!(
+        error("fn f() -> Bits = Bits { i: 1, f: 2 }").as_deref(),
+        Some("a literal of the union `Bits` must initialize exactly one field")
+    );
+    assert_eq!(
+        error("fn f() -> Shape = Shape { Empty: {}, Circle: 1 }").as_deref(),
+        Some("a literal of the enum `Shape` must name exactly one variant")
+    );
+}
+
+#[test]
+fn variants_are_constructed_with_their_values() {
+    assert_eq!(error("fn f() -> Shape = Shape::Circle").as_deref(), Some("variant `Circle` of `Shape` takes a value"));
+    assert_eq!(error("fn f() -> Shape = Shape::Empty(1)").as_deref(), Some("variant `Empty` of `Shape` takes no value"));
+    assert_eq!(error("fn f() -> Shape = Shape::Square").as_deref(), Some("no variant `Square` in enum `Shape`"));
+    assert_eq!(error("fn f() -> Shape = Shape::Square(1)").as_deref(), Some("no variant or function `Square` in enum `Shape`"));
+}
+
+#[test]
+fn variants_of_several_enums_are_qualified() {
+    assert_eq!(
+        error("ty Size =\n    | Empty\n    | Full\n\nfn f() -> Size = Empty").as_deref(),
+        Some("`Empty` is a variant of several enums: `Shape`, `Size`")
+    );
+}
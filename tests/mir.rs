@@ -0,0 +1,127 @@
+mod common;
+
+use oko::mir::{BlockId, Operand, Place, Program, Terminator};
+
+const SHOW: &str = "
+ty Vec2 = x y: int
+
+trait Show {
+    fn show(&self) -> int
+}
+
+impl Show for Vec2 {
+    fn show(&self) -> int = self.x
+}
+";
+
+fn lower(code: &str) -> Program {
+    oko::mir::lower(&common::resolve(code).unwrap()).unwrap()
+}
+
+fn main_of(program: &Program) -> usize {
+    program.bodies.iter().position(|body| body.name == "main").unwrap()
+}
+
+fn invalid(program: &Program) -> String {
+    oko::mir::validate(program).unwrap_err().message
+}
+
+#[test]
+fn functions_are_blocks_of_typed_locals() {
+    let program = lower(&format!("{SHOW}\nfn main() -> int = Vec2 {{ x: 1, y: 2 }}.show()\n"));
+    let main = format!("{:?}", program.body("main").unwrap());
+
+    assert_eq!(main, "fn main() -> int {
+    let _0: int
+    let _1: Vec2
+    let _2: &Vec2
+    let _3: int
+
+    bb0: {
+        _1 = Vec2 { x: const 1: int, y: const 2: int }
+        _2 = &_1
+        _3 = Show_4Vec2_show(move _2) -> bb1
+    }
+
+    bb1: {
+        _0 = move _3
+        return
+    }
+}
+");
+    assert!(oko::mir::validate(&program).is_ok());
+}
+
+#[test]
+fn lambdas_are_bodies_receiving_their_captures() {
+    let program = lower("fn apply(f: fn(int) -> int) -> int = f(1)\n\nfn main() -> int = 0\n\nfn g(k: int) -> int = apply(fn(x: int) -> int = k)\n");
+    let lambda = format!("{:?}", program.body("g::lambda0").unwrap());
+
+    assert!(lambda.contains("let _2: int // captured k\n"), "{lambda}");
+    assert!(format!("{program:?}").contains("= closure g::lambda0 [copy _1]"), "{program:?}");
+    assert!(oko::mir::validate(&program).is_ok());
+}
+
+#[test]
+fn shared_borrows_and_null_pointers_are_accepted() {
+    for code in [
+        "fn len(v: &int) -> int = 0\n\nfn f(v: &mut int) -> int = len(v)",
+        "fn len(p: *int) -> int = 0\n\nfn f() -> int = len(0)"
+    ] {
+        assert!(common::compile(&format!("{code}\n\nfn main() -> int = 0\n")).is_ok(), "{code}")
+    }
+}
+
+#[test]
+fn locals_are_assigned_before_they_are_read_and_moved_once() {
+    let code = format!("{SHOW}\nfn main() -> int = Vec2 {{ x: 1, y: 2 }}.show()\n");
+    let main = main_of(&lower(&code));
+
+    let mut unassigned = lower(&code);
+    unassigned.bodies[main].blocks[0].statements.remove(0);
+    assert_eq!(invalid(&unassigned), "invalid MIR of `main`: bb0: `_1` is used before it is assigned");
+
+    let mut program = lower(&code);
+    let Some(Terminator::Call { args, .. }) = &mut program.bodies[main].blocks[0].terminator else { panic!() };
+    args[0] = Operand::Move(Place::from(oko::mir::Local(1)));
+    assert!(invalid(&program).contains("expected `&Vec2`, found `Vec2`"));
+
+    let mut moved = lower("fn id(x: int) -> int = x\n\nfn main() -> int = id(1)\n");
+    let main = main_of(&moved);
+    let block = &mut moved.bodies[main].blocks[1];
+
+    block.statements.push(block.statements[0].clone());
+    assert_eq!(invalid(&moved), "invalid MIR of `main`: bb1: `_1` is used after it is moved");
+}
+
+#[test]
+fn blocks_are_terminated_and_jump_to_existing_blocks() {
+    const ID: &str = "fn id(x: int) -> int = x\n\nfn main() -> int = id(1)\n";
+
+    let main = main_of(&lower(ID));
+
+    let mut unterminated = lower(ID);
+    unterminated.bodies[main].blocks[1].terminator = None;
+    assert_eq!(invalid(&unterminated), "invalid MIR of `main`: bb1: the block is not terminated");
+
+    let mut dangling = lower(ID);
+    dangling.bodies[main].blocks[1].terminator = Some(Terminator::Goto(BlockId(7)));
+    assert_eq!(invalid(&dangling), "invalid MIR of `main`: bb1: jump to `bb7`, which does not exist");
+}
+
+#[test]
+fn c_is_generated_from_the_mir() {
+    let c = common::compile("
+fn side() -> int = 1
+
+fn two(a: int, b: int) -> int = a
+
+fn seq(xs: &[int]) -> int = two(side(), { side() xs[side()] })
+
+fn main() -> int = two(side(), side())
+").unwrap();
+
+    // the calls nest unless a statement is evaluated in between
+    assert!(c.contains("int main(void) {\n    return two(side(), side());\n}"), "{c}");
+    assert!(c.contains("int seq(oko_S3int xs) {\n    int _2 = side();\n    side();\n    return two(_2, xs.items[side()]);\n}"), "{c}");
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use std::path::Path;
+
+///
+/// The message of the error of loading the files and the name of the file it points into
+///
+fn error(files: &[(&str, &str)]) -> (String, String) {
+    let err = common::load(files).0.err().expect("the files are loaded");
+    let file = Path::new(&err.filename).file_name().unwrap().to_str().unwrap().to_string();
+
+    (err.message, file)
+}
+
+fn expected(message: &str, file: &str) -> (String, String) {
+    (message.to_string(), file.to_string())
+}
+
+#[test]
+fn items_of_modules_are_prefixed_with_their_path() {
+    let c = common::compile_files(&[
+        ("main.oko", "mod math\n\nuse math::vec::len\n\nfn main() -> int = len(math::one())\n"),
+        ("math/mod.oko", "pub mod vec\n\npub fn one() -> int = 1\n"),
+        ("math/vec.oko", "pub fn len(x: int) -> int = x\n")
+    ]).unwrap();
+
+    assert!(c.contains("int main(void) {\n    return math__vec__len(math__one());"), "{c}");
+    assert!(c.contains("int math__vec__len(int x) {"), "{c}");
+    assert!(c.contains("int math__one(void) {"), "{c}");
+}
+
+#[test]
+fn a_file_is_loaded_once() {
+    let c = common::compile_files(&[
+        ("main.oko", "mod a\n\nmod b\n\nfn main() -> int = a::two()\n"),
+        ("a.oko", "mod b\n\npub fn two() -> int = b::one()\n"),
+        ("b.oko", "pub fn one() -> int = 1\n")
+    ]).unwrap();
+
+    assert_eq!(c.matches("int b__one(void) {").count(), 1, "{c}");
+}
+
+#[test]
+fn missing_files_are_reported_where_they_are_declared() {
+    assert_eq!(
+        error(&[("main.oko", "mod math\n\nfn main() -> int = 0\n"), ("math.oko", "mod vec\n")]),
+        expected("cannot find module `vec`", "math.oko")
+    );
+}
+
+#[test]
+fn module_cycles_are_reported() {
+    assert_eq!(
+        error(&[("main.oko", "mod a\n\nfn main() -> int = 0\n"), ("a.oko", "mod b\n"), ("b.oko", "mod a\n")]),
+        expected("module `a` depends on itself", "b.oko")
+    );
+}
+
+#[test]
+fn import_cycles_are_reported() {
+    assert_eq!(
+        error(&[("main.oko", "mod a\n\nuse a::x\n\nfn main() -> int = x()\n"), ("a.oko", "pub use x\n")]),
+        expected("the import of `x` is cyclic", "a.oko")
+    );
+    assert_eq!(error(&[("main.oko", "use x\n\nfn main() -> int = 0\n")]), expected("the import of `x` is cyclic", "main.oko"));
+}
+
+#[test]
+fn only_pub_items_are_visible_from_other_modules() {
+    let files = |math: &'static str| [("main.oko", "mod math\n\nfn main() -> int = math::one()\n"), ("math.oko", math)];
+
+    assert_eq!(error(&files("fn one() -> int = 1\n")), expected("`one` is private", "main.oko"));
+    assert!(common::load(&files("pub fn one() -> int = 1\n")).0.is_ok());
+
+    assert_eq!(
+        error(&[
+            ("main.oko", "mod a\n\nuse a::one\n\nfn main() -> int = one()\n"),
+            ("a.oko", "mod b\n\nuse b::one\n"),
+            ("b.oko", "pub fn one() -> int = 1\n")
+        ]),
+        expected("`one` is private", "main.oko")
+    );
+}
+
+#[test]
+fn unused_imports_are_resolved() {
+    let math = ("math.oko", "pub fn one() -> int = 1\n");
+
+    assert_eq!(
+        error(&[("main.oko", "mod math\n\nuse math::two\n\nfn main() -> int = 0\n"), math]),
+        expected("cannot find `two` in module `math`", "main.oko")
+    );
+    assert_eq!(
+        error(&[("main.oko", "mod math\n\nuse math::one::two\n\nfn main() -> int = 0\n"), math]),
+        expected("`one` is not a module", "main.oko")
+    );
+}
+
+#[test]
+fn names_are_declared_once_per_module() {
+    assert_eq!(
+        error(&[("main.oko", "mod math\n\nfn math() -> int = 0\n\nfn main() -> int = 0\n"), ("math.oko", "")]),
+        expected("`math` is declared twice", "main.oko")
+    );
+    assert_eq!(
+        error(&[("main.oko", "mod math\n\nuse math::one\n\nfn one() -> int = 0\n\nfn main() -> int = 0\n"), ("math.oko", "pub fn one() -> int = 1\n")]),
+        expected("`one` is already declared in this module", "main.oko")
+    );
+}
+
+#[test]
+fn struct_literals_name_types_of_other_modules() {
+    let c = common::compile_files(&[
+        ("main.oko", "mod geo\n\nfn main() -> int = geo::Point { x: 1 }.x\n"),
+        ("geo.oko", "pub ty Point = x: int\n")
+    ]).unwrap();
+
+    assert!(c.contains("return (struct geo__Point) { .x = 1 }.x;"), "{c}");
+}
+
+#[test]
+fn unreadable_files_are_reported() {
+    let dir = common::write(&[("main.oko/.keep", "")]);
+    let err = oko::driver::load(dir.join("main.oko").to_str().unwrap()).0.err().unwrap();
+
+    assert_eq!(err.message, format!("cannot read `{}`", dir.join("main.oko").display()));
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use oko::package::{Manifest, Package, DEFAULT_ENTRY};
+
+const GEOMETRY: (&str, &str) = ("geometry/oko.toml", "[package]\nname = \"geometry\"\nentry = \"lib.oko\"\n");
+
+fn manifest_error(code: &str) -> String {
+    Manifest::parse(code).err().expect("the manifest is invalid").message
+}
+
+fn resolve(files: &[(&str, &str)]) -> Result <Vec <Package>, (String, PathBuf)> {
+    oko::package::resolve(&common::write(files).join("game"))
+        .0
+        .map_err(|err| (err.message, PathBuf::from(err.filename)))
+}
+
+#[test]
+fn manifests_have_defaults() {
+    let manifest = Manifest::parse("[package]\nname = \"game\"\n").unwrap();
+
+    assert_eq!(manifest.name, "game");
+    assert_eq!(manifest.entry, Path::new(DEFAULT_ENTRY));
+    assert!(manifest.links.is_empty());
+    assert!(manifest.dependencies.is_empty());
+
+    let manifest = Manifest::parse("[package]\nname = \"game\"\nentry = \"game.oko\"\nlinks = [\"m\", \"SDL2\"]\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\n").unwrap();
+
+    assert_eq!(manifest.entry, Path::new("game.oko"));
+    assert_eq!(manifest.links, ["m", "SDL2"]);
+    assert_eq!(manifest.dependencies[0].name, "geometry");
+    assert_eq!(manifest.dependencies[0].path, Path::new("../geometry"));
+}
+
+#[test]
+fn invalid_manifests_are_rejected() {
+    for (code, message) in [
+        ("name = \"game\"\n", "unknown key `name` in the manifest"),
+        ("[dependencies]\n", "the manifest has no `[package]` table"),
+        ("[package]\nentry = \"main.oko\"\n", "the package has no `name`"),
+        ("[package]\nname = \"my-game\"\n", "`my-game` is not a valid package name"),
+        ("[package]\nname = 1\n", "`name` must be a string"),
+        ("[package]\nname = \"game\"\nlinks = \"m\"\n", "`links` must be an array of strings"),
+        ("[package]\nname = \"game\"\nversion = \"1\"\n", "unknown key `version` in `[package]`"),
+        ("[package]\nname = \"game\"\n\n[dependencies]\ngeometry = { git = \"url\" }\n", "unknown key `git` in the dependency `geometry`"),
+        ("[package]\nname = \"game\"\n\n[dependencies]\ngeometry = {}\n", "the dependency `geometry` has no `path`")
+    ] {
+        assert_eq!(manifest_error(code), message, "{code}")
+    }
+}
+
+#[test]
+fn dependencies_are_reachable_by_their_name() {
+    let packages = resolve(&[
+        ("game/oko.toml", "[package]\nname = \"game\"\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\n"),
+        ("game/src/main.oko", "fn main() -> int = geometry::one()\n"),
+        GEOMETRY,
+        ("geometry/lib.oko", "pub fn one() -> int = 1\n\nfn two() -> int = 2\n")
+    ]).unwrap();
+
+    assert_eq!(packages.iter().map(|package| package.manifest.name.as_str()).collect::<Vec <_>>(), ["game", "geometry"]);
+    assert_eq!(packages[0].dependencies, [1]);
+
+    let (_, stmts) = oko::driver::load_packages(&packages).0.unwrap();
+    let stmts = stmts.iter().map(|stmt| format!("{stmt:?}")).collect::<Vec <_>>();
+
+    assert!(stmts[0].contains("fun: geometry__one"), "{stmts:?}");
+    assert!(stmts[1].starts_with("Fn(pub fn geometry__one()"), "{stmts:?}");
+}
+
+#[test]
+fn private_items_of_dependencies_are_hidden() {
+    let err = resolve(&[
+        ("game/oko.toml", "[package]\nname = \"game\"\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\n"),
+        ("game/src/main.oko", "fn main() -> int = geometry::two()\n"),
+        GEOMETRY,
+        ("geometry/lib.oko", "fn two() -> int = 2\n")
+    ]).map(|packages| oko::driver::load_packages(&packages).0.err().unwrap().message);
+
+    assert_eq!(err.as_deref(), Ok("`two` is private"));
+}
+
+#[test]
+fn dependency_cycles_are_reported() {
+    let err = resolve(&[
+        ("game/oko.toml", "[package]\nname = \"game\"\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\n"),
+        ("game/src/main.oko", "fn main() -> int = 0\n"),
+        ("geometry/oko.toml", "[package]\nname = \"geometry\"\nentry = \"lib.oko\"\n\n[dependencies]\ngame = { path = \"../game\" }\n"),
+        ("geometry/lib.oko", "")
+    ]).err().unwrap();
+
+    assert_eq!(err.0, "package `game` depends on itself");
+    assert!(err.1.ends_with("geometry/oko.toml"), "{err:?}");
+}
+
+#[test]
+fn missing_packages_and_entries_are_reported() {
+    let missing = resolve(&[
+        ("game/oko.toml", "[package]\nname = \"game\"\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\n"),
+        ("game/src/main.oko", "fn main() -> int = 0\n")
+    ]).err().unwrap();
+
+    assert!(missing.0.starts_with("cannot find `oko.toml` in "), "{missing:?}");
+
+    let entry = resolve(&[("game/oko.toml", "[package]\nname = \"game\"\n")]).err().unwrap();
+    assert_eq!(entry.0, "cannot find the entry file `src/main.oko`");
+
+    let manifest = resolve(&[("game/oko.toml/.keep", "")]).err().unwrap();
+    assert!(manifest.0.starts_with("cannot read `"), "{manifest:?}");
+}
+
+#[test]
+fn package_names_are_unique() {
+    let err = resolve(&[
+        ("game/oko.toml", "[package]\nname = \"game\"\n\n[dependencies]\ngeometry = { path = \"../geometry\" }\n"),
+        ("game/src/main.oko", "fn main() -> int = 0\n"),
+        ("geometry/oko.toml", "[package]\nname = \"game\"\nentry = \"lib.oko\"\n"),
+        ("geometry/lib.oko", "")
+    ]).err().unwrap();
+
+    assert_eq!(err.0, "two packages are named `game`");
+}
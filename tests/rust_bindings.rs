@@ -0,0 +1,84 @@
+mod common;
+
+use std::process::Command;
+
+const GAME: &str = "
+#[repr(C)]
+ty Vec2 = x y: f32
+
+#[repr(C)]
+ty Shape =
+    | Empty
+    | Circle f32
+
+#[repr(C)]
+ty Body = position: Vec2 + parent: &Body
+
+extern rust fn length(v: &Vec2) -> f32 = v.x
+
+extern rust fn push(body: &mut Body, by: *Vec2, s: Shape) -> &Body = body
+
+extern rust fn raw(p: *mut void) -> int = 0
+
+fn main() -> int = 0
+";
+
+fn bindings(code: &str) -> Result <Option <String>, String> {
+    oko::codegen::rust::bindings(&common::resolve(code)?, "game").map_err(|err| err.message)
+}
+
+#[test]
+fn types_are_repr_c_structs_and_unions() {
+    let rust = bindings(GAME).unwrap().unwrap();
+
+    assert!(rust.contains("#[repr(C)]\n#[derive(Clone, Copy)]\npub struct Vec2 {\n    pub x: f32,\n    pub y: f32,\n}"), "{rust}");
+    assert!(rust.contains("pub struct Body {\n    pub position: Vec2,\n    pub parent: *const Body,\n}"), "{rust}");
+
+    // an enum is its tag and a union of the attached values, as in C
+    assert!(rust.contains("pub struct Shape {\n    pub tag: core::ffi::c_uint,\n    pub value: ShapeValue,\n}"), "{rust}");
+    assert!(rust.contains("pub union ShapeValue {\n    pub Circle: f32,\n}"), "{rust}");
+    assert!(rust.contains("impl Shape {\n    pub const Empty: core::ffi::c_uint = 0;\n    pub const Circle: core::ffi::c_uint = 1;\n}"), "{rust}");
+}
+
+#[test]
+fn functions_are_wrapped_in_shims() {
+    let rust = bindings(GAME).unwrap().unwrap();
+
+    assert!(rust.contains("pub fn length(v: &Vec2) -> f32;"), "{rust}");
+    assert!(rust.contains("pub fn length(v: &Vec2) -> f32 {\n    unsafe { ffi::length(v) }\n}"), "{rust}");
+
+    // a shim taking a raw pointer is unsafe, and references are returned as raw pointers
+    assert!(rust.contains("pub unsafe fn push(body: &mut Body, by: *const Vec2, s: Shape) -> *const Body {"), "{rust}");
+    assert!(rust.contains("pub unsafe fn raw(p: *mut core::ffi::c_void) -> core::ffi::c_int {"), "{rust}");
+
+    assert_eq!(bindings("extern clang fn f() -> int = 0\n\nfn main() -> int = 0\n"), Ok(None));
+}
+
+#[test]
+fn bindings_compile() {
+    let rust = bindings(GAME).unwrap().unwrap();
+    let dir = common::write(&[("game.rs", &rust)]);
+
+    let Ok(output) = Command::new("rustc")
+        .args(["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "game.rs"])
+        .current_dir(&dir)
+        .output() else { return };
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn exported_signatures_are_checked_for_the_language() {
+    for (code, message) in [
+        ("ty A = x: int\n\nextern rust fn f(a: &A) -> int = 0", "`A` is used by Rust, but its layout is not fixed"),
+        ("ty A = x: int\n\nextern clang fn f(a: &A) -> int = 0", "`A` is used by C, but its layout is not fixed"),
+        ("extern rust fn f(x: (int, int)) -> int = 0", "`(int, int)` has no Rust equivalent"),
+        ("extern rust fn f(x: &[int]) -> int = 0", "`&[int]` has no Rust equivalent"),
+        ("extern rust fn f <T> (x: &T) -> int = 0", "exported function `f` is generic")
+    ] {
+        assert_eq!(common::error(&format!("{code}\n\nfn main() -> int = 0\n")).as_deref(), Some(message), "{code}")
+    }
+
+    let diagnostic = common::diagnostics("extern rust fn f <T> (x: &T) -> int = 0\n\nfn main() -> int = 0\n").remove(0);
+    assert_eq!(diagnostic.clarifying, "Rust cannot instantiate it");
+}
@@ -0,0 +1,159 @@
+use core::num::NonZeroUsize;
+use core::time::Duration;
+use std::sync::{mpsc, Arc, Mutex};
+use oko::ecs::{AccessDecl, ExecutionMode, Schedule, World};
+use oko::parse::stmt::Stmt;
+
+struct Position(i32);
+
+struct Velocity(i32);
+
+struct Health;
+
+fn parallel(threads: usize) -> ExecutionMode {
+    ExecutionMode::Parallel {
+        threads: NonZeroUsize::new(threads).unwrap()
+    }
+}
+
+fn world() -> World {
+    let mut world = World::new();
+
+    world.register_named::<Position>("Position");
+    world.register_named::<Velocity>("Velocity");
+    world.register_named::<Health>("Health");
+    world.spawn((Position(0), Velocity(1), Health));
+
+    world
+}
+
+fn batches(schedule: &Schedule, world: &World) -> Vec <Vec <String>> {
+    schedule.report(world).stages.remove(0).1
+}
+
+#[test]
+fn readers_of_the_same_component_are_batched_together() {
+    let world = world();
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(parallel(4)).add_stage("update");
+    schedule.add_query_system::<&Position, _>("update", "a", |_| ());
+    schedule.add_query_system::<(&Position, &Velocity), _>("update", "b", |_| ());
+    schedule.add_query_system::<&mut Health, _>("update", "c", |_| ());
+
+    assert_eq!(batches(&schedule, &world), [["a", "b", "c"]]);
+}
+
+#[test]
+fn a_writer_conflicts_with_readers_and_keeps_the_order() {
+    let world = world();
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(parallel(4)).add_stage("update");
+    schedule.add_query_system::<&Position, _>("update", "read", |_| ());
+    schedule.add_query_system::<&mut Position, _>("update", "write", |_| ());
+    schedule.add_query_system::<&Velocity, _>("update", "other", |_| ());
+    schedule.add_query_system::<(&Position, &Velocity), _>("update", "after", |_| ());
+
+    assert_eq!(batches(&schedule, &world), vec![vec!["read", "other"], vec!["write"], vec!["after"]]);
+}
+
+#[test]
+fn systems_of_unknown_access_run_alone() {
+    let world = world();
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(parallel(4)).add_stage("update");
+    schedule.add_query_system::<&Position, _>("update", "a", |_| ());
+    schedule.add_system("update", "exclusive", |_| ());
+    schedule.add_query_system::<&Velocity, _>("update", "b", |_| ());
+
+    assert_eq!(batches(&schedule, &world), [["a"], ["exclusive"], ["b"]]);
+}
+
+#[test]
+fn single_threaded_mode_runs_systems_one_by_one() {
+    let mut world = world();
+    let order = Arc::new(Mutex::new(vec![]));
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(ExecutionMode::SingleThreaded).add_stage("update");
+
+    for name in ["a", "b", "c"] {
+        let log = order.clone();
+        schedule.add_query_system::<&Position, _>("update", name, move |_| log.lock().unwrap().push(name));
+    }
+
+    assert_eq!(batches(&schedule, &world), [["a"], ["b"], ["c"]]);
+
+    schedule.run_for(&mut world, 2);
+
+    assert_eq!(*order.lock().unwrap(), ["a", "b", "c", "a", "b", "c"]);
+}
+
+#[test]
+fn batched_systems_run_simultaneously() {
+    let mut world = world();
+    let (to_b, from_a) = mpsc::channel();
+    let (to_a, from_b) = mpsc::channel();
+    let met = Arc::new(Mutex::new(vec![]));
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(parallel(2)).add_stage("update");
+
+    // each system waits for the other one, which only succeeds if they run at the same time
+    let (from_b, log) = (Mutex::new(from_b), met.clone());
+    schedule.add_query_system::<&Position, _>("update", "a", move |_| {
+        to_b.send(()).unwrap();
+        log.lock().unwrap().push(from_b.lock().unwrap().recv_timeout(Duration::from_secs(5)).is_ok())
+    });
+
+    let (from_a, log) = (Mutex::new(from_a), met.clone());
+    schedule.add_query_system::<&Velocity, _>("update", "b", move |_| {
+        to_a.send(()).unwrap();
+        log.lock().unwrap().push(from_a.lock().unwrap().recv_timeout(Duration::from_secs(5)).is_ok())
+    });
+
+    schedule.run_for(&mut world, 1);
+
+    assert_eq!(*met.lock().unwrap(), [true, true]);
+}
+
+#[test]
+fn conflicting_systems_run_in_the_order_they_were_added() {
+    let mut world = world();
+    let seen = Arc::new(Mutex::new(vec![]));
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(parallel(4)).add_stage("update");
+    schedule.add_query_system::<(&mut Position, &Velocity), _>("update", "movement", |world| {
+        world.query::<(&mut Position, &Velocity)>().for_each(|_, (position, velocity)| position.0 += velocity.0)
+    });
+
+    let log = seen.clone();
+    schedule.add_query_system::<&Position, _>("update", "observer", move |world| {
+        world.query::<&Position>().for_each(|_, position| log.lock().unwrap().push(position.0))
+    });
+
+    schedule.run_for(&mut world, 3);
+
+    assert_eq!(*seen.lock().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn access_is_derived_from_a_query_signature() {
+    let stmts = oko::parse::parse_code("fn movement(pos: &mut Position, vel: &Velocity, hp: Health) = pos\n", "test.oko").0.unwrap();
+    let Stmt::Fn(fun) = &stmts[0] else {
+        panic!("expected a function")
+    };
+
+    let world = world();
+
+    let mut schedule = Schedule::new();
+    schedule.set_mode(parallel(4)).add_stage("update");
+    schedule.add_system_with_access("update", "movement", AccessDecl::from_signature(&fun.sig), |_| ());
+    schedule.add_query_system::<&Health, _>("update", "reader", |_| ());
+    schedule.add_query_system::<&Position, _>("update", "conflicting", |_| ());
+
+    assert_eq!(batches(&schedule, &world), vec![vec!["movement", "reader"], vec!["conflicting"]]);
+}
@@ -0,0 +1,178 @@
+use oko::ecs::snapshot::{EntitySnapshot, Schema, Snapshot, SnapshotError, Value};
+use oko::ecs::World;
+use oko::persistent_struct;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Health {
+    value: i32
+}
+
+persistent_struct!(Health { value });
+
+#[derive(Debug, Clone, PartialEq)]
+struct Damage {
+    amount: i32
+}
+
+persistent_struct!(Damage { amount });
+
+const TYPES: &str = "
+ty Health = value: int
+
+ty Damage = amount: int
+";
+
+fn schema(code: &str) -> Schema {
+    Schema::from_stmts(&oko::parse::parse_code(code, "test.oko").0.unwrap())
+}
+
+fn world() -> World {
+    let mut world = World::new();
+    world.register_persistent::<Health>("Health");
+    world.register_persistent_event::<Damage>("Damage");
+    world
+}
+
+///
+/// A world with a despawned entity, so the snapshot has a free slot
+///
+fn populated() -> World {
+    let mut world = world();
+
+    let first = world.spawn((Health { value: 1 },));
+    world.spawn((Health { value: 2 },));
+    world.spawn((Health { value: 3 },));
+    world.spawn((Health { value: 4 },));
+    world.despawn(first);
+    world.send(Damage { amount: 5 });
+
+    world
+}
+
+fn healths(world: &World) -> Vec <i32> {
+    let mut healths = vec![];
+    world.query::<&Health>().for_each(|_, health| healths.push(health.value));
+    healths.sort();
+    healths
+}
+
+#[test]
+fn snapshots_round_trip_through_binary_and_json() {
+    let schema = schema(TYPES);
+    let snapshot = populated().snapshot(&schema).unwrap();
+
+    assert_eq!(Snapshot::from_binary(&snapshot.to_binary()).unwrap(), snapshot);
+    assert_eq!(Snapshot::from_json(&snapshot.to_json()).unwrap(), snapshot);
+
+    let mut restored = world();
+    restored.restore(&Snapshot::from_binary(&snapshot.to_binary()).unwrap(), &schema).unwrap();
+
+    assert_eq!(healths(&restored), [2, 3, 4]);
+    assert_eq!(restored.len(), 3);
+    assert_eq!(restored.snapshot(&schema).unwrap(), snapshot);
+}
+
+#[test]
+fn restored_worlds_reuse_the_same_entity_slots() {
+    let schema = schema(TYPES);
+    let mut original = populated();
+    let snapshot = original.snapshot(&schema).unwrap();
+
+    let mut restored = world();
+    restored.restore(&snapshot, &schema).unwrap();
+
+    assert_eq!(restored.spawn((Health { value: 6 },)), original.spawn((Health { value: 6 },)));
+}
+
+#[test]
+fn a_changed_schema_is_rejected() {
+    let snapshot = populated().snapshot(&schema(TYPES)).unwrap();
+    let changed = schema("ty Health = value max: int\n\nty Damage = amount: int\n");
+
+    let mut world = world();
+    let err = world.restore(&snapshot, &changed).unwrap_err();
+
+    assert_eq!(err, SnapshotError::SchemaMismatch(vec![String::from("`Health` changed from `struct value: int` to `struct value: int + max: int`")]));
+    assert!(world.is_empty());
+}
+
+#[test]
+fn schemas_are_read_back_from_their_canonical_form() {
+    let schema = schema("
+ty Handler = None | Some fn(x: int) -> int
+
+ty Bits = union i: int + f: float
+
+ty Vec2 = x y: float
+");
+    let canonical = schema.canonical();
+
+    assert!(canonical.starts_with("ty Bits = union i: int + f: float\nty Handler = enum None | Some "), "{canonical}");
+    assert_eq!(Schema::from_canonical(&canonical).unwrap(), schema);
+    assert!(Schema::from_canonical("ty Vec2 = x: float").is_err());
+}
+
+#[test]
+fn components_outside_of_the_schema_are_not_saved() {
+    let err = populated().snapshot(&schema("ty Damage = amount: int\n")).unwrap_err();
+    assert_eq!(err, SnapshotError::UnknownType(String::from("Health")));
+}
+
+#[test]
+fn truncated_data_is_malformed() {
+    let data = populated().snapshot(&schema(TYPES)).unwrap().to_binary();
+
+    assert!(matches!(Snapshot::from_binary(&data[.. data.len() - 1]), Err(SnapshotError::Malformed(_))));
+    assert!(matches!(Snapshot::from_json("{\"version\": 1"), Err(SnapshotError::Malformed(_))));
+}
+
+fn restore(edit: impl FnOnce(&mut Snapshot)) -> Result <(), SnapshotError> {
+    let schema = schema(TYPES);
+    let mut snapshot = populated().snapshot(&schema).unwrap();
+    edit(&mut snapshot);
+
+    world().restore(&snapshot, &schema)
+}
+
+fn health(value: i64) -> Vec <(String, Value)> {
+    vec![(String::from("Health"), Value::Struct(vec![(String::from("value"), Value::Int(value))]))]
+}
+
+#[test]
+fn entities_saved_twice_are_malformed() {
+    let err = restore(|snapshot| {
+        let mut twice = snapshot.entities[0].clone();
+        twice.components = health(7);
+        snapshot.entities.push(twice)
+    });
+
+    assert!(matches!(err, Err(SnapshotError::Malformed(message)) if message.ends_with("is saved twice")));
+}
+
+#[test]
+fn alive_entities_without_components_are_malformed() {
+    let err = restore(|snapshot| {
+        snapshot.entities.pop();
+    });
+
+    assert!(matches!(err, Err(SnapshotError::Malformed(message)) if message.ends_with("is alive, but not saved")));
+}
+
+#[test]
+fn entities_out_of_the_allocator_are_malformed() {
+    let malformed = Err(SnapshotError::Malformed(String::from("entities do not match the allocator state")));
+
+    assert_eq!(restore(|snapshot| snapshot.free.push(snapshot.free[0])), malformed);
+    assert_eq!(restore(|snapshot| snapshot.free.push(10)), malformed);
+    assert_eq!(restore(|snapshot| snapshot.entities[0].entity.generation += 1), malformed);
+    assert_eq!(restore(|snapshot| {
+        let entity = snapshot.entities[0].entity;
+        snapshot.entities.push(EntitySnapshot {
+            entity: oko::ecs::Entity {
+                index: entity.index + 10,
+                ..entity
+            },
+            components: health(8)
+        })
+    }), malformed);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+const SHOW: &str = "
+ty Vec2 = x y: int
+
+trait Show {
+    fn show(&self) -> int
+}
+";
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{SHOW}\n{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn methods_of_traits_are_dispatched_statically() {
+    let c = common::compile(&format!("{SHOW}
+impl Show for Vec2 {{
+    fn show(&self) -> int = self.x
+}}
+
+impl Show for int {{
+    fn show(&self) -> int = 0
+}}
+
+fn shown <T> (v: &T) -> int = v.show()
+
+fn direct(r: &&Vec2) -> int = r.show()
+
+fn main() -> int = 0
+")).unwrap();
+
+    assert!(c.contains("int Show_4Vec2_show(const Vec2 *self) {\n    return (*self).x;"), "{c}");
+    assert!(c.contains("int Show_3int_show(const int *self) {"), "{c}");
+
+    // the receiver is dereferenced as the method expects
+    assert!(c.contains("return Show_4Vec2_show((*r));"), "{c}");
+
+    // a generic function is dispatched once it is instantiated, so an unused one is not
+    assert!(!c.contains("shown"), "{c}");
+}
+
+#[test]
+fn generic_functions_call_the_methods_of_their_arguments() {
+    let c = common::compile(&format!("{SHOW}
+impl Show for Vec2 {{
+    fn show(&self) -> int = self.x
+}}
+
+fn shown <T> (v: &T) -> int = v.show()
+
+fn f(v: &Vec2) -> int = shown<Vec2>(v)
+
+fn main() -> int = 0
+")).unwrap();
+
+    assert!(c.contains("int shown_4Vec2(const Vec2 *v) {\n    return Show_4Vec2_show(v);"), "{c}");
+}
+
+#[test]
+fn impls_match_their_traits() {
+    for (code, message) in [
+        ("impl Show for Vec2 {\n}", "not all methods of trait `Show` are implemented for `Vec2`"),
+        ("impl Show for Vec2 {\n    fn show(&self) -> int = 0\n    fn hide(&self) -> int = 0\n}", "`hide` is not a method of trait `Show`"),
+        ("impl Show for Vec2 {\n    fn show(self) -> int = 0\n}", "method `show` does not match its declaration in trait `Show`"),
+        ("impl Nope for Vec2 {\n}", "cannot find trait `Nope`")
+    ] {
+        assert_eq!(error(code).as_deref(), Some(message), "{code}")
+    }
+}
+
+#[test]
+fn a_trait_is_implemented_once_per_type() {
+    let show = "impl Show for Vec2 {\n    fn show(&self) -> int = 0\n}\n";
+    assert_eq!(error(&format!("{show}\n{show}")).as_deref(), Some("trait `Show` is already implemented for `Vec2`"));
+}
+
+#[test]
+fn methods_are_looked_up_in_impls() {
+    assert_eq!(
+        common::compile(&format!("{SHOW}\nfn f(v: int) -> int = v.show()\n\nfn main() -> int = 0\n")),
+        Err(String::from("no method `show` for type `int`"))
+    );
+}
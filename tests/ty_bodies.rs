@@ -0,0 +1,66 @@
+mod common;
+
+use oko::parse::stmt::{Stmt, TyStmtBody};
+
+fn parse(code: &str) -> TyStmtBody {
+    match oko::parse::parse_code(code, "test.oko").0.unwrap().remove(0) {
+        Stmt::Ty(ty) => ty.body,
+        other => panic!("not a `ty`: {other:?}")
+    }
+}
+
+fn fields(code: &str) -> Vec <String> {
+    match parse(code) {
+        TyStmtBody::Struct(body) => body.fields.0.iter().flat_map(|field| field.names.iter().map(|name| format!("{}: {:?}", name.name, field.ty))).collect(),
+        _ => panic!("not a struct")
+    }
+}
+
+fn variants(code: &str) -> Vec <String> {
+    match parse(code) {
+        TyStmtBody::Enum(body) => body.fields.0.iter().map(|field| match &field.attached_type {
+            Some(ty) => format!("{} {ty:?}", field.name.name),
+            None => field.name.name.clone()
+        }).collect(),
+        _ => panic!("not an enum")
+    }
+}
+
+#[test]
+fn struct_bodies_span_several_lines() {
+    let expected = ["health: u8", "position: Vec2"];
+
+    assert_eq!(fields("ty Player = {\n    health: u8\n    position: Vec2\n}\n"), expected);
+    assert_eq!(fields("ty Player = {\n    health: u8 +\n    position: Vec2\n}\n"), expected);
+    assert_eq!(fields("ty Player = health: u8 + position: Vec2\n"), expected);
+}
+
+#[test]
+fn enum_variants_continue_on_lines_starting_with_a_bar() {
+    let expected = ["Empty", "Circle int"];
+
+    assert_eq!(variants("ty Shape =\n    | Empty\n    | Circle int\n"), expected);
+    assert_eq!(variants("ty Shape = Empty | Circle int\n"), expected);
+    assert_eq!(variants("ty Shape = | Empty\n    | Circle int\n"), expected);
+}
+
+#[test]
+fn bodies_may_end_the_file() {
+    assert_eq!(fields("ty Player = health: u8"), ["health: u8"]);
+    assert_eq!(fields("ty Player = {\n    health: u8\n}"), ["health: u8"]);
+    assert_eq!(variants("ty Shape =\n    | Empty\n    | Circle int"), ["Empty", "Circle int"]);
+}
+
+#[test]
+fn block_bodies_are_closed_and_not_empty() {
+    let clarifying = |code: &str| common::diagnostics(code).remove(0).clarifying;
+
+    assert_eq!(clarifying("ty Player = {\n    health: u8\n\nfn main() -> int = 0\n"), "expected `}`");
+    assert_eq!(clarifying("ty Player = {\n}\n"), "expected at least one field");
+}
+
+#[test]
+fn block_bodies_are_lowered_as_structs() {
+    let c = common::compile("ty Player = {\n    health: u8\n    position: int\n}\n\nfn f(p: Player) -> int = p.position\n\nfn main() -> int = 0").unwrap();
+    assert!(c.contains("struct Player {\n    uint8_t health;\n    int position;\n};"), "{c}");
+}
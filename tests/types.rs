@@ -0,0 +1,56 @@
+mod common;
+
+fn error(code: &str) -> Option <String> {
+    common::error(&format!("{code}\n\nfn main() -> int = 0\n"))
+}
+
+fn compile(code: &str) -> Result <String, String> {
+    common::compile(&format!("{code}\n\nfn main() -> int = 0\n"))
+}
+
+#[test]
+fn arrays_slices_and_tuples_are_lowered_to_structs() {
+    let c = common::compile("
+fn first(xs: &[int]) -> int = xs[0]
+
+fn last(xs: &mut [&int]) -> &int = xs[1]
+
+fn third(xs: [int; 4]) -> int = xs[2]
+
+fn second(pair: (int, float)) -> float = pair[1]
+
+fn main() -> int = 0
+").unwrap();
+
+    assert!(c.contains("struct oko_A4_3int {\n    int items[4];\n};"), "{c}");
+    assert!(c.contains("struct oko_S3int {\n    const int *items;\n    size_t len;\n};"), "{c}");
+    assert!(c.contains("struct oko_RMSR3int {\n    const int **items;\n    size_t len;\n};"), "{c}");
+    assert!(c.contains("struct oko_T2_3int5float {\n    int f0;\n    float f1;\n};"), "{c}");
+
+    assert!(c.contains("int first(oko_S3int xs) {\n    return xs.items[0];"), "{c}");
+    assert!(c.contains("const int *last(oko_RMSR3int xs) {\n    return xs.items[1];"), "{c}");
+    assert!(c.contains("int third(oko_A4_3int xs) {\n    return xs.items[2];"), "{c}");
+    assert!(c.contains("float second(oko_T2_3int5float pair) {\n    return pair.f1;"), "{c}");
+}
+
+#[test]
+fn arrays_are_not_empty_and_slices_are_behind_an_indirection() {
+    assert_eq!(error("fn f(xs: [int; 0]) -> int = 0").as_deref(), Some("an array cannot be empty"));
+    assert_eq!(error("fn f(xs: [int]) -> int = 0").as_deref(), Some("a slice cannot be used by value"));
+    assert_eq!(error("fn f(xs: *[int]) -> int = 0"), None);
+}
+
+#[test]
+fn tuples_are_indexed_by_literals_in_bounds() {
+    assert_eq!(
+        compile("fn f(p: (int, int), i: int) -> int = p[i]"),
+        Err(String::from("a tuple can only be indexed by an integer literal"))
+    );
+    assert_eq!(compile("fn f(p: (int, int)) -> int = p[2]"), Err(String::from("index 2 is out of bounds for `(int, int)`")));
+    assert!(compile("fn f(p: (int, int), i: int) -> int = p[1]").is_ok());
+}
+
+#[test]
+fn only_arrays_slices_and_tuples_are_indexed() {
+    assert_eq!(compile("fn f(x: int) -> int = x[0]"), Err(String::from("cannot index into a value of type `int`")));
+}
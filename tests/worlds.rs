@@ -0,0 +1,169 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use oko::ecs::{Schedule, Universe, World};
+
+#[test]
+fn worlds_may_declare_components_of_the_same_name() {
+    let code = "
+world Game {
+    ty Health = value: int
+
+    fn heal(h: Health) -> Health = h
+}
+
+world Ui {
+    ty Health = shown: int
+
+    fn show(h: Health) -> Health = h
+}
+
+fn main() -> int = 0
+";
+
+    assert_eq!(common::error(code), None);
+
+    let c = common::compile(code).unwrap();
+
+    assert!(c.contains("struct Game__Health {\n    int value;"), "{c}");
+    assert!(c.contains("struct Ui__Health {\n    int shown;"), "{c}");
+    assert!(c.contains("Game__Health Game__heal(Game__Health h)"), "{c}");
+    assert!(c.contains("Ui__Health Ui__show(Ui__Health h)"), "{c}");
+}
+
+#[test]
+fn a_component_of_another_world_is_rejected() {
+    let code = "
+world Game {
+    ty Health = value: int
+    ty Armor = value: int
+}
+
+world Ui {
+    ty Health = shown: int
+
+    fn show(h: Health, a: Armor) -> Health = h
+}
+
+fn main() -> int = 0
+";
+
+    assert_eq!(common::error(code).as_deref(), Some("`Armor` belongs to world `Game`"));
+}
+
+#[test]
+fn a_component_is_rejected_outside_of_its_world() {
+    let code = "
+world Game {
+    ty Health = value: int
+}
+
+fn main(h: Health) -> int = 0
+";
+
+    assert_eq!(common::error(code).as_deref(), Some("`Health` belongs to world `Game`"));
+}
+
+#[test]
+fn a_top_level_type_is_shared_by_the_worlds() {
+    let code = "
+ty Health = value: int
+
+world Game {
+    ty Health = local: int
+
+    fn own(h: Health) -> Health = h
+}
+
+world Ui {
+    fn shared(h: Health) -> Health = h
+}
+
+fn main() -> int = 0
+";
+
+    let c = common::compile(code).unwrap();
+
+    assert!(c.contains("Game__Health Game__own(Game__Health h)"), "{c}");
+    assert!(c.contains("Health Ui__shared(Health h)"), "{c}");
+}
+
+#[test]
+fn enum_variants_of_a_world_are_resolved() {
+    let code = "
+world Game {
+    ty State =
+        | Running
+        | Paused
+
+    fn pause() -> State = Paused
+}
+
+fn main() -> int = 0
+";
+
+    assert!(common::compile(code).unwrap().contains("Game__State Game__pause(void)"));
+}
+
+#[test]
+fn worlds_are_unique_and_not_nested() {
+    assert_eq!(
+        common::error("world Game {\n}\n\nworld Game {\n}\n").as_deref(),
+        Some("world `Game` is declared twice")
+    );
+    assert_eq!(
+        common::error("world Game {\n    world Ui {\n    }\n}\n").as_deref(),
+        Some("world `Ui` is declared inside of world `Game`")
+    );
+}
+
+#[test]
+fn channels_connect_two_existing_worlds() {
+    let worlds = "world Game {\n}\n\nworld Ui {\n}\n\nty Damage = amount: int\n";
+
+    assert_eq!(
+        common::error(&format!("{worlds}\nchannel Game -> Menu: Damage\n")).as_deref(),
+        Some("no world named `Menu`")
+    );
+    assert_eq!(
+        common::error(&format!("{worlds}\nchannel Game -> Game: Damage\n")).as_deref(),
+        Some("channel from world `Game` to itself")
+    );
+}
+
+#[test]
+fn channels_are_not_supported_yet() {
+    let code = "world Game {\n}\n\nworld Ui {\n}\n\nty Damage = amount: int\n\nchannel Game -> Ui: Damage\n\nfn main() -> int = 0\n";
+    let diagnostics = common::diagnostics(code);
+
+    assert_eq!(diagnostics[0].message, "`channel` is not supported yet");
+    assert_eq!(diagnostics[0].spanned, "Game");
+}
+
+struct Damage(u32);
+
+#[test]
+fn events_cross_worlds_at_frame_boundaries() {
+    let received = Arc::new(Mutex::new(vec![]));
+    let mut universe = Universe::new();
+
+    universe.add_world("Game", World::new(), Schedule::new());
+    universe.add_world("Ui", World::new(), Schedule::new());
+
+    let sender = universe.channel::<Damage>("Game", "Ui");
+
+    let game = universe.world_mut("Game").unwrap();
+    game.schedule.add_stage("update");
+    game.schedule.add_system("update", "attack", move |world: &World| sender.send(Damage(world.tick() as u32)));
+
+    let log = received.clone();
+    let ui = universe.world_mut("Ui").unwrap();
+    ui.schedule.add_stage("update");
+    ui.schedule.on::<Damage>("show", move |world, damage| log.lock().unwrap().push((world.tick(), damage.0)));
+
+    universe.run_for(3);
+
+    // an event sent during a frame is handled by the receiving world on its next tick
+    assert_eq!(*received.lock().unwrap(), [(1, 0), (2, 1)]);
+    assert_eq!(universe.world("Ui").unwrap().world.tick(), 3);
+}